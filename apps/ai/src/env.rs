@@ -9,6 +9,8 @@ pub struct Env {
     pub sentry_dsn: Option<String>,
     pub supabase_url: String,
     pub openrouter_api_key: String,
+    pub llm_routing: Option<hypr_llm_proxy::RoutingConfig>,
+    pub llm_cache_ttl_secs: Option<u64>,
     api_keys: HashMap<Provider, String>,
 }
 
@@ -41,6 +43,10 @@ impl Env {
             sentry_dsn: optional("SENTRY_DSN"),
             supabase_url: required("SUPABASE_URL"),
             openrouter_api_key: required("OPENROUTER_API_KEY"),
+            llm_routing: optional("LLM_ROUTING").map(|v| {
+                serde_json::from_str(&v).unwrap_or_else(|e| panic!("LLM_ROUTING is invalid: {e}"))
+            }),
+            llm_cache_ttl_secs: optional("LLM_CACHE_TTL_SECS").and_then(|v| v.parse().ok()),
             api_keys,
         }
     }
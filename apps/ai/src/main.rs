@@ -16,7 +16,15 @@ use env::env;
 pub use auth::DEVICE_FINGERPRINT_HEADER;
 
 fn app() -> Router {
-    let llm_config = hypr_llm_proxy::LlmProxyConfig::new(&env().openrouter_api_key);
+    let mut llm_config = hypr_llm_proxy::LlmProxyConfig::new(&env().openrouter_api_key);
+    if let Some(routing) = env().llm_routing.clone() {
+        llm_config = llm_config.with_routing(routing);
+    }
+    if let Some(ttl) = env().llm_cache_ttl_secs {
+        llm_config = llm_config.with_cache(
+            hypr_llm_proxy::CacheConfig::default().with_ttl(std::time::Duration::from_secs(ttl)),
+        );
+    }
     let stt_config = hypr_transcribe_proxy::SttProxyConfig::new(env().api_keys());
     let auth_state = AuthState::new(&env().supabase_url);
 
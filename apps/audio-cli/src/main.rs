@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+use futures_util::StreamExt;
+
+mod stats;
+mod stt;
+
+use hypr_audio_pipeline::{AudioPipeline, ChannelMode, ProcessingConfig, SETTINGS_KEY};
+use hypr_audio_utils::{ResampleExtDynamicNew, chunk_size_for_stt, source_from_path};
+use stats::{ChannelLevels, LevelStats, print_report};
+use stt::SttArgs;
+
+// Must match `SAMPLE_RATE` in the listener plugin.
+const SAMPLE_RATE: u32 = 16 * 1000;
+
+#[derive(Parser)]
+#[command(name = "audio")]
+#[command(about = "Offline replay of the listener audio pipeline")]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(about = "Run mic/speaker recordings through the listener processing stages")]
+    Replay {
+        #[arg(long, help = "Mic recording (e.g. audio_mic.wav)")]
+        mic: Option<PathBuf>,
+
+        #[arg(long, help = "Speaker recording (e.g. audio_spk.wav)")]
+        speaker: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            default_value = ".",
+            help = "Directory for processed WAVs"
+        )]
+        output: PathBuf,
+
+        #[arg(
+            short,
+            long,
+            help = "settings.json, or a JSON file containing only the processing config"
+        )]
+        config: Option<PathBuf>,
+
+        #[arg(long, help = "owhisper endpoint to stream processed audio to")]
+        stt_url: Option<String>,
+
+        #[arg(long, env = "STT_API_KEY", default_value = "")]
+        stt_api_key: String,
+
+        #[arg(long)]
+        stt_model: Option<String>,
+
+        #[arg(
+            long,
+            help = "Pace STT uploads at real time instead of as fast as possible"
+        )]
+        realtime: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Commands::Replay {
+            mic,
+            speaker,
+            output,
+            config,
+            stt_url,
+            stt_api_key,
+            stt_model,
+            realtime,
+        } => {
+            let stt = stt_url.map(|base_url| SttArgs {
+                base_url,
+                api_key: stt_api_key,
+                model: stt_model,
+                realtime,
+            });
+            replay(mic, speaker, output, config, stt).await
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+async fn replay(
+    mic: Option<PathBuf>,
+    speaker: Option<PathBuf>,
+    output: PathBuf,
+    config: Option<PathBuf>,
+    stt: Option<SttArgs>,
+) -> Result<(), String> {
+    let mode = match (&mic, &speaker) {
+        (Some(_), Some(_)) => ChannelMode::MicAndSpeaker,
+        (Some(_), None) => ChannelMode::MicOnly,
+        (None, Some(_)) => ChannelMode::SpeakerOnly,
+        (None, None) => return Err("at least one of --mic or --speaker is required".to_string()),
+    };
+
+    let config = match config {
+        Some(path) => load_config(&path)?,
+        None => ProcessingConfig::default(),
+    };
+    eprintln!("mode: {:?}", mode);
+    eprintln!(
+        "config: {}",
+        serde_json::to_string(&config).unwrap_or_default()
+    );
+
+    let mic_chunks = match &mic {
+        Some(path) => read_chunks(path).await?,
+        None => Vec::new(),
+    };
+    let spk_chunks = match &speaker {
+        Some(path) => read_chunks(path).await?,
+        None => Vec::new(),
+    };
+
+    let mut pipeline = AudioPipeline::new(&config, SAMPLE_RATE);
+    let mut mic_levels = ChannelLevels {
+        name: "mic",
+        input: LevelStats::default(),
+        output: LevelStats::default(),
+    };
+    let mut spk_levels = ChannelLevels {
+        name: "speaker",
+        input: LevelStats::default(),
+        output: LevelStats::default(),
+    };
+    let mut pairs: Vec<(Arc<[f32]>, Arc<[f32]>)> = Vec::new();
+
+    let started = Instant::now();
+
+    // The source actor flushes after every chunk it receives, so interleave the
+    // two channels one chunk at a time and flush after each. Arrival times are
+    // taken from each file's own sample position, as if captured live.
+    let mut mic_at = Duration::ZERO;
+    let mut spk_at = Duration::ZERO;
+    let mut mic_iter = mic_chunks.into_iter();
+    let mut spk_iter = spk_chunks.into_iter();
+    loop {
+        let mic_chunk = mic_iter.next();
+        let spk_chunk = spk_iter.next();
+        if mic_chunk.is_none() && spk_chunk.is_none() {
+            break;
+        }
+
+        if let Some(chunk) = mic_chunk {
+            mic_levels.input.observe(&chunk);
+            mic_at += samples_to_duration(chunk.len());
+            pipeline.push_mic_at(chunk, mic_at);
+            drain_pairs(&mut pipeline, mode, &mut pairs);
+        }
+        if let Some(chunk) = spk_chunk {
+            spk_levels.input.observe(&chunk);
+            spk_at += samples_to_duration(chunk.len());
+            pipeline.push_speaker_at(chunk, spk_at);
+            drain_pairs(&mut pipeline, mode, &mut pairs);
+        }
+    }
+    // The longer recording leaves chunks in the joiner below `MAX_LAG`; pad them
+    // out as the single-channel modes would.
+    if mode == ChannelMode::MicAndSpeaker {
+        drain_pairs(&mut pipeline, ChannelMode::MicOnly, &mut pairs);
+        drain_pairs(&mut pipeline, ChannelMode::SpeakerOnly, &mut pairs);
+    }
+
+    let elapsed = started.elapsed();
+
+    for (mic, spk) in &pairs {
+        mic_levels.output.observe(mic);
+        spk_levels.output.observe(spk);
+    }
+
+    std::fs::create_dir_all(&output).map_err(|e| e.to_string())?;
+    if mode.uses_mic() {
+        let path = output.join("processed_mic.wav");
+        write_wav(&path, pairs.iter().map(|(mic, _)| mic))?;
+        eprintln!("wrote {}", path.display());
+    }
+    if mode.uses_speaker() {
+        let path = output.join("processed_spk.wav");
+        write_wav(&path, pairs.iter().map(|(_, spk)| spk))?;
+        eprintln!("wrote {}", path.display());
+    }
+
+    let channels: Vec<ChannelLevels> = [mic_levels, spk_levels]
+        .into_iter()
+        .filter(|c| c.input.samples() > 0)
+        .collect();
+    print_report(&pipeline, &channels, SAMPLE_RATE, elapsed);
+
+    if let Some(stt) = stt {
+        println!();
+        stt::stream_pairs(stt, mode, SAMPLE_RATE, pairs).await?;
+    }
+
+    Ok(())
+}
+
+fn samples_to_duration(samples: usize) -> Duration {
+    Duration::from_secs_f64(samples as f64 / SAMPLE_RATE as f64)
+}
+
+fn drain_pairs(
+    pipeline: &mut AudioPipeline,
+    mode: ChannelMode,
+    pairs: &mut Vec<(Arc<[f32]>, Arc<[f32]>)>,
+) {
+    while let Some(pair) = pipeline.pop_pair(mode) {
+        pairs.push(pair);
+    }
+}
+
+fn load_config(path: &Path) -> Result<ProcessingConfig, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    if value.get(SETTINGS_KEY).is_some() {
+        return Ok(ProcessingConfig::from_settings(&value));
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("invalid processing config: {}", e))
+}
+
+async fn read_chunks(path: &Path) -> Result<Vec<Vec<f32>>, String> {
+    let source = source_from_path(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let chunk_size = chunk_size_for_stt(SAMPLE_RATE);
+
+    let chunks = source
+        .resampled_chunks(SAMPLE_RATE, chunk_size)
+        .map_err(|e| e.to_string())?
+        .collect::<Vec<_>>()
+        .await;
+
+    chunks
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_wav<'a>(path: &Path, chunks: impl Iterator<Item = &'a Arc<[f32]>>) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec).map_err(|e| e.to_string())?;
+    for chunk in chunks {
+        for &sample in chunk.iter() {
+            writer.write_sample(sample).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.finalize().map_err(|e| e.to_string())
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use hypr_audio_pipeline::AudioPipeline;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LevelStats {
+    samples: u64,
+    sum_sq: f64,
+    peak: f32,
+    clipped: u64,
+}
+
+impl LevelStats {
+    pub fn observe(&mut self, data: &[f32]) {
+        for &sample in data {
+            let abs = sample.abs();
+            self.samples += 1;
+            self.sum_sq += (sample as f64) * (sample as f64);
+            self.peak = self.peak.max(abs);
+            if abs >= 1.0 {
+                self.clipped += 1;
+            }
+        }
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    fn rms_dbfs(&self) -> f64 {
+        if self.samples == 0 {
+            return f64::NEG_INFINITY;
+        }
+        to_dbfs((self.sum_sq / self.samples as f64).sqrt())
+    }
+
+    fn peak_dbfs(&self) -> f64 {
+        to_dbfs(self.peak as f64)
+    }
+}
+
+fn to_dbfs(value: f64) -> f64 {
+    if value <= 0.0 {
+        f64::NEG_INFINITY
+    } else {
+        20.0 * value.log10()
+    }
+}
+
+pub struct ChannelLevels {
+    pub name: &'static str,
+    pub input: LevelStats,
+    pub output: LevelStats,
+}
+
+pub fn print_report(
+    pipeline: &AudioPipeline,
+    channels: &[ChannelLevels],
+    sample_rate: u32,
+    elapsed: Duration,
+) {
+    println!(
+        "{:<10} {:>10} {:>12} {:>12} {:>12} {:>12} {:>8}",
+        "Channel", "Seconds", "In RMS", "In Peak", "Out RMS", "Out Peak", "Clipped"
+    );
+    println!("{}", "-".repeat(82));
+    for channel in channels {
+        println!(
+            "{:<10} {:>10.2} {:>12.1} {:>12.1} {:>12.1} {:>12.1} {:>8}",
+            channel.name,
+            channel.input.samples() as f64 / sample_rate as f64,
+            channel.input.rms_dbfs(),
+            channel.input.peak_dbfs(),
+            channel.output.rms_dbfs(),
+            channel.output.peak_dbfs(),
+            channel.output.clipped,
+        );
+    }
+    println!();
+
+    println!(
+        "{:<10} {:<10} {:>8} {:>8} {:>12} {:>12} {:>12}",
+        "Stage", "Node", "Frames", "Errors", "Mean (us)", "Max (us)", "Total (ms)"
+    );
+    println!("{}", "-".repeat(78));
+    for (channel, node, metrics) in pipeline.metrics() {
+        println!(
+            "{:<10} {:<10} {:>8} {:>8} {:>12} {:>12} {:>12.1}",
+            channel,
+            node,
+            metrics.frames,
+            metrics.errors,
+            metrics.mean().as_micros(),
+            metrics.max.as_micros(),
+            metrics.total.as_secs_f64() * 1000.0,
+        );
+    }
+    println!();
+
+    let joiner = pipeline.joiner_stats();
+    println!(
+        "joiner: paired={} mic_padded={} spk_padded={} mic_dropped={} spk_dropped={}",
+        joiner.paired, joiner.mic_padded, joiner.spk_padded, joiner.mic_dropped, joiner.spk_dropped
+    );
+
+    let drift = pipeline.drift_metrics();
+    println!(
+        "drift: {} ppm={:.1} ratio={:.6} offset_samples={:.0}",
+        if drift.converged {
+            "converged"
+        } else {
+            "estimating"
+        },
+        drift.drift_ppm,
+        drift.ratio,
+        drift.offset_samples
+    );
+
+    let audio_secs = channels
+        .iter()
+        .map(|c| c.input.samples())
+        .max()
+        .unwrap_or(0) as f64
+        / sample_rate as f64;
+    if audio_secs > 0.0 {
+        println!(
+            "processed {:.2}s of audio in {:.2}s (realtime factor {:.3})",
+            audio_secs,
+            elapsed.as_secs_f64(),
+            elapsed.as_secs_f64() / audio_secs
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use owhisper_client::{FinalizeHandle, ListenClient};
+use owhisper_interface::stream::StreamResponse;
+use owhisper_interface::{ControlMessage, MixedMessage};
+
+use hypr_audio_pipeline::ChannelMode;
+use hypr_audio_utils::f32_to_i16_bytes;
+
+const FINALIZE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct SttArgs {
+    pub base_url: String,
+    pub api_key: String,
+    pub model: Option<String>,
+    pub realtime: bool,
+}
+
+pub async fn stream_pairs(
+    args: SttArgs,
+    mode: ChannelMode,
+    sample_rate: u32,
+    pairs: Vec<(Arc<[f32]>, Arc<[f32]>)>,
+) -> Result<(), String> {
+    let params = owhisper_interface::ListenParams {
+        model: args.model.clone(),
+        sample_rate,
+        ..Default::default()
+    };
+
+    let builder = ListenClient::builder()
+        .api_base(args.base_url.clone())
+        .api_key(args.api_key.clone())
+        .params(params);
+
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+
+    match mode {
+        ChannelMode::MicAndSpeaker => {
+            let (tx, rx) = tokio::sync::mpsc::channel(32);
+            spawn_sender(
+                tx,
+                pairs,
+                sample_rate,
+                args.realtime,
+                done_tx,
+                |mic, spk| MixedMessage::Audio((bytes_of(mic), bytes_of(spk))),
+            );
+
+            let client = builder.build_dual().await;
+            let (stream, handle) = client
+                .from_realtime_audio(tokio_stream::wrappers::ReceiverStream::new(rx))
+                .await
+                .map_err(|e| format!("failed to connect: {:?}", e))?;
+            drain(stream, handle, done_rx).await
+        }
+        ChannelMode::MicOnly | ChannelMode::SpeakerOnly => {
+            let (tx, rx) = tokio::sync::mpsc::channel(32);
+            spawn_sender(
+                tx,
+                pairs,
+                sample_rate,
+                args.realtime,
+                done_tx,
+                move |mic, spk| {
+                    let data = if mode == ChannelMode::MicOnly {
+                        mic
+                    } else {
+                        spk
+                    };
+                    MixedMessage::Audio(bytes_of(data))
+                },
+            );
+
+            let client = builder.build_single().await;
+            let (stream, handle) = client
+                .from_realtime_audio(tokio_stream::wrappers::ReceiverStream::new(rx))
+                .await
+                .map_err(|e| format!("failed to connect: {:?}", e))?;
+            drain(stream, handle, done_rx).await
+        }
+    }
+}
+
+fn bytes_of(data: &[f32]) -> Bytes {
+    f32_to_i16_bytes(data.iter().copied())
+}
+
+fn spawn_sender<T, F>(
+    tx: tokio::sync::mpsc::Sender<MixedMessage<T, ControlMessage>>,
+    pairs: Vec<(Arc<[f32]>, Arc<[f32]>)>,
+    sample_rate: u32,
+    realtime: bool,
+    done_tx: tokio::sync::oneshot::Sender<()>,
+    to_message: F,
+) where
+    T: Send + 'static,
+    F: Fn(&[f32], &[f32]) -> MixedMessage<T, ControlMessage> + Send + 'static,
+{
+    tokio::spawn(async move {
+        for (mic, spk) in pairs {
+            let chunk_duration = Duration::from_secs_f64(mic.len() as f64 / sample_rate as f64);
+
+            if tx.send(to_message(&mic, &spk)).await.is_err() {
+                break;
+            }
+
+            if realtime {
+                tokio::time::sleep(chunk_duration).await;
+            }
+        }
+
+        let _ = done_tx.send(());
+    });
+}
+
+async fn drain<S, E, H>(
+    stream: S,
+    handle: H,
+    done_rx: tokio::sync::oneshot::Receiver<()>,
+) -> Result<(), String>
+where
+    S: Stream<Item = Result<StreamResponse, E>>,
+    E: std::fmt::Debug,
+    H: FinalizeHandle,
+{
+    futures_util::pin_mut!(stream);
+    futures_util::pin_mut!(done_rx);
+
+    loop {
+        tokio::select! {
+            _ = &mut done_rx => break,
+            item = stream.next() => match item {
+                Some(Ok(response)) => print_response(&response),
+                Some(Err(e)) => return Err(format!("stream error: {:?}", e)),
+                None => return Ok(()),
+            },
+        }
+    }
+
+    handle.finalize().await;
+
+    let expected = handle.expected_finalize_count();
+    let mut finalized = 0usize;
+
+    while finalized < expected {
+        match tokio::time::timeout(FINALIZE_TIMEOUT, stream.next()).await {
+            Ok(Some(Ok(response))) => {
+                if let StreamResponse::TranscriptResponse { from_finalize, .. } = &response
+                    && *from_finalize
+                {
+                    finalized += 1;
+                }
+                print_response(&response);
+            }
+            Ok(Some(Err(e))) => return Err(format!("stream error: {:?}", e)),
+            Ok(None) => break,
+            Err(_) => {
+                eprintln!("timed out waiting for finalize ({finalized}/{expected})");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_response(response: &StreamResponse) {
+    if let StreamResponse::TranscriptResponse {
+        start,
+        duration,
+        is_final: true,
+        channel,
+        channel_index,
+        ..
+    } = response
+    {
+        let Some(transcript) = channel.alternatives.first().map(|a| a.transcript.trim()) else {
+            return;
+        };
+        if transcript.is_empty() {
+            return;
+        }
+
+        println!(
+            "[{:>8.2}s - {:>8.2}s] ch{} {}",
+            start,
+            start + duration,
+            channel_index.first().copied().unwrap_or(0),
+            transcript
+        );
+    }
+}
@@ -11,6 +11,26 @@ use store::*;
 use tauri_plugin_permissions::{Permission, PermissionsPluginExt};
 use tauri_plugin_windows::{AppWindow, WindowsPluginExt};
 
+async fn init_local_db(app: &tauri::AppHandle<tauri::Wry>) {
+    use tauri_plugin_db2::Database2PluginExt;
+    use tauri_plugin_fs_sync::FsSyncPluginExt;
+
+    let encryption_key = match app.fs_sync().encryption_key() {
+        Ok(key) => key.map(|key| key.as_bytes().to_vec()),
+        Err(tauri_plugin_fs_sync::Error::Locked) => {
+            tracing::info!("local_db_waiting_for_unlock");
+            return;
+        }
+        Err(e) => {
+            tracing::error!("failed_to_init_local: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = app.db2().init_local(encryption_key).await {
+        tracing::error!("failed_to_init_local: {}", e);
+    }
+}
+
 #[tokio::main]
 pub async fn main() {
     // Handle CLI commands early, before single-instance plugin takes over.
@@ -127,6 +147,7 @@ pub async fn main() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_store2::init())
         .plugin(tauri_plugin_settings::init())
+        .plugin(tauri_plugin_tantivy::init())
         .plugin(tauri_plugin_sfx::init())
         .plugin(tauri_plugin_windows::init())
         .plugin(tauri_plugin_js::init())
@@ -199,13 +220,19 @@ pub async fn main() {
                 }
             }
 
-            tokio::spawn(async move {
-                use tauri_plugin_db2::Database2PluginExt;
+            {
+                use tauri_plugin_fs_sync::EncryptionUnlocked;
+                use tauri_specta::Event;
+
+                // With a passphrase, the key only exists once it's unlocked.
+                let handle = app_handle.clone();
+                EncryptionUnlocked::listen_any(&app_handle, move |_| {
+                    let handle = handle.clone();
+                    tokio::spawn(async move { init_local_db(&handle).await });
+                });
+            }
 
-                if let Err(e) = app_clone.db2().init_local().await {
-                    tracing::error!("failed_to_init_local: {}", e);
-                }
-            });
+            tokio::spawn(async move { init_local_db(&app_clone).await });
 
             if let (Some(ctx), Some(handle)) = (&root_supervisor_ctx, root_supervisor_handle) {
                 supervisor::monitor_supervisor(handle, ctx.is_exiting.clone(), app_handle.clone());
@@ -0,0 +1,352 @@
+import { generateText, type LanguageModel } from "ai";
+
+import {
+  commands as tantivyCommands,
+  type SearchDocument,
+  type SearchHit,
+} from "@hypr/plugin-tantivy";
+import {
+  type Citation,
+  type MeetingPassage,
+  commands as templateCommands,
+} from "@hypr/plugin-template";
+
+import {
+  collectEnhancedNotesContent,
+  extractPlainText,
+  mergeContent,
+  toEpochMs,
+  toTrimmedString,
+} from "../contexts/search/engine/utils";
+import { type Store as MainStore } from "../store/tinybase/store/main";
+import { buildSegments, SegmentKey, type WordLike } from "../utils/segment";
+import {
+  defaultRenderLabelContext,
+  SpeakerLabelManager,
+} from "../utils/segment/shared";
+
+// Sessions are indexed into tantivy's default collection, next to whatever
+// else lives there, so every query is scoped to this type.
+const SESSION_DOC_TYPE = "session";
+const PASSAGE_LIMIT = 8;
+const FALLBACK_PASSAGE_CHARS = 1000;
+// Transcripts change on every word while recording; re-embedding a session
+// that often would keep the embedding model busy for nothing.
+const REINDEX_DEBOUNCE_MS = 5_000;
+// The plugin opens its index in the background on start, so the first pass
+// can run before the collection is there.
+const INITIAL_SYNC_ATTEMPTS = 10;
+const INITIAL_SYNC_RETRY_MS = 1_000;
+// `[12:34]` or `[1:02:03]` at the start of a transcript line.
+const LINE_TIMESTAMP = /^\[(?:(\d+):)?(\d+):(\d{2})\]/;
+
+export type MeetingsFilters = {
+  createdAt?: { gte?: number; lte?: number };
+  participantId?: string;
+};
+
+export type MeetingsAnswer = {
+  text: string;
+  passages: MeetingPassage[];
+  citations: Citation[];
+};
+
+function participantFacet(humanId: string) {
+  return `/participants/${humanId}`;
+}
+
+function formatTimestamp(ms: number) {
+  const secs = Math.floor(ms / 1000);
+  const h = Math.floor(secs / 3600);
+  const m = Math.floor(secs / 60) % 60;
+  const s = String(secs % 60).padStart(2, "0");
+  return h > 0 ? `${h}:${String(m).padStart(2, "0")}:${s}` : `${m}:${s}`;
+}
+
+function parseTimestamp(line: string): number | null {
+  const match = LINE_TIMESTAMP.exec(line);
+  if (!match) {
+    return null;
+  }
+  const [, h, m, s] = match;
+  return ((Number(h ?? 0) * 60 + Number(m)) * 60 + Number(s)) * 1000;
+}
+
+function parseWords(wordsJson: unknown): WordLike[] {
+  if (typeof wordsJson !== "string" || !wordsJson) {
+    return [];
+  }
+
+  try {
+    return (JSON.parse(wordsJson) as WordLike[])
+      .map((w) => ({
+        text: w.text,
+        start_ms: w.start_ms,
+        end_ms: w.end_ms,
+        channel: w.channel,
+      }))
+      .sort((a, b) => a.start_ms - b.start_ms);
+  } catch {
+    return [];
+  }
+}
+
+// One `[m:ss] Speaker: text` line per segment, so a retrieved passage can be
+// traced back to the moment it was said.
+function timestampedTranscript(store: MainStore, sessionId: string): string {
+  const lines: string[] = [];
+  const ctx = defaultRenderLabelContext(store);
+
+  store.forEachRow("transcripts", (transcriptId) => {
+    const transcriptSessionId = store.getCell(
+      "transcripts",
+      transcriptId,
+      "session_id",
+    );
+    if (transcriptSessionId !== sessionId) {
+      return;
+    }
+
+    const words = parseWords(
+      store.getCell("transcripts", transcriptId, "words"),
+    );
+    const segments = buildSegments(words, [], []);
+    const manager = SpeakerLabelManager.fromSegments(segments, ctx);
+
+    for (const segment of segments) {
+      if (segment.words.length === 0) {
+        continue;
+      }
+      const speaker = SegmentKey.renderLabel(segment.key, ctx, manager);
+      const text = segment.words.map((w) => w.text).join(" ");
+      lines.push(
+        `[${formatTimestamp(segment.words[0].start_ms)}] ${speaker}: ${text}`,
+      );
+    }
+  });
+
+  return lines.join("\n");
+}
+
+function createSessionDocument(
+  store: MainStore,
+  sessionId: string,
+): SearchDocument {
+  const notes = mergeContent([
+    extractPlainText(store.getCell("sessions", sessionId, "raw_md")),
+    extractPlainText(collectEnhancedNotesContent(store, sessionId)),
+  ]);
+
+  const facets: string[] = [];
+  store.forEachRow("mapping_session_participant", (mappingId) => {
+    const row = store.getRow("mapping_session_participant", mappingId);
+    if (row.session_id === sessionId && row.human_id) {
+      facets.push(participantFacet(row.human_id));
+    }
+  });
+
+  return {
+    id: sessionId,
+    doc_type: SESSION_DOC_TYPE,
+    language: null,
+    title:
+      toTrimmedString(store.getCell("sessions", sessionId, "title")) ||
+      "Untitled",
+    content: [notes, timestampedTranscript(store, sessionId)]
+      .filter(Boolean)
+      .join("\n\n"),
+    created_at: toEpochMs(store.getCell("sessions", sessionId, "created_at")),
+    facets,
+  };
+}
+
+async function indexSession(store: MainStore, sessionId: string) {
+  const result = store.hasRow("sessions", sessionId)
+    ? await tantivyCommands.updateDocument(
+        createSessionDocument(store, sessionId),
+        null,
+      )
+    : await tantivyCommands.removeDocument(sessionId, null);
+
+  if (result.status === "error") {
+    throw new Error(result.error);
+  }
+}
+
+// Sends every session; the plugin only rewrites the ones that changed since
+// they were last indexed, so this is cheap after the first run.
+async function indexAllSessions(store: MainStore, isStopped: () => boolean) {
+  for (let attempt = 1; ; attempt++) {
+    try {
+      for (const sessionId of store.getRowIds("sessions")) {
+        if (isStopped()) {
+          return;
+        }
+        await indexSession(store, sessionId);
+      }
+      return;
+    } catch (error) {
+      if (isStopped() || attempt >= INITIAL_SYNC_ATTEMPTS) {
+        throw error;
+      }
+      await new Promise((resolve) =>
+        setTimeout(resolve, INITIAL_SYNC_RETRY_MS),
+      );
+    }
+  }
+}
+
+/**
+ * Mirrors sessions into the tantivy index used for cross-meeting chat, and
+ * keeps it current as sessions and their transcripts change. Returns a
+ * cleanup function.
+ */
+export function syncMeetingsIndex(store: MainStore): () => void {
+  const pending = new Map<string, ReturnType<typeof setTimeout>>();
+  let stopped = false;
+
+  const schedule = (sessionId: string) => {
+    clearTimeout(pending.get(sessionId));
+    pending.set(
+      sessionId,
+      setTimeout(() => {
+        pending.delete(sessionId);
+        void indexSession(store, sessionId).catch(console.error);
+      }, REINDEX_DEBOUNCE_MS),
+    );
+  };
+
+  void indexAllSessions(store, () => stopped).catch(console.error);
+
+  const listenerIds = [
+    store.addRowListener("sessions", null, (_store, _tableId, rowId) =>
+      schedule(rowId),
+    ),
+    store.addRowListener("transcripts", null, (_store, _tableId, rowId) => {
+      const sessionId = store.getCell("transcripts", rowId, "session_id");
+      if (sessionId) {
+        schedule(sessionId);
+      }
+    }),
+  ];
+
+  return () => {
+    stopped = true;
+    listenerIds.forEach((id) => store.delListener(id));
+    pending.forEach((timeout) => clearTimeout(timeout));
+    pending.clear();
+  };
+}
+
+function passageStartMs(content: string, text: string): number | null {
+  const start = content.indexOf(text);
+  if (start >= 0) {
+    const lineStart = content.lastIndexOf("\n", start) + 1;
+    const fromLine = parseTimestamp(content.slice(lineStart));
+    if (fromLine !== null) {
+      return fromLine;
+    }
+  }
+
+  // The passage began in the notes, or mid-way through a line.
+  for (const line of text.split("\n")) {
+    const ms = parseTimestamp(line);
+    if (ms !== null) {
+      return ms;
+    }
+  }
+  return null;
+}
+
+function toMeetingPassage(hit: SearchHit): MeetingPassage {
+  const { document } = hit;
+  const text =
+    hit.passage?.text ??
+    hit.content_snippet?.fragment ??
+    document.content.slice(0, FALLBACK_PASSAGE_CHARS);
+
+  return {
+    sessionId: document.id,
+    title: document.title || null,
+    date: new Date(document.created_at).toISOString().slice(0, 10),
+    startMs: passageStartMs(document.content, text),
+    text,
+  };
+}
+
+export async function retrieveMeetingPassages(
+  question: string,
+  filters: MeetingsFilters = {},
+): Promise<MeetingPassage[]> {
+  const result = await tantivyCommands.search({
+    query: question,
+    filters: {
+      created_at: filters.createdAt
+        ? {
+            gte: filters.createdAt.gte ?? null,
+            lte: filters.createdAt.lte ?? null,
+            gt: null,
+            lt: null,
+            eq: null,
+          }
+        : null,
+      doc_type: SESSION_DOC_TYPE,
+      facet: filters.participantId
+        ? participantFacet(filters.participantId)
+        : null,
+    },
+    limit: PASSAGE_LIMIT,
+    options: {
+      fuzzy: true,
+      distance: null,
+      snippets: true,
+      snippet_max_chars: FALLBACK_PASSAGE_CHARS,
+      phrase_slop: null,
+      hybrid: true,
+    },
+  });
+
+  if (result.status === "error") {
+    throw new Error(result.error);
+  }
+
+  return result.data.hits.map(toMeetingPassage);
+}
+
+/**
+ * Answers a question from passages across all of the user's meetings. The
+ * model is whichever one the user picked, so this runs through local-llm or
+ * the hosted llm-proxy alike.
+ */
+export async function askAcrossMeetings(params: {
+  model: LanguageModel;
+  question: string;
+  filters?: MeetingsFilters;
+  language?: string | null;
+  signal?: AbortSignal;
+}): Promise<MeetingsAnswer> {
+  const { model, question, filters, language = null, signal } = params;
+
+  const passages = await retrieveMeetingPassages(question, filters);
+
+  const system = await templateCommands.render({
+    meetingsChatSystem: { language, passages },
+  });
+  if (system.status === "error") {
+    throw new Error(system.error);
+  }
+
+  const { text } = await generateText({
+    model,
+    system: system.data,
+    prompt: question,
+    abortSignal: signal,
+  });
+
+  const citations = await templateCommands.collectCitations(text, passages);
+  if (citations.status === "error") {
+    throw new Error(citations.error);
+  }
+
+  return { text, passages, citations: citations.data };
+}
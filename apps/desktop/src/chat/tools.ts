@@ -1,14 +1,19 @@
-import { tool } from "ai";
+import { type LanguageModel, tool } from "ai";
 import { z } from "zod";
 
+import type { Citation } from "@hypr/plugin-template";
+
 import { searchFiltersSchema } from "../contexts/search/engine/types";
 import type { SearchFilters, SearchHit } from "../contexts/search/engine/types";
+import { askAcrossMeetings } from "./meetings";
 
 export interface ToolDependencies {
   search: (
     query: string,
     filters?: SearchFilters | null,
   ) => Promise<SearchHit[]>;
+  model: LanguageModel | null;
+  language: string | null;
 }
 
 const buildSearchSessionsTool = (deps: ToolDependencies) =>
@@ -38,8 +43,50 @@ const buildSearchSessionsTool = (deps: ToolDependencies) =>
     },
   });
 
+const buildAskMeetingsTool = (deps: ToolDependencies) =>
+  tool({
+    description: `
+  Answer a question from what was said across all of the user's meetings.
+  Use it for questions spanning several meetings, like what was decided about a topic.
+  Returns an answer citing passages as [n], and the meeting each marker points to.
+  `.trim(),
+    inputSchema: z.object({
+      question: z.string().describe("The question to answer"),
+      since: z
+        .number()
+        .optional()
+        .describe("Only meetings created at or after this time, in epoch ms"),
+      until: z
+        .number()
+        .optional()
+        .describe("Only meetings created at or before this time, in epoch ms"),
+    }),
+    execute: async (
+      params: { question: string; since?: number; until?: number },
+      { abortSignal },
+    ) => {
+      if (!deps.model) {
+        throw new Error("No language model is configured");
+      }
+
+      const { text, citations } = await askAcrossMeetings({
+        model: deps.model,
+        question: params.question,
+        filters:
+          params.since !== undefined || params.until !== undefined
+            ? { createdAt: { gte: params.since, lte: params.until } }
+            : {},
+        language: deps.language,
+        signal: abortSignal,
+      });
+
+      return { text, citations };
+    },
+  });
+
 export const buildChatTools = (deps: ToolDependencies) => ({
   search_sessions: buildSearchSessionsTool(deps),
+  ask_meetings: buildAskMeetingsTool(deps),
 });
 
 export type Tools = {
@@ -55,6 +102,10 @@ export type Tools = {
       }>;
     };
   };
+  ask_meetings: {
+    input: { question: string; since?: number; until?: number };
+    output: { text: string; citations: Citation[] };
+  };
 };
 
 export type ToolPartType = `tool-${keyof Tools}`;
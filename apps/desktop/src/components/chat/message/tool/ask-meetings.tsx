@@ -0,0 +1,96 @@
+import { MessagesSquareIcon } from "lucide-react";
+import { useCallback } from "react";
+
+import type { Citation } from "@hypr/plugin-template";
+
+import * as main from "../../../../store/tinybase/store/main";
+import { useTabs } from "../../../../store/zustand/tabs";
+import { Disclosure } from "../shared";
+import { ToolRenderer } from "../types";
+
+type Renderer = ToolRenderer<"tool-ask_meetings">;
+type Part = Parameters<Renderer>[0]["part"];
+
+export const ToolAskMeetings: Renderer = ({ part }) => {
+  const disabled =
+    part.state === "input-streaming" || part.state === "input-available";
+
+  return (
+    <Disclosure
+      icon={<MessagesSquareIcon className="w-3 h-3" />}
+      title={getTitle(part)}
+      disabled={disabled}
+    >
+      <RenderContent part={part} />
+    </Disclosure>
+  );
+};
+
+const getTitle = (part: Part) => {
+  if (part.state === "input-streaming") {
+    return "Preparing question...";
+  }
+  if (part.state === "input-available") {
+    return `Asking meetings: ${part.input.question}`;
+  }
+  if (part.state === "output-available") {
+    return `Asked meetings: ${part.input.question}`;
+  }
+  if (part.state === "output-error") {
+    return part.input
+      ? `Question failed: ${part.input.question}`
+      : "Question failed";
+  }
+  return "Ask meetings";
+};
+
+function RenderContent({ part }: { part: Part }) {
+  if (part.state === "output-available" && part.output) {
+    const { text, citations } = part.output;
+
+    return (
+      <div className="flex flex-col gap-2 text-xs">
+        <p className="whitespace-pre-wrap">{text}</p>
+        {citations.length > 0 && (
+          <div className="flex flex-col gap-1">
+            {citations.map((citation) => (
+              <RenderCitation
+                key={`${citation.marker}-${citation.sessionId}`}
+                citation={citation}
+              />
+            ))}
+          </div>
+        )}
+      </div>
+    );
+  }
+
+  if (part.state === "output-error") {
+    return <div className="text-sm text-red-500">Error: {part.errorText}</div>;
+  }
+
+  return null;
+}
+
+function RenderCitation({ citation }: { citation: Citation }) {
+  const title = main.UI.useCell(
+    "sessions",
+    citation.sessionId,
+    "title",
+    main.STORE_ID,
+  );
+  const openNew = useTabs((state) => state.openNew);
+
+  const handleClick = useCallback(() => {
+    openNew({ type: "sessions", id: citation.sessionId });
+  }, [openNew, citation.sessionId]);
+
+  return (
+    <button
+      className="text-left text-muted-foreground hover:text-neutral-800 truncate"
+      onClick={handleClick}
+    >
+      [{citation.marker}] {title || "Untitled"}
+    </button>
+  );
+}
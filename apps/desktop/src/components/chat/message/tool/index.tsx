@@ -1,5 +1,6 @@
 import type { ToolPartType } from "../../../../chat/tools";
 import type { Part } from "../types";
+import { ToolAskMeetings } from "./ask-meetings";
 import { ToolSearchSessions } from "./search";
 
 export function Tool({
@@ -10,5 +11,8 @@ export function Tool({
   if (part.type === "tool-search_sessions") {
     return <ToolSearchSessions part={part} />;
   }
+  if (part.type === "tool-ask_meetings") {
+    return <ToolAskMeetings part={part} />;
+  }
   return <pre>{JSON.stringify(part)}</pre>;
 }
@@ -0,0 +1,96 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { LockIcon } from "lucide-react";
+import { type FormEvent, type ReactNode, useState } from "react";
+
+import { commands as fsSyncCommands } from "@hypr/plugin-fs-sync";
+import { Button } from "@hypr/ui/components/ui/button";
+import { Input } from "@hypr/ui/components/ui/input";
+
+export const ENCRYPTION_STATUS_QUERY_KEY = ["encryption-status"];
+
+export function useEncryptionStatus() {
+  return useQuery({
+    queryKey: ENCRYPTION_STATUS_QUERY_KEY,
+    queryFn: async () => {
+      const result = await fsSyncCommands.encryptionStatus();
+      if (result.status === "error") {
+        throw new Error(result.error);
+      }
+      return result.data;
+    },
+  });
+}
+
+// Notes can't be read while a passphrase-derived key is locked, so the
+// stores aren't mounted until it's unlocked.
+export function EncryptionGate({ children }: { children: ReactNode }) {
+  const { data: status, isPending } = useEncryptionStatus();
+
+  if (isPending) {
+    return null;
+  }
+  if (status?.enabled && !status.unlocked) {
+    return <UnlockScreen />;
+  }
+  return <>{children}</>;
+}
+
+function UnlockScreen() {
+  const queryClient = useQueryClient();
+  const [passphrase, setPassphrase] = useState("");
+
+  const unlock = useMutation({
+    mutationFn: async (passphrase: string) => {
+      const result = await fsSyncCommands.unlockEncryption(passphrase);
+      if (result.status === "error") {
+        throw new Error(
+          result.error === "encryption_wrong_passphrase"
+            ? "Wrong passphrase"
+            : result.error,
+        );
+      }
+    },
+    onSuccess: () => {
+      void queryClient.invalidateQueries({
+        queryKey: ENCRYPTION_STATUS_QUERY_KEY,
+      });
+    },
+  });
+
+  const handleSubmit = (e: FormEvent) => {
+    e.preventDefault();
+    if (passphrase) {
+      unlock.mutate(passphrase);
+    }
+  };
+
+  return (
+    <div className="flex h-screen w-screen items-center justify-center bg-neutral-50">
+      <form
+        onSubmit={handleSubmit}
+        className="flex w-80 flex-col gap-3 rounded-xl border border-neutral-200 bg-white p-6"
+      >
+        <div className="flex items-center gap-2">
+          <LockIcon size={16} />
+          <h2 className="font-semibold">Your data is locked</h2>
+        </div>
+        <p className="text-sm text-neutral-500">
+          Enter the passphrase you chose when turning on encryption.
+        </p>
+        <Input
+          type="password"
+          autoFocus
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          placeholder="Passphrase"
+        />
+        {unlock.isError && (
+          <span className="text-xs text-red-600">{unlock.error.message}</span>
+        )}
+        <Button type="submit" disabled={!passphrase || unlock.isPending}>
+          Unlock
+        </Button>
+      </form>
+    </div>
+  );
+}
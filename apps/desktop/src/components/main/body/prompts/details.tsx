@@ -63,6 +63,7 @@ function PromptDetails({ selectedTask }: { selectedTask: TaskType }) {
               participants: [],
               template: null,
               transcripts: [],
+              partialSummaries: [],
             },
           }
         : { titleUser: { enhancedNote: "" } };
@@ -125,12 +125,13 @@ export function TabContentNote({
   const { data: audioUrl } = useQuery({
     enabled: listenerStatus === "inactive",
     queryKey: ["audio", tab.id, "url"],
-    queryFn: () => fsSyncCommands.audioPath(tab.id),
+    queryFn: () => fsSyncCommands.audioExist(tab.id),
     select: (result) => {
-      if (result.status === "error") {
+      if (result.status === "error" || !result.data) {
         return null;
       }
-      return convertFileSrc(result.data);
+      // Served by fs-sync, which decrypts encrypted recordings in memory.
+      return convertFileSrc(tab.id, "hypr-audio");
     },
   });
 
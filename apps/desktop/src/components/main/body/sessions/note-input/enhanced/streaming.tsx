@@ -18,7 +18,9 @@ export function StreamingView({ enhancedNoteId }: { enhancedNoteId: string }) {
 
   let statusText: string | null = null;
   if (isGenerating && !hasContent) {
-    if (step?.type === "analyzing") {
+    if (step?.type === "summarizing") {
+      statusText = `Reading transcript (part ${step.current} of ${step.total})...`;
+    } else if (step?.type === "analyzing") {
       statusText = "Analyzing structure...";
     } else if (step?.type === "generating") {
       statusText = "Generating...";
@@ -11,17 +11,15 @@ type SectionDraft = TemplateSection & { key: string };
 
 function createDraft(section: TemplateSection, key?: string): SectionDraft {
   return {
+    ...section,
     key: key ?? crypto.randomUUID(),
-    title: section.title,
-    description: section.description,
   };
 }
 
-function toSection(draft: SectionDraft): TemplateSection {
-  return {
-    title: draft.title,
-    description: draft.description,
-  };
+// Instructions, format and condition aren't edited here, but must survive
+// edits to the title and description.
+function toSection({ key: _key, ...section }: SectionDraft): TemplateSection {
+  return section;
 }
 
 function sameSection(draft: SectionDraft, section?: TemplateSection) {
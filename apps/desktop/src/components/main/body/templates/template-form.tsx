@@ -8,6 +8,7 @@ import * as main from "../../../../store/tinybase/store/main";
 import { DangerZone } from "../resource-list";
 import { RelatedSessions } from "./related-sessions";
 import { SectionsList } from "./sections-editor";
+import { TemplateIssues } from "./template-issues";
 
 function normalizeTemplatePayload(template: unknown): Template {
   const record = (
@@ -25,6 +26,7 @@ function normalizeTemplatePayload(template: unknown): Template {
     sections = record.sections.map((s: unknown) => {
       const sec = s as Record<string, unknown>;
       return {
+        ...(sec as Partial<TemplateSection>),
         title: typeof sec.title === "string" ? sec.title : "",
         description: typeof sec.description === "string" ? sec.description : "",
       };
@@ -158,6 +160,9 @@ export function TemplateForm({
               />
             )}
           </form.Field>
+          <form.Subscribe selector={(state) => state.values}>
+            {(values) => <TemplateIssues {...values} />}
+          </form.Subscribe>
         </div>
 
         <div className="p-6 border-b border-neutral-200">
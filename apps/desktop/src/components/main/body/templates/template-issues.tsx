@@ -0,0 +1,60 @@
+import { useQuery } from "@tanstack/react-query";
+import { AlertTriangle } from "lucide-react";
+
+import { commands as templateCommands } from "@hypr/plugin-template";
+import type { TemplateSection } from "@hypr/store";
+
+// Checked while editing, so a typo in a variable shows up here rather than
+// as a confusing note after the meeting.
+export function TemplateIssues({
+  title,
+  description,
+  sections,
+}: {
+  title: string;
+  description: string;
+  sections: TemplateSection[];
+}) {
+  const { data: issues = [] } = useQuery({
+    queryKey: ["template-issues", title, description, sections],
+    queryFn: async () => {
+      const result = await templateCommands.validateTemplate({
+        title,
+        description: description || null,
+        sections: sections.map((section) => ({
+          title: section.title,
+          description: section.description || null,
+          instructions: section.instructions ?? null,
+          format: section.format ?? null,
+          condition: section.condition ?? null,
+        })),
+      });
+      if (result.status === "error") {
+        throw new Error(result.error);
+      }
+      return result.data;
+    },
+  });
+
+  if (issues.length === 0) {
+    return null;
+  }
+
+  return (
+    <ul className="mt-3 flex flex-col gap-1">
+      {issues.map((issue, index) => (
+        <li
+          key={index}
+          className="flex items-start gap-2 text-xs text-amber-700"
+        >
+          <AlertTriangle className="size-3.5 shrink-0 mt-px" />
+          <span>
+            {issue.section !== null &&
+              `${sections[issue.section]?.title || `Section ${issue.section + 1}`}: `}
+            {issue.message}
+          </span>
+        </li>
+      ))}
+    </ul>
+  );
+}
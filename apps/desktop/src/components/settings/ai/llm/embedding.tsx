@@ -0,0 +1,52 @@
+import { open as selectFile } from "@tauri-apps/plugin-dialog";
+import { useCallback } from "react";
+
+import { Button } from "@hypr/ui/components/ui/button";
+
+import { useConfigValues } from "../../../../config/use-config";
+import * as settings from "../../../../store/tinybase/store/settings";
+
+export function SelectEmbeddingModel() {
+  const { embedding_model_path } = useConfigValues([
+    "embedding_model_path",
+  ] as const);
+
+  const handleSetPath = settings.UI.useSetValueCallback(
+    "embedding_model_path",
+    (path: string) => path,
+    [],
+    settings.STORE_ID,
+  );
+
+  const handleChoose = useCallback(async () => {
+    const selection = await selectFile({
+      title: "Select an embedding model",
+      multiple: false,
+      directory: false,
+      filters: [{ name: "GGUF", extensions: ["gguf"] }],
+    });
+    if (typeof selection === "string") {
+      handleSetPath(selection);
+    }
+  }, [handleSetPath]);
+
+  return (
+    <div className="flex flex-col gap-3">
+      <h3 className="text-md font-semibold">Semantic search</h3>
+      <div className="flex flex-col gap-2 p-4 rounded-xl border border-neutral-200 bg-neutral-50">
+        <div className="flex flex-row items-center gap-4">
+          <span className="flex-1 min-w-0 truncate text-sm text-neutral-600">
+            {embedding_model_path || "No embedding model selected"}
+          </span>
+          <Button variant="outline" size="sm" onClick={handleChoose}>
+            Choose file
+          </Button>
+        </div>
+        <span className="text-xs text-neutral-500">
+          A GGUF embedding model lets search and chat match meetings by meaning,
+          not only by keywords.
+        </span>
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,6 @@
 import { ConfigureProviders } from "./configure";
 import { LlmSettingsProvider } from "./context";
+import { SelectEmbeddingModel } from "./embedding";
 import { SelectProviderAndModel } from "./select";
 
 export function LLM() {
@@ -8,6 +9,7 @@ export function LLM() {
       <div className="space-y-6 mt-4">
         <SelectProviderAndModel />
         <ConfigureProviders />
+        <SelectEmbeddingModel />
       </div>
     </LlmSettingsProvider>
   );
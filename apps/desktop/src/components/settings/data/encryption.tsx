@@ -0,0 +1,110 @@
+import { useMutation, useQueryClient } from "@tanstack/react-query";
+import { LockIcon, XCircleIcon } from "lucide-react";
+import { useState } from "react";
+
+import { commands as fsSyncCommands } from "@hypr/plugin-fs-sync";
+import { Button } from "@hypr/ui/components/ui/button";
+import { Input } from "@hypr/ui/components/ui/input";
+
+import {
+  ENCRYPTION_STATUS_QUERY_KEY,
+  useEncryptionStatus,
+} from "../../encryption-gate";
+
+export function Encryption() {
+  const queryClient = useQueryClient();
+  const { data: status } = useEncryptionStatus();
+  const [passphrase, setPassphrase] = useState("");
+  const [confirmation, setConfirmation] = useState("");
+
+  const enable = useMutation({
+    mutationFn: async (passphrase: string | null) => {
+      const result = await fsSyncCommands.enableEncryption(passphrase);
+      if (result.status === "error") {
+        throw new Error(result.error);
+      }
+      return result.data;
+    },
+    onSuccess: () => {
+      setPassphrase("");
+      setConfirmation("");
+      void queryClient.invalidateQueries({
+        queryKey: ENCRYPTION_STATUS_QUERY_KEY,
+      });
+    },
+  });
+
+  if (!status) {
+    return null;
+  }
+
+  if (status.enabled) {
+    return (
+      <div className="flex items-center gap-2 p-4 rounded-xl border border-neutral-200 bg-neutral-50 text-sm">
+        <LockIcon size={14} />
+        <span>
+          Notes, recordings and the database are encrypted with{" "}
+          {status.keySource === "passphrase"
+            ? "your passphrase"
+            : "a key kept in the system keychain"}
+          .
+        </span>
+      </div>
+    );
+  }
+
+  const passphraseValid = passphrase.length > 0 && passphrase === confirmation;
+
+  return (
+    <div className="flex flex-col gap-3 p-4 rounded-xl border border-neutral-200 bg-neutral-50">
+      <p className="text-sm text-neutral-600">
+        Encrypt notes and recordings on this device. The database follows on
+        the next start. A passphrase has to be entered on every start and
+        can't be recovered if forgotten.
+      </p>
+
+      <div className="flex flex-row items-center gap-2">
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={enable.isPending}
+          onClick={() => enable.mutate(null)}
+        >
+          Use system keychain
+        </Button>
+      </div>
+
+      <div className="flex flex-row items-center gap-2">
+        <Input
+          type="password"
+          value={passphrase}
+          onChange={(e) => setPassphrase(e.target.value)}
+          placeholder="Passphrase"
+          className="bg-white"
+        />
+        <Input
+          type="password"
+          value={confirmation}
+          onChange={(e) => setConfirmation(e.target.value)}
+          placeholder="Confirm passphrase"
+          className="bg-white"
+        />
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={!passphraseValid || enable.isPending}
+          onClick={() => enable.mutate(passphrase)}
+        >
+          Use passphrase
+        </Button>
+      </div>
+
+      {enable.isError && (
+        <div className="flex items-center gap-2 text-xs text-red-600">
+          <XCircleIcon size={14} />
+          <span>Encryption failed: {enable.error.message}</span>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -14,8 +14,10 @@ import { importFromJson } from "../../../store/tinybase/store/importer";
 import * as main from "../../../store/tinybase/store/main";
 import { save } from "../../../store/tinybase/store/save";
 import { StyledStreamdown } from "../ai/shared";
+import { Encryption } from "./encryption";
 import { ImportPreview } from "./import-preview";
 import { SourceItem } from "./source-item";
+import { Trash } from "./trash";
 
 type DryRunResult = {
   source: ImportSourceKind;
@@ -150,6 +152,16 @@ export function Data() {
           </div>
         )}
       </div>
+
+      <div className="mt-6 flex flex-col gap-3">
+        <h3 className="text-md font-semibold">Encryption</h3>
+        <Encryption />
+      </div>
+
+      <div className="mt-6 flex flex-col gap-3">
+        <h3 className="text-md font-semibold">Recently deleted</h3>
+        <Trash />
+      </div>
     </div>
   );
 }
@@ -0,0 +1,86 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { XCircleIcon } from "lucide-react";
+
+import { commands as fsSyncCommands } from "@hypr/plugin-fs-sync";
+import { Button } from "@hypr/ui/components/ui/button";
+
+import { sessionOps } from "../../../store/tinybase/persister/session/ops";
+
+const TRASH_QUERY_KEY = ["fs-sync", "trash"];
+
+export function Trash() {
+  const queryClient = useQueryClient();
+
+  const { data: trashed } = useQuery({
+    queryKey: TRASH_QUERY_KEY,
+    queryFn: async () => {
+      const result = await fsSyncCommands.listTrash();
+      if (result.status === "error") {
+        throw new Error(result.error);
+      }
+      return result.data;
+    },
+  });
+
+  const restore = useMutation({
+    mutationFn: async (sessionId: string) => {
+      const result = await sessionOps.restoreSession(sessionId);
+      if (result.status === "error") {
+        throw new Error(result.error);
+      }
+    },
+    onSettled: () => {
+      void queryClient.invalidateQueries({ queryKey: TRASH_QUERY_KEY });
+    },
+  });
+
+  if (!trashed) {
+    return null;
+  }
+
+  return (
+    <div className="flex flex-col gap-3 p-4 rounded-xl border border-neutral-200 bg-neutral-50">
+      <p className="text-sm text-neutral-600">
+        Deleted notes are kept here for 30 days before they're removed for
+        good.
+      </p>
+
+      {trashed.length === 0 ? (
+        <p className="text-sm text-neutral-400">Nothing here.</p>
+      ) : (
+        <div className="flex flex-col gap-2">
+          {trashed.map((session) => (
+            <div
+              key={session.sessionId}
+              className="flex flex-row items-center justify-between gap-2 text-sm"
+            >
+              <div className="flex flex-col min-w-0">
+                <span className="truncate">
+                  {session.title || "Untitled"}
+                </span>
+                <span className="text-xs text-neutral-500">
+                  Deleted {new Date(session.trashedAt).toLocaleDateString()}
+                </span>
+              </div>
+              <Button
+                variant="outline"
+                size="sm"
+                disabled={restore.isPending}
+                onClick={() => restore.mutate(session.sessionId)}
+              >
+                Restore
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {restore.isError && (
+        <div className="flex items-center gap-2 text-xs text-red-600">
+          <XCircleIcon size={14} />
+          <span>Restore failed: {restore.error.message}</span>
+        </div>
+      )}
+    </div>
+  );
+}
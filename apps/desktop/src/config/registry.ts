@@ -5,6 +5,7 @@ import {
   commands as localSttCommands,
   type SupportedSttModel,
 } from "@hypr/plugin-local-stt";
+import { commands as tantivyCommands } from "@hypr/plugin-tantivy";
 
 export type ConfigKey =
   | "autostart"
@@ -20,7 +21,8 @@ export type ConfigKey =
   | "save_recordings"
   | "telemetry_consent"
   | "current_llm_provider"
-  | "current_llm_model";
+  | "current_llm_model"
+  | "embedding_model_path";
 
 type ConfigValueType<K extends ConfigKey> =
   (typeof CONFIG_REGISTRY)[K]["default"];
@@ -148,4 +150,14 @@ export const CONFIG_REGISTRY = {
     key: "current_llm_model",
     default: undefined,
   },
+
+  embedding_model_path: {
+    key: "embedding_model_path",
+    default: undefined,
+    sideEffect: async (value: string | undefined, _) => {
+      if (value) {
+        await tantivyCommands.setEmbeddingModel(value);
+      }
+    },
+  },
 } satisfies Record<ConfigKey, ConfigDefinition>;
@@ -9,6 +9,7 @@ import {
   useState,
 } from "react";
 
+import { syncMeetingsIndex } from "../../../chat/meetings";
 import { type Store as MainStore } from "../../../store/tinybase/store/main";
 import { buildOramaFilters } from "./filters";
 import { indexHumans, indexOrganizations, indexSessions } from "./indexing";
@@ -92,8 +93,10 @@ export function SearchEngineProvider({
     };
 
     void initializeIndex();
+    const stopMeetingsSync = syncMeetingsIndex(store);
 
     return () => {
+      stopMeetingsSync();
       listenerIds.current.forEach((id) => {
         store.delListener(id);
       });
@@ -1,12 +1,31 @@
+import { getIdentifier } from "@tauri-apps/api/app";
 import { usePrevious } from "@uidotdev/usehooks";
+import {
+  generateObject,
+  type JSONSchema7,
+  jsonSchema,
+  type LanguageModel,
+} from "ai";
 import { useCallback, useEffect, useRef, useState } from "react";
 
 import { commands as analyticsCommands } from "@hypr/plugin-analytics";
+import { commands as db2Commands } from "@hypr/plugin-db2";
+import { commands as hooksCommands } from "@hypr/plugin-hooks";
+import { commands as settingsCommands } from "@hypr/plugin-settings";
+import {
+  type ActionItems,
+  commands as templateCommands,
+} from "@hypr/plugin-template";
 import { md2json } from "@hypr/tiptap/shared";
 
 import { useListener } from "../contexts/listener";
+import { buildSessionPath } from "../store/tinybase/persister/shared/paths";
 import * as main from "../store/tinybase/store/main";
+import * as settings from "../store/tinybase/store/settings";
 import { createTaskId } from "../store/zustand/ai-task/task-configs";
+import {
+  getActionItemsArgs,
+} from "../store/zustand/ai-task/task-configs/enhance-transform";
 import { useTabs } from "../store/zustand/tabs";
 import type { Tab } from "../store/zustand/tabs/schema";
 import { useAITaskTask } from "./useAITaskTask";
@@ -52,6 +71,7 @@ export function useAutoEnhance(tab: Extract<Tab, { type: "sessions" }>) {
   tabRef.current = tab;
 
   const store = main.UI.useStore(main.STORE_ID);
+  const settingsStore = settings.UI.useStore(settings.STORE_ID);
 
   const titleTaskId = createTaskId(sessionId, "title");
   const handleTitleSuccess = useCallback(
@@ -98,9 +118,24 @@ export function useAutoEnhance(tab: Extract<Tab, { type: "sessions" }>) {
         } catch (error) {
           console.error("Failed to convert markdown to JSON:", error);
         }
+
+        if (settingsStore && model) {
+          extractActionItems(sessionId, model, store, settingsStore).catch(
+            (error) => {
+              console.error("[action-items] extraction failed:", error);
+            },
+          );
+        }
       }
     },
-    [autoEnhancedNoteId, store, sessionId, model, titleTask.start],
+    [
+      autoEnhancedNoteId,
+      store,
+      settingsStore,
+      sessionId,
+      model,
+      titleTask.start,
+    ],
   );
 
   const enhanceTask = useAITaskTask(enhanceTaskId, "enhance", {
@@ -193,3 +228,71 @@ export function useAutoEnhance(tab: Extract<Tab, { type: "sessions" }>) {
 
   return { skipReason };
 }
+
+// Runs on whichever model the user picked, with the schema as structured
+// output. Grammar-constrained decoding only applies to the bundled model,
+// which local-llm's own `extract_action_items` covers.
+async function extractActionItems(
+  sessionId: string,
+  model: LanguageModel,
+  store: main.Store,
+  settingsStore: settings.Store,
+) {
+  const userId = store.getValue("user_id");
+  if (typeof userId !== "string" || !userId) {
+    throw new Error("no user to store action items for");
+  }
+
+  const { system, user, people } = getActionItemsArgs(
+    sessionId,
+    store,
+    settingsStore,
+  );
+
+  const [systemPrompt, userPrompt, schema] = await Promise.all([
+    templateCommands.render({ actionItemsSystem: system }),
+    templateCommands.render({ actionItemsUser: user }),
+    templateCommands.actionItemsSchema(user.participants),
+  ]);
+  if (systemPrompt.status === "error") throw new Error(systemPrompt.error);
+  if (userPrompt.status === "error") throw new Error(userPrompt.error);
+  if (schema.status === "error") throw new Error(schema.error);
+
+  const { object } = await generateObject({
+    model,
+    temperature: 0,
+    schema: jsonSchema<ActionItems>(schema.data as JSONSchema7),
+    system: systemPrompt.data,
+    prompt: userPrompt.data,
+  });
+
+  const result = await db2Commands.storeActionItems(
+    sessionId,
+    userId,
+    people,
+    user,
+    object,
+  );
+  if (result.status === "error") {
+    throw new Error(result.error);
+  }
+
+  const [dataDirPath, bundleId] = await Promise.all([
+    settingsCommands.settingsBase().then((r) => {
+      if (r.status === "error") throw new Error(r.error);
+      return r.data;
+    }),
+    getIdentifier().catch(() => "com.hyprnote.stable"),
+  ]);
+
+  await hooksCommands.runEventHooks({
+    afterActionItemsExtracted: {
+      args: {
+        resource_dir: buildSessionPath(dataDirPath, sessionId),
+        app_hyprnote: bundleId,
+        session_id: sessionId,
+        action_items: JSON.stringify(result.data),
+      },
+    },
+  });
+}
@@ -14,6 +14,7 @@ import { init as initWindowsPlugin } from "@hypr/plugin-windows";
 import "@hypr/ui/globals.css";
 
 import { ErrorComponent, NotFoundComponent } from "./components/control";
+import { EncryptionGate } from "./components/encryption-gate";
 import { EventListeners } from "./components/event-listeners";
 import { TaskManager } from "./components/task-manager";
 import { createToolRegistry } from "./contexts/tool-registry/core";
@@ -110,13 +111,15 @@ function AppWithTiny() {
   return (
     <QueryClientProvider client={queryClient}>
       <TinyTickProvider manager={manager}>
-        <TinyBaseProvider>
-          <StoreComponent />
-          <SettingsStoreComponent />
-          <App />
-          {!isIframeContext && <TaskManager />}
-          {!isIframeContext && <EventListeners />}
-        </TinyBaseProvider>
+        <EncryptionGate>
+          <TinyBaseProvider>
+            <StoreComponent />
+            <SettingsStoreComponent />
+            <App />
+            {!isIframeContext && <TaskManager />}
+            {!isIframeContext && <EventListeners />}
+          </TinyBaseProvider>
+        </EncryptionGate>
       </TinyTickProvider>
     </QueryClientProvider>
   );
@@ -17,6 +17,8 @@ import { ShellProvider } from "../../../contexts/shell";
 import { useRegisterTools } from "../../../contexts/tool";
 import { ToolRegistryProvider } from "../../../contexts/tool";
 import { useDeeplinkHandler } from "../../../hooks/useDeeplinkHandler";
+import { useLanguageModel } from "../../../hooks/useLLMConnection";
+import * as main from "../../../store/tinybase/store/main";
 import { useTabs } from "../../../store/zustand/tabs";
 
 export const Route = createFileRoute("/app/main/_layout")({
@@ -106,8 +108,14 @@ function Component() {
 
 function ToolRegistration() {
   const { search } = useSearchEngine();
+  const model = useLanguageModel();
+  const language = main.UI.useValue("ai_language", main.STORE_ID) ?? null;
 
-  useRegisterTools("chat", () => buildChatTools({ search }), [search]);
+  useRegisterTools(
+    "chat",
+    () => buildChatTools({ search, model, language }),
+    [search, model, language],
+  );
 
   return null;
 }
@@ -1,6 +1,5 @@
 import type { MergeableStore, OptionalSchemas } from "tinybase/with-schemas";
 
-import { commands as fs2Commands } from "@hypr/plugin-fs2";
 import {
   commands as fsSyncCommands,
   type JsonValue,
@@ -109,9 +108,10 @@ async function loadSingleEntity<
   const dataDir = await getDataDir();
   const filePath = buildEntityFilePath(dataDir, dirName, entityId);
 
-  const readResult = await fs2Commands.readTextFile(filePath);
-  if (readResult.status === "error") {
-    if (isFileNotFoundError(readResult.error)) {
+  // Read through fs-sync, which decrypts notes that are encrypted at rest.
+  const parseResult = await fsSyncCommands.readDocument(filePath);
+  if (parseResult.status === "error") {
+    if (isFileNotFoundError(parseResult.error)) {
       const loaded = { [tableName]: {} } as LoadedData<TStorage>;
       const result = deletionMarker.markForEntity(loaded, entityId);
 
@@ -122,12 +122,6 @@ async function loadSingleEntity<
     return undefined;
   }
 
-  const parseResult = await fsSyncCommands.deserialize(readResult.data);
-
-  if (parseResult.status === "error") {
-    return undefined;
-  }
-
   const entity = fromFrontmatter(
     parseResult.data.frontmatter as Record<string, unknown>,
     parseResult.data.content.trim(),
@@ -72,7 +72,28 @@ export async function renameFolder(
   return { status: "ok" };
 }
 
+export async function restoreSession(
+  sessionId: string,
+): Promise<{ status: "ok" } | { status: "error"; error: string }> {
+  const { reloadSessions } = getConfig();
+
+  const result = await fsSyncCommands.restoreSessionFolder(sessionId);
+
+  if (result.status === "error") {
+    console.error("[SessionOps] restoreSessionFolder failed:", result.error);
+    return { status: "error", error: result.error };
+  }
+
+  if (result.data === null) {
+    return { status: "error", error: "Session is not in the trash" };
+  }
+
+  await reloadSessions();
+  return { status: "ok" };
+}
+
 export const sessionOps = {
   moveSessionToFolder,
   renameFolder,
+  restoreSession,
 };
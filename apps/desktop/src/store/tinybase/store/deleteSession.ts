@@ -1,7 +1,5 @@
 import { useCallback } from "react";
 
-import { commands as fsSyncCommands } from "@hypr/plugin-fs-sync";
-
 import * as main from "./main";
 
 type Store = NonNullable<ReturnType<typeof main.UI.useStore>>;
@@ -25,8 +23,8 @@ export async function deleteSessionCascade(
   indexes: ReturnType<typeof main.UI.useIndexes>,
   sessionId: string,
 ): Promise<void> {
-  await fsSyncCommands.audioDelete(sessionId);
-
+  // The session folder, recording included, is moved to the trash once the
+  // rows are gone, and can be restored from there until it's purged.
   if (!indexes) {
     store.delRow("sessions", sessionId);
     return;
@@ -65,6 +65,10 @@ export const SETTINGS_MAPPING = {
       type: "string",
       path: ["ai", "current_stt_model"],
     },
+    embedding_model_path: {
+      type: "string",
+      path: ["ai", "embedding_model_path"],
+    },
   },
   tables: {
     ai_providers: {
@@ -1,13 +1,24 @@
+import type { Human } from "@hypr/plugin-db2";
 import type {
+  ActionItemsSystem,
+  ActionItemsUser,
   EnhanceTemplate,
   Participant,
+  PreviousMeeting,
   Segment,
+  SectionCondition,
+  SectionFormat,
   Session,
   TemplateSection,
+  TemplateVariables,
   Transcript,
 } from "@hypr/plugin-template";
 
 import type { TaskArgsMap, TaskArgsMapTransformed, TaskConfig } from ".";
+import {
+  collectEnhancedNotesContent,
+  extractPlainText,
+} from "../../../../contexts/search/engine/utils";
 import {
   buildSegments,
   type RuntimeSpeakerHint,
@@ -78,9 +89,76 @@ async function transformArgs(
       sessionContext.segments,
       sessionContext.transcriptsMeta,
     ),
+    partialSummaries: [],
+    variables: getTemplateVariables(sessionId, store),
   };
 }
 
+// Action items are extracted from the same context the note is enhanced
+// from. The people come along as stored rows, the user included, so that
+// owners can be matched to them.
+export function getActionItemsArgs(
+  sessionId: string,
+  store: MainStore,
+  settingsStore: SettingsStore,
+): { system: ActionItemsSystem; user: ActionItemsUser; people: Human[] } {
+  const sessionContext = getSessionContext(sessionId, store);
+
+  return {
+    system: { language: getLanguage(settingsStore) },
+    user: {
+      session: sessionContext.session,
+      participants: sessionContext.participants,
+      transcripts: formatTranscripts(
+        sessionContext.rawMd,
+        sessionContext.segments,
+        sessionContext.transcriptsMeta,
+      ),
+    },
+    people: getPeople(sessionId, store),
+  };
+}
+
+function getPeople(sessionId: string, store: MainStore): Human[] {
+  const userId = store.getValue("user_id");
+  const humanIds = new Set<string>();
+  if (typeof userId === "string" && userId) {
+    humanIds.add(userId);
+  }
+
+  store.forEachRow("mapping_session_participant", (mappingId, _forEachCell) => {
+    const humanId = getOptionalStringCell(
+      store,
+      "mapping_session_participant",
+      mappingId,
+      "human_id",
+    );
+    if (
+      humanId &&
+      getOptionalStringCell(
+        store,
+        "mapping_session_participant",
+        mappingId,
+        "session_id",
+      ) === sessionId
+    ) {
+      humanIds.add(humanId);
+    }
+  });
+
+  return [...humanIds].map((id) => ({
+    id,
+    organization_id:
+      getOptionalStringCell(store, "humans", id, "org_id") ?? null,
+    is_user: id === userId,
+    full_name: getOptionalStringCell(store, "humans", id, "name") ?? null,
+    email: getOptionalStringCell(store, "humans", id, "email") ?? null,
+    job_title: getOptionalStringCell(store, "humans", id, "job_title") ?? null,
+    linkedin_username:
+      getOptionalStringCell(store, "humans", id, "linkedin_username") ?? null,
+  }));
+}
+
 function formatTranscripts(
   rawMd: string,
   segments: SegmentPayload[],
@@ -150,14 +228,18 @@ function getSessionData(sessionId: string, store: MainStore): Session {
 
   if (eventId) {
     const eventTitle = getStringCell(store, "events", eventId, "title");
+    const startedAt =
+      getOptionalStringCell(store, "events", eventId, "started_at") ?? null;
+    const endedAt =
+      getOptionalStringCell(store, "events", eventId, "ended_at") ?? null;
     return {
       title: eventTitle || rawTitle || null,
-      startedAt:
-        getOptionalStringCell(store, "events", eventId, "started_at") ?? null,
-      endedAt:
-        getOptionalStringCell(store, "events", eventId, "ended_at") ?? null,
+      startedAt,
+      endedAt,
       event: {
-        name: eventTitle || rawTitle || "",
+        name: eventTitle,
+        startedAt,
+        endedAt,
       },
     };
   }
@@ -255,7 +337,17 @@ function parseTemplateSections(raw: unknown): TemplateSection[] {
 
         const description =
           typeof record.description === "string" ? record.description : null;
-        return { title, description };
+        const instructions =
+          typeof record.instructions === "string" && record.instructions.trim()
+            ? record.instructions
+            : null;
+        return {
+          title,
+          description,
+          instructions,
+          format: (record.format as SectionFormat | undefined) ?? null,
+          condition: (record.condition as SectionCondition | undefined) ?? null,
+        };
       }
 
       return null;
@@ -263,6 +355,149 @@ function parseTemplateSections(raw: unknown): TemplateSection[] {
     .filter((section): section is TemplateSection => section !== null);
 }
 
+function getTemplateVariables(
+  sessionId: string,
+  store: MainStore,
+): TemplateVariables {
+  return {
+    externalParticipants: getExternalParticipants(sessionId, store),
+    previousMeeting: getPreviousMeeting(sessionId, store),
+  };
+}
+
+function emailDomain(email: string | undefined) {
+  const at = email?.lastIndexOf("@") ?? -1;
+  return at >= 0 ? email!.slice(at + 1).toLowerCase() : undefined;
+}
+
+// Participants from another organization than the user, by email domain when
+// both are known, or else by the organization they're filed under.
+function getExternalParticipants(
+  sessionId: string,
+  store: MainStore,
+): string[] {
+  const userId = store.getValue("user_id");
+  if (typeof userId !== "string" || !userId) {
+    return [];
+  }
+
+  const userDomain = emailDomain(
+    getOptionalStringCell(store, "humans", userId, "email"),
+  );
+  const userOrgId = getOptionalStringCell(store, "humans", userId, "org_id");
+
+  const external: string[] = [];
+  store.forEachRow("mapping_session_participant", (mappingId, _forEachCell) => {
+    const mappingSessionId = getOptionalStringCell(
+      store,
+      "mapping_session_participant",
+      mappingId,
+      "session_id",
+    );
+    const humanId = getOptionalStringCell(
+      store,
+      "mapping_session_participant",
+      mappingId,
+      "human_id",
+    );
+    if (mappingSessionId !== sessionId || !humanId || humanId === userId) {
+      return;
+    }
+
+    const name = getStringCell(store, "humans", humanId, "name");
+    const domain = emailDomain(
+      getOptionalStringCell(store, "humans", humanId, "email"),
+    );
+    const orgId = getOptionalStringCell(
+      store,
+      "humans",
+      humanId,
+      "org_id",
+    );
+
+    const isExternal =
+      userDomain && domain
+        ? domain !== userDomain
+        : Boolean(userOrgId && orgId && orgId !== userOrgId);
+    if (name && isExternal) {
+      external.push(name);
+    }
+  });
+
+  return external;
+}
+
+// The latest earlier occurrence of the same recurring event that has notes.
+function getPreviousMeeting(
+  sessionId: string,
+  store: MainStore,
+): PreviousMeeting | null {
+  const eventId = getOptionalStringCell(
+    store,
+    "sessions",
+    sessionId,
+    "event_id",
+  );
+  const seriesId = eventId
+    ? getOptionalStringCell(store, "events", eventId, "recurrence_series_id")
+    : undefined;
+  if (!eventId || !seriesId) {
+    return null;
+  }
+
+  const startedAt = getStringCell(store, "events", eventId, "started_at");
+  let previous: PreviousMeeting | null = null;
+  let previousStartedAt = "";
+
+  store.forEachRow("sessions", (otherId, _forEachCell) => {
+    const otherEventId = getOptionalStringCell(
+      store,
+      "sessions",
+      otherId,
+      "event_id",
+    );
+    if (!otherEventId || otherId === sessionId) {
+      return;
+    }
+    if (
+      getOptionalStringCell(
+        store,
+        "events",
+        otherEventId,
+        "recurrence_series_id",
+      ) !== seriesId
+    ) {
+      return;
+    }
+
+    const otherStartedAt = getStringCell(
+      store,
+      "events",
+      otherEventId,
+      "started_at",
+    );
+    if (otherStartedAt >= startedAt || otherStartedAt <= previousStartedAt) {
+      return;
+    }
+
+    const summary = extractPlainText(
+      collectEnhancedNotesContent(store, otherId),
+    );
+    if (!summary) {
+      return;
+    }
+
+    previousStartedAt = otherStartedAt;
+    previous = {
+      title: getStringCell(store, "events", otherEventId, "title") || null,
+      date: otherStartedAt.slice(0, 10) || null,
+      summary,
+    };
+  });
+
+  return previous;
+}
+
 function getTranscriptSegmentsFromMeta(
   transcripts: TranscriptMeta[],
   store: MainStore,
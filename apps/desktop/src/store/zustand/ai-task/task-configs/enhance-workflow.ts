@@ -1,5 +1,6 @@
 import {
   generateObject,
+  generateText,
   type LanguageModel,
   smoothStream,
   streamText,
@@ -8,6 +9,7 @@ import { z } from "zod";
 
 import {
   type EnhanceTemplate,
+  type Template,
   commands as templateCommands,
   type TemplateSection,
 } from "@hypr/plugin-template";
@@ -39,6 +41,13 @@ export const enhanceWorkflow: Pick<
   ],
 };
 
+// Transcript tokens per request. The local model reads at most 16k tokens
+// (`DEFAULT_MAX_INPUT_TOKENS` in crates/llama), shared with the instructions
+// and raw notes; hosted models have far more room.
+const LOCAL_CHUNK_TOKENS = 10_000;
+const HOSTED_CHUNK_TOKENS = 60_000;
+const LOCAL_PROVIDERS = ["ollama", "lmstudio", "custom"];
+
 async function* executeWorkflow(params: {
   model: LanguageModel;
   args: TaskArgsMapTransformed["enhance"];
@@ -46,7 +55,14 @@ async function* executeWorkflow(params: {
   signal: AbortSignal;
   store: Store;
 }) {
-  const { model, args, onProgress, signal, store } = params;
+  const { model, onProgress, signal, store } = params;
+
+  const args = await summarizeChunksIfNeeded({
+    model,
+    args: params.args,
+    onProgress,
+    signal,
+  });
 
   const sections = await generateTemplateIfNeeded({
     model,
@@ -62,10 +78,11 @@ async function* executeWorkflow(params: {
 
   const system = await getSystemPrompt(argsWithTemplate);
   const prompt = await getUserPrompt(argsWithTemplate, store);
+  const template = await resolveTemplate(argsWithTemplate);
 
   yield* generateSummary({
     model,
-    args: argsWithTemplate,
+    template,
     system,
     prompt,
     onProgress,
@@ -91,10 +108,18 @@ async function getUserPrompt(
   args: TaskArgsMapTransformed["enhance"],
   store: Store,
 ) {
-  const { session, participants, template, transcripts } = args;
+  const {
+    session,
+    participants,
+    template,
+    transcripts,
+    partialSummaries,
+    variables,
+  } = args;
 
   const ctx = {
     content: transcripts,
+    partial_summaries: partialSummaries,
     session,
     participants,
     template,
@@ -115,6 +140,8 @@ async function getUserPrompt(
       participants,
       template,
       transcripts,
+      partialSummaries,
+      variables,
     },
   });
 
@@ -125,6 +152,105 @@ async function getUserPrompt(
   return result.data;
 }
 
+// Sections whose condition doesn't hold are left out of the prompt, so the
+// output is checked against what the model was actually asked to write.
+async function resolveTemplate(args: TaskArgsMapTransformed["enhance"]) {
+  const { session, participants, template, transcripts, partialSummaries } =
+    args;
+
+  if (!template) {
+    return null;
+  }
+
+  const result = await templateCommands.resolveTemplate({
+    session,
+    participants,
+    template,
+    transcripts,
+    partialSummaries,
+    variables: args.variables,
+  });
+
+  if (result.status === "error") {
+    throw new Error(result.error);
+  }
+
+  return result.data;
+}
+
+function chunkTokenBudget(model: LanguageModel) {
+  const provider = typeof model === "string" ? model : model.provider;
+  return LOCAL_PROVIDERS.some((id) => provider.startsWith(id))
+    ? LOCAL_CHUNK_TOKENS
+    : HOSTED_CHUNK_TOKENS;
+}
+
+async function renderTemplate(tpl: Template) {
+  const result = await templateCommands.render(tpl);
+  if (result.status === "error") {
+    throw new Error(result.error);
+  }
+  return result.data;
+}
+
+// Map step for meetings that don't fit in one request: take notes on each
+// chunk of the transcript, then let the regular enhance pass summarize them.
+async function summarizeChunksIfNeeded(params: {
+  model: LanguageModel;
+  args: TaskArgsMapTransformed["enhance"];
+  onProgress: (step: any) => void;
+  signal: AbortSignal;
+}): Promise<TaskArgsMapTransformed["enhance"]> {
+  const { model, args, onProgress, signal } = params;
+
+  const plan = await templateCommands.planChunks(
+    args.transcripts,
+    chunkTokenBudget(model),
+  );
+  if (plan.status === "error") {
+    throw new Error(plan.error);
+  }
+
+  const chunks = plan.data;
+  if (chunks.length <= 1) {
+    return args;
+  }
+
+  const system = await renderTemplate({
+    chunkSystem: { language: args.language },
+  });
+
+  const partialSummaries: string[] = [];
+  for (const [index, transcripts] of chunks.entries()) {
+    onProgress({
+      type: "summarizing",
+      current: index + 1,
+      total: chunks.length,
+    });
+
+    const prompt = await renderTemplate({
+      chunkUser: {
+        session: args.session,
+        participants: args.participants,
+        transcripts,
+        part: index + 1,
+        total: chunks.length,
+      },
+    });
+
+    const result = await generateText({
+      model,
+      temperature: 0,
+      system,
+      prompt,
+      abortSignal: signal,
+    });
+    partialSummaries.push(result.text.trim());
+  }
+
+  return { ...args, partialSummaries };
+}
+
 async function generateTemplateIfNeeded(params: {
   model: LanguageModel;
   args: TaskArgsMapTransformed["enhance"];
@@ -178,17 +304,17 @@ async function generateTemplateIfNeeded(params: {
 
 async function* generateSummary(params: {
   model: LanguageModel;
-  args: TaskArgsMapTransformed["enhance"];
+  template: EnhanceTemplate | null;
   system: string;
   prompt: string;
   onProgress: (step: any) => void;
   signal: AbortSignal;
 }) {
-  const { model, args, system, prompt, onProgress, signal } = params;
+  const { model, template, system, prompt, onProgress, signal } = params;
 
   onProgress({ type: "generating" });
 
-  const validator = createValidator(args.template);
+  const validator = createValidator(template);
 
   yield* withEarlyValidationRetry(
     (retrySignal, { previousFeedback }) => {
@@ -33,6 +33,7 @@ export type TasksActions = {
 
 export type TaskStepInfo<T extends TaskType = TaskType> = T extends "enhance"
   ?
+      | { type: "summarizing"; current: number; total: number }
       | { type: "analyzing" }
       | { type: "generating" }
       | { type: "retrying"; attempt: number; reason: string }
@@ -235,6 +235,12 @@ export const createGeneralSlice = <
             draft.live.device = payload.device;
           }),
         );
+      } else if (payload.type === "audio_device_switched") {
+        set((state) =>
+          mutate(state, (draft) => {
+            draft.live.device = payload.device;
+          }),
+        );
       } else if (payload.type === "connecting") {
         set((state) =>
           mutate(state, (draft) => {
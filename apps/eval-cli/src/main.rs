@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::Arc;
 
@@ -5,17 +6,21 @@ use clap::{Parser, Subcommand};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
 mod report;
+mod stt;
 mod submissions;
 
 use hypr_eval::{
-    DEFAULT_MODELS, EvalResult, Executor, ExecutorProgress, OpenRouterClient, parse_config,
+    Baseline, DEFAULT_MODELS, EvalCase, EvalResult, Executor, ExecutorProgress, LlamaClient,
+    OpenRouterClient, compare, parse_config,
 };
-use report::{render_json, render_results};
+use hypr_gbnf::Grammar;
+use report::{render_comparison, render_json, render_results, render_stt_json, render_stt_results};
+use stt::{SttBackend, SttRunConfig, all_samples, filter_samples, run_stt};
 use submissions::{all_cases, filter_cases};
 
 #[derive(Parser)]
 #[command(name = "evals")]
-#[command(about = "LLM evaluation runner")]
+#[command(about = "LLM and speech-to-text evaluation runner")]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
@@ -24,20 +29,55 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     Run {
-        #[arg(short, long, value_delimiter = ',')]
-        tasks: Option<Vec<String>>,
+        #[command(flatten)]
+        eval: EvalArgs,
 
         #[arg(short, long, default_value = "table")]
         output: String,
 
-        #[arg(short, long, value_delimiter = ',')]
-        models: Option<Vec<String>>,
+        /// Compare against a baseline saved with `evals baseline save`.
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Fail when a significant regression drops a case's pass rate by at
+        /// least this much.
+        #[arg(long, default_value_t = 0.1, requires = "baseline")]
+        regression_threshold: f64,
+    },
+    #[command(subcommand)]
+    Baseline(BaselineCommand),
+    /// Transcribe the hypr-data corpus and score it against ground truth.
+    Stt {
+        #[arg(short, long, value_enum)]
+        backend: SttBackend,
 
+        /// ggml model for whisper-local, or the model directory for moonshine.
         #[arg(long)]
-        no_cache: bool,
+        model_path: Option<PathBuf>,
 
+        #[arg(long, value_enum, default_value = "tiny")]
+        moonshine_size: MoonshineSize,
+
+        /// Base URL of the owhisper-compatible endpoint, e.g. ws://127.0.0.1:52693/v1.
         #[arg(long)]
-        cache_dir: Option<String>,
+        api_base: Option<String>,
+
+        #[arg(long, env = "STT_API_KEY")]
+        api_key: Option<String>,
+
+        /// Model name passed to the endpoint.
+        #[arg(long)]
+        model: Option<String>,
+
+        #[arg(short, long, value_delimiter = ',')]
+        samples: Option<Vec<String>>,
+
+        /// Stream audio at playback speed, as during a meeting.
+        #[arg(long)]
+        realtime: bool,
+
+        #[arg(short, long, default_value = "table")]
+        output: String,
     },
     List,
     Completion {
@@ -46,6 +86,53 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum BaselineCommand {
+    /// Run the evals and save their pass counts as the new baseline.
+    Save {
+        path: PathBuf,
+
+        #[command(flatten)]
+        eval: EvalArgs,
+    },
+}
+
+#[derive(clap::Args)]
+struct EvalArgs {
+    #[arg(short, long, value_delimiter = ',')]
+    tasks: Option<Vec<String>>,
+
+    #[arg(short, long, value_delimiter = ',', conflicts_with = "local_model")]
+    models: Option<Vec<String>>,
+
+    #[arg(long)]
+    no_cache: bool,
+
+    #[arg(long)]
+    cache_dir: Option<String>,
+
+    /// Generate with a GGUF model on this machine instead of OpenRouter.
+    #[arg(long)]
+    local_model: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value = "remote")]
+    grader: Grader,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Grader {
+    /// Grade LLM rubrics with a model on OpenRouter.
+    Remote,
+    /// Only run deterministic rubrics like the format rules; needs no network.
+    Format,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum MoonshineSize {
+    Tiny,
+    Base,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 enum Shell {
     Bash,
@@ -59,13 +146,48 @@ fn main() -> ExitCode {
 
     match cli.command {
         Commands::Run {
-            tasks,
+            eval,
             output,
-            models,
-            no_cache,
-            cache_dir,
+            baseline,
+            regression_threshold,
         } => {
-            if let Err(e) = run_evals(tasks, output, models, no_cache, cache_dir) {
+            let gate = baseline.map(|path| (path, regression_threshold));
+            if let Err(e) = run_evals(eval, output, gate) {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Baseline(BaselineCommand::Save { path, eval }) => {
+            if let Err(e) = save_baseline(&path, eval) {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Stt {
+            backend,
+            model_path,
+            moonshine_size,
+            api_base,
+            api_key,
+            model,
+            samples,
+            realtime,
+            output,
+        } => {
+            let config = SttRunConfig {
+                backend,
+                model_path,
+                moonshine_size: match moonshine_size {
+                    MoonshineSize::Tiny => owhisper_config::MoonshineModelSize::Tiny,
+                    MoonshineSize::Base => owhisper_config::MoonshineModelSize::Base,
+                },
+                api_base,
+                api_key,
+                model,
+                realtime,
+            };
+
+            if let Err(e) = run_stt_evals(&config, samples, output) {
                 eprintln!("Error: {}", e);
                 return ExitCode::FAILURE;
             }
@@ -82,40 +204,101 @@ fn main() -> ExitCode {
 }
 
 fn run_evals(
-    task_filter: Option<Vec<String>>,
+    args: EvalArgs,
     output_format: String,
-    model_override: Option<Vec<String>>,
-    no_cache: bool,
-    cache_dir: Option<String>,
+    gate: Option<(PathBuf, f64)>,
 ) -> Result<(), String> {
+    // Loaded up front so a bad path fails before any tokens are spent.
+    let baseline = match &gate {
+        Some((path, _)) => Some(Baseline::load(path).map_err(|e| e.to_string())?),
+        None => None,
+    };
+
+    let json = output_format == "json";
+    let results = execute_evals(args, !json)?;
+
+    let rendered = if json {
+        render_json(&results)
+    } else {
+        render_results(&results)
+    };
+
+    if let (Some(baseline), Some((_, threshold))) = (baseline, gate) {
+        let comparisons = compare(&baseline, &Baseline::from_results(&results));
+        // With JSON output, stdout stays a single document.
+        render_comparison(&comparisons, threshold, json)?;
+    }
+
+    rendered
+}
+
+fn save_baseline(path: &Path, args: EvalArgs) -> Result<(), String> {
+    let results = execute_evals(args, true)?;
+    render_results(&results)?;
+
+    let baseline = Baseline::from_results(&results);
+    baseline.save(path).map_err(|e| e.to_string())?;
+    println!(
+        "Saved {} cases to {}",
+        baseline.entries.len(),
+        path.display()
+    );
+
+    Ok(())
+}
+
+fn execute_evals(args: EvalArgs, show_progress: bool) -> Result<Vec<EvalResult>, String> {
     let cfg = parse_config();
 
-    if cfg.openrouter_api_key.is_empty() {
+    let remote_grader = args.grader == Grader::Remote;
+    if cfg.openrouter_api_key.is_empty() && (args.local_model.is_none() || remote_grader) {
         return Err("OPENROUTER_API_KEY environment variable is not set".to_string());
     }
 
     let all = all_cases();
-    let selected_cases = filter_cases(&all, task_filter.as_deref());
+    let mut selected_cases = filter_cases(&all, args.tasks.as_deref());
 
     if selected_cases.is_empty() {
         return Err("no cases matched the filter".to_string());
     }
 
-    let cache_dir_opt = if no_cache { None } else { cache_dir };
-    let client = Arc::new(OpenRouterClient::with_cache_dir(
+    if !remote_grader {
+        selected_cases = selected_cases
+            .into_iter()
+            .map(EvalCase::without_llm_rubrics)
+            .collect();
+    }
+
+    let cache_dir_opt = if args.no_cache { None } else { args.cache_dir };
+    let openrouter = Arc::new(OpenRouterClient::with_cache_dir(
         cfg.openrouter_api_key.clone(),
         cache_dir_opt,
     ));
 
-    let models =
-        model_override.unwrap_or_else(|| DEFAULT_MODELS.iter().map(|s| s.to_string()).collect());
-
-    let executor = Executor::new(client.clone());
+    let (executor, models, usage_client) = match &args.local_model {
+        Some(path) => {
+            // Same constraint the app puts on local note generation.
+            let local = LlamaClient::new(path)
+                .map_err(|e| e.to_string())?
+                .with_grammar(Grammar::Enhance { sections: None });
+            let models = vec![local.model_name().to_string()];
+            let executor = Executor::new(Arc::new(local)).with_grader_client(openrouter);
+            (executor, models, None)
+        }
+        None => {
+            let models = args
+                .models
+                .unwrap_or_else(|| DEFAULT_MODELS.iter().map(|s| s.to_string()).collect());
+            (Executor::new(openrouter.clone()), models, Some(openrouter))
+        }
+    };
 
-    if output_format == "json" {
+    if !show_progress {
         let mut results = executor.execute(&selected_cases, &models);
-        resolve_usage(&client, &mut results);
-        return render_json(&results).map_err(|e| e.to_string());
+        if let Some(client) = &usage_client {
+            resolve_usage(client, &mut results);
+        }
+        return Ok(results);
     }
 
     let gen_total = executor.total_generations(&selected_cases, &models);
@@ -148,9 +331,43 @@ fn run_evals(
     gen_bar.finish();
     eval_bar.finish();
 
-    resolve_usage(&client, &mut results);
+    if let Some(client) = &usage_client {
+        resolve_usage(client, &mut results);
+    }
+
+    Ok(results)
+}
+
+fn run_stt_evals(
+    config: &SttRunConfig,
+    sample_filter: Option<Vec<String>>,
+    output_format: String,
+) -> Result<(), String> {
+    let all = all_samples();
+    let selected = filter_samples(&all, sample_filter.as_deref());
+
+    if selected.is_empty() {
+        return Err("no samples matched the filter".to_string());
+    }
+
+    if output_format == "json" {
+        let results = run_stt(config, &selected, |_| {})?;
+        return render_stt_json(&results);
+    }
+
+    let bar = ProgressBar::new(selected.len() as u64);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{prefix:>12} [{bar:30.white}] {pos}/{len}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar.set_prefix("Samples");
+
+    let results = run_stt(config, &selected, |_| bar.inc(1))?;
+    bar.finish();
 
-    render_results(&results).map_err(|e| e.to_string())
+    render_stt_results(&results)
 }
 
 fn resolve_usage(client: &OpenRouterClient, results: &mut [EvalResult]) {
@@ -1,6 +1,10 @@
 use comfy_table::{Cell, Color, ContentArrangement, Table, presets::UTF8_FULL_CONDENSED};
 
-use hypr_eval::EvalResult;
+use hypr_eval::{
+    CaseComparison, ComparisonStatus, DiarizationErrors, ErrorCounts, EvalResult, PassStats,
+};
+
+use crate::stt::SttResult;
 
 pub fn render_json(results: &[EvalResult]) -> std::result::Result<(), String> {
     let json = serde_json::to_string_pretty(
@@ -133,6 +137,191 @@ pub fn render_results(results: &[EvalResult]) -> std::result::Result<(), String>
     Ok(())
 }
 
+pub fn render_comparison(
+    comparisons: &[CaseComparison],
+    threshold: f64,
+    to_stderr: bool,
+) -> std::result::Result<(), String> {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        "Case", "Model", "Baseline", "Current", "Change", "Status",
+    ]);
+
+    let mut failed = 0;
+    for c in comparisons {
+        let (status, color) = match c.status {
+            ComparisonStatus::Regressed if c.fails(threshold) => {
+                failed += 1;
+                ("regressed", Some(Color::Red))
+            }
+            ComparisonStatus::Errored => {
+                failed += 1;
+                ("errored", Some(Color::Red))
+            }
+            ComparisonStatus::Regressed => ("regressed (below threshold)", Some(Color::Yellow)),
+            ComparisonStatus::Improved => ("improved", Some(Color::Green)),
+            ComparisonStatus::Unchanged => ("unchanged", None),
+            ComparisonStatus::New => ("new", None),
+            ComparisonStatus::Missing => ("missing", Some(Color::Yellow)),
+        };
+
+        let mut status = Cell::new(status);
+        if let Some(color) = color {
+            status = status.fg(color);
+        }
+
+        table.add_row(vec![
+            Cell::new(&c.case_id),
+            Cell::new(&c.model),
+            Cell::new(format_pass_stats(c.baseline.as_ref())),
+            Cell::new(format_pass_stats(c.current.as_ref())),
+            Cell::new(
+                c.delta()
+                    .map_or("-".to_string(), |d| format!("{:+.0}pp", d * 100.0)),
+            ),
+            status,
+        ]);
+    }
+
+    if to_stderr {
+        eprintln!("{}", table);
+    } else {
+        println!("{}", table);
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{} case(s) errored or regressed by at least {:.0}pp against the baseline",
+            failed,
+            threshold * 100.0
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn render_stt_json(results: &[SttResult]) -> std::result::Result<(), String> {
+    let json = serde_json::to_string_pretty(
+        &results
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "sample_id": r.sample_id,
+                    "backend": r.backend,
+                    "wer": r.wer.rate(),
+                    "cer": r.cer.rate(),
+                    "der": r.der.map(|d| d.rate()),
+                    "word_errors": r.wer,
+                    "char_errors": r.cer,
+                    "diarization_errors": r.der,
+                    "realtime_factor": r.realtime_factor(),
+                    "first_partial_ms": r.first_partial_ms,
+                    "audio_ms": r.audio_ms,
+                    "elapsed_ms": r.elapsed_ms,
+                    "error": r.error,
+                })
+            })
+            .collect::<Vec<_>>(),
+    )
+    .map_err(|e| format!("Failed to encode JSON: {}", e))?;
+
+    println!("{}", json);
+
+    if results.iter().any(|r| r.error.is_some()) {
+        return Err("evaluation failed".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn render_stt_results(results: &[SttResult]) -> std::result::Result<(), String> {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        "Sample",
+        "Backend",
+        "WER",
+        "CER",
+        "DER",
+        "RTF",
+        "First partial",
+    ]);
+
+    let mut wer = ErrorCounts::default();
+    let mut cer = ErrorCounts::default();
+    let mut der: Option<DiarizationErrors> = None;
+    let mut audio_ms = 0;
+    let mut elapsed_ms = 0;
+    let mut first_partials: Vec<u64> = Vec::new();
+    let mut error_details: Vec<String> = Vec::new();
+
+    for r in results {
+        let mut row: Vec<Cell> = vec![Cell::new(&r.sample_id), Cell::new(&r.backend)];
+
+        if let Some(ref err) = r.error {
+            row.extend((0..4).map(|_| Cell::new("-")));
+            row.push(Cell::new("error").fg(Color::Red));
+            table.add_row(row);
+            error_details.push(format!("{}: {}", r.sample_id, err));
+            continue;
+        }
+
+        wer.merge(&r.wer);
+        cer.merge(&r.cer);
+        if let Some(d) = &r.der {
+            der.get_or_insert_with(DiarizationErrors::default).merge(d);
+        }
+        audio_ms += r.audio_ms;
+        elapsed_ms += r.elapsed_ms;
+        first_partials.extend(r.first_partial_ms);
+
+        row.push(Cell::new(format_rate(r.wer.rate())));
+        row.push(Cell::new(format_rate(r.cer.rate())));
+        row.push(Cell::new(
+            r.der.map_or("-".to_string(), |d| format_rate(d.rate())),
+        ));
+        row.push(Cell::new(format!("{:.2}x", r.realtime_factor())));
+        row.push(Cell::new(format_latency(r.first_partial_ms)));
+        table.add_row(row);
+    }
+
+    // Corpus-level rates weight each sample by its length, like the rates
+    // usually reported for STT benchmarks.
+    let mean_first_partial = (!first_partials.is_empty())
+        .then(|| first_partials.iter().sum::<u64>() / first_partials.len() as u64);
+    table.add_row(vec![
+        Cell::new("Total"),
+        Cell::new(""),
+        Cell::new(format_rate(wer.rate())),
+        Cell::new(format_rate(cer.rate())),
+        Cell::new(der.map_or("-".to_string(), |d| format_rate(d.rate()))),
+        Cell::new(if audio_ms == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.2}x", elapsed_ms as f64 / audio_ms as f64)
+        }),
+        Cell::new(format_latency(mean_first_partial)),
+    ]);
+
+    println!("{}", table);
+
+    if !error_details.is_empty() {
+        eprintln!();
+        eprintln!("\x1b[31mErrors:\x1b[0m");
+        for detail in &error_details {
+            eprintln!("\x1b[31m  - {}\x1b[0m", detail);
+        }
+        return Err("evaluation failed".to_string());
+    }
+
+    Ok(())
+}
+
 fn extract_rubric_names(results: &[EvalResult]) -> Vec<String> {
     for r in results {
         if r.error.is_none() && !r.scores.is_empty() {
@@ -152,3 +341,28 @@ fn format_cost(cost: f64) -> String {
         format!("{:.4}", cost)
     }
 }
+
+fn format_pass_stats(stats: Option<&PassStats>) -> String {
+    match stats {
+        Some(s) => format!(
+            "{}/{} ({:.0}%, CI {:.0}-{:.0}%)",
+            s.pass_count,
+            s.samples,
+            s.pass_rate * 100.0,
+            s.confidence_interval.lower * 100.0,
+            s.confidence_interval.upper * 100.0
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn format_rate(rate: f64) -> String {
+    format!("{:.1}%", rate * 100.0)
+}
+
+fn format_latency(ms: Option<u64>) -> String {
+    match ms {
+        Some(ms) => format!("{}ms", ms),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,353 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use axum::{Router, error_handling::HandleError, http::StatusCode};
+use futures_util::StreamExt;
+
+use hypr_audio_utils::AudioFormatExt;
+use hypr_eval::{
+    DiarizationErrors, ErrorCounts, HypothesisWord, char_error_rate, diarization_error_rate,
+    hypothesis_turns, parse_diarization, parse_transcription, word_error_rate,
+};
+use hypr_language::ISO639;
+use owhisper_client::ListenClient;
+use owhisper_interface::stream::StreamResponse;
+use owhisper_interface::{ControlMessage, ListenParams, MixedMessage};
+
+const SAMPLE_RATE: u32 = 16000;
+const CHUNK_SAMPLES: usize = 512;
+
+/// A recording from `hypr-data` with ground truth for both words and speakers.
+pub struct SttSample {
+    pub id: &'static str,
+    pub language: ISO639,
+    pub audio_path: &'static str,
+    pub transcription_json: &'static str,
+    pub diarization_json: &'static str,
+}
+
+pub fn all_samples() -> Vec<SttSample> {
+    macro_rules! sample {
+        ($id:ident, $language:expr) => {
+            SttSample {
+                id: stringify!($id),
+                language: $language,
+                audio_path: hypr_data::$id::AUDIO_PATH,
+                transcription_json: hypr_data::$id::TRANSCRIPTION_JSON,
+                diarization_json: hypr_data::$id::DIARIZATION_JSON,
+            }
+        };
+    }
+
+    vec![
+        sample!(english_1, ISO639::En),
+        sample!(english_2, ISO639::En),
+        sample!(korean_1, ISO639::Ko),
+        sample!(korean_2, ISO639::Ko),
+    ]
+}
+
+pub fn filter_samples<'a>(
+    samples: &'a [SttSample],
+    filter: Option<&[String]>,
+) -> Vec<&'a SttSample> {
+    match filter {
+        None => samples.iter().collect(),
+        Some(ids) => samples
+            .iter()
+            .filter(|s| ids.iter().any(|id| id == s.id))
+            .collect(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SttBackend {
+    /// whisper.cpp through `transcribe-whisper-local`; `--model-path` is a ggml file.
+    WhisperLocal,
+    /// Moonshine ONNX; `--model-path` is a directory with the encoder, decoder and tokenizer.
+    Moonshine,
+    /// Any owhisper-compatible endpoint at `--api-base`, live or a recorded mock.
+    Owhisper,
+}
+
+impl SttBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WhisperLocal => "whisper-local",
+            Self::Moonshine => "moonshine",
+            Self::Owhisper => "owhisper",
+        }
+    }
+}
+
+pub struct SttRunConfig {
+    pub backend: SttBackend,
+    pub model_path: Option<PathBuf>,
+    pub moonshine_size: owhisper_config::MoonshineModelSize,
+    pub api_base: Option<String>,
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    /// Send audio at playback speed instead of as fast as the backend takes it.
+    pub realtime: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SttResult {
+    pub sample_id: String,
+    pub backend: String,
+    pub wer: ErrorCounts,
+    pub cer: ErrorCounts,
+    /// `None` when the backend doesn't label speakers.
+    pub der: Option<DiarizationErrors>,
+    pub audio_ms: u64,
+    pub elapsed_ms: u64,
+    /// How long the first words took to come back: after they were spoken
+    /// when streaming in realtime, otherwise after the first audio was sent.
+    pub first_partial_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl SttResult {
+    pub fn realtime_factor(&self) -> f64 {
+        if self.audio_ms == 0 {
+            return 0.0;
+        }
+        self.elapsed_ms as f64 / self.audio_ms as f64
+    }
+}
+
+struct Transcription {
+    words: Vec<HypothesisWord>,
+    audio_ms: u64,
+    elapsed_ms: u64,
+    first_partial_ms: Option<u64>,
+}
+
+pub fn run_stt(
+    config: &SttRunConfig,
+    samples: &[&SttSample],
+    on_result: impl Fn(&SttResult),
+) -> Result<Vec<SttResult>, String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+
+    runtime.block_on(async {
+        let (api_base, server) = match config.backend {
+            SttBackend::Owhisper => {
+                let api_base = config
+                    .api_base
+                    .clone()
+                    .ok_or("--api-base is required for the owhisper backend")?;
+                (api_base, None)
+            }
+            backend => {
+                let (api_base, server) = spawn_local_server(local_router(backend, config)?).await?;
+                (api_base, Some(server))
+            }
+        };
+
+        let mut results = Vec::with_capacity(samples.len());
+        for sample in samples {
+            let scored = transcribe(&api_base, config, sample)
+                .await
+                .and_then(|transcription| score(sample, transcription));
+
+            let mut result = scored.unwrap_or_else(|error| SttResult {
+                error: Some(error),
+                ..Default::default()
+            });
+            result.sample_id = sample.id.to_string();
+            result.backend = config.backend.name().to_string();
+
+            on_result(&result);
+            results.push(result);
+        }
+
+        if let Some(server) = server {
+            server.abort();
+        }
+
+        Ok::<_, String>(results)
+    })
+}
+
+fn local_router(backend: SttBackend, config: &SttRunConfig) -> Result<Router, String> {
+    let model_path = config
+        .model_path
+        .clone()
+        .ok_or("--model-path is required for local backends")?;
+
+    let router = match backend {
+        SttBackend::WhisperLocal => Router::new().route_service(
+            "/v1/listen",
+            HandleError::new(
+                hypr_transcribe_whisper_local::TranscribeService::builder()
+                    .model_path(model_path)
+                    .build(),
+                move |err: String| async move { (StatusCode::INTERNAL_SERVER_ERROR, err) },
+            ),
+        ),
+        SttBackend::Moonshine => {
+            let file = |name: &str| model_path.join(name).to_string_lossy().to_string();
+            Router::new().route_service(
+                "/v1/listen",
+                hypr_transcribe_moonshine::TranscribeService::builder()
+                    .model_size(config.moonshine_size.clone())
+                    .encoder_path(file("encoder_model.onnx"))
+                    .decoder_path(file("decoder_model_merged.onnx"))
+                    .tokenizer_path(file("tokenizer.json"))
+                    .build(),
+            )
+        }
+        SttBackend::Owhisper => unreachable!("owhisper is not served locally"),
+    };
+
+    Ok(router)
+}
+
+async fn spawn_local_server(
+    router: Router,
+) -> Result<(String, tokio::task::JoinHandle<()>), String> {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+        .await
+        .map_err(|e| e.to_string())?;
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+
+    let handle = tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            eprintln!("STT server error: {}", e);
+        }
+    });
+
+    Ok((format!("http://{}/v1", addr), handle))
+}
+
+async fn transcribe(
+    api_base: &str,
+    config: &SttRunConfig,
+    sample: &SttSample,
+) -> Result<Transcription, String> {
+    let file = std::fs::File::open(sample.audio_path)
+        .map_err(|e| format!("{}: {}", sample.audio_path, e))?;
+    let source = rodio::Decoder::try_from(file).map_err(|e| e.to_string())?;
+    let chunks: Vec<bytes::Bytes> = source
+        .to_i16_le_chunks(SAMPLE_RATE, CHUNK_SAMPLES)
+        .collect()
+        .await;
+
+    // 16-bit mono samples.
+    let audio_ms =
+        chunks.iter().map(|c| c.len() as u64 / 2).sum::<u64>() * 1000 / SAMPLE_RATE as u64;
+    let chunk_ms = CHUNK_SAMPLES as u64 * 1000 / SAMPLE_RATE as u64;
+    let realtime = config.realtime;
+
+    let audio = futures_util::stream::iter(chunks)
+        .then(move |chunk| async move {
+            if realtime {
+                tokio::time::sleep(Duration::from_millis(chunk_ms)).await;
+            }
+            MixedMessage::Audio(chunk)
+        })
+        .chain(futures_util::stream::iter([MixedMessage::Control(
+            ControlMessage::CloseStream,
+        )]));
+
+    let client = ListenClient::builder()
+        .api_base(api_base)
+        .api_key(config.api_key.clone().unwrap_or_default())
+        .params(ListenParams {
+            model: config.model.clone(),
+            languages: vec![sample.language.into()],
+            ..Default::default()
+        })
+        .build_single()
+        .await;
+
+    let started = Instant::now();
+    let (stream, _handle) = client
+        .from_realtime_audio(Box::pin(audio))
+        .await
+        .map_err(|e| e.to_string())?;
+    futures_util::pin_mut!(stream);
+
+    let mut words = Vec::new();
+    let mut first_partial_ms = None;
+
+    // Generous enough for slow CPU-only runs, but a stalled backend still
+    // fails the sample instead of hanging the whole run.
+    let deadline = Duration::from_millis(audio_ms * 3 + 60_000);
+    let collect = async {
+        while let Some(response) = stream.next().await {
+            match response.map_err(|e| e.to_string())? {
+                StreamResponse::TranscriptResponse {
+                    is_final, channel, ..
+                } => {
+                    let Some(alternative) = channel.alternatives.into_iter().next() else {
+                        continue;
+                    };
+
+                    if let (None, Some(first)) = (first_partial_ms, alternative.words.first()) {
+                        let elapsed_ms = started.elapsed().as_millis() as u64;
+                        first_partial_ms = Some(if realtime {
+                            elapsed_ms.saturating_sub((first.end * 1000.0) as u64)
+                        } else {
+                            elapsed_ms
+                        });
+                    }
+
+                    if is_final {
+                        words.extend(alternative.words.into_iter().map(|w| HypothesisWord {
+                            text: w.punctuated_word.unwrap_or(w.word),
+                            start_ms: (w.start * 1000.0) as u64,
+                            end_ms: (w.end * 1000.0) as u64,
+                            speaker: w.speaker.map(|s| s.to_string()),
+                        }));
+                    }
+                }
+                StreamResponse::ErrorResponse { error_message, .. } => return Err(error_message),
+                _ => {}
+            }
+        }
+        Ok::<_, String>(())
+    };
+
+    tokio::time::timeout(deadline, collect)
+        .await
+        .map_err(|_| format!("no response within {}s", deadline.as_secs()))??;
+
+    Ok(Transcription {
+        words,
+        audio_ms,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        first_partial_ms,
+    })
+}
+
+fn score(sample: &SttSample, transcription: Transcription) -> Result<SttResult, String> {
+    let reference = parse_transcription(sample.transcription_json).map_err(|e| e.to_string())?;
+    let turns = parse_diarization(sample.diarization_json).map_err(|e| e.to_string())?;
+
+    // English words carry their leading space and Korean ones have spaces as
+    // separate entries, so concatenating restores the original text either way.
+    let reference_text: String = reference.iter().map(|w| w.text.as_str()).collect();
+    let hypothesis_text = transcription
+        .words
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let hypothesis_turns = hypothesis_turns(&transcription.words);
+    let der =
+        (!hypothesis_turns.is_empty()).then(|| diarization_error_rate(&turns, &hypothesis_turns));
+
+    Ok(SttResult {
+        wer: word_error_rate(&reference_text, &hypothesis_text),
+        cer: char_error_rate(&reference_text, &hypothesis_text),
+        der,
+        audio_ms: transcription.audio_ms,
+        elapsed_ms: transcription.elapsed_ms,
+        first_partial_ms: transcription.first_partial_ms,
+        ..Default::default()
+    })
+}
@@ -385,6 +385,40 @@ impl AEC {
     }
 }
 
+impl hypr_audio_utils::AudioProcessor for AEC {
+    fn name(&self) -> &'static str {
+        "aec"
+    }
+
+    fn process(
+        &mut self,
+        frame: &mut [f32],
+        reference: Option<&[f32]>,
+    ) -> Result<(), hypr_audio_utils::Error> {
+        let Some(reference) = reference else {
+            return Ok(());
+        };
+
+        let processed = self.process_streaming(frame, reference).map_err(|e| {
+            hypr_audio_utils::Error::Processor {
+                name: "aec",
+                message: e.to_string(),
+            }
+        })?;
+        frame[..processed.len()].copy_from_slice(&processed);
+
+        Ok(())
+    }
+
+    fn latency(&self) -> usize {
+        self.block_len - self.block_shift
+    }
+
+    fn reset(&mut self) {
+        AEC::reset(self);
+    }
+}
+
 // cargo test -p aec --no-default-features --features 128
 // cargo test -p aec --no-default-features --features 256
 // cargo test -p aec --no-default-features --features 512
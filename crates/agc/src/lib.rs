@@ -1,8 +1,11 @@
 use dagc::MonoAgc;
+use hypr_audio_utils::AudioProcessor;
 use hypr_vad_ext::{StreamingVad, VadConfig};
 
 pub struct VadAgc {
     agc: MonoAgc,
+    desired_output_rms: f32,
+    distortion_factor: f32,
     vad: Option<StreamingVad>,
     vad_cfg: VadConfig,
     mask_non_speech: bool,
@@ -12,6 +15,8 @@ impl VadAgc {
     pub fn new(desired_output_rms: f32, distortion_factor: f32) -> Self {
         Self {
             agc: MonoAgc::new(desired_output_rms, distortion_factor).expect("failed_to_create_agc"),
+            desired_output_rms,
+            distortion_factor,
             vad: None,
             vad_cfg: VadConfig::default(),
             mask_non_speech: false,
@@ -52,6 +57,31 @@ impl VadAgc {
     pub fn gain(&self) -> f32 {
         self.agc.gain()
     }
+
+    pub fn reset(&mut self) {
+        self.agc = MonoAgc::new(self.desired_output_rms, self.distortion_factor)
+            .expect("failed_to_create_agc");
+        self.vad = None;
+    }
+}
+
+impl AudioProcessor for VadAgc {
+    fn name(&self) -> &'static str {
+        "agc"
+    }
+
+    fn process(
+        &mut self,
+        frame: &mut [f32],
+        _reference: Option<&[f32]>,
+    ) -> Result<(), hypr_audio_utils::Error> {
+        VadAgc::process(self, frame);
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        VadAgc::reset(self);
+    }
 }
 
 impl Default for VadAgc {
@@ -0,0 +1,121 @@
+use hypr_audio_utils::ProcessorChain;
+
+pub const SETTINGS_KEY: &str = "audio_processing";
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProcessorNode {
+    Agc {
+        #[serde(default)]
+        mask_non_speech: bool,
+    },
+    VadMask,
+    Loudness,
+    Aec,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProcessingConfig {
+    pub mic: Vec<ProcessorNode>,
+    pub speaker: Vec<ProcessorNode>,
+    /// Runs on the mic side of each mic/speaker pair, with the speaker chunk as reference.
+    pub joined: Vec<ProcessorNode>,
+    /// Resample the speaker channel to follow the mic's clock.
+    pub drift_compensation: bool,
+}
+
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            mic: vec![ProcessorNode::Agc {
+                mask_non_speech: true,
+            }],
+            speaker: vec![ProcessorNode::Agc {
+                mask_non_speech: false,
+            }],
+            joined: vec![],
+            drift_compensation: true,
+        }
+    }
+}
+
+impl ProcessingConfig {
+    pub fn from_settings(settings: &serde_json::Value) -> Self {
+        let Some(value) = settings.get(SETTINGS_KEY) else {
+            return Self::default();
+        };
+
+        serde_json::from_value(value.clone()).unwrap_or_else(|error| {
+            tracing::warn!(?error, "invalid_audio_processing_settings");
+            Self::default()
+        })
+    }
+}
+
+pub fn build_chain(nodes: &[ProcessorNode], sample_rate: u32) -> ProcessorChain {
+    let mut chain = ProcessorChain::new();
+
+    for node in nodes {
+        match node {
+            ProcessorNode::Agc { mask_non_speech } => {
+                chain.push(Box::new(
+                    hypr_agc::VadAgc::default().with_masking(*mask_non_speech),
+                ));
+            }
+            ProcessorNode::VadMask => {
+                chain.push(Box::new(hypr_vad_ext::VadMask::default()));
+            }
+            ProcessorNode::Loudness => {
+                chain.push(Box::new(hypr_audio::LoudnessNormalizer::new(sample_rate)));
+            }
+            ProcessorNode::Aec => match hypr_aec::AEC::new() {
+                Ok(aec) => chain.push(Box::new(aec)),
+                Err(error) => tracing::warn!(?error, "aec_unavailable_skipping_node"),
+            },
+        }
+    }
+
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_key_uses_default() {
+        let config = ProcessingConfig::from_settings(&serde_json::json!({}));
+        assert_eq!(config, ProcessingConfig::default());
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        let config = ProcessingConfig::from_settings(&serde_json::json!({
+            "audio_processing": {
+                "mic": [{ "type": "agc" }, { "type": "vad_mask" }],
+                "joined": [{ "type": "aec" }]
+            }
+        }));
+
+        assert_eq!(
+            config.mic,
+            vec![
+                ProcessorNode::Agc {
+                    mask_non_speech: false
+                },
+                ProcessorNode::VadMask
+            ]
+        );
+        assert_eq!(config.speaker, ProcessingConfig::default().speaker);
+        assert_eq!(config.joined, vec![ProcessorNode::Aec]);
+    }
+
+    #[test]
+    fn test_invalid_config_uses_default() {
+        let config = ProcessingConfig::from_settings(&serde_json::json!({
+            "audio_processing": { "mic": [{ "type": "reverb" }] }
+        }));
+        assert_eq!(config, ProcessingConfig::default());
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelMode {
+    MicOnly,
+    SpeakerOnly,
+    MicAndSpeaker,
+}
+
+impl ChannelMode {
+    pub fn uses_mic(self) -> bool {
+        matches!(self, ChannelMode::MicOnly | ChannelMode::MicAndSpeaker)
+    }
+
+    pub fn uses_speaker(self) -> bool {
+        matches!(self, ChannelMode::SpeakerOnly | ChannelMode::MicAndSpeaker)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JoinerStats {
+    pub paired: u64,
+    pub mic_padded: u64,
+    pub spk_padded: u64,
+    pub mic_dropped: u64,
+    pub spk_dropped: u64,
+}
+
+/// Pairs mic and speaker chunks by arrival order, filling the missing side with
+/// silence once one channel lags too far behind.
+pub struct Joiner {
+    mic: VecDeque<Arc<[f32]>>,
+    spk: VecDeque<Arc<[f32]>>,
+    silence_cache: HashMap<usize, Arc<[f32]>>,
+    stats: JoinerStats,
+}
+
+impl Joiner {
+    const MAX_LAG: usize = 4;
+    const MAX_QUEUE_SIZE: usize = 30;
+
+    pub fn new() -> Self {
+        Self {
+            mic: VecDeque::new(),
+            spk: VecDeque::new(),
+            silence_cache: HashMap::new(),
+            stats: JoinerStats::default(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.mic.clear();
+        self.spk.clear();
+    }
+
+    pub fn stats(&self) -> JoinerStats {
+        self.stats
+    }
+
+    fn get_silence(&mut self, len: usize) -> Arc<[f32]> {
+        self.silence_cache
+            .entry(len)
+            .or_insert_with(|| Arc::from(vec![0.0; len]))
+            .clone()
+    }
+
+    pub fn push_mic(&mut self, data: Arc<[f32]>) {
+        self.mic.push_back(data);
+        if self.mic.len() > Self::MAX_QUEUE_SIZE {
+            tracing::warn!("mic_queue_overflow");
+            self.mic.pop_front();
+            self.stats.mic_dropped += 1;
+        }
+    }
+
+    pub fn push_spk(&mut self, data: Arc<[f32]>) {
+        self.spk.push_back(data);
+        if self.spk.len() > Self::MAX_QUEUE_SIZE {
+            tracing::warn!("spk_queue_overflow");
+            self.spk.pop_front();
+            self.stats.spk_dropped += 1;
+        }
+    }
+
+    pub fn pop_pair(&mut self, mode: ChannelMode) -> Option<(Arc<[f32]>, Arc<[f32]>)> {
+        if self.mic.front().is_some() && self.spk.front().is_some() {
+            self.stats.paired += 1;
+            return Some((self.mic.pop_front()?, self.spk.pop_front()?));
+        }
+
+        match mode {
+            ChannelMode::MicOnly => {
+                if let Some(mic) = self.mic.pop_front() {
+                    let spk = self.get_silence(mic.len());
+                    self.stats.spk_padded += 1;
+                    return Some((mic, spk));
+                }
+            }
+            ChannelMode::SpeakerOnly => {
+                if let Some(spk) = self.spk.pop_front() {
+                    let mic = self.get_silence(spk.len());
+                    self.stats.mic_padded += 1;
+                    return Some((mic, spk));
+                }
+            }
+            ChannelMode::MicAndSpeaker => {
+                if self.mic.front().is_some()
+                    && self.spk.is_empty()
+                    && self.mic.len() > Self::MAX_LAG
+                {
+                    let mic = self.mic.pop_front()?;
+                    let spk = self.get_silence(mic.len());
+                    self.stats.spk_padded += 1;
+                    return Some((mic, spk));
+                }
+                if self.spk.front().is_some()
+                    && self.mic.is_empty()
+                    && self.spk.len() > Self::MAX_LAG
+                {
+                    let spk = self.spk.pop_front()?;
+                    let mic = self.get_silence(spk.len());
+                    self.stats.mic_padded += 1;
+                    return Some((mic, spk));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for Joiner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(value: f32) -> Arc<[f32]> {
+        Arc::from(vec![value; 4])
+    }
+
+    #[test]
+    fn test_pairs_in_arrival_order() {
+        let mut joiner = Joiner::new();
+        joiner.push_mic(chunk(1.0));
+        joiner.push_spk(chunk(2.0));
+
+        let (mic, spk) = joiner.pop_pair(ChannelMode::MicAndSpeaker).unwrap();
+        assert_eq!(mic[0], 1.0);
+        assert_eq!(spk[0], 2.0);
+        assert!(joiner.pop_pair(ChannelMode::MicAndSpeaker).is_none());
+        assert_eq!(joiner.stats().paired, 1);
+    }
+
+    #[test]
+    fn test_pads_lagging_speaker_with_silence() {
+        let mut joiner = Joiner::new();
+        for _ in 0..=Joiner::MAX_LAG {
+            joiner.push_mic(chunk(1.0));
+        }
+
+        let (mic, spk) = joiner.pop_pair(ChannelMode::MicAndSpeaker).unwrap();
+        assert_eq!(mic.len(), spk.len());
+        assert!(spk.iter().all(|&s| s == 0.0));
+        assert_eq!(joiner.stats().spk_padded, 1);
+    }
+}
@@ -0,0 +1,165 @@
+mod config;
+mod joiner;
+
+pub use config::*;
+pub use joiner::*;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hypr_audio_utils::{
+    DriftCompensator, DriftEstimator, DriftMetrics, ProcessorChain, ProcessorMetrics,
+    chunk_size_for_stt,
+};
+
+/// The listener's processing stages without any actor or I/O concerns:
+/// per-channel chains, the mic/speaker joiner, and the post-join chain.
+pub struct AudioPipeline {
+    mic_chain: ProcessorChain,
+    spk_chain: ProcessorChain,
+    joined_chain: ProcessorChain,
+    joiner: Joiner,
+    drift: DriftEstimator,
+    compensator: Option<DriftCompensator>,
+    started: Instant,
+}
+
+impl AudioPipeline {
+    pub fn new(config: &ProcessingConfig, sample_rate: u32) -> Self {
+        let compensator = if config.drift_compensation {
+            DriftCompensator::new(chunk_size_for_stt(sample_rate))
+                .inspect_err(|error| tracing::warn!(?error, "drift_compensator_unavailable"))
+                .ok()
+        } else {
+            None
+        };
+
+        Self {
+            mic_chain: build_chain(&config.mic, sample_rate),
+            spk_chain: build_chain(&config.speaker, sample_rate),
+            joined_chain: build_chain(&config.joined, sample_rate),
+            joiner: Joiner::new(),
+            drift: DriftEstimator::new(sample_rate),
+            compensator,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.joiner.reset();
+        self.mic_chain.reset();
+        self.spk_chain.reset();
+        self.joined_chain.reset();
+        self.drift.reset();
+        if let Some(compensator) = &mut self.compensator {
+            compensator.reset();
+        }
+        self.started = Instant::now();
+    }
+
+    pub fn push_mic(&mut self, data: Vec<f32>) -> Arc<[f32]> {
+        self.push_mic_at(data, self.started.elapsed())
+    }
+
+    /// Like [`Self::push_mic`], with an explicit arrival time for drift
+    /// estimation (offline replay has no meaningful wall clock).
+    pub fn push_mic_at(&mut self, mut data: Vec<f32>, at: Duration) -> Arc<[f32]> {
+        self.drift.observe_reference(data.len(), at);
+
+        if let Err(e) = self.mic_chain.process(&mut data, None) {
+            tracing::warn!(error = ?e, "mic_processing_failed");
+        }
+        let arc = Arc::<[f32]>::from(data);
+        self.joiner.push_mic(Arc::clone(&arc));
+        arc
+    }
+
+    /// Returns the processed speaker chunks ready for pairing. Drift
+    /// compensation re-chunks the channel, so a push can yield zero or two.
+    pub fn push_speaker(&mut self, data: Vec<f32>) -> Vec<Arc<[f32]>> {
+        self.push_speaker_at(data, self.started.elapsed())
+    }
+
+    pub fn push_speaker_at(&mut self, data: Vec<f32>, at: Duration) -> Vec<Arc<[f32]>> {
+        self.drift.observe_target(data.len(), at);
+
+        let chunks = match &mut self.compensator {
+            Some(compensator) => {
+                if let Some(drift) = self.drift.drift()
+                    && let Err(e) = compensator.set_drift(drift)
+                {
+                    tracing::warn!(error = ?e, "drift_compensation_failed");
+                }
+
+                match compensator.push(&data) {
+                    Ok(()) => std::iter::from_fn(|| compensator.pop_chunk()).collect(),
+                    Err(e) => {
+                        tracing::warn!(error = ?e, "drift_compensation_failed");
+                        vec![data]
+                    }
+                }
+            }
+            None => vec![data],
+        };
+
+        chunks
+            .into_iter()
+            .map(|mut chunk| {
+                if let Err(e) = self.spk_chain.process(&mut chunk, None) {
+                    tracing::warn!(error = ?e, "spk_processing_failed");
+                }
+                let arc = Arc::<[f32]>::from(chunk);
+                self.joiner.push_spk(Arc::clone(&arc));
+                arc
+            })
+            .collect()
+    }
+
+    pub fn pop_pair(&mut self, mode: ChannelMode) -> Option<(Arc<[f32]>, Arc<[f32]>)> {
+        let (mic, spk) = self.joiner.pop_pair(mode)?;
+
+        if self.joined_chain.is_empty() {
+            return Some((mic, spk));
+        }
+
+        let mut data = mic.to_vec();
+        if let Err(e) = self.joined_chain.process(&mut data, Some(&spk)) {
+            tracing::warn!(error = ?e, "joined_processing_failed");
+        }
+        Some((Arc::<[f32]>::from(data), spk))
+    }
+
+    pub fn metrics(&self) -> impl Iterator<Item = (&'static str, &'static str, ProcessorMetrics)> {
+        let tag = |channel: &'static str, chain: &ProcessorChain| {
+            chain
+                .metrics()
+                .map(move |(node, metrics)| (channel, node, metrics))
+                .collect::<Vec<_>>()
+        };
+
+        tag("mic", &self.mic_chain)
+            .into_iter()
+            .chain(tag("speaker", &self.spk_chain))
+            .chain(tag("joined", &self.joined_chain))
+    }
+
+    pub fn reset_metrics(&mut self) {
+        self.mic_chain.reset_metrics();
+        self.spk_chain.reset_metrics();
+        self.joined_chain.reset_metrics();
+    }
+
+    pub fn joiner_stats(&self) -> JoinerStats {
+        self.joiner.stats()
+    }
+
+    pub fn drift_metrics(&self) -> DriftMetrics {
+        DriftMetrics {
+            drift_ppm: self.drift.drift().unwrap_or(0.0) * 1e6,
+            ratio: self.compensator.as_ref().map_or(1.0, |c| c.ratio()),
+            offset_samples: self.drift.offset_samples(),
+            observations: self.drift.observations(),
+            converged: self.drift.is_converged(),
+        }
+    }
+}
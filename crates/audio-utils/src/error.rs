@@ -22,4 +22,6 @@ pub enum Error {
     EmptyChannelSet,
     #[error("too many channels: {count}")]
     TooManyChannels { count: usize },
+    #[error("processor {name} failed: {message}")]
+    Processor { name: &'static str, message: String },
 }
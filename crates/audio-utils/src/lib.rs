@@ -6,11 +6,13 @@ use hypr_audio_interface::AsyncSource;
 
 mod error;
 mod pcm;
+mod processor;
 mod resampler;
 mod vorbis;
 
 pub use error::*;
 pub use pcm::*;
+pub use processor::*;
 pub use resampler::*;
 pub use vorbis::*;
 
@@ -0,0 +1,200 @@
+use std::time::{Duration, Instant};
+
+pub trait AudioProcessor: Send {
+    fn name(&self) -> &'static str;
+
+    /// Processes `frame` in place. `reference` is the far-end signal paired with
+    /// the frame, available only to nodes running after mic/speaker are joined.
+    fn process(&mut self, frame: &mut [f32], reference: Option<&[f32]>)
+    -> Result<(), crate::Error>;
+
+    /// Algorithmic delay introduced by this node, in samples.
+    fn latency(&self) -> usize {
+        0
+    }
+
+    fn reset(&mut self);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessorMetrics {
+    pub frames: u64,
+    pub samples: u64,
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl ProcessorMetrics {
+    pub fn mean(&self) -> Duration {
+        if self.frames == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.frames as u32
+        }
+    }
+
+    fn record(&mut self, samples: usize, elapsed: Duration, failed: bool) {
+        self.frames += 1;
+        self.samples += samples as u64;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        if failed {
+            self.errors += 1;
+        }
+    }
+}
+
+struct Node {
+    processor: Box<dyn AudioProcessor>,
+    metrics: ProcessorMetrics,
+}
+
+#[derive(Default)]
+pub struct ProcessorChain {
+    nodes: Vec<Node>,
+}
+
+impl ProcessorChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, processor: impl AudioProcessor + 'static) -> Self {
+        self.push(Box::new(processor));
+        self
+    }
+
+    pub fn push(&mut self, processor: Box<dyn AudioProcessor>) {
+        self.nodes.push(Node {
+            processor,
+            metrics: ProcessorMetrics::default(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Runs every node in order. A failing node leaves the frame as it was and
+    /// the chain keeps going; the last error is returned once all nodes ran.
+    pub fn process(
+        &mut self,
+        frame: &mut [f32],
+        reference: Option<&[f32]>,
+    ) -> Result<(), crate::Error> {
+        let mut result = Ok(());
+
+        for node in &mut self.nodes {
+            let started = Instant::now();
+            let outcome = node.processor.process(frame, reference);
+            node.metrics.record(frame.len(), started.elapsed(), outcome.is_err());
+
+            if let Err(e) = outcome {
+                result = Err(e);
+            }
+        }
+
+        result
+    }
+
+    pub fn latency(&self) -> usize {
+        self.nodes.iter().map(|n| n.processor.latency()).sum()
+    }
+
+    pub fn reset(&mut self) {
+        for node in &mut self.nodes {
+            node.processor.reset();
+        }
+    }
+
+    pub fn metrics(&self) -> impl Iterator<Item = (&'static str, ProcessorMetrics)> + '_ {
+        self.nodes.iter().map(|n| (n.processor.name(), n.metrics))
+    }
+
+    pub fn reset_metrics(&mut self) {
+        for node in &mut self.nodes {
+            node.metrics = ProcessorMetrics::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Gain(f32);
+
+    impl AudioProcessor for Gain {
+        fn name(&self) -> &'static str {
+            "gain"
+        }
+
+        fn process(
+            &mut self,
+            frame: &mut [f32],
+            _reference: Option<&[f32]>,
+        ) -> Result<(), crate::Error> {
+            frame.iter_mut().for_each(|s| *s *= self.0);
+            Ok(())
+        }
+
+        fn latency(&self) -> usize {
+            3
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    struct Failing;
+
+    impl AudioProcessor for Failing {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn process(
+            &mut self,
+            _frame: &mut [f32],
+            _reference: Option<&[f32]>,
+        ) -> Result<(), crate::Error> {
+            Err(crate::Error::Processor {
+                name: self.name(),
+                message: "boom".to_string(),
+            })
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    #[test]
+    fn test_chain_runs_nodes_in_order() {
+        let mut chain = ProcessorChain::new().with(Gain(2.0)).with(Gain(0.25));
+        let mut frame = vec![1.0, -1.0, 0.5];
+
+        chain.process(&mut frame, None).unwrap();
+
+        assert_eq!(frame, vec![0.5, -0.5, 0.25]);
+        assert_eq!(chain.latency(), 6);
+    }
+
+    #[test]
+    fn test_chain_continues_after_failure() {
+        let mut chain = ProcessorChain::new().with(Failing).with(Gain(2.0));
+        let mut frame = vec![0.25; 4];
+
+        assert!(chain.process(&mut frame, None).is_err());
+        assert_eq!(frame, vec![0.5; 4]);
+
+        let metrics: Vec<_> = chain.metrics().collect();
+        assert_eq!(metrics[0].0, "failing");
+        assert_eq!(metrics[0].1.errors, 1);
+        assert_eq!(metrics[1].1.frames, 1);
+        assert_eq!(metrics[1].1.samples, 4);
+        assert_eq!(metrics[1].1.errors, 0);
+    }
+}
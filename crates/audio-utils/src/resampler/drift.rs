@@ -0,0 +1,294 @@
+use std::time::Duration;
+
+use rubato::{FastFixedIn, PolynomialDegree, Resampler};
+
+use super::driver::RubatoChunkResampler;
+
+/// Largest clock skew we try to follow. Real devices sit well under 100ppm;
+/// anything beyond this is a stall or a device switch, not drift.
+const MAX_DRIFT_PPM: f64 = 1000.0;
+/// Time constant of the exponential forgetting in the rate fits.
+const FIT_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// How much history both fits need before the estimate is reported.
+const MIN_SPAN: Duration = Duration::from_secs(2 * 60);
+/// A channel that goes quiet for longer than this is treated as having paused
+/// (e.g. a capture node that only runs while something plays) rather than slow.
+const MAX_GAP: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DriftMetrics {
+    /// How much faster the target clock runs than the reference, in ppm.
+    pub drift_ppm: f64,
+    /// Output/input ratio currently applied to the target channel.
+    pub ratio: f64,
+    /// Samples the target channel is ahead of the reference (negative: behind).
+    pub offset_samples: f64,
+    pub observations: u64,
+    pub converged: bool,
+}
+
+/// Exponentially weighted least-squares fit of delivered samples against
+/// arrival time; the slope is the channel's effective sample rate.
+#[derive(Default)]
+struct RateFit {
+    total: f64,
+    first_at: Option<f64>,
+    last_at: Option<f64>,
+    sw: f64,
+    st: f64,
+    sy: f64,
+    stt: f64,
+    sty: f64,
+}
+
+impl RateFit {
+    fn observe(&mut self, samples: usize, at: f64, nominal_rate: f64) {
+        let first_at = *self.first_at.get_or_insert(at);
+
+        let decay = match self.last_at {
+            Some(last_at) => {
+                let gap = at - last_at;
+                if gap > MAX_GAP.as_secs_f64() {
+                    // Bridge the pause at the nominal rate so it doesn't read as a slow clock.
+                    self.total += (gap * nominal_rate - samples as f64).max(0.0);
+                }
+                (-(gap.max(0.0)) / FIT_WINDOW.as_secs_f64()).exp()
+            }
+            None => 1.0,
+        };
+        self.last_at = Some(at);
+        self.total += samples as f64;
+
+        let t = at - first_at;
+        let y = self.total;
+        self.sw = self.sw * decay + 1.0;
+        self.st = self.st * decay + t;
+        self.sy = self.sy * decay + y;
+        self.stt = self.stt * decay + t * t;
+        self.sty = self.sty * decay + t * y;
+    }
+
+    fn span(&self) -> f64 {
+        match (self.first_at, self.last_at) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        }
+    }
+
+    fn rate(&self) -> Option<f64> {
+        if self.sw == 0.0 {
+            return None;
+        }
+
+        let mean_t = self.st / self.sw;
+        let mean_y = self.sy / self.sw;
+        let var_t = self.stt / self.sw - mean_t * mean_t;
+        if var_t <= 0.0 {
+            return None;
+        }
+
+        Some((self.sty / self.sw - mean_t * mean_y) / var_t)
+    }
+}
+
+/// Estimates the clock skew between two channels that are nominally at the
+/// same sample rate (both already resampled to it) from how fast each one
+/// actually delivers samples.
+pub struct DriftEstimator {
+    nominal_rate: f64,
+    reference: RateFit,
+    target: RateFit,
+    observations: u64,
+}
+
+impl DriftEstimator {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            nominal_rate: sample_rate as f64,
+            reference: RateFit::default(),
+            target: RateFit::default(),
+            observations: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.reference = RateFit::default();
+        self.target = RateFit::default();
+        self.observations = 0;
+    }
+
+    /// Records `samples` of the reference channel arriving at `at`, measured
+    /// from any fixed origin shared with [`Self::observe_target`].
+    pub fn observe_reference(&mut self, samples: usize, at: Duration) {
+        self.reference
+            .observe(samples, at.as_secs_f64(), self.nominal_rate);
+        self.observations += 1;
+    }
+
+    pub fn observe_target(&mut self, samples: usize, at: Duration) {
+        self.target
+            .observe(samples, at.as_secs_f64(), self.nominal_rate);
+        self.observations += 1;
+    }
+
+    pub fn is_converged(&self) -> bool {
+        let min_span = MIN_SPAN.as_secs_f64();
+        self.reference.span() >= min_span && self.target.span() >= min_span
+    }
+
+    /// Skew as a fraction (`1e-4` is the target running 100ppm fast), or
+    /// `None` until both channels have enough history.
+    pub fn drift(&self) -> Option<f64> {
+        if !self.is_converged() {
+            return None;
+        }
+
+        let reference = self.reference.rate()?;
+        let target = self.target.rate()?;
+        if reference <= 0.0 {
+            return None;
+        }
+
+        let max = MAX_DRIFT_PPM * 1e-6;
+        Some((target / reference - 1.0).clamp(-max, max))
+    }
+
+    pub fn offset_samples(&self) -> f64 {
+        self.target.total - self.reference.total
+    }
+
+    pub fn observations(&self) -> u64 {
+        self.observations
+    }
+}
+
+/// Resamples a channel by a slowly varying ratio close to 1.0 so it keeps
+/// pace with the reference clock, emitting fixed-size chunks.
+pub struct DriftCompensator {
+    driver: RubatoChunkResampler<FastFixedIn<f32>, 1>,
+    ratio: f64,
+}
+
+impl DriftCompensator {
+    const MAX_RELATIVE_RATIO: f64 = 1.0 + 2.0 * MAX_DRIFT_PPM * 1e-6;
+
+    pub fn new(output_chunk_size: usize) -> Result<Self, crate::Error> {
+        // Small input blocks keep the extra buffering well under a chunk.
+        let input_block_size = (output_chunk_size / 4).max(1);
+        let resampler = FastFixedIn::<f32>::new(
+            1.0,
+            Self::MAX_RELATIVE_RATIO,
+            PolynomialDegree::Cubic,
+            input_block_size,
+            1,
+        )?;
+
+        Ok(Self {
+            driver: RubatoChunkResampler::new(resampler, output_chunk_size, input_block_size),
+            ratio: 1.0,
+        })
+    }
+
+    /// Follows `drift` as reported by [`DriftEstimator::drift`]. The change is
+    /// ramped over the next block to avoid clicks.
+    pub fn set_drift(&mut self, drift: f64) -> Result<(), crate::Error> {
+        let ratio = 1.0 / (1.0 + drift);
+        if (ratio - self.ratio).abs() < 1e-9 {
+            return Ok(());
+        }
+
+        self.driver
+            .resampler_mut()
+            .set_resample_ratio_relative(ratio, true)?;
+        self.ratio = ratio;
+        Ok(())
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    pub fn push(&mut self, samples: &[f32]) -> Result<(), crate::Error> {
+        for &sample in samples {
+            self.driver.push_sample(sample);
+        }
+        self.driver.process_all_ready_blocks()?;
+        Ok(())
+    }
+
+    pub fn pop_chunk(&mut self) -> Option<Vec<f32>> {
+        self.driver.take_full_chunk()
+    }
+
+    pub fn reset(&mut self) {
+        self.driver.resampler_mut().reset();
+        self.driver.clear_input();
+        self.driver.take_all_output();
+        self.ratio = 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+    const CHUNK: usize = 1920;
+
+    fn feed(estimator: &mut DriftEstimator, secs: f64, drift: f64) {
+        let chunk_secs = CHUNK as f64 / RATE as f64;
+        let mut next_ref = chunk_secs;
+        let mut next_tgt = chunk_secs / (1.0 + drift);
+
+        while next_ref <= secs || next_tgt <= secs {
+            if next_ref <= next_tgt {
+                estimator.observe_reference(CHUNK, Duration::from_secs_f64(next_ref));
+                next_ref += chunk_secs;
+            } else {
+                estimator.observe_target(CHUNK, Duration::from_secs_f64(next_tgt));
+                next_tgt += chunk_secs / (1.0 + drift);
+            }
+        }
+    }
+
+    #[test]
+    fn test_estimator_needs_history() {
+        let mut estimator = DriftEstimator::new(RATE);
+        feed(&mut estimator, 30.0, 200e-6);
+
+        assert!(!estimator.is_converged());
+        assert_eq!(estimator.drift(), None);
+    }
+
+    #[test]
+    fn test_estimator_tracks_skew() {
+        for ppm in [-300.0, 0.0, 50.0, 200.0] {
+            let mut estimator = DriftEstimator::new(RATE);
+            feed(&mut estimator, 180.0, ppm * 1e-6);
+
+            let estimated = estimator.drift().unwrap() * 1e6;
+            assert!(
+                (estimated - ppm).abs() < 1.0,
+                "expected {ppm}ppm, got {estimated}ppm"
+            );
+        }
+    }
+
+    #[test]
+    fn test_estimator_bridges_pauses() {
+        let mut estimator = DriftEstimator::new(RATE);
+        let chunk_secs = CHUNK as f64 / RATE as f64;
+
+        let mut at = 0.0;
+        while at < 180.0 {
+            at += chunk_secs;
+            estimator.observe_reference(CHUNK, Duration::from_secs_f64(at));
+            // The target pauses for 10s in the middle, as an app's capture node would.
+            if !(60.0..70.0).contains(&at) {
+                estimator.observe_target(CHUNK, Duration::from_secs_f64(at));
+            }
+        }
+
+        assert!(estimator.drift().unwrap().abs() < 10e-6);
+    }
+}
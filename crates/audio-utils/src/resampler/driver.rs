@@ -34,6 +34,11 @@ impl<R: Resampler<f32>, const CHANNELS: usize> RubatoChunkResampler<R, CHANNELS>
         }
     }
 
+    /// Gives access to the wrapped resampler, e.g. to adjust its ratio in place.
+    pub(crate) fn resampler_mut(&mut self) -> &mut R {
+        &mut self.resampler
+    }
+
     /// Checks whether any resampled output is available.
     pub(crate) fn output_is_empty(&self) -> bool {
         self.output_queue.is_empty()
@@ -1,8 +1,10 @@
+mod drift;
 mod driver;
 mod dynamic_new;
 mod dynamic_old;
 mod static_new;
 
+pub use drift::*;
 pub use dynamic_new::*;
 pub use dynamic_old::*;
 pub use static_new::*;
@@ -235,4 +237,89 @@ mod tests {
 
         assert_eq!(expected_second_segment, actual_second_segment,);
     }
+
+    fn tone(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 16000.0).sin() * 0.5)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_drift_compensation_with_skewed_source() {
+        let drift = 300e-6;
+        let secs = 150.0;
+        let chunk_size = 1920;
+
+        // Both devices claim 16kHz, but the speaker's clock runs fast and
+        // delivers more samples over the same wall-clock time.
+        let mic = DynamicRateSource::new(vec![(tone((secs * 16000.0) as usize), 16000)]);
+        let spk = DynamicRateSource::new(vec![(
+            tone((secs * 16000.0 * (1.0 + drift)) as usize),
+            16000,
+        )]);
+
+        let mic_chunks: Vec<Vec<f32>> = ResamplerDynamicNew::new(mic, 16000, chunk_size)
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        let spk_chunks: Vec<Vec<f32>> = ResamplerDynamicNew::new(spk, 16000, chunk_size)
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+
+        let chunk_secs = chunk_size as f64 / 16000.0;
+        let mut estimator = DriftEstimator::new(16000);
+        let (mut i, mut j) = (0, 0);
+        while i < mic_chunks.len() || j < spk_chunks.len() {
+            let mic_at = (i + 1) as f64 * chunk_secs;
+            let spk_at = (j + 1) as f64 * chunk_secs / (1.0 + drift);
+
+            if j >= spk_chunks.len() || (i < mic_chunks.len() && mic_at <= spk_at) {
+                estimator.observe_reference(
+                    mic_chunks[i].len(),
+                    std::time::Duration::from_secs_f64(mic_at),
+                );
+                i += 1;
+            } else {
+                estimator.observe_target(
+                    spk_chunks[j].len(),
+                    std::time::Duration::from_secs_f64(spk_at),
+                );
+                j += 1;
+            }
+        }
+
+        let estimated = estimator.drift().unwrap();
+        assert!(
+            (estimated - drift).abs() < 5e-6,
+            "estimated {}ppm",
+            estimated * 1e6
+        );
+
+        let mic_total: usize = mic_chunks.iter().map(Vec::len).sum();
+        let spk_total: usize = spk_chunks.iter().map(Vec::len).sum();
+        assert!(spk_total - mic_total > 600);
+
+        let out_chunk = 64;
+        let mut compensator = DriftCompensator::new(out_chunk).unwrap();
+        compensator.set_drift(estimated).unwrap();
+
+        let mut compensated = Vec::new();
+        for chunk in &spk_chunks {
+            compensator.push(chunk).unwrap();
+            while let Some(out) = compensator.pop_chunk() {
+                compensated.extend(out);
+            }
+        }
+
+        assert!(
+            (compensated.len() as i64 - mic_total as i64).abs() < 2 * out_chunk as i64,
+            "compensated {} vs mic {}",
+            compensated.len(),
+            mic_total
+        );
+        assert!(compensated.iter().all(|s| s.abs() <= 0.51));
+    }
 }
@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Read, Write};
 use std::num::{NonZeroU8, NonZeroU32};
 use std::path::Path;
 
@@ -128,6 +128,86 @@ pub fn decode_vorbis_to_mono_wav_file(
     decode_vorbis_to_wav_file_with_mode(ogg_path, wav_path, DecodeMode::Mono)
 }
 
+/// Decodes Ogg Vorbis from any reader, mixed down to mono and written to
+/// `pcm` as raw little-endian `f32` samples one block at a time, so a long
+/// recording is never held in memory.
+pub fn decode_vorbis_mono_to_pcm(ogg: impl Read, mut pcm: impl Write) -> Result<(), Error> {
+    let mut decoder = VorbisDecoder::new(ogg)?;
+
+    while let Some(block) = decoder.decode_audio_block()? {
+        let samples = block.samples();
+        if samples.is_empty() {
+            continue;
+        }
+
+        let frame_count = samples[0].len();
+        for (index, channel) in samples.iter().enumerate() {
+            if channel.len() != frame_count {
+                return Err(Error::ChannelDataLengthMismatch { channel: index });
+            }
+        }
+
+        let channel_count = samples.len() as f32;
+        let mut bytes = Vec::with_capacity(frame_count * 4);
+        for frame in 0..frame_count {
+            let sum: f32 = samples.iter().map(|channel| channel[frame]).sum();
+            bytes.extend_from_slice(&(sum / channel_count).to_le_bytes());
+        }
+        pcm.write_all(&bytes)?;
+    }
+
+    Ok(())
+}
+
+/// Encodes raw little-endian `f32` mono samples as they're read, with the
+/// one channel on both sides, so a long recording is never held in memory.
+/// Returns `ogg` once the stream is finished.
+pub fn encode_pcm_to_vorbis_mono_as_stereo<W: Write>(
+    mut pcm: impl Read,
+    ogg: W,
+    sample_rate: NonZeroU32,
+    settings: VorbisEncodeSettings,
+) -> Result<W, Error> {
+    let mut encoder = VorbisEncoderBuilder::new(sample_rate, NonZeroU8::new(2).unwrap(), ogg)?
+        .bitrate_management_strategy(VorbisBitrateManagementStrategy::QualityVbr {
+            target_quality: settings.quality,
+        })
+        .build()?;
+
+    let mut bytes = vec![0u8; settings.block_size.max(1) * 4];
+    loop {
+        let len = read_up_to(&mut pcm, &mut bytes)?;
+        // A torn sample at the end is dropped.
+        let samples: Vec<f32> = bytes[..len]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        if !samples.is_empty() {
+            encoder.encode_audio_block([samples.as_slice(), samples.as_slice()])?;
+        }
+        if len < bytes.len() {
+            break;
+        }
+    }
+
+    Ok(encoder.finish()?)
+}
+
+// Fills `buf` unless the reader ends first, since a reader may hand out less
+// than asked for before then.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
 fn decode_vorbis_to_wav_file_with_mode(
     ogg_path: impl AsRef<Path>,
     wav_path: impl AsRef<Path>,
@@ -122,10 +122,14 @@ impl AudioInput {
     }
 
     pub fn from_speaker() -> Self {
+        Self::from_speaker_with_target(CaptureTarget::System)
+    }
+
+    pub fn from_speaker_with_target(target: CaptureTarget) -> Self {
         Self {
             source: AudioSource::RealtimeSpeaker,
             mic: None,
-            speaker: Some(SpeakerInput::new().unwrap()),
+            speaker: Some(SpeakerInput::with_target(target).unwrap()),
             data: None,
         }
     }
@@ -12,6 +12,13 @@ const ANALYZE_CHUNK_SIZE: usize = 512;
 
 pub struct NormalizedSource<S: hypr_audio_interface::AsyncSource> {
     source: S,
+    normalizer: LoudnessNormalizer,
+}
+
+/// EBU R128 loudness normalization with a true-peak limiter, usable either as a
+/// stream adapter (`NormalizedSource`) or as a node in a `ProcessorChain`.
+pub struct LoudnessNormalizer {
+    sample_rate: u32,
     gain_linear: f32,
     ebur128: EbuR128,
     loudness_buffer: Vec<f32>,
@@ -63,14 +70,24 @@ pub trait NormalizeExt<S: hypr_audio_interface::AsyncSource> {
 
 impl<S: hypr_audio_interface::AsyncSource> NormalizeExt<S> for S {
     fn normalize(self) -> NormalizedSource<S> {
-        let sample_rate = self.sample_rate();
+        let normalizer = LoudnessNormalizer::new(self.sample_rate());
+
+        NormalizedSource {
+            source: self,
+            normalizer,
+        }
+    }
+}
+
+impl LoudnessNormalizer {
+    pub fn new(sample_rate: u32) -> Self {
         let ebur128 = EbuR128::new(CHANNELS, sample_rate, Mode::I | Mode::TRUE_PEAK)
             .expect("Failed to create EBU R128 analyzer");
 
         let true_peak_limit = 10_f32.powf(TRUE_PEAK_LIMIT as f32 / 20.0);
 
-        NormalizedSource {
-            source: self,
+        Self {
+            sample_rate,
             gain_linear: 1.0,
             ebur128,
             loudness_buffer: Vec::with_capacity(ANALYZE_CHUNK_SIZE),
@@ -78,6 +95,51 @@ impl<S: hypr_audio_interface::AsyncSource> NormalizeExt<S> for S {
             true_peak_limit,
         }
     }
+
+    pub fn process_sample(&mut self, sample: f32) -> f32 {
+        self.loudness_buffer.push(sample);
+
+        if self.loudness_buffer.len() >= ANALYZE_CHUNK_SIZE {
+            let _ = self.ebur128.add_frames_f32(&self.loudness_buffer);
+            self.loudness_buffer.clear();
+
+            if let Ok(current_lufs) = self.ebur128.loudness_global()
+                && current_lufs.is_finite()
+                && current_lufs < 0.0
+            {
+                let gain_db = TARGET_LUFS - current_lufs;
+                self.gain_linear = 10_f32.powf(gain_db as f32 / 20.0);
+            }
+        }
+
+        let amplified = sample * self.gain_linear;
+        self.limiter.process(amplified, self.true_peak_limit)
+    }
+}
+
+impl hypr_audio_utils::AudioProcessor for LoudnessNormalizer {
+    fn name(&self) -> &'static str {
+        "loudness"
+    }
+
+    fn process(
+        &mut self,
+        frame: &mut [f32],
+        _reference: Option<&[f32]>,
+    ) -> Result<(), hypr_audio_utils::Error> {
+        for sample in frame.iter_mut() {
+            *sample = self.process_sample(*sample);
+        }
+        Ok(())
+    }
+
+    fn latency(&self) -> usize {
+        self.limiter.lookahead_samples
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.sample_rate);
+    }
 }
 
 impl<S: hypr_audio_interface::AsyncSource + Unpin> Stream for NormalizedSource<S> {
@@ -88,27 +150,7 @@ impl<S: hypr_audio_interface::AsyncSource + Unpin> Stream for NormalizedSource<S
         let mut inner = std::pin::pin!(this.source.as_stream());
 
         match inner.as_mut().poll_next(cx) {
-            Poll::Ready(Some(sample)) => {
-                this.loudness_buffer.push(sample);
-
-                if this.loudness_buffer.len() >= ANALYZE_CHUNK_SIZE {
-                    let _ = this.ebur128.add_frames_f32(&this.loudness_buffer);
-                    this.loudness_buffer.clear();
-
-                    if let Ok(current_lufs) = this.ebur128.loudness_global()
-                        && current_lufs.is_finite()
-                        && current_lufs < 0.0
-                    {
-                        let gain_db = TARGET_LUFS - current_lufs;
-                        this.gain_linear = 10_f32.powf(gain_db as f32 / 20.0);
-                    }
-                }
-
-                let amplified = sample * this.gain_linear;
-                let limited = this.limiter.process(amplified, this.true_peak_limit);
-
-                Poll::Ready(Some(limited))
-            }
+            Poll::Ready(Some(sample)) => Poll::Ready(Some(this.normalizer.process_sample(sample))),
             Poll::Pending => Poll::Pending,
             Poll::Ready(None) => Poll::Ready(None),
         }
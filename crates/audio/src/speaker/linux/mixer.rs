@@ -0,0 +1,101 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Sums the output of several capture nodes into one mono signal.
+///
+/// Nodes deliver buffers independently, so samples are only released once
+/// every node has some queued. A node that stops delivering (paused or corked
+/// playback) is treated as silent once another node is `max_pending` ahead.
+pub(super) struct NodeMixer {
+    queues: HashMap<u32, VecDeque<f32>>,
+    max_pending: usize,
+}
+
+impl NodeMixer {
+    pub fn new(max_pending: usize) -> Self {
+        Self {
+            queues: HashMap::new(),
+            max_pending,
+        }
+    }
+
+    pub fn add_node(&mut self, id: u32) {
+        self.queues.entry(id).or_default();
+    }
+
+    pub fn remove_node(&mut self, id: u32) {
+        self.queues.remove(&id);
+    }
+
+    pub fn push(&mut self, id: u32, samples: &[f32]) {
+        if let Some(queue) = self.queues.get_mut(&id) {
+            queue.extend(samples);
+        }
+    }
+
+    pub fn drain(&mut self, out: &mut Vec<f32>) {
+        out.clear();
+
+        let shortest = self.queues.values().map(VecDeque::len).min().unwrap_or(0);
+        let longest = self.queues.values().map(VecDeque::len).max().unwrap_or(0);
+        let n = if longest > self.max_pending {
+            longest
+        } else {
+            shortest
+        };
+
+        if n == 0 {
+            return;
+        }
+
+        out.resize(n, 0.0);
+        for queue in self.queues.values_mut() {
+            let take = n.min(queue.len());
+            for (o, s) in out.iter_mut().zip(queue.drain(..take)) {
+                *o += s;
+            }
+        }
+
+        for o in out.iter_mut() {
+            *o = o.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waits_for_all_nodes() {
+        let mut mixer = NodeMixer::new(8);
+        let mut out = Vec::new();
+        mixer.add_node(1);
+        mixer.add_node(2);
+
+        mixer.push(1, &[0.25; 4]);
+        mixer.drain(&mut out);
+        assert!(out.is_empty());
+
+        mixer.push(2, &[0.5; 2]);
+        mixer.drain(&mut out);
+        assert_eq!(out, vec![0.75; 2]);
+    }
+
+    #[test]
+    fn test_treats_stalled_node_as_silence() {
+        let mut mixer = NodeMixer::new(4);
+        let mut out = Vec::new();
+        mixer.add_node(1);
+        mixer.add_node(2);
+
+        mixer.push(1, &[0.8; 6]);
+        mixer.drain(&mut out);
+        assert_eq!(out, vec![0.8; 6]);
+
+        mixer.remove_node(1);
+        mixer.push(2, &[1.0; 2]);
+        mixer.push(2, &[1.0; 2]);
+        mixer.drain(&mut out);
+        assert_eq!(out, vec![1.0; 4]);
+    }
+}
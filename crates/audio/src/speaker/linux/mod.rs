@@ -0,0 +1,190 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::thread;
+
+use anyhow::Result;
+use futures_util::Stream;
+use ringbuf::{
+    HeapCons, HeapProd, HeapRb,
+    traits::{Consumer, Split},
+};
+
+use super::{BUFFER_SIZE, CHUNK_SIZE, CaptureTarget};
+
+#[cfg(any(feature = "pipewire", test))]
+mod mixer;
+#[cfg(feature = "pipewire")]
+mod pipewire;
+mod pulse;
+#[cfg(any(feature = "pipewire", test))]
+mod targets;
+
+const SAMPLE_RATE: u32 = 48000;
+
+pub struct SpeakerInput {
+    sample_rate: u32,
+    target: CaptureTarget,
+}
+
+struct WakerState {
+    waker: Option<Waker>,
+    has_data: bool,
+}
+
+pub struct SpeakerStream {
+    consumer: HeapCons<f32>,
+    waker_state: Arc<Mutex<WakerState>>,
+    current_sample_rate: Arc<AtomicU32>,
+    read_buffer: Vec<f32>,
+    stop_signal: Arc<AtomicBool>,
+    _capture_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl SpeakerInput {
+    pub fn new() -> Result<Self> {
+        Self::with_target(CaptureTarget::System)
+    }
+
+    pub fn with_target(target: CaptureTarget) -> Result<Self> {
+        Ok(Self {
+            sample_rate: SAMPLE_RATE,
+            target,
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn stream(self) -> SpeakerStream {
+        let rb = HeapRb::<f32>::new(BUFFER_SIZE);
+        let (producer, consumer) = rb.split();
+
+        let waker_state = Arc::new(Mutex::new(WakerState {
+            waker: None,
+            has_data: false,
+        }));
+
+        let current_sample_rate = Arc::new(AtomicU32::new(self.sample_rate));
+        let stop_signal = Arc::new(AtomicBool::new(false));
+
+        let capture_thread = {
+            let target = self.target;
+            let waker_state = waker_state.clone();
+            let current_sample_rate = current_sample_rate.clone();
+            let stop_signal = stop_signal.clone();
+
+            thread::spawn(move || {
+                if let Err(e) = capture(
+                    target,
+                    producer,
+                    waker_state,
+                    current_sample_rate,
+                    stop_signal,
+                ) {
+                    tracing::error!(error = ?e, "speaker_capture_thread_failed");
+                }
+            })
+        };
+
+        SpeakerStream {
+            consumer,
+            waker_state,
+            current_sample_rate,
+            read_buffer: vec![0.0f32; CHUNK_SIZE],
+            stop_signal,
+            _capture_thread: Some(capture_thread),
+        }
+    }
+}
+
+fn capture(
+    target: CaptureTarget,
+    producer: HeapProd<f32>,
+    waker_state: Arc<Mutex<WakerState>>,
+    current_sample_rate: Arc<AtomicU32>,
+    stop_signal: Arc<AtomicBool>,
+) -> Result<()> {
+    #[cfg(feature = "pipewire")]
+    if let Some(targets) = targets::Targets::new(target) {
+        match pipewire::Session::connect() {
+            Ok(session) => match session.has_match(&targets) {
+                Ok(true) => {
+                    tracing::info!(?targets, "pipewire_app_capture");
+                    return session.run(targets, producer, waker_state, stop_signal);
+                }
+                Ok(false) => tracing::info!(?targets, "pipewire_no_match_falling_back_to_pulse"),
+                Err(e) => tracing::warn!(error = ?e, "pipewire_unavailable_falling_back_to_pulse"),
+            },
+            Err(e) => tracing::warn!(error = ?e, "pipewire_unavailable_falling_back_to_pulse"),
+        }
+    }
+
+    #[cfg(not(feature = "pipewire"))]
+    if target != CaptureTarget::System {
+        tracing::warn!(
+            ?target,
+            "app_capture_requires_pipewire_falling_back_to_pulse"
+        );
+    }
+
+    pulse::capture_loop(producer, waker_state, current_sample_rate, stop_signal)
+}
+
+fn wake_consumer(waker_state: &Arc<Mutex<WakerState>>) {
+    let should_wake = {
+        let mut state = waker_state.lock().unwrap();
+        if !state.has_data {
+            state.has_data = true;
+            state.waker.take()
+        } else {
+            None
+        }
+    };
+
+    if let Some(waker) = should_wake {
+        waker.wake();
+    }
+}
+
+impl SpeakerStream {
+    pub fn sample_rate(&self) -> u32 {
+        self.current_sample_rate.load(Ordering::Acquire)
+    }
+}
+
+impl Stream for SpeakerStream {
+    type Item = Vec<f32>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.as_mut().get_mut();
+        let popped = this.consumer.pop_slice(&mut this.read_buffer);
+
+        if popped > 0 {
+            return Poll::Ready(Some(this.read_buffer[..popped].to_vec()));
+        }
+
+        {
+            let mut state = this.waker_state.lock().unwrap();
+            state.has_data = false;
+            state.waker = Some(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for SpeakerStream {
+    fn drop(&mut self) {
+        self.stop_signal.store(true, Ordering::Release);
+        if let Ok(mut state) = self.waker_state.lock()
+            && let Some(waker) = state.waker.take()
+        {
+            waker.wake();
+        }
+    }
+}
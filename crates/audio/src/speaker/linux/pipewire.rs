@@ -0,0 +1,394 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use pipewire as pw;
+use pw::spa;
+use ringbuf::{HeapProd, traits::Producer};
+
+use super::mixer::NodeMixer;
+use super::targets::Targets;
+use super::{SAMPLE_RATE, WakerState, wake_consumer};
+
+// 20ms at 48kHz. Bounds how long a stalled node can hold back the others.
+const MAX_PENDING: usize = 960;
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An application's stream, as seen in the PipeWire graph.
+struct AppNode {
+    id: u32,
+    serial: String,
+    /// Lowercased process binary and application name.
+    names: Vec<String>,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Playback,
+    Recording,
+}
+
+struct NodeCapture {
+    _listener: pw::stream::StreamListener<u32>,
+    _stream: pw::stream::Stream,
+}
+
+struct Shared {
+    producer: HeapProd<f32>,
+    mixer: NodeMixer,
+    scratch: Vec<f32>,
+    waker_state: Arc<Mutex<WakerState>>,
+}
+
+impl Shared {
+    fn flush(&mut self) {
+        self.mixer.drain(&mut self.scratch);
+        if self.scratch.is_empty() {
+            return;
+        }
+
+        let pushed = self.producer.push_slice(&self.scratch);
+        if pushed < self.scratch.len() {
+            tracing::warn!(dropped = self.scratch.len() - pushed, "samples_dropped");
+        }
+        if pushed > 0 {
+            wake_consumer(&self.waker_state);
+        }
+    }
+}
+
+pub(super) struct Session {
+    mainloop: pw::main_loop::MainLoop,
+    _context: pw::context::Context,
+    core: pw::core::Core,
+}
+
+impl Session {
+    pub fn connect() -> Result<Self> {
+        pw::init();
+
+        let mainloop = pw::main_loop::MainLoop::new(None)
+            .map_err(|e| anyhow!("failed to create PipeWire main loop: {e}"))?;
+        let context = pw::context::Context::new(&mainloop)
+            .map_err(|e| anyhow!("failed to create PipeWire context: {e}"))?;
+        let core = context
+            .connect(None)
+            .map_err(|e| anyhow!("failed to connect to PipeWire: {e}"))?;
+
+        Ok(Self {
+            mainloop,
+            _context: context,
+            core,
+        })
+    }
+
+    /// Whether any application in `targets` is playing something right now.
+    /// Mic users only count once they've opened a microphone.
+    pub fn has_match(&self, targets: &Targets) -> Result<bool> {
+        let nodes: Rc<RefCell<Vec<AppNode>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let registry = self
+            .core
+            .get_registry()
+            .map_err(|e| anyhow!("failed to get PipeWire registry: {e}"))?;
+        let _registry_listener = registry
+            .add_listener_local()
+            .global({
+                let nodes = nodes.clone();
+                move |global| {
+                    if let Some(node) = match_app_node(global) {
+                        nodes.borrow_mut().push(node);
+                    }
+                }
+            })
+            .register();
+
+        self.roundtrip()?;
+
+        let nodes = nodes.borrow();
+        let mut targets = targets.clone();
+        for node in nodes.iter().filter(|n| n.kind == NodeKind::Recording) {
+            targets.add_mic_user(&node.names);
+        }
+
+        Ok(nodes
+            .iter()
+            .any(|n| n.kind == NodeKind::Playback && targets.matches(&n.names)))
+    }
+
+    /// Records the playback of every application in `targets`, following
+    /// their output nodes (and new mic users) as they come and go, until
+    /// `stop_signal` is set.
+    pub fn run(
+        self,
+        targets: Targets,
+        producer: HeapProd<f32>,
+        waker_state: Arc<Mutex<WakerState>>,
+        stop_signal: Arc<AtomicBool>,
+    ) -> Result<()> {
+        let targets = Rc::new(RefCell::new(targets));
+        let shared = Rc::new(RefCell::new(Shared {
+            producer,
+            mixer: NodeMixer::new(MAX_PENDING),
+            scratch: Vec::with_capacity(MAX_PENDING * 2),
+            waker_state,
+        }));
+        let playbacks: Rc<RefCell<HashMap<u32, AppNode>>> = Rc::new(RefCell::new(HashMap::new()));
+        let captures: Rc<RefCell<HashMap<u32, NodeCapture>>> =
+            Rc::new(RefCell::new(HashMap::new()));
+
+        let registry = self
+            .core
+            .get_registry()
+            .map_err(|e| anyhow!("failed to get PipeWire registry: {e}"))?;
+
+        let _registry_listener = registry
+            .add_listener_local()
+            .global({
+                let core = self.core.clone();
+                let shared = shared.clone();
+                let playbacks = playbacks.clone();
+                let captures = captures.clone();
+
+                move |global| {
+                    let Some(node) = match_app_node(global) else {
+                        return;
+                    };
+
+                    match node.kind {
+                        NodeKind::Recording => {
+                            if !targets.borrow_mut().add_mic_user(&node.names) {
+                                return;
+                            }
+                            tracing::info!(node = node.id, names = ?node.names, "pipewire_mic_user_added");
+
+                            let targets = targets.borrow();
+                            for playback in playbacks.borrow().values() {
+                                if targets.matches(&playback.names) {
+                                    attach(&core, playback, &shared, &captures);
+                                }
+                            }
+                        }
+                        NodeKind::Playback => {
+                            if targets.borrow().matches(&node.names) {
+                                attach(&core, &node, &shared, &captures);
+                            }
+                            playbacks.borrow_mut().insert(node.id, node);
+                        }
+                    }
+                }
+            })
+            .global_remove({
+                let shared = shared.clone();
+                let playbacks = playbacks.clone();
+                let captures = captures.clone();
+
+                move |id| {
+                    playbacks.borrow_mut().remove(&id);
+                    if captures.borrow_mut().remove(&id).is_some() {
+                        tracing::info!(node = id, "pipewire_node_detached");
+                        let mut shared = shared.borrow_mut();
+                        shared.mixer.remove_node(id);
+                        shared.flush();
+                    }
+                }
+            })
+            .register();
+
+        let stop_timer = self.mainloop.loop_().add_timer({
+            let mainloop = self.mainloop.clone();
+            move |_| {
+                if stop_signal.load(Ordering::Acquire) {
+                    mainloop.quit();
+                }
+            }
+        });
+        stop_timer
+            .update_timer(Some(STOP_POLL_INTERVAL), Some(STOP_POLL_INTERVAL))
+            .into_result()
+            .map_err(|e| anyhow!("failed to arm PipeWire stop timer: {e}"))?;
+
+        self.mainloop.run();
+
+        captures.borrow_mut().clear();
+        Ok(())
+    }
+
+    /// Runs the loop until the server has sent everything queued so far,
+    /// e.g. every existing global after a registry is created.
+    fn roundtrip(&self) -> Result<()> {
+        let pending = self
+            .core
+            .sync(0)
+            .map_err(|e| anyhow!("failed to sync with PipeWire: {e}"))?;
+
+        let _core_listener = self
+            .core
+            .add_listener_local()
+            .done({
+                let mainloop = self.mainloop.clone();
+                move |id, seq| {
+                    if id == pw::core::PW_ID_CORE && seq == pending {
+                        mainloop.quit();
+                    }
+                }
+            })
+            .register();
+
+        self.mainloop.run();
+        Ok(())
+    }
+}
+
+fn attach(
+    core: &pw::core::Core,
+    node: &AppNode,
+    shared: &Rc<RefCell<Shared>>,
+    captures: &Rc<RefCell<HashMap<u32, NodeCapture>>>,
+) {
+    if captures.borrow().contains_key(&node.id) {
+        return;
+    }
+
+    match capture_node(core, node, shared.clone()) {
+        Ok(capture) => {
+            tracing::info!(node = node.id, names = ?node.names, "pipewire_node_attached");
+            shared.borrow_mut().mixer.add_node(node.id);
+            captures.borrow_mut().insert(node.id, capture);
+        }
+        Err(e) => {
+            tracing::warn!(error = ?e, node = node.id, names = ?node.names, "pipewire_node_attach_failed");
+        }
+    }
+}
+
+/// Application playback and recording streams, leaving out this process's
+/// own (including the capture streams opened here).
+fn match_app_node(
+    global: &pw::registry::GlobalObject<&spa::utils::dict::DictRef>,
+) -> Option<AppNode> {
+    if global.type_ != pw::types::ObjectType::Node {
+        return None;
+    }
+
+    let props = global.props?;
+    let kind = match props.get("media.class")? {
+        "Stream/Output/Audio" => NodeKind::Playback,
+        "Stream/Input/Audio" => NodeKind::Recording,
+        _ => return None,
+    };
+
+    if props.get("application.process.id") == Some(std::process::id().to_string().as_str()) {
+        return None;
+    }
+
+    let names: Vec<String> = [
+        props.get("application.process.binary"),
+        props.get("application.name"),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::to_lowercase)
+    .collect();
+    if names.is_empty() {
+        return None;
+    }
+
+    let serial = props
+        .get("object.serial")
+        .map(str::to_string)
+        .unwrap_or_else(|| global.id.to_string());
+
+    Some(AppNode {
+        id: global.id,
+        serial,
+        names,
+        kind,
+    })
+}
+
+fn capture_node(
+    core: &pw::core::Core,
+    node: &AppNode,
+    shared: Rc<RefCell<Shared>>,
+) -> Result<NodeCapture> {
+    let props = pw::properties::properties! {
+        *pw::keys::MEDIA_TYPE => "Audio",
+        *pw::keys::MEDIA_CATEGORY => "Capture",
+        *pw::keys::NODE_NAME => "hyprnote-speaker-capture",
+        "target.object" => node.serial.as_str(),
+        "node.dont-reconnect" => "true",
+    };
+
+    let stream = pw::stream::Stream::new(core, "hyprnote-capture", props)
+        .map_err(|e| anyhow!("failed to create PipeWire stream: {e}"))?;
+
+    let listener = stream
+        .add_local_listener_with_user_data(node.id)
+        .process(move |stream, node_id| {
+            let Some(mut buffer) = stream.dequeue_buffer() else {
+                return;
+            };
+
+            let datas = buffer.datas_mut();
+            let Some(data) = datas.first_mut() else {
+                return;
+            };
+
+            let size = data.chunk().size() as usize;
+            let Some(bytes) = data.data() else {
+                return;
+            };
+
+            let samples: Vec<f32> = bytes[..size.min(bytes.len())]
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect();
+
+            let mut shared = shared.borrow_mut();
+            shared.mixer.push(*node_id, &samples);
+            shared.flush();
+        })
+        .register()
+        .map_err(|e| anyhow!("failed to register PipeWire stream listener: {e}"))?;
+
+    let mut audio_info = spa::param::audio::AudioInfoRaw::new();
+    audio_info.set_format(spa::param::audio::AudioFormat::F32LE);
+    audio_info.set_rate(SAMPLE_RATE);
+    audio_info.set_channels(1);
+
+    let format = spa::pod::Object {
+        type_: spa::utils::SpaTypes::ObjectParamFormat.as_raw(),
+        id: spa::param::ParamType::EnumFormat.as_raw(),
+        properties: audio_info.into(),
+    };
+    let values: Vec<u8> = spa::pod::serialize::PodSerializer::serialize(
+        std::io::Cursor::new(Vec::new()),
+        &spa::pod::Value::Object(format),
+    )
+    .map_err(|e| anyhow!("failed to serialize PipeWire format: {e:?}"))?
+    .0
+    .into_inner();
+    let mut params =
+        [spa::pod::Pod::from_bytes(&values)
+            .ok_or_else(|| anyhow!("invalid PipeWire format pod"))?];
+
+    stream
+        .connect(
+            spa::utils::Direction::Input,
+            None,
+            pw::stream::StreamFlags::AUTOCONNECT | pw::stream::StreamFlags::MAP_BUFFERS,
+            &mut params,
+        )
+        .map_err(|e| anyhow!("failed to connect PipeWire stream: {e}"))?;
+
+    Ok(NodeCapture {
+        _listener: listener,
+        _stream: stream,
+    })
+}
@@ -1,91 +1,19 @@
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
-use std::task::{Poll, Waker};
 use std::thread;
 
 use anyhow::{Context, Result};
-use futures_util::Stream;
 use libpulse_binding as pulse;
 use pulse::context::{Context as PaContext, FlagSet as ContextFlagSet};
 use pulse::mainloop::threaded::Mainloop;
 use pulse::sample::{Format, Spec};
 use pulse::stream::{FlagSet as StreamFlagSet, Stream as PaStream};
-use ringbuf::{
-    HeapCons, HeapProd, HeapRb,
-    traits::{Consumer, Producer, Split},
-};
+use ringbuf::{HeapProd, traits::Producer};
 
-use super::{BUFFER_SIZE, CHUNK_SIZE};
+use super::{CHUNK_SIZE, SAMPLE_RATE, WakerState, wake_consumer};
 
-const SAMPLE_RATE: u32 = 48000;
-
-pub struct SpeakerInput {
-    sample_rate: u32,
-}
-
-struct WakerState {
-    waker: Option<Waker>,
-    has_data: bool,
-}
-
-pub struct SpeakerStream {
-    consumer: HeapCons<f32>,
-    waker_state: Arc<Mutex<WakerState>>,
-    current_sample_rate: Arc<AtomicU32>,
-    read_buffer: Vec<f32>,
-    stop_signal: Arc<AtomicBool>,
-    _capture_thread: Option<thread::JoinHandle<()>>,
-}
-
-impl SpeakerInput {
-    pub fn new() -> Result<Self> {
-        Ok(Self {
-            sample_rate: SAMPLE_RATE,
-        })
-    }
-
-    pub fn sample_rate(&self) -> u32 {
-        self.sample_rate
-    }
-
-    pub fn stream(self) -> SpeakerStream {
-        let rb = HeapRb::<f32>::new(BUFFER_SIZE);
-        let (producer, consumer) = rb.split();
-
-        let waker_state = Arc::new(Mutex::new(WakerState {
-            waker: None,
-            has_data: false,
-        }));
-
-        let current_sample_rate = Arc::new(AtomicU32::new(self.sample_rate));
-        let stop_signal = Arc::new(AtomicBool::new(false));
-
-        let capture_thread = {
-            let waker_state = waker_state.clone();
-            let current_sample_rate = current_sample_rate.clone();
-            let stop_signal = stop_signal.clone();
-
-            thread::spawn(move || {
-                if let Err(e) =
-                    capture_loop(producer, waker_state, current_sample_rate, stop_signal)
-                {
-                    tracing::error!(error = ?e, "PulseAudio capture thread failed");
-                }
-            })
-        };
-
-        SpeakerStream {
-            consumer,
-            waker_state,
-            current_sample_rate,
-            read_buffer: vec![0.0f32; CHUNK_SIZE],
-            stop_signal,
-            _capture_thread: Some(capture_thread),
-        }
-    }
-}
-
-fn capture_loop(
+/// Records the default sink's monitor, i.e. everything the machine plays.
+pub(super) fn capture_loop(
     mut producer: HeapProd<f32>,
     waker_state: Arc<Mutex<WakerState>>,
     current_sample_rate: Arc<AtomicU32>,
@@ -283,60 +211,3 @@ fn get_default_monitor_device(mainloop: &mut Mainloop, context: &PaContext) -> O
         .ok()
         .flatten()
 }
-
-fn wake_consumer(waker_state: &Arc<Mutex<WakerState>>) {
-    let should_wake = {
-        let mut state = waker_state.lock().unwrap();
-        if !state.has_data {
-            state.has_data = true;
-            state.waker.take()
-        } else {
-            None
-        }
-    };
-
-    if let Some(waker) = should_wake {
-        waker.wake();
-    }
-}
-
-impl SpeakerStream {
-    pub fn sample_rate(&self) -> u32 {
-        self.current_sample_rate.load(Ordering::Acquire)
-    }
-}
-
-impl Stream for SpeakerStream {
-    type Item = Vec<f32>;
-
-    fn poll_next(
-        mut self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> Poll<Option<Self::Item>> {
-        let this = self.as_mut().get_mut();
-        let popped = this.consumer.pop_slice(&mut this.read_buffer);
-
-        if popped > 0 {
-            return Poll::Ready(Some(this.read_buffer[..popped].to_vec()));
-        }
-
-        {
-            let mut state = this.waker_state.lock().unwrap();
-            state.has_data = false;
-            state.waker = Some(cx.waker().clone());
-        }
-
-        Poll::Pending
-    }
-}
-
-impl Drop for SpeakerStream {
-    fn drop(&mut self) {
-        self.stop_signal.store(true, Ordering::Release);
-        if let Ok(mut state) = self.waker_state.lock()
-            && let Some(waker) = state.waker.take()
-        {
-            waker.wake();
-        }
-    }
-}
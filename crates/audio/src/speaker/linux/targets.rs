@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use super::CaptureTarget;
+
+/// The applications whose playback is recorded, matched by lowercased process
+/// binary or application name.
+#[derive(Debug, Clone)]
+pub(super) struct Targets {
+    apps: HashSet<String>,
+    follow_mic_users: bool,
+}
+
+impl Targets {
+    /// `None` when `target` covers the whole system.
+    pub fn new(target: CaptureTarget) -> Option<Self> {
+        match target {
+            CaptureTarget::System => None,
+            CaptureTarget::Apps(apps) if apps.is_empty() => None,
+            CaptureTarget::Apps(apps) => Some(Self {
+                apps: apps.iter().map(|a| a.to_lowercase()).collect(),
+                follow_mic_users: false,
+            }),
+            CaptureTarget::MicUsers => Some(Self {
+                apps: HashSet::new(),
+                follow_mic_users: true,
+            }),
+        }
+    }
+
+    pub fn matches(&self, names: &[String]) -> bool {
+        names.iter().any(|name| self.apps.contains(name))
+    }
+
+    /// Starts recording an application that opened a microphone, if mic users
+    /// are followed. Returns whether it wasn't recorded before.
+    pub fn add_mic_user(&mut self, names: &[String]) -> bool {
+        if !self.follow_mic_users || names.is_empty() || self.matches(names) {
+            return false;
+        }
+
+        self.apps.extend(names.iter().cloned());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_apps_match_case_insensitively() {
+        let targets = Targets::new(CaptureTarget::Apps(vec!["Zoom".into()])).unwrap();
+
+        assert!(targets.matches(&names(&["zoom", "zoom meeting"])));
+        assert!(!targets.matches(&names(&["firefox"])));
+    }
+
+    #[test]
+    fn test_system_and_empty_apps_have_no_targets() {
+        assert!(Targets::new(CaptureTarget::System).is_none());
+        assert!(Targets::new(CaptureTarget::Apps(vec![])).is_none());
+    }
+
+    #[test]
+    fn test_mic_users_are_added_once() {
+        let mut targets = Targets::new(CaptureTarget::MicUsers).unwrap();
+        assert!(!targets.matches(&names(&["firefox"])));
+
+        assert!(targets.add_mic_user(&names(&["firefox", "firefox web browser"])));
+        assert!(!targets.add_mic_user(&names(&["firefox"])));
+        assert!(targets.matches(&names(&["firefox web browser"])));
+    }
+
+    #[test]
+    fn test_fixed_apps_ignore_mic_users() {
+        let mut targets = Targets::new(CaptureTarget::Apps(vec!["zoom".into()])).unwrap();
+
+        assert!(!targets.add_mic_user(&names(&["firefox"])));
+        assert!(!targets.matches(&names(&["firefox"])));
+    }
+}
@@ -37,6 +37,23 @@ type InnerStream = PlatformSpeakerStream;
 #[cfg(test)]
 type InnerStream = mock::MockInnerStream;
 
+/// Which playback the speaker stream records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CaptureTarget {
+    /// Everything the default output device plays.
+    #[default]
+    System,
+    /// Only the output of these applications, matched by process binary or
+    /// application name. Linux only (PipeWire); elsewhere this records the
+    /// whole system.
+    Apps(Vec<String>),
+    /// The output of applications recording from a microphone (in a call),
+    /// followed as they start and stop, excluding this process. Linux only
+    /// (PipeWire); elsewhere, or when no such application plays anything,
+    /// this records the whole system.
+    MicUsers,
+}
+
 // https://github.com/floneum/floneum/blob/50afe10/interfaces/kalosm-sound/src/source/mic.rs#L41
 pub struct SpeakerInput {
     inner: PlatformSpeakerInput,
@@ -48,6 +65,21 @@ impl SpeakerInput {
         Ok(Self { inner })
     }
 
+    pub fn with_target(target: CaptureTarget) -> Result<Self> {
+        #[cfg(target_os = "linux")]
+        let inner = PlatformSpeakerInput::with_target(target)?;
+
+        #[cfg(not(target_os = "linux"))]
+        let inner = {
+            if target != CaptureTarget::System {
+                tracing::debug!(?target, "app_capture_unsupported");
+            }
+            PlatformSpeakerInput::new()?
+        };
+
+        Ok(Self { inner })
+    }
+
     pub fn sample_rate(&self) -> u32 {
         self.inner.sample_rate()
     }
@@ -0,0 +1,11 @@
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Keyring(#[from] keyring::Error),
+    #[error("key derivation failed: {0}")]
+    KeyDerivation(String),
+    #[error("invalid key")]
+    InvalidKey,
+}
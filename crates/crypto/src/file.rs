@@ -0,0 +1,179 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{DecryptReader, EncryptWriter, Key, MAGIC};
+
+fn locked() -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, "encrypted_data_locked")
+}
+
+fn tmp_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+pub fn is_encrypted_file(path: &Path) -> io::Result<bool> {
+    let mut magic = [0u8; MAGIC.len()];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// A file opened with [`open`], decrypted as it's read when it's encrypted.
+pub enum FileReader {
+    Plain(BufReader<File>),
+    Encrypted(DecryptReader<BufReader<File>>),
+}
+
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            FileReader::Plain(reader) => reader.read(buf),
+            FileReader::Encrypted(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Opens a file that may or may not be encrypted yet, so callers work the
+/// same before, during and after migrating a folder. Nothing is read until
+/// asked for, so large files like recordings can be streamed.
+pub fn open(path: &Path, key: Option<&Key>) -> io::Result<FileReader> {
+    if !is_encrypted_file(path)? {
+        return Ok(FileReader::Plain(BufReader::new(File::open(path)?)));
+    }
+    let key = key.ok_or_else(locked)?;
+    Ok(FileReader::Encrypted(DecryptReader::new(
+        BufReader::new(File::open(path)?),
+        key,
+    )?))
+}
+
+/// Reads all of a file that may or may not be encrypted yet.
+pub fn read(path: &Path, key: Option<&Key>) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    open(path, key)?.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+pub fn read_to_string(path: &Path, key: Option<&Key>) -> io::Result<String> {
+    String::from_utf8(read(path, key)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Writes `contents`, encrypted chunk by chunk on the way to the file when
+/// there's a key.
+pub fn write(path: &Path, contents: impl AsRef<[u8]>, key: Option<&Key>) -> io::Result<()> {
+    match key {
+        Some(key) => {
+            let mut writer = EncryptWriter::new(BufWriter::new(File::create(path)?), key)?;
+            writer.write_all(contents.as_ref())?;
+            writer.finish()?;
+            Ok(())
+        }
+        None => std::fs::write(path, contents),
+    }
+}
+
+/// Copies as much of an encrypted file into `out` as authenticates, for a
+/// stream that was never finished, e.g. because the app stopped while
+/// writing it. The tail that wasn't sealed yet is lost. Returns how many
+/// bytes were copied.
+pub fn copy_partial(path: &Path, key: &Key, out: &mut impl Write) -> io::Result<u64> {
+    let mut reader = DecryptReader::new(BufReader::new(File::open(path)?), key)?;
+    let mut copied = 0;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(copied),
+            Ok(n) => {
+                out.write_all(&buf[..n])?;
+                copied += n as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => return Ok(copied),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// [`copy_partial`] into memory.
+pub fn read_partial(path: &Path, key: &Key) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    copy_partial(path, key, &mut out)?;
+    Ok(out)
+}
+
+/// Overwrites a file with zeros before removing it, for plain copies of
+/// encrypted data. Copy-on-write filesystems and SSDs may still keep the
+/// old blocks; this keeps them from being read back through the file.
+pub fn remove_securely(path: &Path) -> io::Result<()> {
+    let mut left = std::fs::metadata(path)?.len();
+    {
+        let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+        let zeros = [0u8; 64 * 1024];
+        while left > 0 {
+            let n = left.min(zeros.len() as u64) as usize;
+            file.write_all(&zeros[..n])?;
+            left -= n as u64;
+        }
+        file.sync_all()?;
+    }
+    std::fs::remove_file(path)
+}
+
+/// Streams `src` into an encrypted `dst`, without holding it in memory.
+pub fn encrypt_file(src: &Path, dst: &Path, key: &Key) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(src)?);
+    let mut writer = EncryptWriter::new(BufWriter::new(File::create(dst)?), key)?;
+    io::copy(&mut reader, &mut writer)?;
+    writer
+        .finish()?
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()
+}
+
+pub fn decrypt_file(src: &Path, dst: &Path, key: &Key) -> io::Result<()> {
+    let mut reader = DecryptReader::new(BufReader::new(File::open(src)?), key)?;
+    let mut writer = BufWriter::new(File::create(dst)?);
+    io::copy(&mut reader, &mut writer)?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
+/// Encrypts a plain file where it is. The original is only replaced once
+/// the encrypted copy is complete, so an interruption leaves it untouched.
+/// Returns `false` if it was already encrypted.
+pub fn encrypt_in_place(path: &Path, key: &Key) -> io::Result<bool> {
+    if is_encrypted_file(path)? {
+        return Ok(false);
+    }
+
+    let tmp = tmp_path(path, ".enc.tmp");
+    if let Err(e) = encrypt_file(path, &tmp, key) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(true)
+}
+
+/// The reverse of [`encrypt_in_place`], for turning encryption off.
+pub fn decrypt_in_place(path: &Path, key: &Key) -> io::Result<bool> {
+    if !is_encrypted_file(path)? {
+        return Ok(false);
+    }
+
+    // Whatever was decrypted before a failure is plain, so it's wiped
+    // rather than just unlinked.
+    let tmp = tmp_path(path, ".dec.tmp");
+    if let Err(e) = decrypt_file(path, &tmp, key).and_then(|()| std::fs::rename(&tmp, path)) {
+        if tmp.exists() {
+            let _ = remove_securely(&tmp);
+        }
+        return Err(e);
+    }
+    Ok(true)
+}
@@ -0,0 +1,119 @@
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::aead::rand_core::RngCore;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+
+/// A 256-bit key for the local data: the database, recordings and notes.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Key([u8; KEY_LEN]);
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+impl Key {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; KEY_LEN];
+        OsRng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, crate::Error> {
+        let bytes: [u8; KEY_LEN] = bytes.try_into().map_err(|_| crate::Error::InvalidKey)?;
+        Ok(Self(bytes))
+    }
+
+    /// Derives the key from a passphrase with Argon2id, for machines without
+    /// a usable OS keyring. The salt is not secret but has to be kept.
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self, crate::Error> {
+        let mut bytes = [0u8; KEY_LEN];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut bytes)
+            .map_err(|e| crate::Error::KeyDerivation(e.to_string()))?;
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, crate::Error> {
+        Self::from_bytes(&from_hex(hex).ok_or(crate::Error::InvalidKey)?)
+    }
+}
+
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Where the key lives in the OS keyring: the macOS Keychain, Windows
+/// Credential Manager or the Secret Service on Linux.
+pub struct Keystore {
+    service: String,
+    user: String,
+}
+
+impl Keystore {
+    pub fn new(service: impl Into<String>, user: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            user: user.into(),
+        }
+    }
+
+    fn entry(&self) -> Result<keyring::Entry, crate::Error> {
+        Ok(keyring::Entry::new(&self.service, &self.user)?)
+    }
+
+    pub fn load(&self) -> Result<Option<Key>, crate::Error> {
+        match self.entry()?.get_password() {
+            Ok(hex) => Key::from_hex(&hex).map(Some),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, key: &Key) -> Result<(), crate::Error> {
+        Ok(self.entry()?.set_password(&key.to_hex())?)
+    }
+
+    pub fn load_or_create(&self) -> Result<Key, crate::Error> {
+        if let Some(key) = self.load()? {
+            return Ok(key);
+        }
+        let key = Key::generate();
+        self.save(&key)?;
+        Ok(key)
+    }
+
+    pub fn delete(&self) -> Result<(), crate::Error> {
+        match self.entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
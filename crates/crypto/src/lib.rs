@@ -0,0 +1,158 @@
+mod error;
+mod file;
+mod key;
+mod stream;
+
+pub use error::*;
+pub use file::*;
+pub use key::*;
+pub use stream::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let key = Key::generate();
+
+        for len in [0, 1, 1000, 64 * 1024, 64 * 1024 + 1, 3 * 64 * 1024 + 17] {
+            let plaintext = sample(len);
+            let ciphertext = encrypt(&plaintext, &key).unwrap();
+            assert!(is_encrypted(&ciphertext));
+            assert_eq!(
+                decrypt(&ciphertext, &key).unwrap(),
+                plaintext,
+                "len {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_streaming_writes() {
+        let key = Key::generate();
+        let plaintext = sample(200_000);
+
+        let mut writer = EncryptWriter::new(Vec::new(), &key).unwrap();
+        for piece in plaintext.chunks(4096 + 3) {
+            writer.write_all(piece).unwrap();
+        }
+        let ciphertext = writer.finish().unwrap();
+
+        let mut reader = DecryptReader::new(ciphertext.as_slice(), &key).unwrap();
+        let mut out = Vec::new();
+        let mut buf = [0u8; 777];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(out, plaintext);
+    }
+
+    #[test]
+    fn test_rejects_tampering() {
+        let key = Key::generate();
+        let ciphertext = encrypt(&sample(150_000), &key).unwrap();
+
+        assert!(decrypt(&ciphertext, &Key::generate()).is_err());
+
+        let mut flipped = ciphertext.clone();
+        flipped[100_000] ^= 1;
+        assert!(decrypt(&flipped, &key).is_err());
+
+        // Cut at a chunk boundary, which would otherwise look complete.
+        let boundary = 8 + 19 + (64 * 1024 + 16);
+        assert!(decrypt(&ciphertext[..boundary], &key).is_err());
+        assert!(decrypt(&ciphertext[..ciphertext.len() - 1], &key).is_err());
+
+        let mut unfinished = Vec::new();
+        {
+            let mut writer = EncryptWriter::new(&mut unfinished, &key).unwrap();
+            writer.write_all(&sample(10)).unwrap();
+            writer.flush().unwrap();
+        }
+        assert!(decrypt(&unfinished, &key).is_err());
+    }
+
+    #[test]
+    fn test_read_partial() {
+        let key = Key::generate();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audio.pcm");
+        let plaintext = sample(3 * 64 * 1024 + 100);
+
+        {
+            let file = std::fs::File::create(&path).unwrap();
+            let mut writer = EncryptWriter::new(file, &key).unwrap();
+            writer.write_all(&plaintext).unwrap();
+            writer.flush().unwrap();
+        }
+        // The last full chunk is only sealed as such once the stream goes on
+        // or finishes, so it's lost along with the unwritten tail.
+        assert_eq!(
+            read_partial(&path, &key).unwrap(),
+            plaintext[..2 * 64 * 1024]
+        );
+
+        write(&path, &plaintext, Some(&key)).unwrap();
+        assert_eq!(read_partial(&path, &key).unwrap(), plaintext);
+        let mut streamed = Vec::new();
+        open(&path, Some(&key))
+            .unwrap()
+            .read_to_end(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, plaintext);
+
+        remove_securely(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_passphrase() {
+        let salt = generate_salt();
+        let a = Key::from_passphrase("correct horse", &salt).unwrap();
+        let b = Key::from_passphrase("correct horse", &salt).unwrap();
+        let c = Key::from_passphrase("correct horse", &generate_salt()).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        assert_eq!(Key::from_hex(&a.to_hex()).unwrap(), a);
+        assert!(Key::from_hex("abc").is_err());
+        assert_eq!(format!("{:?}", a), "Key(..)");
+    }
+
+    #[test]
+    fn test_encrypt_in_place() {
+        let key = Key::generate();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("_memo.md");
+        std::fs::write(&path, "# Notes\n").unwrap();
+
+        assert_eq!(read_to_string(&path, None).unwrap(), "# Notes\n");
+        assert!(encrypt_in_place(&path, &key).unwrap());
+        assert!(!encrypt_in_place(&path, &key).unwrap());
+        assert!(is_encrypted_file(&path).unwrap());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        assert_eq!(
+            read(&path, None).unwrap_err().kind(),
+            std::io::ErrorKind::PermissionDenied
+        );
+        assert_eq!(read_to_string(&path, Some(&key)).unwrap(), "# Notes\n");
+
+        write(&path, "# Edited\n", Some(&key)).unwrap();
+        assert!(decrypt_in_place(&path, &Key::generate()).is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert!(decrypt_in_place(&path, &key).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Edited\n");
+    }
+}
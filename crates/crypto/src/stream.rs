@@ -0,0 +1,191 @@
+//! Files are encrypted with XChaCha20-Poly1305 in the STREAM construction:
+//! a header, then the plaintext in fixed-size chunks that are each sealed
+//! with their position in the stream. Chunks can't be reordered or dropped,
+//! and a file cut short fails to decrypt instead of reading as complete.
+//!
+//! ```text
+//! MAGIC (8 bytes) | nonce (19 bytes) | chunk ... | last chunk
+//! ```
+//!
+//! Every chunk but the last holds exactly `CHUNK_LEN` bytes of plaintext;
+//! the last one holds fewer, possibly none.
+
+use std::io::{self, Read, Write};
+
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305};
+
+use crate::Key;
+
+pub const MAGIC: &[u8; 8] = b"HYPRENC1";
+
+const NONCE_LEN: usize = 19;
+const TAG_LEN: usize = 16;
+const CHUNK_LEN: usize = 64 * 1024;
+const HEADER_LEN: usize = MAGIC.len() + NONCE_LEN;
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+pub struct EncryptWriter<W: Write> {
+    inner: W,
+    encryptor: Option<EncryptorBE32<XChaCha20Poly1305>>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> EncryptWriter<W> {
+    pub fn new(mut inner: W, key: &Key) -> io::Result<Self> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        inner.write_all(MAGIC)?;
+        inner.write_all(&nonce)?;
+
+        let aead = XChaCha20Poly1305::new(key.as_bytes().into());
+        Ok(Self {
+            inner,
+            encryptor: Some(EncryptorBE32::from_aead(aead, nonce.as_ref().into())),
+            buf: Vec::with_capacity(CHUNK_LEN),
+        })
+    }
+
+    /// Seals the last chunk. A writer dropped without this leaves a file
+    /// that is rejected as truncated.
+    pub fn finish(mut self) -> io::Result<W> {
+        let encryptor = self
+            .encryptor
+            .take()
+            .ok_or_else(|| invalid_data("stream already finished"))?;
+        let chunk = encryptor
+            .encrypt_last(self.buf.as_slice())
+            .map_err(|_| invalid_data("encryption failed"))?;
+        self.inner.write_all(&chunk)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncryptWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let encryptor = self
+            .encryptor
+            .as_mut()
+            .ok_or_else(|| invalid_data("stream already finished"))?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        // A full chunk is only sealed once more data follows it, so the
+        // last chunk is always short.
+        if self.buf.len() == CHUNK_LEN {
+            let chunk = encryptor
+                .encrypt_next(self.buf.as_slice())
+                .map_err(|_| invalid_data("encryption failed"))?;
+            self.inner.write_all(&chunk)?;
+            self.buf.clear();
+        }
+
+        let take = data.len().min(CHUNK_LEN - self.buf.len());
+        self.buf.extend_from_slice(&data[..take]);
+        Ok(take)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct DecryptReader<R: Read> {
+    inner: R,
+    decryptor: Option<DecryptorBE32<XChaCha20Poly1305>>,
+    // One byte more than a sealed chunk, to tell a full chunk from the last.
+    sealed: Vec<u8>,
+    plain: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> DecryptReader<R> {
+    pub fn new(mut inner: R, key: &Key) -> io::Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        inner
+            .read_exact(&mut header)
+            .map_err(|_| invalid_data("not an encrypted file"))?;
+        if !is_encrypted(&header) {
+            return Err(invalid_data("not an encrypted file"));
+        }
+
+        let aead = XChaCha20Poly1305::new(key.as_bytes().into());
+        let nonce = &header[MAGIC.len()..];
+        Ok(Self {
+            inner,
+            decryptor: Some(DecryptorBE32::from_aead(aead, nonce.into())),
+            sealed: Vec::with_capacity(CHUNK_LEN + TAG_LEN + 1),
+            plain: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        let want = CHUNK_LEN + TAG_LEN + 1;
+        while self.sealed.len() < want {
+            let start = self.sealed.len();
+            self.sealed.resize(want, 0);
+            let n = self.inner.read(&mut self.sealed[start..])?;
+            self.sealed.truncate(start + n);
+            if n == 0 {
+                break;
+            }
+        }
+
+        self.pos = 0;
+        if self.sealed.len() == want {
+            let decryptor = self.decryptor.as_mut().expect("checked by caller");
+            self.plain = decryptor
+                .decrypt_next(&self.sealed[..CHUNK_LEN + TAG_LEN])
+                .map_err(|_| invalid_data("decryption failed"))?;
+            self.sealed.drain(..CHUNK_LEN + TAG_LEN);
+        } else {
+            let decryptor = self.decryptor.take().expect("checked by caller");
+            self.plain = decryptor
+                .decrypt_last(self.sealed.as_slice())
+                .map_err(|_| invalid_data("decryption failed"))?;
+            self.sealed.clear();
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.plain.len() {
+            if self.decryptor.is_none() {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let n = out.len().min(self.plain.len() - self.pos);
+        out[..n].copy_from_slice(&self.plain[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+pub fn encrypt(plaintext: &[u8], key: &Key) -> io::Result<Vec<u8>> {
+    let mut writer = EncryptWriter::new(Vec::new(), key)?;
+    writer.write_all(plaintext)?;
+    writer.finish()
+}
+
+pub fn decrypt(ciphertext: &[u8], key: &Key) -> io::Result<Vec<u8>> {
+    let mut plaintext = Vec::new();
+    DecryptReader::new(ciphertext, key)?.read_to_end(&mut plaintext)?;
+    Ok(plaintext)
+}
@@ -19,6 +19,7 @@ fn run(name: &str) {
             start_ms: Some((v["start"].as_f64().unwrap() * 1000.0) as u64),
             end_ms: Some((v["end"].as_f64().unwrap() * 1000.0) as u64),
             confidence: Some(1.0),
+            channel: None,
         })
         .collect();
 
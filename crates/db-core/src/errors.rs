@@ -14,6 +14,14 @@ pub enum Error {
     InvalidDatabaseConfig(String),
     #[error("invalid input: {0}")]
     InvalidInput(String),
+    #[error("migration {0} was changed after it was applied")]
+    MigrationChanged(i32),
+    #[error("migration {0} has no down script")]
+    MigrationIrreversible(i32),
+    #[error("migration {0} is newer than this build")]
+    MigrationUnknown(i32),
+    #[error("table {0} can't be synced: it or its updated_at column is missing")]
+    SyncTableUnavailable(String),
 }
 
 impl Serialize for Error {
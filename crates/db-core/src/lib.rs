@@ -1,16 +1,25 @@
 use std::sync::Arc;
 
 mod errors;
+mod migrate;
+mod sync;
+
 pub use errors::*;
+pub use migrate::*;
+pub use sync::*;
 
 pub use libsql;
 
-pub const MIGRATION_TABLE_SQL: &str = include_str!("./migration.sql");
-
 #[derive(Clone)]
 pub enum Database {
     StaticConnection(libsql::Connection),
     DynamicConnection(Arc<libsql::Database>),
+    /// Reads and writes go to the local connection; `sync()` exchanges rows
+    /// with the remote one.
+    SyncedConnection {
+        local: libsql::Connection,
+        remote: Arc<libsql::Database>,
+    },
 }
 
 impl Database {
@@ -18,11 +27,25 @@ impl Database {
         match self {
             Database::StaticConnection(conn) => Ok(conn.clone()),
             Database::DynamicConnection(db) => db.connect().map_err(Into::into),
+            Database::SyncedConnection { local, .. } => Ok(local.clone()),
+        }
+    }
+
+    pub fn remote_conn(&self) -> Result<Option<libsql::Connection>, crate::Error> {
+        match self {
+            Database::SyncedConnection { remote, .. } => Ok(Some(remote.connect()?)),
+            _ => Ok(None),
         }
     }
 
-    pub async fn sync(&self) -> Result<(), crate::Error> {
-        Ok(())
+    /// Pushes local changes to the remote and pulls remote ones, leaving both
+    /// sides with the same rows. Does nothing unless the database was built
+    /// with both `.local()` and `.remote()`.
+    pub async fn sync(&self, tables: &[SyncTable]) -> Result<SyncSummary, crate::Error> {
+        match self.remote_conn()? {
+            Some(remote) => sync_tables(&self.conn()?, &remote, tables).await,
+            None => Ok(SyncSummary::default()),
+        }
     }
 }
 
@@ -30,7 +53,10 @@ impl Database {
 struct DatabaseConfig {
     memory: Option<bool>,
     local_path: Option<std::path::PathBuf>,
+    read_only: bool,
     remote_config: Option<(String, String)>,
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<Vec<u8>>,
 }
 
 #[derive(Default)]
@@ -49,16 +75,49 @@ impl DatabaseBuilder {
         self
     }
 
+    /// Opens the local database without write access, e.g. to read another
+    /// app's copy without altering it.
+    pub fn read_only(mut self) -> Self {
+        self.config.read_only = true;
+        self
+    }
+
     pub fn remote(mut self, url: impl Into<String>, token: impl Into<String>) -> Self {
         self.config.remote_config = Some((url.into(), token.into()));
         self
     }
 
+    /// Encrypts the local database file with `key`. A file created without
+    /// one has to be converted with [`encrypt_in_place`] first.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.config.encryption_key = Some(key.into());
+        self
+    }
+
+    async fn build_local(&self, path: &std::path::Path) -> Result<libsql::Database, crate::Error> {
+        let mut builder = libsql::Builder::new_local(path);
+
+        if self.config.read_only {
+            builder = builder.flags(libsql::OpenFlags::SQLITE_OPEN_READ_ONLY);
+        }
+
+        #[cfg(feature = "encryption")]
+        if let Some(key) = &self.config.encryption_key {
+            builder = builder.encryption_config(libsql::EncryptionConfig::new(
+                libsql::Cipher::Aes256Cbc,
+                key.clone().into(),
+            ));
+        }
+
+        Ok(builder.build().await?)
+    }
+
     pub async fn build(self) -> Result<Database, crate::Error> {
         let db = match (
             self.config.memory,
-            self.config.local_path,
-            self.config.remote_config,
+            self.config.local_path.as_deref(),
+            self.config.remote_config.clone(),
         ) {
             (Some(true), _, _) => {
                 let db = libsql::Builder::new_local(":memory:").build().await?;
@@ -66,7 +125,7 @@ impl DatabaseBuilder {
                 Database::StaticConnection(conn)
             }
             (_, Some(path), None) => {
-                let db = libsql::Builder::new_local(path).build().await?;
+                let db = self.build_local(path).await?;
                 let conn = db.connect()?;
                 Database::StaticConnection(conn)
             }
@@ -75,12 +134,12 @@ impl DatabaseBuilder {
                 Database::DynamicConnection(Arc::new(db))
             }
             (_, Some(path), Some((url, token))) => {
-                let db = libsql::Builder::new_remote_replica(path, url, token)
-                    .read_your_writes(true)
-                    .sync_interval(std::time::Duration::from_secs(300))
-                    .build()
-                    .await?;
-                Database::DynamicConnection(Arc::new(db))
+                let local = self.build_local(path).await?;
+                let remote = libsql::Builder::new_remote(url, token).build().await?;
+                Database::SyncedConnection {
+                    local: local.connect()?,
+                    remote: Arc::new(remote),
+                }
             }
             (_, None, None) => Err(crate::Error::InvalidDatabaseConfig(
                 "either '.memory()' or '.local()' or '.remote()' must be called".to_string(),
@@ -91,105 +150,42 @@ impl DatabaseBuilder {
     }
 }
 
-pub enum TrackingSource {
-    Pragma,
-    Table,
-}
-
-// TODO:
-// Turso on AWS does not support user_version PRAGMA. So this solution works for local-only (current status of desktop app) & cloud-only (admin db),
-// but will not work once we start syncing local & cloud. At that point, we should do table-based tracking even though pragma is supported.
-impl TrackingSource {
-    pub async fn new(conn: &libsql::Connection) -> Result<Self, crate::Error> {
-        let result = match conn.query("PRAGMA user_version", ()).await {
-            Ok(v) => Ok::<Option<libsql::Rows>, crate::Error>(Some(v)),
-            Err(libsql::Error::Hrana(_)) => Ok(None),
-            Err(e) => Err(e.into()),
-        }?;
-
-        match result {
-            None => Ok(Self::Table),
-            Some(_) => Ok(Self::Pragma),
-        }
-    }
-
-    pub async fn get(&self, conn: &libsql::Connection) -> Result<i32, crate::Error> {
-        match self {
-            TrackingSource::Pragma => {
-                let version: i32 = conn
-                    .query("PRAGMA user_version", ())
-                    .await?
-                    .next()
-                    .await?
-                    .unwrap()
-                    .get(0)
-                    .unwrap_or(0);
-
-                Ok(version)
-            }
-            TrackingSource::Table => {
-                let mut result = conn
-                    .query("SELECT MAX(version) FROM _migrations", ())
-                    .await?;
-
-                let row = result.next().await?;
-                let version: Option<i32> = if let Some(row) = row {
-                    row.get(0)?
-                } else {
-                    None
-                };
-
-                Ok(version.unwrap_or(0))
-            }
-        }
-    }
-
-    pub async fn set(&self, tx: &libsql::Transaction, version: i32) -> Result<(), crate::Error> {
-        match self {
-            TrackingSource::Pragma => {
-                tx.execute(&format!("PRAGMA user_version = {}", version), ())
-                    .await?;
-
-                Ok(())
-            }
-            TrackingSource::Table => {
-                tx.execute(
-                    "INSERT INTO _migrations (version) VALUES (?)",
-                    vec![version],
-                )
-                .await?;
-
-                Ok(())
-            }
-        }
+/// Encrypts a plain database file where it is, so that it opens with
+/// `.encryption_key(key)` from then on. Returns `false` if there was no
+/// plain database at `path`, e.g. because it's already encrypted.
+#[cfg(feature = "encryption")]
+pub async fn encrypt_in_place(
+    path: impl AsRef<std::path::Path>,
+    key: &[u8],
+) -> Result<bool, crate::Error> {
+    use std::io::Read;
+
+    const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+    let path = path.as_ref();
+    let mut header = [0u8; 16];
+    let is_plain = std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok()
+        && &header == SQLITE_HEADER;
+    if !is_plain {
+        return Ok(false);
     }
-}
-
-pub async fn migrate(
-    conn: &libsql::Connection,
-    migrations: Vec<impl AsRef<str>>,
-) -> Result<(), crate::Error> {
-    let tracking = TrackingSource::new(conn).await?;
 
-    if matches!(tracking, TrackingSource::Table) {
-        conn.execute(MIGRATION_TABLE_SQL, ()).await?;
-    }
+    let db = libsql::Builder::new_local(path).build().await?;
+    let conn = db.connect()?;
 
-    let current_version: i32 = tracking.get(conn).await?;
-    let latest_version = migrations.len() as i32;
-
-    if current_version < latest_version {
-        let tx = conn.transaction().await?;
-
-        for migration in migrations.iter().skip(current_version as usize) {
-            tx.execute(migration.as_ref(), ()).await?;
-        }
-
-        tracking.set(&tx, latest_version).await?;
-        tx.commit().await?;
-    }
+    // The cipher has to match the one `.encryption_key()` opens with, and
+    // rekeying is not supported in WAL mode.
+    let hex_key: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+    conn.query("PRAGMA journal_mode = DELETE", ()).await?;
+    conn.execute_batch(&format!(
+        "PRAGMA cipher = 'aes256cbc'; PRAGMA hexrekey = '{}';",
+        hex_key
+    ))
+    .await?;
 
-    Ok(())
+    Ok(true)
 }
 
 pub trait SqlTable {
@@ -0,0 +1,319 @@
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+pub const MIGRATION_TABLE_SQL: &str = include_str!("./migration.sql");
+
+const RECORD_SQL: &str = "INSERT INTO _migrations (version, checksum) VALUES (?, ?)
+    ON CONFLICT (version) DO UPDATE SET checksum = excluded.checksum";
+
+/// One step of a schema's history, applied once and in order. With `down`,
+/// the step can also be rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub up: &'static str,
+    pub down: Option<&'static str>,
+}
+
+impl Migration {
+    pub const fn new(up: &'static str) -> Self {
+        Self { up, down: None }
+    }
+
+    pub const fn with_down(self, down: &'static str) -> Self {
+        Self {
+            up: self.up,
+            down: Some(down),
+        }
+    }
+
+    /// Recorded with the version when applied. Line endings are ignored, as
+    /// they depend on how the source was checked out rather than what it says.
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.up.replace("\r\n", "\n").as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+/// A script [`migrate`] or [`rollback`] would run next, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStep {
+    pub version: i32,
+    pub sql: &'static str,
+}
+
+// Versions up to `current` are applied. `checksums` holds what was recorded
+// for them, which is nothing for versions applied before checksums were.
+struct History {
+    current: i32,
+    checksums: BTreeMap<i32, Option<String>>,
+    has_table: bool,
+    has_checksum_column: bool,
+}
+
+// Applied migrations are recorded in `_migrations` rather than `PRAGMA user_version`:
+// Turso does not support the pragma, and a synced database has to be tracked
+// the same way locally and in the cloud.
+async fn history(conn: &libsql::Connection) -> Result<History, crate::Error> {
+    let mut rows = conn
+        .query(
+            "SELECT COUNT(*), COALESCE(SUM(name = 'checksum'), 0) FROM pragma_table_info('_migrations')",
+            (),
+        )
+        .await?;
+    let has_table = match rows.next().await? {
+        Some(row) if row.get::<i64>(0)? > 0 => Some(row.get::<i64>(1)? > 0),
+        _ => None,
+    };
+
+    let mut checksums = BTreeMap::new();
+    if let Some(has_checksum_column) = has_table {
+        let sql = if has_checksum_column {
+            "SELECT version, checksum FROM _migrations"
+        } else {
+            "SELECT version, NULL FROM _migrations"
+        };
+        let mut rows = conn.query(sql, ()).await?;
+        while let Some(row) = rows.next().await? {
+            checksums.insert(row.get::<i32>(0)?, row.get::<Option<String>>(1)?);
+        }
+    }
+
+    let current = match checksums.keys().next_back() {
+        Some(version) => *version,
+        None => legacy_version(conn).await?,
+    };
+
+    Ok(History {
+        current,
+        checksums,
+        has_table: has_table.is_some(),
+        has_checksum_column: has_table.unwrap_or(false),
+    })
+}
+
+// Databases migrated before table-based tracking only have the pragma.
+async fn legacy_version(conn: &libsql::Connection) -> Result<i32, crate::Error> {
+    let mut rows = match conn.query("PRAGMA user_version", ()).await {
+        Ok(rows) => rows,
+        Err(libsql::Error::Hrana(_)) => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    match rows.next().await? {
+        Some(row) => Ok(row.get(0).unwrap_or(0)),
+        None => Ok(0),
+    }
+}
+
+fn migration(migrations: &[Migration], version: i32) -> Option<&Migration> {
+    usize::try_from(version - 1)
+        .ok()
+        .and_then(|idx| migrations.get(idx))
+}
+
+// An applied migration whose script has changed since means databases out
+// there no longer match what the code expects, so nothing else is run.
+fn verify(history: &History, migrations: &[Migration]) -> Result<(), crate::Error> {
+    for (version, checksum) in &history.checksums {
+        if let (Some(checksum), Some(migration)) = (checksum, migration(migrations, *version))
+            && migration.checksum() != *checksum
+        {
+            return Err(crate::Error::MigrationChanged(*version));
+        }
+    }
+    Ok(())
+}
+
+fn pending_steps(history: &History, migrations: &[Migration]) -> Vec<MigrationStep> {
+    migrations
+        .iter()
+        .enumerate()
+        .skip(history.current.max(0) as usize)
+        .map(|(idx, migration)| MigrationStep {
+            version: idx as i32 + 1,
+            sql: migration.up,
+        })
+        .collect()
+}
+
+fn rollback_steps(
+    history: &History,
+    migrations: &[Migration],
+    target: i32,
+) -> Result<Vec<MigrationStep>, crate::Error> {
+    if target < 0 {
+        return Err(crate::Error::InvalidInput(format!(
+            "invalid rollback target: {}",
+            target
+        )));
+    }
+
+    (target + 1..=history.current)
+        .rev()
+        .map(|version| {
+            let migration =
+                migration(migrations, version).ok_or(crate::Error::MigrationUnknown(version))?;
+            let sql = migration
+                .down
+                .ok_or(crate::Error::MigrationIrreversible(version))?;
+            Ok(MigrationStep { version, sql })
+        })
+        .collect()
+}
+
+async fn ensure_table(conn: &libsql::Connection, history: &History) -> Result<(), crate::Error> {
+    if !history.has_table {
+        conn.execute(MIGRATION_TABLE_SQL, ()).await?;
+    } else if !history.has_checksum_column {
+        conn.execute("ALTER TABLE _migrations ADD COLUMN checksum TEXT", ())
+            .await?;
+    }
+    Ok(())
+}
+
+// Gives every applied version a row with its checksum. Legacy databases only
+// had the latest version recorded, and none had checksums, so those are
+// taken from the scripts as they are now.
+async fn stamp(
+    conn: &libsql::Connection,
+    history: &History,
+    migrations: &[Migration],
+) -> Result<(), crate::Error> {
+    for version in 1..=history.current {
+        let Some(migration) = migration(migrations, version) else {
+            break;
+        };
+        if !matches!(history.checksums.get(&version), Some(Some(_))) {
+            conn.execute(RECORD_SQL, (version, migration.checksum()))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// The scripts [`migrate`] would run, without running them.
+pub async fn pending_migrations(
+    conn: &libsql::Connection,
+    migrations: &[Migration],
+) -> Result<Vec<MigrationStep>, crate::Error> {
+    let history = history(conn).await?;
+    verify(&history, migrations)?;
+    Ok(pending_steps(&history, migrations))
+}
+
+/// The down scripts [`rollback`] would run to get back to `target`, without
+/// running them.
+pub async fn pending_rollback(
+    conn: &libsql::Connection,
+    migrations: &[Migration],
+    target: i32,
+) -> Result<Vec<MigrationStep>, crate::Error> {
+    let history = history(conn).await?;
+    verify(&history, migrations)?;
+    rollback_steps(&history, migrations, target)
+}
+
+/// Applies the migrations not applied yet, in one transaction. Fails without
+/// changing anything if an applied one was edited since.
+pub async fn migrate(
+    conn: &libsql::Connection,
+    migrations: &[Migration],
+) -> Result<(), crate::Error> {
+    let history = history(conn).await?;
+    verify(&history, migrations)?;
+    ensure_table(conn, &history).await?;
+
+    // Left alone, so an older build can still open a database a newer one
+    // has migrated, e.g. through sync.
+    if history.current > migrations.len() as i32 {
+        tracing::warn!(
+            "database_newer_than_migrations: {} > {}",
+            history.current,
+            migrations.len()
+        );
+    }
+
+    let pending = pending_steps(&history, migrations);
+    let unstamped = (1..=history.current.min(migrations.len() as i32))
+        .any(|version| !matches!(history.checksums.get(&version), Some(Some(_))));
+    if pending.is_empty() && !unstamped {
+        return Ok(());
+    }
+
+    let tx = conn.transaction().await?;
+    stamp(&tx, &history, migrations).await?;
+
+    for step in pending {
+        let checksum = migrations[step.version as usize - 1].checksum();
+        tx.execute_batch(step.sql).await?;
+        tx.execute(RECORD_SQL, (step.version, checksum)).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Runs down scripts, newest first and in one transaction, until `target` is
+/// the latest applied version. Fails before running any if one of them has
+/// no down script.
+///
+/// Only `conn` is rolled back. A synced table whose down script removes it or
+/// its `updated_at` column can't be synced until it's migrated again.
+pub async fn rollback(
+    conn: &libsql::Connection,
+    migrations: &[Migration],
+    target: i32,
+) -> Result<(), crate::Error> {
+    let history = history(conn).await?;
+    verify(&history, migrations)?;
+    let steps = rollback_steps(&history, migrations, target)?;
+    if steps.is_empty() {
+        return Ok(());
+    }
+
+    ensure_table(conn, &history).await?;
+    let tx = conn.transaction().await?;
+    stamp(&tx, &history, migrations).await?;
+
+    for step in steps {
+        tx.execute_batch(step.sql).await?;
+        tx.execute(
+            "DELETE FROM _migrations WHERE version = ?",
+            vec![step.version],
+        )
+        .await?;
+    }
+
+    // With no rows left, the next run falls back to the pragma.
+    if target == 0 {
+        match tx.execute("PRAGMA user_version = 0", ()).await {
+            Ok(_) | Err(libsql::Error::Hrana(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// The schema as its `CREATE` statements in a stable order, leaving out the
+/// migration bookkeeping. For comparing databases or snapshotting in tests.
+pub async fn schema(conn: &libsql::Connection) -> Result<String, crate::Error> {
+    let mut rows = conn
+        .query(
+            "SELECT sql FROM sqlite_master
+            WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%' AND name != '_migrations'
+            ORDER BY type, name",
+            (),
+        )
+        .await?;
+
+    let mut statements = Vec::new();
+    while let Some(row) = rows.next().await? {
+        statements.push(format!("{};", row.get::<String>(0)?));
+    }
+    Ok(statements.join("\n\n"))
+}
@@ -1,4 +1,5 @@
 CREATE TABLE IF NOT EXISTS _migrations (
   version INTEGER PRIMARY KEY,
-  applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+  applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  checksum TEXT
 )
@@ -0,0 +1,604 @@
+use std::collections::{BTreeSet, HashMap};
+
+use libsql::{Connection, Value};
+
+// Rows fetched per query; SQLite caps the number of parameters.
+const FETCH_CHUNK: usize = 500;
+
+/// What to do when rows on the two sides share a unique key but not their
+/// row key, like a tag with the same name created on two devices. The row
+/// with the smaller row key is kept, so both sides settle on the same one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnUniqueConflict {
+    /// The other row is folded into the kept one: rows referencing it are
+    /// re-pointed, then it's deleted.
+    Merge,
+    /// The other row stays, with the unique columns set to NULL.
+    Clear,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UniqueKey {
+    pub columns: &'static [&'static str],
+    pub on_conflict: OnUniqueConflict,
+}
+
+/// A table kept in sync between a local and a remote database.
+///
+/// Rows are identified by `key`, and need an `updated_at` column that
+/// changes on every write. Deleted rows must be recorded in
+/// `_tombstones (table_name, row_id, deleted_at)`, with the key columns
+/// joined by `/` as `row_id`. Triggers in the owning crate's migrations are
+/// the usual way to maintain both.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncTable {
+    pub name: &'static str,
+    pub key: &'static [&'static str],
+    /// Columns merged on their own instead of following the rest of the row,
+    /// each paired with the column recording when it last changed. Concurrent
+    /// edits to different columns on two devices then both survive.
+    pub merged_columns: &'static [(&'static str, &'static str)],
+    /// Unique constraints other than the key, which would otherwise stop a
+    /// row from the other side being written.
+    pub unique: &'static [UniqueKey],
+    /// Columns of other tables holding this table's key, as `(table, column)`.
+    /// Re-pointed when [`OnUniqueConflict::Merge`] folds a row away.
+    pub referenced_by: &'static [(&'static str, &'static str)],
+}
+
+impl SyncTable {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            key: &["id"],
+            merged_columns: &[],
+            unique: &[],
+            referenced_by: &[],
+        }
+    }
+
+    pub const fn with_key(self, key: &'static [&'static str]) -> Self {
+        Self { key, ..self }
+    }
+
+    pub const fn with_merged_columns(
+        self,
+        merged_columns: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self {
+            merged_columns,
+            ..self
+        }
+    }
+
+    pub const fn with_unique(self, unique: &'static [UniqueKey]) -> Self {
+        Self { unique, ..self }
+    }
+
+    pub const fn with_referenced_by(
+        self,
+        referenced_by: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self {
+            referenced_by,
+            ..self
+        }
+    }
+
+    fn key_expr(&self) -> String {
+        self.key.join(" || '/' || ")
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// Rows written to the remote.
+    pub pushed: usize,
+    /// Rows written to the local database.
+    pub pulled: usize,
+    /// Rows removed on either side because the other side deleted them.
+    pub deleted: usize,
+    /// Rows that collided with another on a unique key.
+    pub conflicts: usize,
+}
+
+type Row = Vec<(String, Value)>;
+
+struct Side {
+    versions: HashMap<String, String>,
+    tombstones: HashMap<String, String>,
+}
+
+impl Side {
+    async fn load(conn: &Connection, table: &SyncTable) -> Result<Self, crate::Error> {
+        let mut versions = HashMap::new();
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT {}, updated_at FROM {}",
+                    table.key_expr(),
+                    table.name
+                ),
+                (),
+            )
+            .await?;
+        while let Some(row) = rows.next().await? {
+            versions.insert(row.get::<String>(0)?, row.get::<String>(1)?);
+        }
+
+        let mut tombstones = HashMap::new();
+        let mut rows = conn
+            .query(
+                "SELECT row_id, deleted_at FROM _tombstones WHERE table_name = ?",
+                vec![table.name],
+            )
+            .await?;
+        while let Some(row) = rows.next().await? {
+            tombstones.insert(row.get::<String>(0)?, row.get::<String>(1)?);
+        }
+
+        Ok(Self {
+            versions,
+            tombstones,
+        })
+    }
+}
+
+async fn fetch_rows(
+    conn: &Connection,
+    table: &SyncTable,
+    keys: &[&String],
+) -> Result<HashMap<String, Row>, crate::Error> {
+    let mut found = HashMap::new();
+
+    for chunk in keys.chunks(FETCH_CHUNK) {
+        let sql = format!(
+            "SELECT {key} AS _sync_key, * FROM {table} WHERE {key} IN ({placeholders})",
+            key = table.key_expr(),
+            table = table.name,
+            placeholders = vec!["?"; chunk.len()].join(", "),
+        );
+        let params: Vec<Value> = chunk.iter().map(|k| Value::Text(k.to_string())).collect();
+        let mut rows = conn.query(&sql, params).await?;
+
+        while let Some(row) = rows.next().await? {
+            let key = row.get::<String>(0)?;
+            let values = (1..row.column_count())
+                .map(|idx| {
+                    let name = row.column_name(idx).unwrap_or_default().to_string();
+                    Ok((name, row.get_value(idx)?))
+                })
+                .collect::<Result<Row, crate::Error>>()?;
+            found.insert(key, values);
+        }
+    }
+
+    for key in keys {
+        if !found.contains_key(*key) {
+            return Err(crate::Error::InvalidInput(format!(
+                "{} row {} vanished",
+                table.name, key
+            )));
+        }
+    }
+    Ok(found)
+}
+
+// Statements for one side, run as a single transaction so a sync that fails
+// part way leaves each side as it was, and a remote side costs one round trip.
+#[derive(Default)]
+struct Batch(Vec<String>);
+
+impl Batch {
+    fn write(&mut self, table: &SyncTable, key: &str, row: &Row) {
+        let key_expr = table.key_expr();
+        let key = literal(&Value::Text(key.to_string()));
+
+        // Clearing `updated_at` first means the upsert below always changes
+        // it, so triggers that stamp local edits leave the copied value alone.
+        self.0.push(format!(
+            "UPDATE {} SET updated_at = '' WHERE {} = {}",
+            table.name, key_expr, key
+        ));
+
+        let columns: Vec<&str> = row.iter().map(|(name, _)| name.as_str()).collect();
+        self.0.push(format!(
+            "INSERT INTO {table} ({columns}) VALUES ({values})
+            ON CONFLICT ({conflict}) DO UPDATE SET {updates}",
+            table = table.name,
+            columns = columns.join(", "),
+            values = row
+                .iter()
+                .map(|(_, value)| literal(value))
+                .collect::<Vec<_>>()
+                .join(", "),
+            conflict = table.key.join(", "),
+            updates = columns
+                .iter()
+                .map(|c| format!("{c} = excluded.{c}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+
+        self.0.push(format!(
+            "DELETE FROM _tombstones WHERE table_name = '{}' AND row_id = {}",
+            table.name, key
+        ));
+    }
+
+    fn delete(&mut self, table: &SyncTable, key: &str, deleted_at: &str) {
+        self.0.push(format!(
+            "DELETE FROM {} WHERE {} = {}",
+            table.name,
+            table.key_expr(),
+            literal(&Value::Text(key.to_string()))
+        ));
+        self.tombstone(table, key, deleted_at);
+    }
+
+    // Keeps the original deletion time, not when this side heard about it.
+    fn tombstone(&mut self, table: &SyncTable, key: &str, deleted_at: &str) {
+        self.0.push(format!(
+            "INSERT OR REPLACE INTO _tombstones (table_name, row_id, deleted_at) VALUES ('{}', {}, {})",
+            table.name,
+            literal(&Value::Text(key.to_string())),
+            literal(&Value::Text(deleted_at.to_string())),
+        ));
+    }
+
+    // Deleted with this side's own triggers, so the other side sees the
+    // folded row as deleted when it's processed. The kept row is copied over
+    // in the same transaction, for the re-pointed rows to refer to.
+    fn merge(&mut self, tables: &[SyncTable], table: &SyncTable, from: &str, into: (&str, &Row)) {
+        let from = literal(&Value::Text(from.to_string()));
+        let (into_key, into_row) = into;
+        let into = literal(&Value::Text(into_key.to_string()));
+
+        for (child, column) in table.referenced_by {
+            self.0.push(format!(
+                "UPDATE OR IGNORE {child} SET {column} = {into} WHERE {column} = {from}"
+            ));
+
+            // What's left would have duplicated a row already pointing at
+            // `into`. Rows keyed by the column go; others keep their data.
+            let keyed = tables
+                .iter()
+                .any(|t| t.name == *child && t.key.contains(column));
+            self.0.push(if keyed {
+                format!("DELETE FROM {child} WHERE {column} = {from}")
+            } else {
+                format!("UPDATE {child} SET {column} = NULL WHERE {column} = {from}")
+            });
+        }
+
+        self.0.push(format!(
+            "DELETE FROM {} WHERE {} = {}",
+            table.name,
+            table.key_expr(),
+            from
+        ));
+        self.write(table, into_key, into_row);
+    }
+
+    fn clear(&mut self, table: &SyncTable, unique: &UniqueKey, key: &str) {
+        self.0.push(format!(
+            "UPDATE {} SET {} WHERE {} = {}",
+            table.name,
+            unique
+                .columns
+                .iter()
+                .map(|c| format!("{c} = NULL"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            table.key_expr(),
+            literal(&Value::Text(key.to_string()))
+        ));
+    }
+
+    fn extend(&mut self, other: Batch) {
+        self.0.extend(other.0);
+    }
+
+    // Foreign keys are checked on commit, as a row can come before the one
+    // it refers to within a batch.
+    async fn apply(self, conn: &Connection) -> Result<(), crate::Error> {
+        if !self.0.is_empty() {
+            let statements: Vec<String> =
+                std::iter::once("PRAGMA defer_foreign_keys = ON".to_string())
+                    .chain(self.0)
+                    .collect();
+            conn.execute_transactional_batch(&statements.join(";\n"))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+// Batches are plain SQL, so values are inlined. Text is written as a blob
+// cast back to text, which holds any byte sequence without escaping.
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Real(f) if f.is_finite() => format!("{:?}", f),
+        Value::Real(f) if f.is_sign_negative() => "-9e999".to_string(),
+        Value::Real(_) => "9e999".to_string(),
+        Value::Text(s) if !s.contains('\0') => format!("'{}'", s.replace('\'', "''")),
+        Value::Text(s) => format!("CAST({} AS TEXT)", hex_blob(s.as_bytes())),
+        Value::Blob(b) => hex_blob(b),
+    }
+}
+
+fn hex_blob(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("X'{}'", hex)
+}
+
+fn text<'r>(row: &'r Row, column: &str) -> &'r str {
+    row.iter()
+        .find(|(name, _)| name == column)
+        .and_then(|(_, value)| match value {
+            Value::Text(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Last writer wins for the row as a whole, then each merged column takes
+/// whichever side changed it last. Timestamps are RFC 3339 in UTC, so they
+/// compare as strings; ties go to the remote so both sides agree.
+fn merge(table: &SyncTable, local: &Row, remote: &Row) -> Row {
+    let local_newer = text(local, "updated_at") > text(remote, "updated_at");
+    let (winner, loser) = if local_newer {
+        (local, remote)
+    } else {
+        (remote, local)
+    };
+
+    let mut merged = winner.clone();
+    for (column, changed_at) in table.merged_columns {
+        if text(loser, changed_at) <= text(winner, changed_at) {
+            continue;
+        }
+
+        for (name, value) in merged.iter_mut() {
+            if (name == column || name == changed_at)
+                && let Some((_, loser_value)) = loser.iter().find(|(n, _)| n == name)
+            {
+                *value = loser_value.clone();
+            }
+        }
+    }
+
+    merged
+}
+
+// Row keys by the JSON of their values for one unique key. NULLs never
+// collide, so rows with any are left out.
+async fn unique_values(
+    conn: &Connection,
+    table: &SyncTable,
+    unique: &UniqueKey,
+) -> Result<HashMap<String, String>, crate::Error> {
+    let sql = format!(
+        "SELECT json_array({columns}), {key} FROM {table} WHERE {not_null}",
+        columns = unique.columns.join(", "),
+        key = table.key_expr(),
+        table = table.name,
+        not_null = unique
+            .columns
+            .iter()
+            .map(|c| format!("{c} IS NOT NULL"))
+            .collect::<Vec<_>>()
+            .join(" AND "),
+    );
+
+    let mut values = HashMap::new();
+    let mut rows = conn.query(&sql, ()).await?;
+    while let Some(row) = rows.next().await? {
+        values.insert(row.get::<String>(0)?, row.get::<String>(1)?);
+    }
+    Ok(values)
+}
+
+// Settles rows that share a unique key across the two sides before any are
+// copied, since copying either would fail on the other side's constraint.
+async fn resolve_unique_conflicts(
+    local: &Connection,
+    remote: &Connection,
+    tables: &[SyncTable],
+    table: &SyncTable,
+) -> Result<usize, crate::Error> {
+    let mut conflicts = 0;
+
+    for unique in table.unique {
+        let local_values = unique_values(local, table, unique).await?;
+        let remote_values = unique_values(remote, table, unique).await?;
+
+        // (kept, dropped), by the side the dropped row is on.
+        let mut on_local = Vec::new();
+        let mut on_remote = Vec::new();
+        for (value, local_key) in &local_values {
+            match remote_values.get(value) {
+                Some(remote_key) if local_key < remote_key => {
+                    on_remote.push((local_key, remote_key))
+                }
+                Some(remote_key) if local_key > remote_key => {
+                    on_local.push((remote_key, local_key))
+                }
+                _ => {}
+            }
+        }
+        conflicts += on_local.len() + on_remote.len();
+
+        for (conn, other, pairs) in [(local, remote, on_local), (remote, local, on_remote)] {
+            let kept_keys: Vec<&String> = pairs.iter().map(|(kept, _)| *kept).collect();
+            let kept_rows = match unique.on_conflict {
+                OnUniqueConflict::Merge => fetch_rows(other, table, &kept_keys).await?,
+                OnUniqueConflict::Clear => HashMap::new(),
+            };
+
+            let mut batch = Batch::default();
+            for (kept, dropped) in pairs {
+                match unique.on_conflict {
+                    OnUniqueConflict::Merge => {
+                        batch.merge(tables, table, dropped, (kept, &kept_rows[kept]))
+                    }
+                    OnUniqueConflict::Clear => batch.clear(table, unique, dropped),
+                }
+            }
+            batch.apply(conn).await?;
+        }
+    }
+
+    Ok(conflicts)
+}
+
+// A side rolled back past the migration that made a table syncable can't
+// take part until it's migrated again.
+async fn check_syncable(conn: &Connection, table: &SyncTable) -> Result<(), crate::Error> {
+    let mut rows = conn
+        .query(
+            "SELECT 1 FROM pragma_table_info(?) WHERE name = 'updated_at'",
+            [table.name],
+        )
+        .await?;
+    match rows.next().await? {
+        Some(_) => Ok(()),
+        None => Err(crate::Error::SyncTableUnavailable(table.name.to_string())),
+    }
+}
+
+/// Brings `local` and `remote` to the same rows for every table, in order.
+/// Rows are compared by `updated_at`, so syncing is idempotent and safe to
+/// retry after a failure part way through. Each table's writes go to each
+/// side in one transaction, and deletes in a last one per side. Nothing is
+/// written unless every table can be synced on both sides.
+pub async fn sync_tables(
+    local: &Connection,
+    remote: &Connection,
+    tables: &[SyncTable],
+) -> Result<SyncSummary, crate::Error> {
+    for table in tables {
+        check_syncable(local, table).await?;
+        check_syncable(remote, table).await?;
+    }
+
+    let mut summary = SyncSummary::default();
+    let mut local_deletes = Vec::new();
+    let mut remote_deletes = Vec::new();
+
+    for table in tables {
+        summary.conflicts += resolve_unique_conflicts(local, remote, tables, table).await?;
+
+        let local_side = Side::load(local, table).await?;
+        let remote_side = Side::load(remote, table).await?;
+
+        let keys: BTreeSet<&String> = local_side
+            .versions
+            .keys()
+            .chain(remote_side.versions.keys())
+            .chain(local_side.tombstones.keys())
+            .chain(remote_side.tombstones.keys())
+            .collect();
+
+        // Rows whose contents are needed: present on one side without a
+        // newer tombstone on the other, or present on both but different.
+        let mut local_needed = Vec::new();
+        let mut remote_needed = Vec::new();
+        for key in &keys {
+            let local_version = local_side.versions.get(*key);
+            let remote_version = remote_side.versions.get(*key);
+            match (local_version, remote_version) {
+                (Some(l), Some(r)) if l == r => {}
+                (Some(_), Some(_)) => {
+                    local_needed.push(*key);
+                    remote_needed.push(*key);
+                }
+                (Some(l), None) => {
+                    if !matches!(remote_side.tombstones.get(*key), Some(d) if d >= l) {
+                        local_needed.push(*key);
+                    }
+                }
+                (None, Some(r)) => {
+                    if !matches!(local_side.tombstones.get(*key), Some(d) if d >= r) {
+                        remote_needed.push(*key);
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        let local_rows = fetch_rows(local, table, &local_needed).await?;
+        let remote_rows = fetch_rows(remote, table, &remote_needed).await?;
+
+        let mut local_batch = Batch::default();
+        let mut remote_batch = Batch::default();
+        let mut local_delete = Batch::default();
+        let mut remote_delete = Batch::default();
+        for key in keys {
+            let local_version = local_side.versions.get(key);
+            let remote_version = remote_side.versions.get(key);
+            let local_tombstone = local_side.tombstones.get(key);
+            let remote_tombstone = remote_side.tombstones.get(key);
+
+            match (local_version, remote_version) {
+                (Some(l), Some(r)) if l == r => {}
+                (Some(_), Some(_)) => {
+                    let local_row = &local_rows[key];
+                    let remote_row = &remote_rows[key];
+                    let merged = merge(table, local_row, remote_row);
+
+                    if &merged != local_row {
+                        local_batch.write(table, key, &merged);
+                        summary.pulled += 1;
+                    }
+                    if &merged != remote_row {
+                        remote_batch.write(table, key, &merged);
+                        summary.pushed += 1;
+                    }
+                }
+                (Some(l), None) => match remote_tombstone {
+                    Some(deleted_at) if deleted_at >= l => {
+                        local_delete.delete(table, key, deleted_at);
+                        summary.deleted += 1;
+                    }
+                    _ => {
+                        remote_batch.write(table, key, &local_rows[key]);
+                        summary.pushed += 1;
+                    }
+                },
+                (None, Some(r)) => match local_tombstone {
+                    Some(deleted_at) if deleted_at >= r => {
+                        remote_delete.delete(table, key, deleted_at);
+                        summary.deleted += 1;
+                    }
+                    _ => {
+                        local_batch.write(table, key, &remote_rows[key]);
+                        summary.pulled += 1;
+                    }
+                },
+                (None, None) => match (local_tombstone, remote_tombstone) {
+                    (Some(deleted_at), None) => remote_batch.tombstone(table, key, deleted_at),
+                    (None, Some(deleted_at)) => local_batch.tombstone(table, key, deleted_at),
+                    _ => {}
+                },
+            }
+        }
+
+        local_batch.apply(local).await?;
+        remote_batch.apply(remote).await?;
+        local_deletes.push(local_delete);
+        remote_deletes.push(remote_delete);
+    }
+
+    // Children before parents, so no row is left pointing at a deleted one.
+    for (conn, deletes) in [(local, local_deletes), (remote, remote_deletes)] {
+        let mut batch = Batch::default();
+        for delete in deletes.into_iter().rev() {
+            batch.extend(delete);
+        }
+        batch.apply(conn).await?;
+    }
+
+    Ok(summary)
+}
@@ -0,0 +1,73 @@
+//! Migrates a local user database, or rolls it back.
+//!
+//! Only the file at `<path>` is changed, never its cloud copy. After a
+//! rollback, syncing fails until the app migrates the file again.
+//!
+//! ```sh
+//! cargo run -p db-user --example migrate -- <path> --dry-run
+//! cargo run -p db-user --example migrate -- <path> --to 38
+//! ```
+
+use clap::Parser;
+
+#[derive(Parser)]
+struct Args {
+    /// The `db.sqlite` to migrate
+    path: std::path::PathBuf,
+    /// Roll back to this version instead of migrating to the latest
+    #[arg(long)]
+    to: Option<i32>,
+    /// Print the SQL that would run, without running it
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), db_user::Error> {
+    let args = Args::parse();
+
+    let db = hypr_db_core::DatabaseBuilder::default()
+        .local(&args.path)
+        .build()
+        .await?;
+    let conn = db.conn()?;
+
+    let steps = match args.to {
+        Some(target) => hypr_db_core::pending_rollback(&conn, &db_user::MIGRATIONS, target).await?,
+        None => hypr_db_core::pending_migrations(&conn, &db_user::MIGRATIONS).await?,
+    };
+
+    if steps.is_empty() {
+        println!("-- up to date");
+        return Ok(());
+    }
+
+    if args.dry_run {
+        let direction = if args.to.is_some() { "down" } else { "up" };
+        for step in &steps {
+            println!(
+                "-- {} {}\n{}\n",
+                direction,
+                step.version,
+                step.sql.trim_end()
+            );
+        }
+        return Ok(());
+    }
+
+    let done = match args.to {
+        Some(target) => {
+            hypr_db_core::rollback(&conn, &db_user::MIGRATIONS, target).await?;
+            "rolled back"
+        }
+        None => {
+            hypr_db_core::migrate(&conn, &db_user::MIGRATIONS).await?;
+            "applied"
+        }
+    };
+    for step in &steps {
+        println!("-- {} {}", done, step.version);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS action_items (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  text TEXT NOT NULL,
+  owner_id TEXT DEFAULT NULL,
+  owner_name TEXT DEFAULT NULL,
+  due_date TEXT DEFAULT NULL,
+  source_start INTEGER NOT NULL,
+  source_end INTEGER NOT NULL,
+  completed BOOLEAN NOT NULL DEFAULT FALSE,
+  external_url TEXT DEFAULT NULL,
+  created_at TEXT NOT NULL,
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+  FOREIGN KEY (owner_id) REFERENCES humans(id) ON DELETE
+  SET
+    NULL
+);
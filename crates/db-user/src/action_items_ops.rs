@@ -0,0 +1,276 @@
+use hypr_db_core::SqlTable;
+
+use super::{ActionItem, Human, UserDatabase};
+
+impl UserDatabase {
+    pub async fn upsert_action_item(&self, item: ActionItem) -> Result<ActionItem, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "INSERT INTO {} (
+                id,
+                session_id,
+                text,
+                owner_id,
+                owner_name,
+                due_date,
+                source_start,
+                source_end,
+                completed,
+                external_url,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                text = excluded.text,
+                owner_id = excluded.owner_id,
+                owner_name = excluded.owner_name,
+                due_date = excluded.due_date,
+                source_start = excluded.source_start,
+                source_end = excluded.source_end,
+                completed = excluded.completed,
+                external_url = excluded.external_url
+            RETURNING *",
+            ActionItem::sql_table()
+        );
+
+        let params = (
+            item.id,
+            item.session_id,
+            item.text,
+            item.owner_id,
+            item.owner_name,
+            item.due_date,
+            item.source_start,
+            item.source_end,
+            item.completed,
+            item.external_url,
+            item.created_at.to_rfc3339(),
+        );
+
+        let mut rows = conn.query(&sql, params).await?;
+        let row = rows.next().await?.unwrap();
+        let item: ActionItem = libsql::de::from_row(&row)?;
+        Ok(item)
+    }
+
+    pub async fn delete_action_item(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!("DELETE FROM {} WHERE id = ?", ActionItem::sql_table());
+        conn.execute(&sql, vec![id.into()]).await?;
+        Ok(())
+    }
+
+    pub async fn list_session_action_items(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<ActionItem>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY source_start, created_at",
+            ActionItem::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: ActionItem = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Stores a fresh extraction for the session, dropping the previous one.
+    ///
+    /// Items that were completed or already pushed to a tracker are kept, so
+    /// re-running the extraction doesn't undo what the user or an integration
+    /// did with them.
+    pub async fn replace_session_action_items(
+        &self,
+        session_id: impl Into<String>,
+        items: Vec<ActionItem>,
+    ) -> Result<Vec<ActionItem>, crate::Error> {
+        let session_id = session_id.into();
+
+        {
+            let conn = self.conn()?;
+            let sql = format!(
+                "DELETE FROM {} WHERE session_id = ? AND completed = FALSE AND external_url IS NULL",
+                ActionItem::sql_table()
+            );
+            conn.execute(&sql, vec![session_id.clone()]).await?;
+        }
+
+        for item in items {
+            self.upsert_action_item(ActionItem {
+                session_id: session_id.clone(),
+                ..item
+            })
+            .await?;
+        }
+
+        self.list_session_action_items(session_id).await
+    }
+}
+
+/// Finds the participant an extracted owner name refers to, by full name and
+/// then by first name, ignoring case.
+pub fn match_participant<'a>(name: &str, participants: &'a [Human]) -> Option<&'a Human> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let full_name = |h: &&Human| {
+        h.full_name
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    participants
+        .iter()
+        .find(|h| full_name(h).eq_ignore_ascii_case(name))
+        .or_else(|| {
+            let mut candidates = participants.iter().filter(|h| {
+                full_name(h)
+                    .split_whitespace()
+                    .next()
+                    .is_some_and(|first| first.eq_ignore_ascii_case(name))
+            });
+
+            // Two "Alex"es in one meeting is ambiguous, so don't guess.
+            match (candidates.next(), candidates.next()) {
+                (Some(human), None) => Some(human),
+                _ => None,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::match_participant;
+    use crate::{ActionItem, Human, Session, tests::setup_db};
+
+    fn action_item(session_id: &str, text: &str) -> ActionItem {
+        ActionItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            text: text.to_string(),
+            owner_id: None,
+            owner_name: None,
+            due_date: None,
+            source_start: 0,
+            source_end: 0,
+            completed: false,
+            external_url: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_action_items() {
+        let db = setup_db().await;
+
+        let user = db
+            .upsert_human(Human {
+                full_name: Some("John Doe".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Test Session".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![],
+                record_start: None,
+                record_end: None,
+                pre_meeting_memo_html: None,
+            })
+            .await
+            .unwrap();
+
+        let pushed = db
+            .upsert_action_item(ActionItem {
+                owner_id: Some(user.id.clone()),
+                owner_name: Some("John".to_string()),
+                due_date: Some("2025-01-03".to_string()),
+                source_start: 2,
+                source_end: 4,
+                ..action_item(&session.id, "Update the changelog")
+            })
+            .await
+            .unwrap();
+        assert_eq!(pushed.source_end, 4);
+        assert!(!pushed.completed);
+
+        let pushed = db
+            .upsert_action_item(ActionItem {
+                external_url: Some("https://linear.app/hypr/issue/HYP-1".to_string()),
+                ..pushed
+            })
+            .await
+            .unwrap();
+
+        db.upsert_action_item(action_item(&session.id, "Book a room"))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.list_session_action_items(&session.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let items = db
+            .replace_session_action_items(
+                &session.id,
+                vec![action_item(&session.id, "Send the recap")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            items.iter().map(|i| i.text.as_str()).collect::<Vec<_>>(),
+            vec!["Send the recap", "Update the changelog"]
+        );
+        assert_eq!(items[1], pushed);
+
+        db.delete_action_item(&pushed.id).await.unwrap();
+        assert_eq!(
+            db.list_session_action_items(&session.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_match_participant() {
+        let human = |name: &str| Human {
+            full_name: Some(name.to_string()),
+            ..Human::default()
+        };
+        let participants = vec![human("Alex Kim"), human("Alex Park"), human("Jamie Lee")];
+
+        let matched =
+            |name| match_participant(name, &participants).and_then(|h| h.full_name.clone());
+        assert_eq!(matched("alex kim"), Some("Alex Kim".to_string()));
+        assert_eq!(matched("Jamie"), Some("Jamie Lee".to_string()));
+        assert_eq!(matched("Alex"), None);
+        assert_eq!(matched("Sam"), None);
+        assert_eq!(matched(""), None);
+    }
+}
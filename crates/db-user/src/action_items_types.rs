@@ -0,0 +1,24 @@
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("action_items")]
+    pub struct ActionItem {
+        pub id: String,
+        pub session_id: String,
+        pub text: String,
+        /// Participant the item was matched to, if any.
+        pub owner_id: Option<String>,
+        /// Owner as the model named them, kept even when no participant matched.
+        pub owner_name: Option<String>,
+        /// `YYYY-MM-DD`.
+        pub due_date: Option<String>,
+        /// First transcript segment the item was extracted from.
+        pub source_start: u32,
+        /// Last transcript segment the item was extracted from, inclusive.
+        pub source_end: u32,
+        pub completed: bool,
+        /// Where an integration pushed the item to, e.g. a Linear issue.
+        pub external_url: Option<String>,
+        pub created_at: chrono::DateTime<chrono::Utc>,
+    }
+}
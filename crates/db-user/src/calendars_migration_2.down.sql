@@ -0,0 +1,16 @@
+-- Rows stop syncing.
+DROP TRIGGER IF EXISTS calendars_stamp_insert;
+
+DROP TRIGGER IF EXISTS calendars_stamp_update;
+
+DROP TRIGGER IF EXISTS calendars_tombstone;
+
+DROP TRIGGER IF EXISTS calendars_untombstone;
+
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'calendars';
+
+ALTER TABLE
+  calendars DROP COLUMN updated_at;
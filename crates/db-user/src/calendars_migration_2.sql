@@ -0,0 +1,53 @@
+ALTER TABLE
+  calendars
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  calendars
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS calendars_stamp_insert
+AFTER
+INSERT
+  ON calendars WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  calendars
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS calendars_stamp_update
+AFTER
+UPDATE
+  ON calendars WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  calendars
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS calendars_tombstone
+AFTER
+  DELETE ON calendars BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('calendars', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS calendars_untombstone
+AFTER
+INSERT
+  ON calendars BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'calendars'
+  AND row_id = NEW.id;
+END;
@@ -0,0 +1,93 @@
+use hypr_db_core::SqlTable;
+
+use super::{Decision, UserDatabase};
+
+impl UserDatabase {
+    pub async fn upsert_decision(&self, decision: Decision) -> Result<Decision, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "INSERT INTO {} (
+                id,
+                session_id,
+                text,
+                source_start,
+                source_end,
+                created_at
+            ) VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                text = excluded.text,
+                source_start = excluded.source_start,
+                source_end = excluded.source_end
+            RETURNING *",
+            Decision::sql_table()
+        );
+
+        let params = (
+            decision.id,
+            decision.session_id,
+            decision.text,
+            decision.source_start,
+            decision.source_end,
+            decision.created_at.to_rfc3339(),
+        );
+
+        let mut rows = conn.query(&sql, params).await?;
+        let row = rows.next().await?.unwrap();
+        let decision: Decision = libsql::de::from_row(&row)?;
+        Ok(decision)
+    }
+
+    pub async fn delete_decision(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!("DELETE FROM {} WHERE id = ?", Decision::sql_table());
+        conn.execute(&sql, vec![id.into()]).await?;
+        Ok(())
+    }
+
+    pub async fn list_session_decisions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<Decision>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY source_start, created_at",
+            Decision::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: Decision = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Stores a fresh extraction for the session, dropping the previous one.
+    pub async fn replace_session_decisions(
+        &self,
+        session_id: impl Into<String>,
+        decisions: Vec<Decision>,
+    ) -> Result<Vec<Decision>, crate::Error> {
+        let session_id = session_id.into();
+
+        {
+            let conn = self.conn()?;
+            let sql = format!("DELETE FROM {} WHERE session_id = ?", Decision::sql_table());
+            conn.execute(&sql, vec![session_id.clone()]).await?;
+        }
+
+        for decision in decisions {
+            self.upsert_decision(Decision {
+                session_id: session_id.clone(),
+                ..decision
+            })
+            .await?;
+        }
+
+        self.list_session_decisions(session_id).await
+    }
+}
@@ -0,0 +1,15 @@
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("decisions")]
+    pub struct Decision {
+        pub id: String,
+        pub session_id: String,
+        pub text: String,
+        /// First transcript segment the decision was extracted from.
+        pub source_start: u32,
+        /// Last transcript segment the decision was extracted from, inclusive.
+        pub source_end: u32,
+        pub created_at: chrono::DateTime<chrono::Utc>,
+    }
+}
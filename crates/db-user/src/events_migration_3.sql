@@ -0,0 +1,53 @@
+ALTER TABLE
+  events
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  events
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS events_stamp_insert
+AFTER
+INSERT
+  ON events WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  events
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS events_stamp_update
+AFTER
+UPDATE
+  ON events WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  events
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS events_tombstone
+AFTER
+  DELETE ON events BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('events', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS events_untombstone
+AFTER
+INSERT
+  ON events BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'events'
+  AND row_id = NEW.id;
+END;
@@ -0,0 +1,53 @@
+ALTER TABLE
+  humans
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  humans
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS humans_stamp_insert
+AFTER
+INSERT
+  ON humans WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  humans
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS humans_stamp_update
+AFTER
+UPDATE
+  ON humans WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  humans
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS humans_tombstone
+AFTER
+  DELETE ON humans BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('humans', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS humans_untombstone
+AFTER
+INSERT
+  ON humans BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'humans'
+  AND row_id = NEW.id;
+END;
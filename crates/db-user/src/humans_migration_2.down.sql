@@ -0,0 +1,3 @@
+-- Humans in the trash come back.
+ALTER TABLE
+  humans DROP COLUMN deleted_at;
@@ -0,0 +1,4 @@
+ALTER TABLE
+  humans
+ADD
+  COLUMN deleted_at TEXT DEFAULT NULL;
@@ -13,11 +13,16 @@ impl UserDatabase {
         Ok(row.map(|row| libsql::de::from_row(&row)).transpose()?)
     }
 
+    /// Moves the human to the trash; see `restore_from_trash`.
     pub async fn delete_human(&self, id: impl Into<String>) -> Result<(), crate::Error> {
         let conn = self.conn()?;
 
-        let sql = format!("DELETE FROM {} WHERE id = ?", Human::sql_table());
-        conn.query(&sql, vec![id.into()]).await?;
+        let sql = format!(
+            "UPDATE {} SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+            Human::sql_table()
+        );
+        conn.execute(&sql, vec![chrono::Utc::now().to_rfc3339(), id.into()])
+            .await?;
         Ok(())
     }
 
@@ -69,12 +74,15 @@ impl UserDatabase {
 
         let mut rows = match &filter {
             None => {
-                let sql = format!("SELECT * FROM {}", Human::sql_table());
+                let sql = format!(
+                    "SELECT * FROM {} WHERE deleted_at IS NULL",
+                    Human::sql_table()
+                );
                 conn.query(&sql, ()).await?
             }
             Some(ListHumanFilter::Search((max, q))) => {
                 let sql = format!(
-                    "SELECT * FROM {} WHERE full_name LIKE ? LIMIT ?",
+                    "SELECT * FROM {} WHERE full_name LIKE ? AND deleted_at IS NULL LIMIT ?",
                     Human::sql_table()
                 );
                 conn.query(&sql, vec![format!("%{}%", q), max.to_string()])
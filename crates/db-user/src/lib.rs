@@ -1,3 +1,5 @@
+mod action_items_ops;
+mod action_items_types;
 mod calendars_ops;
 mod calendars_types;
 mod chat_conversations_ops;
@@ -10,6 +12,8 @@ mod chat_messages_v2_ops;
 mod chat_messages_v2_types;
 mod config_ops;
 mod config_types;
+mod decisions_ops;
+mod decisions_types;
 mod events_ops;
 mod events_types;
 mod extensions_ops;
@@ -18,13 +22,23 @@ mod humans_ops;
 mod humans_types;
 mod organizations_ops;
 mod organizations_types;
+mod session_revisions_ops;
+mod session_revisions_types;
 mod sessions_ops;
 mod sessions_types;
 mod tags_ops;
 mod tags_types;
 mod templates_ops;
 mod templates_types;
+mod transcript_words_ops;
+mod transcript_words_types;
+mod trash_ops;
+mod trash_types;
 
+#[allow(unused)]
+pub use action_items_ops::*;
+#[allow(unused)]
+pub use action_items_types::*;
 #[allow(unused)]
 pub use calendars_ops::*;
 #[allow(unused)]
@@ -50,6 +64,10 @@ pub use config_ops::*;
 #[allow(unused)]
 pub use config_types::*;
 #[allow(unused)]
+pub use decisions_ops::*;
+#[allow(unused)]
+pub use decisions_types::*;
+#[allow(unused)]
 pub use events_ops::*;
 #[allow(unused)]
 pub use events_types::*;
@@ -66,6 +84,10 @@ pub use organizations_ops::*;
 #[allow(unused)]
 pub use organizations_types::*;
 #[allow(unused)]
+pub use session_revisions_ops::*;
+#[allow(unused)]
+pub use session_revisions_types::*;
+#[allow(unused)]
 pub use sessions_ops::*;
 #[allow(unused)]
 pub use sessions_types::*;
@@ -77,7 +99,16 @@ pub use tags_types::*;
 pub use templates_ops::*;
 #[allow(unused)]
 pub use templates_types::*;
+#[allow(unused)]
+pub use transcript_words_ops::*;
+#[allow(unused)]
+pub use transcript_words_types::*;
+#[allow(unused)]
+pub use trash_ops::*;
+#[allow(unused)]
+pub use trash_types::*;
 
+use hypr_db_core::Migration;
 pub use hypr_db_core::{Database, Error};
 
 #[macro_export]
@@ -138,49 +169,135 @@ impl std::ops::Deref for UserDatabase {
     }
 }
 
-// Append only. Do not reorder.
-const MIGRATIONS: [&str; 27] = [
-    include_str!("./calendars_migration.sql"),
-    include_str!("./configs_migration.sql"),
-    include_str!("./events_migration.sql"),
-    include_str!("./humans_migration.sql"),
-    include_str!("./organizations_migration.sql"),
-    include_str!("./sessions_migration.sql"),
-    include_str!("./session_participants_migration.sql"),
-    include_str!("./templates_migration.sql"),
-    include_str!("./chat_groups_migration.sql"),
-    include_str!("./chat_messages_migration.sql"),
-    include_str!("./extension_mappings_migration.sql"),
-    include_str!("./tags_migration.sql"),
-    include_str!("./tag_sessions_migration.sql"),
-    include_str!("./calendars_migration_1.sql"),
-    include_str!("./sessions_migration_1.sql"),
-    include_str!("./sessions_migration_2.sql"),
-    include_str!("./sessions_migration_3.sql"),
-    include_str!("./sessions_migration_4.sql"),
-    include_str!("./chat_groups_migration_1.sql"),
-    include_str!("./events_migration_1.sql"),
-    include_str!("./session_participants_migration_1.sql"),
-    include_str!("./events_migration_2.sql"),
-    include_str!("./chat_messages_migration_1.sql"),
-    include_str!("./chat_messages_migration_2.sql"),
-    include_str!("./templates_migration_1.sql"),
-    include_str!("./chat_conversations_migration.sql"),
-    include_str!("./chat_messages_v2_migration.sql"),
+// Append only. Do not reorder, and do not edit one that has shipped: databases
+// record a checksum of each migration applied and refuse to open with a
+// changed one. Down scripts are optional and only needed to roll back.
+pub const MIGRATIONS: [Migration; 45] = [
+    Migration::new(include_str!("./calendars_migration.sql")),
+    Migration::new(include_str!("./configs_migration.sql")),
+    Migration::new(include_str!("./events_migration.sql")),
+    Migration::new(include_str!("./humans_migration.sql")),
+    Migration::new(include_str!("./organizations_migration.sql")),
+    Migration::new(include_str!("./sessions_migration.sql")),
+    Migration::new(include_str!("./session_participants_migration.sql")),
+    Migration::new(include_str!("./templates_migration.sql")),
+    Migration::new(include_str!("./chat_groups_migration.sql")),
+    Migration::new(include_str!("./chat_messages_migration.sql")),
+    Migration::new(include_str!("./extension_mappings_migration.sql")),
+    Migration::new(include_str!("./tags_migration.sql")),
+    Migration::new(include_str!("./tag_sessions_migration.sql")),
+    Migration::new(include_str!("./calendars_migration_1.sql")),
+    Migration::new(include_str!("./sessions_migration_1.sql")),
+    Migration::new(include_str!("./sessions_migration_2.sql")),
+    Migration::new(include_str!("./sessions_migration_3.sql")),
+    Migration::new(include_str!("./sessions_migration_4.sql")),
+    Migration::new(include_str!("./chat_groups_migration_1.sql")),
+    Migration::new(include_str!("./events_migration_1.sql")),
+    Migration::new(include_str!("./session_participants_migration_1.sql")),
+    Migration::new(include_str!("./events_migration_2.sql")),
+    Migration::new(include_str!("./chat_messages_migration_1.sql")),
+    Migration::new(include_str!("./chat_messages_migration_2.sql")),
+    Migration::new(include_str!("./templates_migration_1.sql")),
+    Migration::new(include_str!("./chat_conversations_migration.sql")),
+    Migration::new(include_str!("./chat_messages_v2_migration.sql")),
+    Migration::new(include_str!("./action_items_migration.sql")),
+    Migration::new(include_str!("./decisions_migration.sql")),
+    Migration::new(include_str!("./tombstones_migration.sql")),
+    Migration::new(include_str!("./humans_migration_1.sql")),
+    Migration::new(include_str!("./events_migration_3.sql")),
+    Migration::new(include_str!("./tags_migration_1.sql")),
+    Migration::new(include_str!("./templates_migration_2.sql")),
+    Migration::new(include_str!("./sessions_migration_5.sql")),
+    Migration::new(include_str!("./sessions_fts_migration.sql")),
+    Migration::new(include_str!("./transcript_words_migration.sql")),
+    Migration::new(include_str!("./sessions_migration_6.sql"))
+        .with_down(include_str!("./sessions_migration_6.down.sql")),
+    Migration::new(include_str!("./templates_migration_3.sql"))
+        .with_down(include_str!("./templates_migration_3.down.sql")),
+    Migration::new(include_str!("./humans_migration_2.sql"))
+        .with_down(include_str!("./humans_migration_2.down.sql")),
+    Migration::new(include_str!("./session_revisions_migration.sql"))
+        .with_down(include_str!("./session_revisions_migration.down.sql")),
+    Migration::new(include_str!("./organizations_migration_1.sql"))
+        .with_down(include_str!("./organizations_migration_1.down.sql")),
+    Migration::new(include_str!("./calendars_migration_2.sql"))
+        .with_down(include_str!("./calendars_migration_2.down.sql")),
+    Migration::new(include_str!("./tag_sessions_migration_1.sql"))
+        .with_down(include_str!("./tag_sessions_migration_1.down.sql")),
+    Migration::new(include_str!("./session_participants_migration_2.sql"))
+        .with_down(include_str!("./session_participants_migration_2.down.sql")),
+];
+
+// Parents before children, so foreign keys resolve on the receiving side.
+const SYNC_TABLES: [hypr_db_core::SyncTable; 11] = [
+    hypr_db_core::SyncTable::new("organizations"),
+    hypr_db_core::SyncTable::new("humans"),
+    hypr_db_core::SyncTable::new("calendars")
+        .with_unique(&[hypr_db_core::UniqueKey {
+            columns: &["tracking_id"],
+            on_conflict: hypr_db_core::OnUniqueConflict::Merge,
+        }])
+        .with_referenced_by(&[("events", "calendar_id")]),
+    hypr_db_core::SyncTable::new("events")
+        .with_unique(&[hypr_db_core::UniqueKey {
+            columns: &["tracking_id"],
+            on_conflict: hypr_db_core::OnUniqueConflict::Merge,
+        }])
+        .with_referenced_by(&[("sessions", "calendar_event_id")]),
+    hypr_db_core::SyncTable::new("tags")
+        .with_unique(&[hypr_db_core::UniqueKey {
+            columns: &["name"],
+            on_conflict: hypr_db_core::OnUniqueConflict::Merge,
+        }])
+        .with_referenced_by(&[("tags_sessions", "tag_id")]),
+    hypr_db_core::SyncTable::new("templates"),
+    // Two sessions for one event keep their notes; one is unlinked instead.
+    hypr_db_core::SyncTable::new("sessions")
+        .with_merged_columns(&[
+            ("raw_memo_html", "raw_memo_updated_at"),
+            ("enhanced_memo_html", "enhanced_memo_updated_at"),
+            ("pre_meeting_memo_html", "pre_meeting_memo_updated_at"),
+        ])
+        .with_unique(&[hypr_db_core::UniqueKey {
+            columns: &["calendar_event_id"],
+            on_conflict: hypr_db_core::OnUniqueConflict::Clear,
+        }]),
+    hypr_db_core::SyncTable::new("tags_sessions").with_key(&["tag_id", "session_id"]),
+    hypr_db_core::SyncTable::new("session_participants").with_key(&["session_id", "human_id"]),
+    hypr_db_core::SyncTable::new("transcript_words").with_unique(&[hypr_db_core::UniqueKey {
+        columns: &["session_id", "position"],
+        on_conflict: hypr_db_core::OnUniqueConflict::Merge,
+    }]),
+    hypr_db_core::SyncTable::new("session_revisions"),
 ];
 
 pub async fn migrate(db: &UserDatabase) -> Result<(), crate::Error> {
     let conn = db.conn()?;
-    hypr_db_core::migrate(&conn, MIGRATIONS.to_vec()).await?;
+    hypr_db_core::migrate(&conn, &MIGRATIONS).await?;
+
+    if let Some(remote) = db.remote_conn()? {
+        hypr_db_core::migrate(&remote, &MIGRATIONS).await?;
+    }
 
     Ok(())
 }
 
+impl UserDatabase {
+    /// Exchanges sessions with their transcripts, note history, tags and
+    /// participants, humans and organizations, calendars and events, and
+    /// templates with the cloud copy. Notes edited on two
+    /// devices keep each device's latest version of every memo rather than one
+    /// device's whole row.
+    pub async fn sync(&self) -> Result<hypr_db_core::SyncSummary, crate::Error> {
+        self.db.sync(&SYNC_TABLES).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::UserDatabase;
-    use crate::migrate;
-    use hypr_db_core::DatabaseBuilder;
+    use crate::{GetSessionFilter, Human, MIGRATIONS, Session, Tag, migrate};
+    use hypr_db_core::{DatabaseBuilder, Migration};
 
     pub async fn setup_db() -> UserDatabase {
         let base_db = DatabaseBuilder::default().memory().build().await.unwrap();
@@ -193,4 +310,391 @@ mod tests {
     async fn test_migrate() {
         let _ = setup_db().await;
     }
+
+    // A diff here that isn't from a new migration means an applied one changed.
+    #[tokio::test]
+    async fn test_schema() {
+        let db = setup_db().await;
+        let schema = hypr_db_core::schema(&db.conn().unwrap()).await.unwrap();
+        insta::assert_snapshot!(schema);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_refuses_edited_history() {
+        let db = setup_db().await;
+        let conn = db.conn().unwrap();
+
+        let mut edited = MIGRATIONS;
+        edited[1] = Migration::new("CREATE TABLE configs (id TEXT);");
+        assert!(matches!(
+            hypr_db_core::migrate(&conn, &edited).await,
+            Err(hypr_db_core::Error::MigrationChanged(2))
+        ));
+        assert!(matches!(
+            hypr_db_core::pending_migrations(&conn, &edited).await,
+            Err(hypr_db_core::Error::MigrationChanged(2))
+        ));
+
+        let mut edited = MIGRATIONS;
+        edited[1] = Migration::new(MIGRATIONS[1].up.replace('\n', "\r\n").leak());
+        hypr_db_core::migrate(&conn, &edited).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrate_stamps_legacy_history() {
+        let db = setup_db().await;
+        let conn = db.conn().unwrap();
+        conn.execute_batch(&format!(
+            "DROP TABLE _migrations;
+            CREATE TABLE _migrations (version INTEGER PRIMARY KEY, applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP);
+            INSERT INTO _migrations (version) VALUES ({});",
+            MIGRATIONS.len()
+        ))
+        .await
+        .unwrap();
+
+        assert!(
+            hypr_db_core::pending_migrations(&conn, &MIGRATIONS)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        migrate(&db).await.unwrap();
+
+        let mut rows = conn
+            .query(
+                "SELECT COUNT(*) FROM _migrations WHERE checksum IS NOT NULL",
+                (),
+            )
+            .await
+            .unwrap();
+        let stamped: i32 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+        assert_eq!(stamped, MIGRATIONS.len() as i32);
+    }
+
+    #[tokio::test]
+    async fn test_rollback() {
+        let db = setup_db().await;
+        let conn = db.conn().unwrap();
+        let schema = hypr_db_core::schema(&conn).await.unwrap();
+        let latest = MIGRATIONS.len() as i32;
+
+        assert!(matches!(
+            hypr_db_core::rollback(&conn, &MIGRATIONS, latest - 9).await,
+            Err(hypr_db_core::Error::MigrationIrreversible(v)) if v == latest - 8
+        ));
+        assert_eq!(hypr_db_core::schema(&conn).await.unwrap(), schema);
+
+        let steps = hypr_db_core::pending_rollback(&conn, &MIGRATIONS, latest - 4)
+            .await
+            .unwrap();
+        assert_eq!(
+            steps.iter().map(|s| s.version).collect::<Vec<_>>(),
+            vec![latest, latest - 1, latest - 2, latest - 3]
+        );
+
+        hypr_db_core::rollback(&conn, &MIGRATIONS, latest - 4)
+            .await
+            .unwrap();
+        assert_ne!(hypr_db_core::schema(&conn).await.unwrap(), schema);
+        assert_eq!(
+            hypr_db_core::pending_migrations(&conn, &MIGRATIONS)
+                .await
+                .unwrap()
+                .len(),
+            4
+        );
+
+        migrate(&db).await.unwrap();
+        assert_eq!(hypr_db_core::schema(&conn).await.unwrap(), schema);
+    }
+
+    // Both devices sync through a file database standing in for sqld;
+    // `test_sync_sqld` covers a real server.
+    async fn setup_devices() -> (tempfile::TempDir, UserDatabase, UserDatabase) {
+        let dir = tempfile::tempdir().unwrap();
+        let cloud = std::sync::Arc::new(
+            libsql::Builder::new_local(dir.path().join("cloud.db"))
+                .build()
+                .await
+                .unwrap(),
+        );
+
+        let mut devices = Vec::new();
+        for name in ["a.db", "b.db"] {
+            let local = libsql::Builder::new_local(dir.path().join(name))
+                .build()
+                .await
+                .unwrap();
+            let db = UserDatabase::from(hypr_db_core::Database::SyncedConnection {
+                local: local.connect().unwrap(),
+                remote: cloud.clone(),
+            });
+            migrate(&db).await.unwrap();
+            devices.push(db);
+        }
+
+        let b = devices.pop().unwrap();
+        let a = devices.pop().unwrap();
+        (dir, a, b)
+    }
+
+    fn session(id: &str, user_id: &str) -> Session {
+        Session {
+            id: id.to_string(),
+            created_at: chrono::Utc::now(),
+            visited_at: chrono::Utc::now(),
+            user_id: user_id.to_string(),
+            calendar_event_id: None,
+            title: "Standup".to_string(),
+            raw_memo_html: String::new(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+            words: vec![],
+            record_start: None,
+            record_end: None,
+            pre_meeting_memo_html: None,
+        }
+    }
+
+    // Timestamps have millisecond resolution.
+    async fn tick() {
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_merges_notes() {
+        let (_dir, a, b) = setup_devices().await;
+
+        let human = a.upsert_human(Human::default()).await.unwrap();
+        a.upsert_session(session("s1", &human.id)).await.unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+
+        let pulled = b.get_session(GetSessionFilter::Id("s1".into())).await;
+        assert_eq!(pulled.unwrap().unwrap().title, "Standup");
+        assert!(b.get_human(&human.id).await.unwrap().is_some());
+
+        // Offline on both devices: A takes notes, then B renames and enhances.
+        let mut on_a = session("s1", &human.id);
+        on_a.raw_memo_html = "<p>ship friday</p>".to_string();
+        a.upsert_session(on_a).await.unwrap();
+        tick().await;
+        let mut on_b = session("s1", &human.id);
+        on_b.title = "Weekly standup".to_string();
+        on_b.enhanced_memo_html = Some("<h1>Summary</h1>".to_string());
+        b.upsert_session(on_b).await.unwrap();
+
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+        a.sync().await.unwrap();
+
+        for db in [&a, &b] {
+            let merged = db
+                .get_session(GetSessionFilter::Id("s1".into()))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(merged.title, "Weekly standup");
+            assert_eq!(merged.raw_memo_html, "<p>ship friday</p>");
+            assert_eq!(
+                merged.enhanced_memo_html.as_deref(),
+                Some("<h1>Summary</h1>")
+            );
+        }
+
+        // Converged, so another round changes nothing.
+        assert_eq!(
+            a.sync().await.unwrap(),
+            hypr_db_core::SyncSummary::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_deletes() {
+        let (_dir, a, b) = setup_devices().await;
+
+        let human = a.upsert_human(Human::default()).await.unwrap();
+        a.upsert_session(session("gone", &human.id)).await.unwrap();
+        a.upsert_session(session("edited", &human.id))
+            .await
+            .unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+
+        // Rows only go for good once they're purged from the trash.
+        a.delete_session("gone").await.unwrap();
+        a.delete_session("edited").await.unwrap();
+        a.purge_trash(chrono::Duration::zero()).await.unwrap();
+        tick().await;
+        // Editing after the delete wins over it.
+        b.visit_session("edited").await.unwrap();
+
+        assert_eq!(a.sync().await.unwrap().deleted, 2);
+        let summary = b.sync().await.unwrap();
+        assert_eq!((summary.deleted, summary.pushed), (1, 1));
+        a.sync().await.unwrap();
+
+        for db in [&a, &b] {
+            let get = |id: &str| db.get_session(GetSessionFilter::Id(id.into()));
+            assert!(get("gone").await.unwrap().is_none());
+            assert!(get("edited").await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_trash() {
+        let (_dir, a, b) = setup_devices().await;
+
+        let human = a.upsert_human(Human::default()).await.unwrap();
+        a.upsert_session(session("trashed", &human.id))
+            .await
+            .unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+
+        tick().await;
+        a.delete_session("trashed").await.unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+
+        assert!(b.list_sessions(None).await.unwrap().is_empty());
+        let trash = b.list_trash().await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id, "trashed");
+    }
+
+    #[tokio::test]
+    async fn test_sync_refuses_rolled_back_tables() {
+        let (_dir, a, b) = setup_devices().await;
+
+        let human = a.upsert_human(Human::default()).await.unwrap();
+        a.upsert_session(session("s1", &human.id)).await.unwrap();
+
+        let latest = MIGRATIONS.len() as i32;
+        hypr_db_core::rollback(&a.conn().unwrap(), &MIGRATIONS, latest - 4)
+            .await
+            .unwrap();
+        assert!(matches!(
+            a.sync().await,
+            Err(hypr_db_core::Error::SyncTableUnavailable(table)) if table == "organizations"
+        ));
+        assert_eq!(
+            b.sync().await.unwrap(),
+            hypr_db_core::SyncSummary::default()
+        );
+
+        migrate(&a).await.unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+        let pulled = b.get_session(GetSessionFilter::Id("s1".into())).await;
+        assert!(pulled.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sync_unique_conflicts() {
+        let (_dir, a, b) = setup_devices().await;
+
+        let human = a.upsert_human(Human::default()).await.unwrap();
+        a.upsert_session(session("s1", &human.id)).await.unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+
+        // Both devices tag the session "work" before hearing from each other.
+        for (db, tag_id) in [(&a, "tag-a"), (&b, "tag-b")] {
+            let tag = Tag {
+                id: tag_id.to_string(),
+                name: "work".to_string(),
+            };
+            db.upsert_tag(tag).await.unwrap();
+            db.assign_tag_to_session(tag_id, "s1").await.unwrap();
+        }
+        b.session_add_participant("s1", &human.id).await.unwrap();
+
+        a.sync().await.unwrap();
+        assert_eq!(b.sync().await.unwrap().conflicts, 1);
+        a.sync().await.unwrap();
+
+        for db in [&a, &b] {
+            let tags = db.list_all_tags().await.unwrap();
+            assert_eq!(tags.len(), 1);
+            assert_eq!(tags[0].id, "tag-a");
+            assert_eq!(db.list_session_tags("s1").await.unwrap().len(), 1);
+            assert_eq!(db.session_list_participants("s1").await.unwrap().len(), 1);
+        }
+
+        a.unassign_tag_from_session("tag-a", "s1").await.unwrap();
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+        assert!(b.list_session_tags("s1").await.unwrap().is_empty());
+
+        assert_eq!(
+            a.sync().await.unwrap(),
+            hypr_db_core::SyncSummary::default()
+        );
+    }
+
+    // Needs a server, e.g. `sqld --http-listen-addr 127.0.0.1:8080` with
+    // `LIBSQL_URL=http://127.0.0.1:8080`.
+    #[tokio::test]
+    #[ignore]
+    async fn test_sync_sqld() {
+        let url = std::env::var("LIBSQL_URL").expect("LIBSQL_URL must be set");
+        let token = std::env::var("LIBSQL_AUTH_TOKEN").unwrap_or_default();
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut devices = Vec::new();
+        for name in ["a.db", "b.db"] {
+            let db = hypr_db_core::DatabaseBuilder::default()
+                .local(dir.path().join(name))
+                .remote(url.clone(), token.clone())
+                .build()
+                .await
+                .unwrap();
+            let db = UserDatabase::from(db);
+            migrate(&db).await.unwrap();
+            devices.push(db);
+        }
+        let (a, b) = (&devices[0], &devices[1]);
+
+        // The server outlives the test, so nothing here can collide with a
+        // previous run.
+        let id = |prefix: &str| format!("{}-{}", prefix, uuid::Uuid::new_v4());
+        let (session_id, tag_name) = (id("session"), id("tag"));
+
+        let human = a.upsert_human(Human::default()).await.unwrap();
+        let mut on_a = session(&session_id, &human.id);
+        on_a.raw_memo_html = "<p>it's a 'quoted' note</p>".to_string();
+        a.upsert_session(on_a).await.unwrap();
+        let tag = a
+            .upsert_tag(Tag {
+                id: id("tag"),
+                name: tag_name.clone(),
+            })
+            .await
+            .unwrap();
+        a.assign_tag_to_session(tag.id, &session_id).await.unwrap();
+        b.upsert_tag(Tag {
+            id: id("tag"),
+            name: tag_name.clone(),
+        })
+        .await
+        .unwrap();
+
+        a.sync().await.unwrap();
+        b.sync().await.unwrap();
+        a.sync().await.unwrap();
+
+        for db in [a, b] {
+            let pulled = db
+                .get_session(GetSessionFilter::Id(session_id.clone()))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(pulled.raw_memo_html, "<p>it's a 'quoted' note</p>");
+            let tags = db.list_session_tags(&session_id).await.unwrap();
+            assert_eq!(tags.len(), 1);
+            assert_eq!(tags[0].name, tag_name);
+        }
+    }
 }
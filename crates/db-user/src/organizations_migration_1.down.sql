@@ -0,0 +1,16 @@
+-- Rows stop syncing.
+DROP TRIGGER IF EXISTS organizations_stamp_insert;
+
+DROP TRIGGER IF EXISTS organizations_stamp_update;
+
+DROP TRIGGER IF EXISTS organizations_tombstone;
+
+DROP TRIGGER IF EXISTS organizations_untombstone;
+
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'organizations';
+
+ALTER TABLE
+  organizations DROP COLUMN updated_at;
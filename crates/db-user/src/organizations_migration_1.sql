@@ -0,0 +1,53 @@
+ALTER TABLE
+  organizations
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  organizations
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS organizations_stamp_insert
+AFTER
+INSERT
+  ON organizations WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  organizations
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS organizations_stamp_update
+AFTER
+UPDATE
+  ON organizations WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  organizations
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS organizations_tombstone
+AFTER
+  DELETE ON organizations BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('organizations', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS organizations_untombstone
+AFTER
+INSERT
+  ON organizations BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'organizations'
+  AND row_id = NEW.id;
+END;
@@ -73,7 +73,7 @@ impl UserDatabase {
         let conn = self.conn()?;
 
         let sql = format!(
-            "SELECT * FROM {} WHERE organization_id = ?",
+            "SELECT * FROM {} WHERE organization_id = ? AND deleted_at IS NULL",
             Human::sql_table()
         );
         let mut rows = conn.query(&sql, vec![organization_id.into()]).await?;
@@ -0,0 +1,18 @@
+-- Rows stop syncing.
+DROP TRIGGER IF EXISTS session_participants_stamp_insert;
+
+DROP TRIGGER IF EXISTS session_participants_stamp_update;
+
+DROP TRIGGER IF EXISTS session_participants_rekey;
+
+DROP TRIGGER IF EXISTS session_participants_tombstone;
+
+DROP TRIGGER IF EXISTS session_participants_untombstone;
+
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'session_participants';
+
+ALTER TABLE
+  session_participants DROP COLUMN updated_at;
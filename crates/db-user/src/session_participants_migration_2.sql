@@ -0,0 +1,80 @@
+ALTER TABLE
+  session_participants
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  session_participants
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS session_participants_stamp_insert
+AFTER
+INSERT
+  ON session_participants WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  session_participants
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  rowid = NEW.rowid;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_participants_stamp_update
+AFTER
+UPDATE
+  ON session_participants WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  session_participants
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  rowid = NEW.rowid;
+END;
+
+-- Rows are identified by both columns, so re-pointing one is a delete of
+-- the old pair as far as the other side is concerned.
+CREATE TRIGGER IF NOT EXISTS session_participants_rekey
+AFTER
+UPDATE
+  OF session_id,
+  human_id ON session_participants WHEN NEW.session_id IS NOT OLD.session_id
+  OR NEW.human_id IS NOT OLD.human_id BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  (
+    'session_participants',
+    OLD.session_id || '/' || OLD.human_id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  );
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'session_participants'
+  AND row_id = NEW.session_id || '/' || NEW.human_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_participants_tombstone
+AFTER
+  DELETE ON session_participants BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  (
+    'session_participants',
+    OLD.session_id || '/' || OLD.human_id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  );
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_participants_untombstone
+AFTER
+INSERT
+  ON session_participants BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'session_participants'
+  AND row_id = NEW.session_id || '/' || NEW.human_id;
+END;
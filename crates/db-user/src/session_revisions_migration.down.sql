@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS session_revisions;
+
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'session_revisions';
@@ -0,0 +1,109 @@
+CREATE TABLE IF NOT EXISTS session_revisions (
+  id TEXT PRIMARY KEY NOT NULL,
+  session_id TEXT NOT NULL,
+  field TEXT NOT NULL,
+  author TEXT NOT NULL,
+  content TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL DEFAULT '',
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_revisions_session ON session_revisions (session_id, field, created_at);
+
+-- Today's memos become the first revisions. Enhanced memos are what the AI
+-- wrote unless edited since, which can't be told apart any more.
+INSERT INTO
+  session_revisions (
+    id,
+    session_id,
+    field,
+    author,
+    content,
+    created_at,
+    updated_at
+  )
+SELECT
+  lower(hex(randomblob(16))),
+  id,
+  'raw',
+  'user',
+  raw_memo_html,
+  raw_memo_updated_at,
+  strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+FROM
+  sessions
+WHERE
+  raw_memo_html != '';
+
+INSERT INTO
+  session_revisions (
+    id,
+    session_id,
+    field,
+    author,
+    content,
+    created_at,
+    updated_at
+  )
+SELECT
+  lower(hex(randomblob(16))),
+  id,
+  'enhanced',
+  'ai',
+  enhanced_memo_html,
+  enhanced_memo_updated_at,
+  strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+FROM
+  sessions
+WHERE
+  enhanced_memo_html IS NOT NULL
+  AND enhanced_memo_html != '';
+
+CREATE TRIGGER IF NOT EXISTS session_revisions_stamp_insert
+AFTER
+INSERT
+  ON session_revisions WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  session_revisions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_revisions_stamp_update
+AFTER
+UPDATE
+  ON session_revisions WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  session_revisions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_revisions_tombstone
+AFTER
+  DELETE ON session_revisions BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  (
+    'session_revisions',
+    OLD.id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  );
+END;
+
+CREATE TRIGGER IF NOT EXISTS session_revisions_untombstone
+AFTER
+INSERT
+  ON session_revisions BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'session_revisions'
+  AND row_id = NEW.id;
+END;
@@ -0,0 +1,381 @@
+use hypr_db_core::SqlTable;
+use similar::{ChangeTag, TextDiff};
+
+use super::{
+    MemoField, RevisionAuthor, RevisionChange, RevisionChangeKind, Session, SessionRevision,
+    UserDatabase,
+};
+
+// The editor saves every few seconds while typing, so a user's saves this
+// close to their last revision are folded into it.
+const COALESCE_WINDOW_MINUTES: i64 = 5;
+
+// Matches the triggers' strftime format, so revisions sort by creation time.
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+// Block-level tags end a line, so a diff lines up with the paragraphs, list
+// items and headings shown in the editor.
+fn memo_text(html: &str) -> String {
+    const BLOCKS: [&str; 14] = [
+        "p",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "li",
+        "div",
+        "blockquote",
+        "pre",
+        "tr",
+        "hr",
+        "br",
+    ];
+
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+
+        let tag = &rest[start + 1..start + len];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let ends_line = tag.starts_with('/') || matches!(name.as_str(), "br" | "hr");
+        if ends_line && BLOCKS.contains(&name.as_str()) {
+            text.push('\n');
+        }
+
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// Records `content` as the field's newest revision unless nothing changed.
+/// With `coalesce`, a user's quick successive saves update their last
+/// revision instead of adding one each. Takes the connection so callers can
+/// record it in the same transaction as the memo it comes from.
+pub(crate) async fn record_memo_revision(
+    conn: &libsql::Connection,
+    session_id: &str,
+    field: MemoField,
+    author: RevisionAuthor,
+    content: &str,
+    coalesce: bool,
+) -> Result<(), crate::Error> {
+    let latest = {
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? AND field = ? ORDER BY created_at DESC, rowid DESC LIMIT 1",
+            SessionRevision::sql_table()
+        );
+        let mut rows = conn
+            .query(&sql, vec![session_id.to_string(), field.to_string()])
+            .await?;
+        match rows.next().await? {
+            Some(row) => Some(libsql::de::from_row::<SessionRevision>(&row)?),
+            None => None,
+        }
+    };
+
+    match latest {
+        Some(latest) if latest.content == content => Ok(()),
+        None if content.is_empty() => Ok(()),
+        Some(latest)
+            if coalesce
+                && author == RevisionAuthor::User
+                && latest.author == RevisionAuthor::User
+                && chrono::Utc::now() - latest.created_at
+                    < chrono::Duration::minutes(COALESCE_WINDOW_MINUTES) =>
+        {
+            let sql = format!(
+                "UPDATE {} SET content = ? WHERE id = ?",
+                SessionRevision::sql_table()
+            );
+            conn.execute(&sql, vec![content.to_string(), latest.id])
+                .await?;
+            Ok(())
+        }
+        _ => {
+            let sql = format!(
+                "INSERT INTO {} (id, session_id, field, author, content, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)",
+                SessionRevision::sql_table()
+            );
+            conn.execute(
+                &sql,
+                vec![
+                    uuid::Uuid::new_v4().to_string(),
+                    session_id.to_string(),
+                    field.to_string(),
+                    author.to_string(),
+                    content.to_string(),
+                    now(),
+                ],
+            )
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+impl UserDatabase {
+    /// Newest first, optionally only for one of the memos.
+    pub async fn list_session_revisions(
+        &self,
+        session_id: impl Into<String>,
+        field: Option<MemoField>,
+    ) -> Result<Vec<SessionRevision>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? AND (? IS NULL OR field = ?)
+            ORDER BY created_at DESC, rowid DESC",
+            SessionRevision::sql_table()
+        );
+        let field = field
+            .map(|f| libsql::Value::Text(f.to_string()))
+            .unwrap_or(libsql::Value::Null);
+        let mut rows = conn
+            .query(
+                &sql,
+                vec![libsql::Value::Text(session_id.into()), field.clone(), field],
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: SessionRevision = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    pub async fn get_session_revision(
+        &self,
+        id: impl Into<String>,
+    ) -> Result<Option<SessionRevision>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE id = ?",
+            SessionRevision::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![id.into()]).await?;
+
+        match rows.next().await? {
+            None => Ok(None),
+            Some(row) => {
+                let item: SessionRevision = libsql::de::from_row(&row)?;
+                Ok(Some(item))
+            }
+        }
+    }
+
+    /// Line-by-line changes from one revision's text to another's, e.g. from
+    /// the AI's summary to the user's edit of it.
+    pub async fn diff_session_revisions(
+        &self,
+        from_id: impl Into<String>,
+        to_id: impl Into<String>,
+    ) -> Result<Vec<RevisionChange>, crate::Error> {
+        let mut contents = Vec::new();
+        for id in [from_id.into(), to_id.into()] {
+            let revision = self
+                .get_session_revision(&id)
+                .await?
+                .ok_or_else(|| crate::Error::InvalidInput(format!("no revision {}", id)))?;
+            contents.push(memo_text(&revision.content));
+        }
+
+        let diff = TextDiff::from_lines(&contents[0], &contents[1]);
+        Ok(diff
+            .iter_all_changes()
+            .map(|change| RevisionChange {
+                kind: match change.tag() {
+                    ChangeTag::Equal => RevisionChangeKind::Equal,
+                    ChangeTag::Insert => RevisionChangeKind::Insert,
+                    ChangeTag::Delete => RevisionChangeKind::Delete,
+                },
+                text: change.value().trim_end_matches('\n').to_string(),
+            })
+            .collect())
+    }
+
+    /// Puts the revision's content back into its memo. The restore is saved
+    /// as a new revision by the user, so nothing after it is lost either.
+    pub async fn restore_session_revision(
+        &self,
+        id: impl Into<String>,
+    ) -> Result<Session, crate::Error> {
+        let id = id.into();
+        let revision = self
+            .get_session_revision(&id)
+            .await?
+            .ok_or_else(|| crate::Error::InvalidInput(format!("no revision {}", id)))?;
+
+        let conn = self.conn()?;
+        // The memo and its revision land together or not at all.
+        let tx = conn.transaction().await?;
+
+        let session = {
+            let sql = format!(
+                "UPDATE sessions SET {} = ? WHERE id = ? RETURNING *",
+                revision.field.column()
+            );
+            let mut rows = tx
+                .query(
+                    &sql,
+                    vec![revision.content.clone(), revision.session_id.clone()],
+                )
+                .await?;
+            let row = rows.next().await?.ok_or_else(|| {
+                crate::Error::InvalidInput(format!("no session {}", revision.session_id))
+            })?;
+            Session::from_row(&row)?
+        };
+
+        record_memo_revision(
+            &tx,
+            &revision.session_id,
+            revision.field,
+            RevisionAuthor::User,
+            &revision.content,
+            false,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::memo_text;
+    use crate::{
+        Human, MemoField, RevisionAuthor, RevisionChange, RevisionChangeKind, Session,
+        tests::setup_db,
+    };
+
+    #[test]
+    fn test_memo_text() {
+        assert_eq!(
+            memo_text(
+                "<h1>Plan</h1><ul><li><p>ship&nbsp;it</p></li><li>R&amp;D</li></ul><p>a<br>b</p>"
+            ),
+            "Plan\nship it\nR&D\na\nb\n"
+        );
+        assert_eq!(memo_text(""), "");
+    }
+
+    #[tokio::test]
+    async fn test_session_revisions() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let mut session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id,
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Planning".to_string(),
+                raw_memo_html: "<p>ship friday</p>".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![],
+                record_start: None,
+                record_end: None,
+                pre_meeting_memo_html: None,
+            })
+            .await
+            .unwrap();
+
+        session.enhanced_memo_html = Some("<p>Ship on Friday.</p><p>Owner: Ada</p>".to_string());
+        let mut session = db
+            .upsert_session_as(session, RevisionAuthor::Ai)
+            .await
+            .unwrap();
+
+        // Quick successive edits make up one revision.
+        session.enhanced_memo_html = Some("<p>Ship on Friday.</p><p>Owner: Bo</p>".to_string());
+        let mut session = db.upsert_session(session).await.unwrap();
+        session.enhanced_memo_html = Some("<p>Ship on Friday.</p><p>Owner: Grace</p>".to_string());
+        let session = db.upsert_session(session).await.unwrap();
+        db.visit_session(&session.id).await.unwrap();
+
+        let enhanced = db
+            .list_session_revisions(&session.id, Some(MemoField::Enhanced))
+            .await
+            .unwrap();
+        assert_eq!(
+            enhanced.iter().map(|r| r.author).collect::<Vec<_>>(),
+            vec![RevisionAuthor::User, RevisionAuthor::Ai]
+        );
+        assert_eq!(
+            db.list_session_revisions(&session.id, None)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let (edited, generated) = (&enhanced[0], &enhanced[1]);
+        assert_eq!(
+            db.diff_session_revisions(&generated.id, &edited.id)
+                .await
+                .unwrap(),
+            vec![
+                RevisionChange {
+                    kind: RevisionChangeKind::Equal,
+                    text: "Ship on Friday.".to_string(),
+                },
+                RevisionChange {
+                    kind: RevisionChangeKind::Delete,
+                    text: "Owner: Ada".to_string(),
+                },
+                RevisionChange {
+                    kind: RevisionChangeKind::Insert,
+                    text: "Owner: Grace".to_string(),
+                },
+            ]
+        );
+
+        let restored = db.restore_session_revision(&generated.id).await.unwrap();
+        assert_eq!(
+            restored.enhanced_memo_html,
+            generated.content.clone().into()
+        );
+        assert_eq!(restored.raw_memo_html, "<p>ship friday</p>");
+
+        let enhanced = db
+            .list_session_revisions(&session.id, Some(MemoField::Enhanced))
+            .await
+            .unwrap();
+        assert_eq!(enhanced.len(), 3);
+        assert_eq!(enhanced[0].author, RevisionAuthor::User);
+        assert_eq!(enhanced[0].content, generated.content);
+        assert_eq!(enhanced[1].content, edited.content);
+    }
+}
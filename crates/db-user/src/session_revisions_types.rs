@@ -0,0 +1,67 @@
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[derive(Copy, strum::EnumString, strum::Display)]
+    pub enum MemoField {
+        #[serde(rename = "raw")]
+        #[strum(serialize = "raw")]
+        Raw,
+        #[serde(rename = "enhanced")]
+        #[strum(serialize = "enhanced")]
+        Enhanced,
+    }
+}
+
+impl MemoField {
+    pub(crate) fn column(&self) -> &'static str {
+        match self {
+            MemoField::Raw => "raw_memo_html",
+            MemoField::Enhanced => "enhanced_memo_html",
+        }
+    }
+}
+
+user_common_derives! {
+    #[derive(Copy, strum::EnumString, strum::Display)]
+    pub enum RevisionAuthor {
+        #[serde(rename = "user")]
+        #[strum(serialize = "user")]
+        User,
+        #[serde(rename = "ai")]
+        #[strum(serialize = "ai")]
+        Ai,
+    }
+}
+
+user_common_derives! {
+    #[sql_table("session_revisions")]
+    pub struct SessionRevision {
+        pub id: String,
+        pub session_id: String,
+        pub field: MemoField,
+        pub author: RevisionAuthor,
+        /// The memo's HTML as saved.
+        pub content: String,
+        pub created_at: chrono::DateTime<chrono::Utc>,
+    }
+}
+
+user_common_derives! {
+    #[derive(Copy)]
+    pub enum RevisionChangeKind {
+        #[serde(rename = "equal")]
+        Equal,
+        #[serde(rename = "insert")]
+        Insert,
+        #[serde(rename = "delete")]
+        Delete,
+    }
+}
+
+user_common_derives! {
+    pub struct RevisionChange {
+        pub kind: RevisionChangeKind,
+        /// One line of the memo's text, without markup.
+        pub text: String,
+    }
+}
@@ -0,0 +1,400 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS sessions_fts USING fts5(
+  session_id UNINDEXED,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants,
+  tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- What gets indexed for each session. Tags are dropped from the memos by
+-- splitting the HTML at '<' and skipping to the matching '>', which works in
+-- plain SQL on both local databases and Turso.
+CREATE VIEW IF NOT EXISTS sessions_search_source AS
+SELECT
+  s.id AS session_id,
+  s.title AS title,
+  (
+    SELECT
+      trim(
+        replace(
+          replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    group_concat(
+                      CASE
+                        WHEN substr(part.value, 1, 1) = '<' THEN substr(part.value, instr(part.value, '>') + 1)
+                        ELSE part.value
+                      END,
+                      ' '
+                    ),
+                    '&nbsp;',
+                    ' '
+                  ),
+                  '&lt;',
+                  '<'
+                ),
+                '&gt;',
+                '>'
+              ),
+              '&quot;',
+              '"'
+            ),
+            '&#39;',
+            ''''
+          ),
+          '&amp;',
+          '&'
+        )
+      )
+    FROM
+      (
+        SELECT
+          '["' || replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    replace(coalesce(s.raw_memo_html, ''), '\', '\\'),
+                    '"',
+                    '\"'
+                  ),
+                  char(10),
+                  ' '
+                ),
+                char(13),
+                ' '
+              ),
+              char(9),
+              ' '
+            ),
+            '<',
+            '","<'
+          ) || '"]' AS parts
+      ) AS html,
+      json_each(
+        CASE
+          WHEN json_valid(html.parts) THEN html.parts
+          ELSE '[]'
+        END
+      ) AS part
+  ) AS raw_memo,
+  (
+    SELECT
+      trim(
+        replace(
+          replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    group_concat(
+                      CASE
+                        WHEN substr(part.value, 1, 1) = '<' THEN substr(part.value, instr(part.value, '>') + 1)
+                        ELSE part.value
+                      END,
+                      ' '
+                    ),
+                    '&nbsp;',
+                    ' '
+                  ),
+                  '&lt;',
+                  '<'
+                ),
+                '&gt;',
+                '>'
+              ),
+              '&quot;',
+              '"'
+            ),
+            '&#39;',
+            ''''
+          ),
+          '&amp;',
+          '&'
+        )
+      )
+    FROM
+      (
+        SELECT
+          '["' || replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    replace(coalesce(s.enhanced_memo_html, ''), '\', '\\'),
+                    '"',
+                    '\"'
+                  ),
+                  char(10),
+                  ' '
+                ),
+                char(13),
+                ' '
+              ),
+              char(9),
+              ' '
+            ),
+            '<',
+            '","<'
+          ) || '"]' AS parts
+      ) AS html,
+      json_each(
+        CASE
+          WHEN json_valid(html.parts) THEN html.parts
+          ELSE '[]'
+        END
+      ) AS part
+  ) AS enhanced_memo,
+  (
+    SELECT
+      group_concat(json_extract(word.value, '$.text'), ' ')
+    FROM
+      json_each(
+        CASE
+          WHEN json_valid(s.words) THEN s.words
+          ELSE '[]'
+        END
+      ) AS word
+  ) AS transcript,
+  (
+    SELECT
+      group_concat(
+        trim(coalesce(h.full_name, '') || ' ' || coalesce(h.email, '')),
+        ' '
+      )
+    FROM
+      session_participants sp
+      JOIN humans h ON h.id = sp.human_id
+    WHERE
+      sp.session_id = s.id
+      AND (
+        sp.deleted = FALSE
+        OR sp.deleted IS NULL
+      )
+  ) AS participants
+FROM
+  sessions s;
+
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_insert
+AFTER
+INSERT
+  ON sessions BEGIN DELETE FROM
+  sessions_fts
+WHERE
+  session_id = NEW.id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_update
+AFTER
+UPDATE
+  OF title,
+  raw_memo_html,
+  enhanced_memo_html,
+  words ON sessions BEGIN DELETE FROM
+  sessions_fts
+WHERE
+  session_id = NEW.id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_delete
+AFTER
+  DELETE ON sessions BEGIN
+DELETE FROM
+  sessions_fts
+WHERE
+  session_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_participant_insert
+AFTER
+INSERT
+  ON session_participants BEGIN DELETE FROM
+  sessions_fts
+WHERE
+  session_id = NEW.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = NEW.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_participant_update
+AFTER
+UPDATE
+  ON session_participants BEGIN DELETE FROM
+  sessions_fts
+WHERE
+  session_id = NEW.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = NEW.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_participant_delete
+AFTER
+  DELETE ON session_participants BEGIN DELETE FROM
+  sessions_fts
+WHERE
+  session_id = OLD.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = OLD.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_human_update
+AFTER
+UPDATE
+  OF full_name,
+  email ON humans BEGIN
+DELETE FROM
+  sessions_fts
+WHERE
+  session_id IN (
+    SELECT
+      session_id
+    FROM
+      session_participants
+    WHERE
+      human_id = NEW.id
+  );
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id IN (
+    SELECT
+      session_id
+    FROM
+      session_participants
+    WHERE
+      human_id = NEW.id
+  );
+END;
@@ -0,0 +1,113 @@
+ALTER TABLE
+  sessions
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE
+  sessions
+ADD
+  COLUMN raw_memo_updated_at TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE
+  sessions
+ADD
+  COLUMN enhanced_memo_updated_at TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE
+  sessions
+ADD
+  COLUMN pre_meeting_memo_updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  sessions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+  raw_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+  enhanced_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+  pre_meeting_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS sessions_stamp_insert
+AFTER
+INSERT
+  ON sessions WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  sessions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+  raw_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+  enhanced_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+  pre_meeting_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_stamp_update
+AFTER
+UPDATE
+  ON sessions WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  sessions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_stamp_raw_memo
+AFTER
+UPDATE
+  OF raw_memo_html ON sessions WHEN NEW.raw_memo_html IS NOT OLD.raw_memo_html
+  AND NEW.raw_memo_updated_at IS OLD.raw_memo_updated_at BEGIN
+UPDATE
+  sessions
+SET
+  raw_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_stamp_enhanced_memo
+AFTER
+UPDATE
+  OF enhanced_memo_html ON sessions WHEN NEW.enhanced_memo_html IS NOT OLD.enhanced_memo_html
+  AND NEW.enhanced_memo_updated_at IS OLD.enhanced_memo_updated_at BEGIN
+UPDATE
+  sessions
+SET
+  enhanced_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_stamp_pre_meeting_memo
+AFTER
+UPDATE
+  OF pre_meeting_memo_html ON sessions WHEN NEW.pre_meeting_memo_html IS NOT OLD.pre_meeting_memo_html
+  AND NEW.pre_meeting_memo_updated_at IS OLD.pre_meeting_memo_updated_at BEGIN
+UPDATE
+  sessions
+SET
+  pre_meeting_memo_updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_tombstone
+AFTER
+  DELETE ON sessions BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('sessions', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_untombstone
+AFTER
+INSERT
+  ON sessions BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'sessions'
+  AND row_id = NEW.id;
+END;
@@ -0,0 +1,3 @@
+-- Sessions in the trash come back.
+ALTER TABLE
+  sessions DROP COLUMN deleted_at;
@@ -0,0 +1,4 @@
+ALTER TABLE
+  sessions
+ADD
+  COLUMN deleted_at TEXT DEFAULT NULL;
@@ -1,8 +1,46 @@
+use super::session_revisions_ops::record_memo_revision;
+use super::transcript_words_ops::write_transcript_words;
 use super::{
     Event, GetSessionFilter, Human, ListSessionFilter, ListSessionFilterCommon,
-    ListSessionFilterSpecific, Session, UserDatabase,
+    ListSessionFilterSpecific, MemoField, RevisionAuthor, Session, SessionSearchHit, UserDatabase,
 };
 
+// Private-use characters bracket matches in `snippet()`, so highlighting can
+// be added after the indexed text has been escaped.
+const MATCH_START: char = '\u{E000}';
+const MATCH_END: char = '\u{E001}';
+
+// Turns free text into an FTS5 query: every word must appear, and the last one
+// may be a prefix so results update while typing. Quoting each word keeps FTS5
+// operators and punctuation in the input from being parsed as query syntax.
+fn fts_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"", term))
+        .collect();
+
+    if terms.is_empty() {
+        return None;
+    }
+    Some(terms.join(" ") + "*")
+}
+
+// Removed tags leave runs of spaces behind in the indexed text.
+fn highlight_snippet(snippet: &str) -> String {
+    snippet
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
 impl UserDatabase {
     pub fn onboarding_session_id() -> String {
         "df1d8c52-6d9d-4471-aff1-5dbd35899cbe".to_string()
@@ -41,21 +79,8 @@ impl UserDatabase {
         &self,
         session_id: impl Into<String>,
     ) -> Result<Vec<owhisper_interface::Word2>, crate::Error> {
-        let conn = self.conn()?;
-        let mut rows = conn
-            .query(
-                "SELECT words FROM sessions WHERE id = ?",
-                vec![session_id.into()],
-            )
-            .await?;
-
-        match rows.next().await? {
-            None => Ok(vec![]),
-            Some(row) => {
-                let words_str: String = row.get(0)?;
-                Ok(serde_json::from_str(&words_str).unwrap_or_default())
-            }
-        }
+        let words = self.list_transcript_words(session_id).await?;
+        Ok(words.into_iter().map(Into::into).collect())
     }
 
     pub async fn get_session(
@@ -65,20 +90,22 @@ impl UserDatabase {
         let conn = self.conn()?;
 
         let mut rows = match filter {
+            // Trashed sessions stay reachable by id, e.g. to preview them
+            // before restoring.
             GetSessionFilter::Id(id) => conn
                 .query("SELECT * FROM sessions WHERE id = ?", vec![id])
                 .await
                 .unwrap(),
             GetSessionFilter::CalendarEventId(id) => conn
                 .query(
-                    "SELECT * FROM sessions WHERE calendar_event_id = ?",
+                    "SELECT * FROM sessions WHERE calendar_event_id = ? AND deleted_at IS NULL",
                     vec![id],
                 )
                 .await
                 .unwrap(),
             GetSessionFilter::TagId(id) => conn
                 .query(
-                    "SELECT * FROM sessions WHERE id IN (SELECT session_id FROM tags WHERE id = ?)",
+                    "SELECT * FROM sessions WHERE id IN (SELECT session_id FROM tags WHERE id = ?) AND deleted_at IS NULL",
                     vec![id],
                 )
                 .await
@@ -87,10 +114,7 @@ impl UserDatabase {
 
         match rows.next().await? {
             None => Ok(None),
-            Some(row) => {
-                let item = Session::from_row(&row)?;
-                Ok(Some(item))
-            }
+            Some(row) => Ok(Some(Session::from_row(&row)?)),
         }
     }
 
@@ -105,12 +129,16 @@ impl UserDatabase {
         Ok(())
     }
 
+    /// Moves the session to the trash; see `restore_from_trash`.
     pub async fn delete_session(&self, id: impl Into<String>) -> Result<(), crate::Error> {
         let session_id = id.into();
         let conn = self.conn()?;
 
-        conn.execute("DELETE FROM sessions WHERE id = ?", vec![session_id])
-            .await?;
+        conn.execute(
+            "UPDATE sessions SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+            vec![chrono::Utc::now().to_rfc3339(), session_id],
+        )
+        .await?;
 
         Ok(())
     }
@@ -130,7 +158,7 @@ impl UserDatabase {
                     "SELECT DISTINCT s.* FROM sessions s
                      LEFT JOIN session_participants sp ON s.id = sp.session_id
                      LEFT JOIN humans h ON sp.human_id = h.id
-                     WHERE s.user_id = ? AND (
+                     WHERE s.user_id = ? AND s.deleted_at IS NULL AND (
                        s.title LIKE ? OR 
                        REPLACE(REPLACE(REPLACE(s.enhanced_memo_html, '<', ' '), '>', ' '), '&nbsp;', ' ') LIKE ? OR
                        REPLACE(REPLACE(REPLACE(s.raw_memo_html, '<', ' '), '>', ' '), '&nbsp;', ' ') LIKE ? OR
@@ -159,7 +187,7 @@ impl UserDatabase {
                 specific: ListSessionFilterSpecific::RecentlyVisited {},
             }) => {
                 conn.query(
-                    "SELECT * FROM sessions WHERE user_id = ? ORDER BY visited_at DESC LIMIT ?",
+                    "SELECT * FROM sessions WHERE user_id = ? AND deleted_at IS NULL ORDER BY visited_at DESC LIMIT ?",
                     vec![user_id, limit.unwrap_or(100).to_string()],
                 )
                 .await?
@@ -174,6 +202,7 @@ impl UserDatabase {
                     LEFT JOIN events e ON s.calendar_event_id = e.id
                     WHERE
                         s.user_id = :user_id AND
+                        s.deleted_at IS NULL AND
                         (
                             (s.calendar_event_id IS NULL AND s.created_at BETWEEN :start_time AND :end_time)
                             OR
@@ -213,7 +242,7 @@ impl UserDatabase {
                 let query = format!(
                     "SELECT DISTINCT s.* FROM sessions s
                      JOIN tags_sessions ts ON s.id = ts.session_id
-                     WHERE s.user_id = ? AND ts.tag_id IN ({})
+                     WHERE s.user_id = ? AND s.deleted_at IS NULL AND ts.tag_id IN ({})
                      ORDER BY s.created_at DESC LIMIT ?",
                     placeholders
                 );
@@ -225,7 +254,10 @@ impl UserDatabase {
                 conn.query(&query, params).await?
             }
             None => {
-                conn.query("SELECT * FROM sessions ORDER BY created_at DESC", ())
+                conn.query(
+                    "SELECT * FROM sessions WHERE deleted_at IS NULL ORDER BY created_at DESC",
+                    (),
+                )
                     .await?
             }
         };
@@ -238,6 +270,48 @@ impl UserDatabase {
         Ok(items)
     }
 
+    /// Ranked full-text search over titles, notes, transcripts and
+    /// participant names, best match first. Title matches weigh the most.
+    pub async fn search_sessions(
+        &self,
+        user_id: impl Into<String>,
+        query: impl AsRef<str>,
+        limit: Option<u8>,
+    ) -> Result<Vec<SessionSearchHit>, crate::Error> {
+        let Some(match_query) = fts_match_query(query.as_ref()) else {
+            return Ok(vec![]);
+        };
+
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT s.*, snippet(sessions_fts, -1, :match_start, :match_end, '…', 16)
+                 FROM sessions_fts
+                 JOIN sessions s ON s.id = sessions_fts.session_id
+                 WHERE sessions_fts MATCH :query AND s.user_id = :user_id AND s.deleted_at IS NULL
+                 ORDER BY bm25(sessions_fts, 0.0, 10.0, 2.0, 2.0, 1.0, 5.0)
+                 LIMIT :limit",
+                libsql::named_params! {
+                    ":match_start": MATCH_START.to_string(),
+                    ":match_end": MATCH_END.to_string(),
+                    ":query": match_query,
+                    ":user_id": user_id.into(),
+                    ":limit": limit.unwrap_or(20),
+                },
+            )
+            .await?;
+
+        let mut hits = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let snippet: String = row.get(row.column_count() - 1)?;
+            hits.push(SessionSearchHit {
+                session: Session::from_row(&row)?,
+                snippet: highlight_snippet(&snippet),
+            });
+        }
+        Ok(hits)
+    }
+
     pub async fn session_list_deleted_participant_ids(
         &self,
         session_id: impl Into<String>,
@@ -259,10 +333,27 @@ impl UserDatabase {
         Ok(ids)
     }
 
+    /// Non-empty `words` replace the session's stored transcript, while empty
+    /// ones leave it as it is, since sessions are read without their words.
+    /// Use `replace_transcript_words` to clear it. Memo changes are recorded
+    /// as revisions by the user.
     pub async fn upsert_session(&self, session: Session) -> Result<Session, crate::Error> {
+        self.upsert_session_as(session, RevisionAuthor::User).await
+    }
+
+    /// Like `upsert_session`, with memo changes recorded as made by `author`,
+    /// e.g. `RevisionAuthor::Ai` when saving an enhanced note.
+    pub async fn upsert_session_as(
+        &self,
+        session: Session,
+        author: RevisionAuthor,
+    ) -> Result<Session, crate::Error> {
         let conn = self.conn()?;
+        // The session, its transcript and its memo revisions are written
+        // together, so a failed save can't leave one without the others.
+        let tx = conn.transaction().await?;
 
-        let mut rows = conn
+        let mut rows = tx
             .query(
                 "INSERT INTO sessions (
                     id,
@@ -317,7 +408,7 @@ impl UserDatabase {
                     ":raw_memo_html": session.raw_memo_html.clone(),
                     ":enhanced_memo_html": session.enhanced_memo_html.clone(),
                     ":conversations": "[]",
-                    ":words": serde_json::to_string(&session.words).unwrap(),
+                    ":words": "[]",
                     ":record_start": session.record_start.map(|dt| dt.to_rfc3339()),
                     ":record_end": session.record_end.map(|dt| dt.to_rfc3339()),
                     ":pre_meeting_memo_html": session.pre_meeting_memo_html.clone(),
@@ -326,8 +417,44 @@ impl UserDatabase {
             .await?;
 
         let row = rows.next().await?.unwrap();
-        let session = Session::from_row(&row)?;
-        Ok(session)
+        let saved = Session::from_row(&row)?;
+        // The upsert isn't finished until its rows are gone.
+        drop((row, rows));
+
+        if !session.words.is_empty() {
+            write_transcript_words(
+                &tx,
+                &saved.id,
+                session.words.iter().cloned().map(Into::into).collect(),
+            )
+            .await?;
+        }
+        record_memo_revision(
+            &tx,
+            &saved.id,
+            MemoField::Raw,
+            author,
+            &saved.raw_memo_html,
+            true,
+        )
+        .await?;
+        if let Some(enhanced_memo_html) = &saved.enhanced_memo_html {
+            record_memo_revision(
+                &tx,
+                &saved.id,
+                MemoField::Enhanced,
+                author,
+                enhanced_memo_html,
+                true,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(Session {
+            words: session.words,
+            ..saved
+        })
     }
 
     pub async fn session_set_event(
@@ -390,7 +517,7 @@ impl UserDatabase {
             .query(
                 "SELECT h.* FROM humans h
                 JOIN session_participants sp ON h.id = sp.human_id
-                WHERE sp.session_id = ? AND (sp.deleted = FALSE OR sp.deleted IS NULL)",
+                WHERE sp.session_id = ? AND (sp.deleted = FALSE OR sp.deleted IS NULL) AND h.deleted_at IS NULL",
                 vec![session_id.into()],
             )
             .await?;
@@ -430,6 +557,7 @@ impl UserDatabase {
 
 #[cfg(test)]
 mod tests {
+    use super::fts_match_query;
     use crate::{Human, Session, tests::setup_db};
 
     #[tokio::test]
@@ -463,6 +591,7 @@ mod tests {
                 end_ms: None,
                 speaker: None,
                 confidence: None,
+                channel: None,
             }],
             record_start: None,
             record_end: None,
@@ -498,4 +627,79 @@ mod tests {
 
         assert_eq!(db.session_get_event(&session.id).await.unwrap(), None);
     }
+
+    #[test]
+    fn test_fts_match_query() {
+        assert_eq!(fts_match_query("  "), None);
+        assert_eq!(
+            fts_match_query("q3 road\"map OR"),
+            Some("\"q3\" \"roadmap\" \"OR\"*".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let guest = db
+            .upsert_human(Human {
+                full_name: Some("Ada Lovelace".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Planning".to_string(),
+                raw_memo_html: r#"<ul data-type="taskList"><li><p>Ship the <strong>roadmap</strong> &amp; budget</p></li></ul>"#.to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![owhisper_interface::Word2 {
+                    text: "kubernetes".to_string(),
+                    ..Default::default()
+                }],
+                record_start: None,
+                record_end: None,
+                pre_meeting_memo_html: None,
+            })
+            .await
+            .unwrap();
+        db.session_add_participant(&session.id, &guest.id)
+            .await
+            .unwrap();
+
+        let search = |query: &str| db.search_sessions(&user.id, query.to_string(), None);
+
+        let hits = search("roadm").await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].snippet,
+            "Ship the <mark>roadmap</mark> &amp; budget"
+        );
+
+        assert_eq!(search("kubernetes").await.unwrap().len(), 1);
+        assert_eq!(search("lovelace").await.unwrap().len(), 1);
+        // Markup is not indexed.
+        assert!(search("strong").await.unwrap().is_empty());
+        assert!(search("taskList").await.unwrap().is_empty());
+
+        db.upsert_human(Human {
+            full_name: Some("Grace Hopper".to_string()),
+            ..guest
+        })
+        .await
+        .unwrap();
+        assert!(search("lovelace").await.unwrap().is_empty());
+        assert_eq!(search("hopper").await.unwrap().len(), 1);
+
+        db.delete_session(&session.id).await.unwrap();
+        assert!(search("planning").await.unwrap().is_empty());
+    }
 }
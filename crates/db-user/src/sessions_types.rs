@@ -15,6 +15,8 @@ user_common_derives! {
         #[specta(skip)]
         #[serde(skip)]
         pub conversations: Vec<()>,
+        /// Empty when read from a migrated database; the stored transcript is
+        /// fetched on its own with `get_words`.
         pub words: Vec<owhisper_interface::Word2>,
         pub record_start: Option<DateTime<Utc>>,
         pub record_end: Option<DateTime<Utc>>,
@@ -124,3 +126,12 @@ user_common_derives! {
         TagFilter { tag_ids: Vec<String> },
     }
 }
+
+user_common_derives! {
+    pub struct SessionSearchHit {
+        pub session: Session,
+        /// HTML-escaped passage around the best match, with matched terms
+        /// wrapped in `<mark>`.
+        pub snippet: String,
+    }
+}
@@ -0,0 +1,18 @@
+-- Rows stop syncing.
+DROP TRIGGER IF EXISTS tags_sessions_stamp_insert;
+
+DROP TRIGGER IF EXISTS tags_sessions_stamp_update;
+
+DROP TRIGGER IF EXISTS tags_sessions_rekey;
+
+DROP TRIGGER IF EXISTS tags_sessions_tombstone;
+
+DROP TRIGGER IF EXISTS tags_sessions_untombstone;
+
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'tags_sessions';
+
+ALTER TABLE
+  tags_sessions DROP COLUMN updated_at;
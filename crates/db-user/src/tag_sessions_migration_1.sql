@@ -0,0 +1,80 @@
+ALTER TABLE
+  tags_sessions
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  tags_sessions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS tags_sessions_stamp_insert
+AFTER
+INSERT
+  ON tags_sessions WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  tags_sessions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  rowid = NEW.rowid;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tags_sessions_stamp_update
+AFTER
+UPDATE
+  ON tags_sessions WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  tags_sessions
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  rowid = NEW.rowid;
+END;
+
+-- Rows are identified by both columns, so re-pointing one is a delete of
+-- the old pair as far as the other side is concerned.
+CREATE TRIGGER IF NOT EXISTS tags_sessions_rekey
+AFTER
+UPDATE
+  OF tag_id,
+  session_id ON tags_sessions WHEN NEW.tag_id IS NOT OLD.tag_id
+  OR NEW.session_id IS NOT OLD.session_id BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  (
+    'tags_sessions',
+    OLD.tag_id || '/' || OLD.session_id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  );
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'tags_sessions'
+  AND row_id = NEW.tag_id || '/' || NEW.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tags_sessions_tombstone
+AFTER
+  DELETE ON tags_sessions BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  (
+    'tags_sessions',
+    OLD.tag_id || '/' || OLD.session_id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  );
+END;
+
+CREATE TRIGGER IF NOT EXISTS tags_sessions_untombstone
+AFTER
+INSERT
+  ON tags_sessions BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'tags_sessions'
+  AND row_id = NEW.tag_id || '/' || NEW.session_id;
+END;
@@ -0,0 +1,53 @@
+ALTER TABLE
+  tags
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  tags
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS tags_stamp_insert
+AFTER
+INSERT
+  ON tags WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  tags
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tags_stamp_update
+AFTER
+UPDATE
+  ON tags WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  tags
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tags_tombstone
+AFTER
+  DELETE ON tags BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('tags', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS tags_untombstone
+AFTER
+INSERT
+  ON tags BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'tags'
+  AND row_id = NEW.id;
+END;
@@ -0,0 +1,53 @@
+ALTER TABLE
+  templates
+ADD
+  COLUMN updated_at TEXT NOT NULL DEFAULT '';
+
+UPDATE
+  templates
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER IF NOT EXISTS templates_stamp_insert
+AFTER
+INSERT
+  ON templates WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  templates
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS templates_stamp_update
+AFTER
+UPDATE
+  ON templates WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  templates
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS templates_tombstone
+AFTER
+  DELETE ON templates BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  ('templates', OLD.id, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS templates_untombstone
+AFTER
+INSERT
+  ON templates BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'templates'
+  AND row_id = NEW.id;
+END;
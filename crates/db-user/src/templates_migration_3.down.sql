@@ -0,0 +1,3 @@
+-- Templates in the trash come back.
+ALTER TABLE
+  templates DROP COLUMN deleted_at;
@@ -0,0 +1,4 @@
+ALTER TABLE
+  templates
+ADD
+  COLUMN deleted_at TEXT DEFAULT NULL;
@@ -9,7 +9,9 @@ impl UserDatabase {
 
         let _user_id = user_id.into();
 
-        let mut rows = conn.query("SELECT * FROM templates", ()).await?;
+        let mut rows = conn
+            .query("SELECT * FROM templates WHERE deleted_at IS NULL", ())
+            .await?;
 
         let mut items = Vec::new();
         while let Some(row) = rows.next().await.unwrap() {
@@ -64,11 +66,15 @@ impl UserDatabase {
         Ok(template)
     }
 
+    /// Moves the template to the trash; see `restore_from_trash`.
     pub async fn delete_template(&self, id: String) -> Result<(), crate::Error> {
         let conn = self.conn()?;
 
-        conn.query("DELETE FROM templates WHERE id = ?", vec![id])
-            .await?;
+        conn.execute(
+            "UPDATE templates SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+            vec![chrono::Utc::now().to_rfc3339(), id],
+        )
+        .await?;
         Ok(())
     }
 }
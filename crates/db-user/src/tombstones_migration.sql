@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS _tombstones (
+  table_name TEXT NOT NULL,
+  row_id TEXT NOT NULL,
+  deleted_at TEXT NOT NULL,
+  PRIMARY KEY (table_name, row_id)
+);
@@ -0,0 +1,442 @@
+CREATE TABLE IF NOT EXISTS transcript_words (
+  id TEXT PRIMARY KEY NOT NULL,
+  session_id TEXT NOT NULL,
+  position INTEGER NOT NULL,
+  text TEXT NOT NULL,
+  speaker_index INTEGER DEFAULT NULL,
+  speaker_id TEXT DEFAULT NULL,
+  speaker_label TEXT DEFAULT NULL,
+  channel INTEGER DEFAULT NULL,
+  start_ms INTEGER DEFAULT NULL,
+  end_ms INTEGER DEFAULT NULL,
+  confidence REAL DEFAULT NULL,
+  updated_at TEXT NOT NULL DEFAULT '',
+  UNIQUE (session_id, position),
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_transcript_words_start ON transcript_words (session_id, start_ms);
+
+INSERT
+  OR IGNORE INTO transcript_words (
+    id,
+    session_id,
+    position,
+    text,
+    speaker_index,
+    speaker_id,
+    speaker_label,
+    start_ms,
+    end_ms,
+    confidence,
+    updated_at
+  )
+SELECT
+  s.id || ':' || word.key,
+  s.id,
+  word.key,
+  coalesce(json_extract(word.value, '$.text'), ''),
+  json_extract(word.value, '$.speaker.value.index'),
+  json_extract(word.value, '$.speaker.value.id'),
+  json_extract(word.value, '$.speaker.value.label'),
+  json_extract(word.value, '$.start_ms'),
+  json_extract(word.value, '$.end_ms'),
+  json_extract(word.value, '$.confidence'),
+  strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+FROM
+  sessions s,
+  json_each(
+    CASE
+      WHEN json_valid(s.words) THEN s.words
+      ELSE '[]'
+    END
+  ) AS word;
+
+CREATE TRIGGER IF NOT EXISTS transcript_words_stamp_insert
+AFTER
+INSERT
+  ON transcript_words WHEN NEW.updated_at = '' BEGIN
+UPDATE
+  transcript_words
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcript_words_stamp_update
+AFTER
+UPDATE
+  ON transcript_words WHEN NEW.updated_at IS OLD.updated_at BEGIN
+UPDATE
+  transcript_words
+SET
+  updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+  id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcript_words_tombstone
+AFTER
+  DELETE ON transcript_words BEGIN
+INSERT
+  OR REPLACE INTO _tombstones (table_name, row_id, deleted_at)
+VALUES
+  (
+    'transcript_words',
+    OLD.id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  );
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcript_words_untombstone
+AFTER
+INSERT
+  ON transcript_words BEGIN
+DELETE FROM
+  _tombstones
+WHERE
+  table_name = 'transcript_words'
+  AND row_id = NEW.id;
+END;
+
+-- Re-indexing a session for every word would make saving a long transcript
+-- quadratic, so writers of many words at once hold the session here and
+-- releasing the hold re-indexes it once. Other writes, like rows arriving
+-- through sync, re-index as they go. Words deleted along with their session
+-- are left to `sessions_fts_delete`.
+CREATE TABLE IF NOT EXISTS sessions_fts_hold (session_id TEXT PRIMARY KEY NOT NULL);
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_word_insert
+AFTER
+INSERT
+  ON transcript_words
+  WHEN NOT EXISTS (
+    SELECT
+      1
+    FROM
+      sessions_fts_hold
+    WHERE
+      session_id = NEW.session_id
+  ) BEGIN
+DELETE FROM
+  sessions_fts
+WHERE
+  session_id = NEW.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = NEW.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_word_update
+AFTER
+UPDATE
+  OF text ON transcript_words
+  WHEN NOT EXISTS (
+    SELECT
+      1
+    FROM
+      sessions_fts_hold
+    WHERE
+      session_id = NEW.session_id
+  ) BEGIN
+DELETE FROM
+  sessions_fts
+WHERE
+  session_id = NEW.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = NEW.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_word_delete
+AFTER
+  DELETE ON transcript_words
+  WHEN NOT EXISTS (
+    SELECT
+      1
+    FROM
+      sessions_fts_hold
+    WHERE
+      session_id = OLD.session_id
+  )
+  AND EXISTS (
+    SELECT
+      1
+    FROM
+      sessions
+    WHERE
+      id = OLD.session_id
+  ) BEGIN
+DELETE FROM
+  sessions_fts
+WHERE
+  session_id = OLD.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = OLD.session_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS sessions_fts_hold_release
+AFTER
+  DELETE ON sessions_fts_hold BEGIN
+DELETE FROM
+  sessions_fts
+WHERE
+  session_id = OLD.session_id;
+INSERT INTO
+  sessions_fts (
+    session_id,
+    title,
+    raw_memo,
+    enhanced_memo,
+    transcript,
+    participants
+  )
+SELECT
+  session_id,
+  title,
+  raw_memo,
+  enhanced_memo,
+  transcript,
+  participants
+FROM
+  sessions_search_source
+WHERE
+  session_id = OLD.session_id;
+END;
+
+DROP VIEW IF EXISTS sessions_search_source;
+
+CREATE VIEW IF NOT EXISTS sessions_search_source AS
+SELECT
+  s.id AS session_id,
+  s.title AS title,
+  (
+    SELECT
+      trim(
+        replace(
+          replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    group_concat(
+                      CASE
+                        WHEN substr(part.value, 1, 1) = '<' THEN substr(part.value, instr(part.value, '>') + 1)
+                        ELSE part.value
+                      END,
+                      ' '
+                    ),
+                    '&nbsp;',
+                    ' '
+                  ),
+                  '&lt;',
+                  '<'
+                ),
+                '&gt;',
+                '>'
+              ),
+              '&quot;',
+              '"'
+            ),
+            '&#39;',
+            ''''
+          ),
+          '&amp;',
+          '&'
+        )
+      )
+    FROM
+      (
+        SELECT
+          '["' || replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    replace(coalesce(s.raw_memo_html, ''), '\', '\\'),
+                    '"',
+                    '\"'
+                  ),
+                  char(10),
+                  ' '
+                ),
+                char(13),
+                ' '
+              ),
+              char(9),
+              ' '
+            ),
+            '<',
+            '","<'
+          ) || '"]' AS parts
+      ) AS html,
+      json_each(
+        CASE
+          WHEN json_valid(html.parts) THEN html.parts
+          ELSE '[]'
+        END
+      ) AS part
+  ) AS raw_memo,
+  (
+    SELECT
+      trim(
+        replace(
+          replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    group_concat(
+                      CASE
+                        WHEN substr(part.value, 1, 1) = '<' THEN substr(part.value, instr(part.value, '>') + 1)
+                        ELSE part.value
+                      END,
+                      ' '
+                    ),
+                    '&nbsp;',
+                    ' '
+                  ),
+                  '&lt;',
+                  '<'
+                ),
+                '&gt;',
+                '>'
+              ),
+              '&quot;',
+              '"'
+            ),
+            '&#39;',
+            ''''
+          ),
+          '&amp;',
+          '&'
+        )
+      )
+    FROM
+      (
+        SELECT
+          '["' || replace(
+            replace(
+              replace(
+                replace(
+                  replace(
+                    replace(coalesce(s.enhanced_memo_html, ''), '\', '\\'),
+                    '"',
+                    '\"'
+                  ),
+                  char(10),
+                  ' '
+                ),
+                char(13),
+                ' '
+              ),
+              char(9),
+              ' '
+            ),
+            '<',
+            '","<'
+          ) || '"]' AS parts
+      ) AS html,
+      json_each(
+        CASE
+          WHEN json_valid(html.parts) THEN html.parts
+          ELSE '[]'
+        END
+      ) AS part
+  ) AS enhanced_memo,
+  (
+    SELECT
+      group_concat(word.text, ' ')
+    FROM
+      (
+        SELECT
+          tw.text
+        FROM
+          transcript_words tw
+        WHERE
+          tw.session_id = s.id
+        ORDER BY
+          tw.position
+      ) AS word
+  ) AS transcript,
+  (
+    SELECT
+      group_concat(
+        trim(coalesce(h.full_name, '') || ' ' || coalesce(h.email, '')),
+        ' '
+      )
+    FROM
+      session_participants sp
+      JOIN humans h ON h.id = sp.human_id
+    WHERE
+      sp.session_id = s.id
+      AND (
+        sp.deleted = FALSE
+        OR sp.deleted IS NULL
+      )
+  ) AS participants
+FROM
+  sessions s;
+
+-- Transcripts only live in `transcript_words` from here on. The JSON column
+-- stays in the schema but is no longer read or written, so it's emptied
+-- rather than left holding a copy that goes stale.
+UPDATE
+  sessions
+SET
+  words = '[]'
+WHERE
+  words IS NOT '[]';
@@ -0,0 +1,614 @@
+use std::collections::HashMap;
+
+use hypr_db_core::SqlTable;
+use owhisper_interface::SpeakerIdentity;
+
+use super::{TranscriptWord, UserDatabase};
+
+// Stable per position, so syncing a re-saved transcript updates words in place
+// instead of deleting and re-creating every one of them.
+fn word_id(session_id: &str, position: u32) -> String {
+    format!("{}:{}", session_id, position)
+}
+
+fn speaker_columns(speaker: &Option<SpeakerIdentity>) -> [libsql::Value; 3] {
+    match speaker {
+        None => [
+            libsql::Value::Null,
+            libsql::Value::Null,
+            libsql::Value::Null,
+        ],
+        Some(SpeakerIdentity::Unassigned { index }) => [
+            libsql::Value::Integer(*index as i64),
+            libsql::Value::Null,
+            libsql::Value::Null,
+        ],
+        Some(SpeakerIdentity::Assigned { id, label }) => [
+            libsql::Value::Null,
+            libsql::Value::Text(id.clone()),
+            libsql::Value::Text(label.clone()),
+        ],
+    }
+}
+
+fn word_params(session_id: &str, word: &TranscriptWord) -> Vec<libsql::Value> {
+    let [speaker_index, speaker_id, speaker_label] = speaker_columns(&word.speaker);
+    let optional = |v: Option<i64>| v.map(libsql::Value::Integer).unwrap_or(libsql::Value::Null);
+
+    vec![
+        libsql::Value::Text(word_id(session_id, word.position)),
+        libsql::Value::Text(session_id.to_string()),
+        libsql::Value::Integer(word.position as i64),
+        libsql::Value::Text(word.text.clone()),
+        speaker_index,
+        speaker_id,
+        speaker_label,
+        optional(word.channel.map(|c| c as i64)),
+        optional(word.start_ms.map(|ms| ms as i64)),
+        optional(word.end_ms.map(|ms| ms as i64)),
+        word.confidence
+            .map(|c| libsql::Value::Real(c as f64))
+            .unwrap_or(libsql::Value::Null),
+    ]
+}
+
+// Rows per INSERT, well under SQLite's limit on bound parameters.
+const WORD_BATCH: usize = 256;
+
+async fn stored_words(
+    conn: &libsql::Connection,
+    session_id: &str,
+) -> Result<HashMap<u32, TranscriptWord>, crate::Error> {
+    let sql = format!(
+        "SELECT * FROM {} WHERE session_id = ?",
+        TranscriptWord::sql_table()
+    );
+    let mut rows = conn.query(&sql, vec![session_id]).await?;
+
+    let mut words = HashMap::new();
+    while let Some(row) = rows.next().await? {
+        let word = TranscriptWord::from_row(&row)?;
+        words.insert(word.position, word);
+    }
+    Ok(words)
+}
+
+// Shared by `replace_transcript_words` and `upsert_session_as`, which run it
+// inside their own transactions. Only words that differ from the stored ones
+// are written, so saving a transcript that grew only appends.
+pub(crate) async fn write_transcript_words(
+    conn: &libsql::Connection,
+    session_id: &str,
+    words: Vec<TranscriptWord>,
+) -> Result<(), crate::Error> {
+    let stored = stored_words(conn, session_id).await?;
+
+    let count = words.len();
+    let changed: Vec<TranscriptWord> = words
+        .into_iter()
+        .enumerate()
+        .map(|(position, word)| TranscriptWord {
+            position: position as u32,
+            ..word
+        })
+        .filter(|word| stored.get(&word.position) != Some(word))
+        .collect();
+    let truncated = stored.keys().any(|&position| position as usize >= count);
+
+    if changed.is_empty() && !truncated {
+        return Ok(());
+    }
+
+    // See `sessions_fts_hold`; the session is re-indexed once, on release.
+    conn.execute(
+        "INSERT OR IGNORE INTO sessions_fts_hold (session_id) VALUES (?)",
+        vec![session_id],
+    )
+    .await?;
+
+    for batch in changed.chunks(WORD_BATCH) {
+        let sql = format!(
+            "INSERT INTO {} (
+                id,
+                session_id,
+                position,
+                text,
+                speaker_index,
+                speaker_id,
+                speaker_label,
+                channel,
+                start_ms,
+                end_ms,
+                confidence
+            ) VALUES {}
+            ON CONFLICT (id) DO UPDATE SET
+                text = excluded.text,
+                speaker_index = excluded.speaker_index,
+                speaker_id = excluded.speaker_id,
+                speaker_label = excluded.speaker_label,
+                channel = excluded.channel,
+                start_ms = excluded.start_ms,
+                end_ms = excluded.end_ms,
+                confidence = excluded.confidence",
+            TranscriptWord::sql_table(),
+            vec!["(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"; batch.len()].join(", ")
+        );
+        let params: Vec<libsql::Value> = batch
+            .iter()
+            .flat_map(|word| word_params(session_id, word))
+            .collect();
+        conn.execute(&sql, params).await?;
+    }
+
+    if truncated {
+        conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE session_id = ? AND position >= ?",
+                TranscriptWord::sql_table()
+            ),
+            (session_id.to_string(), count as i64),
+        )
+        .await?;
+    }
+
+    conn.execute(
+        "DELETE FROM sessions_fts_hold WHERE session_id = ?",
+        vec![session_id],
+    )
+    .await?;
+
+    Ok(())
+}
+
+impl UserDatabase {
+    pub async fn list_transcript_words(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<TranscriptWord>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY position",
+            TranscriptWord::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(TranscriptWord::from_row(&row)?);
+        }
+        Ok(items)
+    }
+
+    /// Words overlapping `[start_ms, end_ms)`, e.g. to show the part of a
+    /// transcript around a playback position without loading all of it.
+    pub async fn list_transcript_words_in_range(
+        &self,
+        session_id: impl Into<String>,
+        start_ms: u64,
+        end_ms: u64,
+    ) -> Result<Vec<TranscriptWord>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE
+                session_id = :session_id AND
+                start_ms < :end_ms AND
+                coalesce(end_ms, start_ms) >= :start_ms
+            ORDER BY position",
+            TranscriptWord::sql_table()
+        );
+        let mut rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":session_id": session_id.into(),
+                    ":start_ms": start_ms as i64,
+                    ":end_ms": end_ms as i64,
+                },
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(TranscriptWord::from_row(&row)?);
+        }
+        Ok(items)
+    }
+
+    /// Stores `words` as the session's whole transcript. Positions follow the
+    /// order of `words`, and words past the end of the new transcript go.
+    pub async fn replace_transcript_words(
+        &self,
+        session_id: impl Into<String>,
+        words: Vec<TranscriptWord>,
+    ) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+        write_transcript_words(&tx, &session_id.into(), words).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Corrects a single word in place, keeping the rest of the transcript.
+    pub async fn update_transcript_word(
+        &self,
+        session_id: impl Into<String>,
+        word: TranscriptWord,
+    ) -> Result<TranscriptWord, crate::Error> {
+        let session_id = session_id.into();
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "UPDATE {} SET
+                text = ?4,
+                speaker_index = ?5,
+                speaker_id = ?6,
+                speaker_label = ?7,
+                channel = ?8,
+                start_ms = ?9,
+                end_ms = ?10,
+                confidence = ?11
+            WHERE id = ?1 AND session_id = ?2 AND position = ?3
+            RETURNING *",
+            TranscriptWord::sql_table()
+        );
+
+        let mut rows = conn.query(&sql, word_params(&session_id, &word)).await?;
+        match rows.next().await? {
+            Some(row) => TranscriptWord::from_row(&row),
+            None => Err(crate::Error::InvalidInput(format!(
+                "session {} has no word at position {}",
+                session_id, word.position
+            ))),
+        }
+    }
+
+    /// Moves every word spoken by `from` over to `to`, e.g. once a diarized
+    /// speaker has been identified as a participant. Returns how many words
+    /// changed.
+    pub async fn relabel_transcript_speaker(
+        &self,
+        session_id: impl Into<String>,
+        from: SpeakerIdentity,
+        to: Option<SpeakerIdentity>,
+    ) -> Result<u64, crate::Error> {
+        let conn = self.conn()?;
+
+        let matches_from = match from {
+            SpeakerIdentity::Unassigned { index } => (
+                "speaker_id IS NULL AND speaker_index = ?5",
+                libsql::Value::Integer(index as i64),
+            ),
+            SpeakerIdentity::Assigned { id, .. } => ("speaker_id = ?5", libsql::Value::Text(id)),
+        };
+        let [speaker_index, speaker_id, speaker_label] = speaker_columns(&to);
+
+        let sql = format!(
+            "UPDATE {} SET
+                speaker_index = ?2,
+                speaker_id = ?3,
+                speaker_label = ?4
+            WHERE session_id = ?1 AND {}",
+            TranscriptWord::sql_table(),
+            matches_from.0
+        );
+
+        let changed = conn
+            .execute(
+                &sql,
+                vec![
+                    libsql::Value::Text(session_id.into()),
+                    speaker_index,
+                    speaker_id,
+                    speaker_label,
+                    matches_from.1,
+                ],
+            )
+            .await?;
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use owhisper_interface::SpeakerIdentity;
+
+    use crate::{
+        GetSessionFilter, Human, MIGRATIONS, Session, TranscriptWord, UserDatabase, tests::setup_db,
+    };
+    use hypr_db_core::DatabaseBuilder;
+
+    fn word(text: &str, speaker: u8, start_ms: u64) -> TranscriptWord {
+        TranscriptWord {
+            position: 0,
+            text: text.to_string(),
+            speaker: Some(SpeakerIdentity::Unassigned { index: speaker }),
+            channel: Some(0),
+            confidence: Some(0.5),
+            start_ms: Some(start_ms),
+            end_ms: Some(start_ms + 400),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transcript_words() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id,
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Standup".to_string(),
+                raw_memo_html: String::new(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![],
+                record_start: None,
+                record_end: None,
+                pre_meeting_memo_html: None,
+            })
+            .await
+            .unwrap();
+
+        db.replace_transcript_words(
+            &session.id,
+            vec![
+                word("shipping", 0, 0),
+                word("friday", 0, 500),
+                word("sounds", 1, 1000),
+                word("good", 1, 1500),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let words = db.list_transcript_words(&session.id).await.unwrap();
+        assert_eq!(
+            words.iter().map(|w| w.position).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            words[3],
+            TranscriptWord {
+                position: 3,
+                ..word("good", 1, 1500)
+            }
+        );
+
+        let range = db
+            .list_transcript_words_in_range(&session.id, 600, 1200)
+            .await
+            .unwrap();
+        assert_eq!(
+            range.iter().map(|w| w.text.as_str()).collect::<Vec<_>>(),
+            vec!["friday", "sounds"]
+        );
+
+        let assigned = SpeakerIdentity::Assigned {
+            id: "human-1".to_string(),
+            label: "Ada".to_string(),
+        };
+        let changed = db
+            .relabel_transcript_speaker(
+                &session.id,
+                SpeakerIdentity::Unassigned { index: 1 },
+                Some(assigned.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(changed, 2);
+
+        let edited = db
+            .update_transcript_word(
+                &session.id,
+                TranscriptWord {
+                    position: 1,
+                    ..word("Friday", 0, 500)
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.text, "Friday");
+        assert!(
+            db.update_transcript_word(
+                &session.id,
+                TranscriptWord {
+                    position: 9,
+                    ..word("x", 0, 0)
+                }
+            )
+            .await
+            .is_err()
+        );
+
+        let words = db.get_words(&session.id).await.unwrap();
+        assert_eq!(
+            words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>(),
+            vec!["shipping", "Friday", "sounds", "good"]
+        );
+        assert_eq!(words[2].speaker, Some(assigned));
+
+        let hits = db
+            .search_sessions(&session.user_id, "friday", None)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, "shipping <mark>Friday</mark> sounds good");
+
+        db.replace_transcript_words(&session.id, vec![word("bye", 0, 0)])
+            .await
+            .unwrap();
+        assert_eq!(
+            db.list_transcript_words(&session.id).await.unwrap().len(),
+            1
+        );
+        assert!(
+            db.search_sessions(&session.user_id, "friday", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        db.delete_session(&session.id).await.unwrap();
+        db.purge_trash(chrono::Duration::zero()).await.unwrap();
+        assert!(
+            db.list_transcript_words(&session.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_session_words() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id,
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Standup".to_string(),
+                raw_memo_html: String::new(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![owhisper_interface::Word2 {
+                    text: "hello".to_string(),
+                    channel: Some(1),
+                    ..Default::default()
+                }],
+                record_start: None,
+                record_end: None,
+                pre_meeting_memo_html: None,
+            })
+            .await
+            .unwrap();
+
+        let words = db.list_transcript_words(&session.id).await.unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].channel, Some(1));
+
+        // Sessions are read without their words, and saving one back that
+        // way keeps its transcript.
+        let mut read = db
+            .get_session(GetSessionFilter::Id(session.id.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(read.words.is_empty());
+        read.title = "Daily standup".to_string();
+        db.upsert_session(read.clone()).await.unwrap();
+        assert_eq!(db.get_words(&session.id).await.unwrap(), session.words);
+
+        // A transcript that grew only writes the new words.
+        let conn = db.conn().unwrap();
+        conn.execute(
+            "UPDATE transcript_words SET updated_at = 'kept' WHERE session_id = ?",
+            vec![session.id.clone()],
+        )
+        .await
+        .unwrap();
+        let mut words = session.words.clone();
+        words.push(owhisper_interface::Word2 {
+            text: "there".to_string(),
+            ..Default::default()
+        });
+        db.upsert_session(Session {
+            words: words.clone(),
+            ..read
+        })
+        .await
+        .unwrap();
+        assert_eq!(db.get_words(&session.id).await.unwrap(), words);
+        let mut rows = conn
+            .query(
+                "SELECT updated_at FROM transcript_words WHERE session_id = ? ORDER BY position",
+                vec![session.id.clone()],
+            )
+            .await
+            .unwrap();
+        let mut stamps = Vec::new();
+        while let Some(row) = rows.next().await.unwrap() {
+            stamps.push(row.get::<String>(0).unwrap());
+        }
+        assert_eq!(stamps[0], "kept");
+        assert_ne!(stamps[1], "kept");
+
+        db.replace_transcript_words(&session.id, vec![])
+            .await
+            .unwrap();
+        assert!(
+            db.list_transcript_words(&session.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_words_json() {
+        let db = UserDatabase::from(DatabaseBuilder::default().memory().build().await.unwrap());
+        let conn = db.conn().unwrap();
+        let before = MIGRATIONS
+            .iter()
+            .position(|m| m.up == include_str!("./transcript_words_migration.sql"))
+            .unwrap();
+        hypr_db_core::migrate(&conn, &MIGRATIONS[..before])
+            .await
+            .unwrap();
+
+        conn.execute("INSERT INTO humans (id, is_user) VALUES ('u1', TRUE)", ())
+            .await
+            .unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, created_at, visited_at, user_id, title, raw_memo_html, conversations, words)
+             VALUES ('s1', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z', 'u1', '', '', '[]', ?)",
+            vec![
+                r#"[
+                    {"text": "hi", "speaker": {"type": "unassigned", "value": {"index": 2}}, "confidence": 0.5, "start_ms": 0, "end_ms": 400},
+                    {"text": "there", "speaker": {"type": "assigned", "value": {"id": "h1", "label": "Ada"}}, "confidence": null, "start_ms": null, "end_ms": null}
+                ]"#,
+            ],
+        )
+        .await
+        .unwrap();
+
+        hypr_db_core::migrate(&conn, &MIGRATIONS).await.unwrap();
+
+        let words = db.list_transcript_words("s1").await.unwrap();
+        assert_eq!(
+            words,
+            vec![
+                TranscriptWord {
+                    position: 0,
+                    channel: None,
+                    ..word("hi", 2, 0)
+                },
+                TranscriptWord {
+                    position: 1,
+                    text: "there".to_string(),
+                    speaker: Some(SpeakerIdentity::Assigned {
+                        id: "h1".to_string(),
+                        label: "Ada".to_string(),
+                    }),
+                    channel: None,
+                    confidence: None,
+                    start_ms: None,
+                    end_ms: None,
+                },
+            ]
+        );
+
+        let hits = db.search_sessions("u1", "there", None).await.unwrap();
+        assert_eq!(hits[0].session.words, vec![]);
+        assert_eq!(hits[0].snippet, "hi <mark>there</mark>");
+    }
+}
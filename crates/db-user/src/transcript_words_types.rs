@@ -0,0 +1,72 @@
+use owhisper_interface::{SpeakerIdentity, Word2};
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("transcript_words")]
+    pub struct TranscriptWord {
+        /// Index of the word within its session's transcript.
+        pub position: u32,
+        pub text: String,
+        pub speaker: Option<SpeakerIdentity>,
+        /// Audio channel the word was heard on, e.g. mic or system audio.
+        pub channel: Option<u8>,
+        pub confidence: Option<f32>,
+        pub start_ms: Option<u64>,
+        pub end_ms: Option<u64>,
+    }
+}
+
+impl TranscriptWord {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
+        let speaker_index: Option<u32> = row.get(4)?;
+        let speaker_id: Option<String> = row.get(5)?;
+        let speaker_label: Option<String> = row.get(6)?;
+
+        let speaker = match (speaker_id, speaker_index) {
+            (Some(id), _) => Some(SpeakerIdentity::Assigned {
+                id,
+                label: speaker_label.unwrap_or_default(),
+            }),
+            (None, Some(index)) => Some(SpeakerIdentity::Unassigned { index: index as u8 }),
+            (None, None) => None,
+        };
+
+        Ok(Self {
+            position: row.get(2)?,
+            text: row.get(3)?,
+            speaker,
+            channel: row.get::<Option<u32>>(7)?.map(|c| c as u8),
+            start_ms: row.get(8)?,
+            end_ms: row.get(9)?,
+            confidence: row.get::<Option<f64>>(10)?.map(|c| c as f32),
+        })
+    }
+}
+
+impl From<Word2> for TranscriptWord {
+    fn from(word: Word2) -> Self {
+        Self {
+            position: 0,
+            text: word.text,
+            speaker: word.speaker,
+            channel: word.channel,
+            confidence: word.confidence,
+            start_ms: word.start_ms,
+            end_ms: word.end_ms,
+        }
+    }
+}
+
+impl From<TranscriptWord> for Word2 {
+    fn from(word: TranscriptWord) -> Self {
+        Self {
+            text: word.text,
+            speaker: word.speaker,
+            confidence: word.confidence,
+            start_ms: word.start_ms,
+            end_ms: word.end_ms,
+            channel: word.channel,
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::{TrashItem, TrashKind, UserDatabase};
+
+const TRASH_KINDS: [TrashKind; 3] = [TrashKind::Session, TrashKind::Template, TrashKind::Human];
+
+// Columns pointing at a human that don't cascade or null on delete.
+// Participants and action item owners do, so they don't hold a human back.
+const HUMAN_REFERENCES: [(&str, &str); 7] = [
+    ("sessions", "user_id"),
+    ("events", "user_id"),
+    ("calendars", "user_id"),
+    ("templates", "user_id"),
+    ("configs", "user_id"),
+    ("chat_groups", "user_id"),
+    ("extension_mappings", "user_id"),
+];
+
+impl UserDatabase {
+    /// Everything deleted and not yet purged, most recently deleted first.
+    pub async fn list_trash(&self) -> Result<Vec<TrashItem>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                "SELECT 'session', id, title, deleted_at FROM sessions WHERE deleted_at IS NOT NULL
+                 UNION ALL
+                 SELECT 'template', id, title, deleted_at FROM templates WHERE deleted_at IS NOT NULL
+                 UNION ALL
+                 SELECT 'human', id, coalesce(full_name, email, ''), deleted_at FROM humans WHERE deleted_at IS NOT NULL
+                 ORDER BY deleted_at DESC",
+                (),
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let kind = match row.get_str(0)? {
+                "session" => TrashKind::Session,
+                "template" => TrashKind::Template,
+                _ => TrashKind::Human,
+            };
+            let deleted_at = chrono::DateTime::parse_from_rfc3339(row.get_str(3)?)
+                .map_err(|e| crate::Error::InvalidInput(e.to_string()))?
+                .with_timezone(&chrono::Utc);
+
+            items.push(TrashItem {
+                kind,
+                id: row.get(1)?,
+                title: row.get(2)?,
+                deleted_at,
+            });
+        }
+        Ok(items)
+    }
+
+    /// Takes an item back out of the trash. Returns whether it was there.
+    pub async fn restore_from_trash(
+        &self,
+        kind: TrashKind,
+        id: impl Into<String>,
+    ) -> Result<bool, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "UPDATE {} SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+            kind.sql_table()
+        );
+        let restored = conn.execute(&sql, vec![id.into()]).await?;
+        Ok(restored > 0)
+    }
+
+    /// Permanently deletes whatever has been in the trash for longer than
+    /// `older_than`, returning how many items went. Humans that still own
+    /// something stay in the trash until that goes too.
+    pub async fn purge_trash(&self, older_than: chrono::Duration) -> Result<u64, crate::Error> {
+        let conn = self.conn()?;
+        let cutoff = (chrono::Utc::now() - older_than).to_rfc3339();
+
+        let mut purged = 0;
+        for kind in TRASH_KINDS {
+            // Sessions and templates go first, so a human who only owned
+            // trashed items is free by the time humans are purged.
+            let keep_referenced = match kind {
+                TrashKind::Human => std::iter::once(" AND is_user = FALSE".to_string())
+                    .chain(HUMAN_REFERENCES.iter().map(|(table, column)| {
+                        format!(
+                            " AND NOT EXISTS (SELECT 1 FROM {table} WHERE {table}.{column} = humans.id)"
+                        )
+                    }))
+                    .collect(),
+                _ => String::new(),
+            };
+            let sql = format!(
+                "DELETE FROM {} WHERE deleted_at IS NOT NULL AND deleted_at <= ?{}",
+                kind.sql_table(),
+                keep_referenced
+            );
+            purged += conn.execute(&sql, vec![cutoff.clone()]).await?;
+        }
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GetSessionFilter, Human, Session, Template, TrashKind, tests::setup_db};
+
+    #[tokio::test]
+    async fn test_trash() {
+        let db = setup_db().await;
+
+        let user = db
+            .upsert_human(Human {
+                is_user: true,
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+        let guest = db
+            .upsert_human(Human {
+                full_name: Some("Grace Hopper".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "Quarterly review".to_string(),
+                raw_memo_html: String::new(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+                words: vec![],
+                record_start: None,
+                record_end: None,
+                pre_meeting_memo_html: None,
+            })
+            .await
+            .unwrap();
+        db.session_add_participant(&session.id, &guest.id)
+            .await
+            .unwrap();
+
+        let template = db
+            .upsert_template(Template {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                title: "1:1".to_string(),
+                description: String::new(),
+                sections: vec![],
+                tags: vec![],
+                context_option: None,
+            })
+            .await
+            .unwrap();
+
+        db.delete_session(&session.id).await.unwrap();
+        db.delete_template(template.id.clone()).await.unwrap();
+        db.delete_human(&guest.id).await.unwrap();
+
+        assert!(db.list_sessions(None).await.unwrap().is_empty());
+        assert!(db.list_templates(&user.id).await.unwrap().is_empty());
+        assert_eq!(db.list_humans(None).await.unwrap(), vec![user.clone()]);
+        assert!(
+            db.get_session(GetSessionFilter::Id(session.id.clone()))
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        let trash = db.list_trash().await.unwrap();
+        let mut titles: Vec<_> = trash.iter().map(|item| item.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["1:1", "Grace Hopper", "Quarterly review"]);
+
+        assert!(
+            db.restore_from_trash(TrashKind::Session, &session.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !db.restore_from_trash(TrashKind::Session, &session.id)
+                .await
+                .unwrap()
+        );
+        assert_eq!(db.list_sessions(None).await.unwrap().len(), 1);
+        assert!(
+            db.session_list_participants(&session.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(db.purge_trash(chrono::Duration::days(30)).await.unwrap(), 0);
+        assert_eq!(db.purge_trash(chrono::Duration::zero()).await.unwrap(), 2);
+        assert!(db.list_trash().await.unwrap().is_empty());
+        assert!(db.get_human(&guest.id).await.unwrap().is_none());
+
+        let colleague = db
+            .upsert_human(Human {
+                full_name: Some("Ada Lovelace".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+        db.upsert_template(Template {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: colleague.id.clone(),
+            title: "Standup".to_string(),
+            description: String::new(),
+            sections: vec![],
+            tags: vec![],
+            context_option: None,
+        })
+        .await
+        .unwrap();
+        db.delete_human(&colleague.id).await.unwrap();
+
+        assert_eq!(db.purge_trash(chrono::Duration::zero()).await.unwrap(), 0);
+        assert!(db.get_human(&colleague.id).await.unwrap().is_some());
+    }
+}
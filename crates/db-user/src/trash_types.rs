@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    pub enum TrashKind {
+        #[serde(rename = "session")]
+        Session,
+        #[serde(rename = "template")]
+        Template,
+        #[serde(rename = "human")]
+        Human,
+    }
+}
+
+impl TrashKind {
+    pub fn sql_table(&self) -> &'static str {
+        match self {
+            TrashKind::Session => "sessions",
+            TrashKind::Template => "templates",
+            TrashKind::Human => "humans",
+        }
+    }
+}
+
+user_common_derives! {
+    pub struct TrashItem {
+        pub kind: TrashKind,
+        pub id: String,
+        /// Session or template title, or the human's name.
+        pub title: String,
+        pub deleted_at: DateTime<Utc>,
+    }
+}
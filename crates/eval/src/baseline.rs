@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{EvalResult, PassStats, Score, calc_pass_stats};
+
+const BASELINE_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BaselineError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported baseline version {0}, expected {BASELINE_VERSION}")]
+    UnsupportedVersion(u32),
+}
+
+/// Pass counts of a known-good run, kept in version control next to the
+/// prompts they were measured with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// Rubric passes of one case on one model, pooled over samples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub case_id: String,
+    pub model: String,
+    pub pass_count: i32,
+    pub total: i32,
+    /// Why the case failed to run; the counts are zero when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BaselineEntry {
+    pub fn pass_stats(&self) -> PassStats {
+        calc_pass_stats(self.pass_count, self.total)
+    }
+
+    fn ran(&self) -> Option<PassStats> {
+        self.error.is_none().then(|| self.pass_stats())
+    }
+}
+
+// Single-sample scores leave the counts at zero and only set `passed`.
+fn score_counts(score: &Score) -> (i32, i32) {
+    if score.samples > 1 {
+        (score.pass_count, score.samples)
+    } else {
+        (score.passed as i32, 1)
+    }
+}
+
+impl Baseline {
+    /// Results that errored are kept with their error and no counts, so a case
+    /// that stops running shows up in a comparison instead of disappearing.
+    pub fn from_results(results: &[EvalResult]) -> Self {
+        let mut entries: Vec<BaselineEntry> = results
+            .iter()
+            .map(|r| {
+                if let Some(error) = &r.error {
+                    return BaselineEntry {
+                        case_id: r.case_id.clone(),
+                        model: r.model.clone(),
+                        pass_count: 0,
+                        total: 0,
+                        error: Some(error.clone()),
+                    };
+                }
+
+                let (pass_count, total) = r
+                    .scores
+                    .iter()
+                    .map(score_counts)
+                    .fold((0, 0), |(p, t), (sp, st)| (p + sp, t + st));
+                BaselineEntry {
+                    case_id: r.case_id.clone(),
+                    model: r.model.clone(),
+                    pass_count,
+                    total,
+                    error: None,
+                }
+            })
+            .collect();
+
+        // Results arrive in completion order; sorting keeps saved files diffable.
+        entries.sort_by(|a, b| (&a.case_id, &a.model).cmp(&(&b.case_id, &b.model)));
+
+        Self {
+            version: BASELINE_VERSION,
+            entries,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BaselineError> {
+        let baseline: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(BaselineError::UnsupportedVersion(baseline.version));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BaselineError> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonStatus {
+    /// The pass rate dropped by more than sampling noise can explain.
+    Regressed,
+    Improved,
+    /// Any change is within the confidence intervals.
+    Unchanged,
+    /// Not in the baseline.
+    New,
+    /// In the baseline, but not run this time.
+    Missing,
+    /// Ran in the baseline, but errored this time.
+    Errored,
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseComparison {
+    pub case_id: String,
+    pub model: String,
+    pub baseline: Option<PassStats>,
+    pub current: Option<PassStats>,
+    pub status: ComparisonStatus,
+}
+
+impl CaseComparison {
+    /// Change in pass rate, negative when it got worse.
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.current.as_ref()?.pass_rate - self.baseline.as_ref()?.pass_rate)
+    }
+
+    /// Whether this comparison should fail the run: a new error, or a
+    /// significant regression whose drop in pass rate is at least `threshold`.
+    pub fn fails(&self, threshold: f64) -> bool {
+        match self.status {
+            ComparisonStatus::Errored => true,
+            ComparisonStatus::Regressed => self.delta().is_some_and(|delta| -delta >= threshold),
+            _ => false,
+        }
+    }
+}
+
+/// Compares pass rates case by case. A change counts as significant only when
+/// the two Wilson intervals don't overlap, so a single flaky sample can't fail
+/// a run; more samples per case make smaller regressions detectable. A case
+/// that errors now but ran in the baseline always counts as a regression.
+pub fn compare(baseline: &Baseline, current: &Baseline) -> Vec<CaseComparison> {
+    let key = |e: &BaselineEntry| (e.case_id.clone(), e.model.clone());
+
+    // The baseline and current entry of each case, either may be absent.
+    type Pair<'a> = (Option<&'a BaselineEntry>, Option<&'a BaselineEntry>);
+    let mut pairs: BTreeMap<(String, String), Pair> = BTreeMap::new();
+    for entry in &baseline.entries {
+        pairs.entry(key(entry)).or_default().0 = Some(entry);
+    }
+    for entry in &current.entries {
+        pairs.entry(key(entry)).or_default().1 = Some(entry);
+    }
+
+    pairs
+        .into_iter()
+        .map(|((case_id, model), (baseline, current))| {
+            let (baseline, current) = (
+                baseline.map(BaselineEntry::ran),
+                current.map(BaselineEntry::ran),
+            );
+            let status = match (&baseline, &current) {
+                (None, _) => ComparisonStatus::New,
+                (_, None) => ComparisonStatus::Missing,
+                (Some(Some(_)), Some(None)) => ComparisonStatus::Errored,
+                (Some(None), Some(None)) => ComparisonStatus::Unchanged,
+                (Some(None), Some(Some(_))) => ComparisonStatus::Improved,
+                (Some(Some(b)), Some(Some(c))) => {
+                    if c.confidence_interval.upper < b.confidence_interval.lower {
+                        ComparisonStatus::Regressed
+                    } else if c.confidence_interval.lower > b.confidence_interval.upper {
+                        ComparisonStatus::Improved
+                    } else {
+                        ComparisonStatus::Unchanged
+                    }
+                }
+            };
+
+            CaseComparison {
+                case_id,
+                model,
+                baseline: baseline.flatten(),
+                current: current.flatten(),
+                status,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(case_id: &str, pass_count: i32, total: i32) -> BaselineEntry {
+        BaselineEntry {
+            case_id: case_id.to_string(),
+            model: "model".to_string(),
+            pass_count,
+            total,
+            error: None,
+        }
+    }
+
+    fn errored(case_id: &str) -> BaselineEntry {
+        BaselineEntry {
+            error: Some("timeout".to_string()),
+            ..entry(case_id, 0, 0)
+        }
+    }
+
+    fn baseline(entries: Vec<BaselineEntry>) -> Baseline {
+        Baseline {
+            version: BASELINE_VERSION,
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_from_results() {
+        let score = |passed, pass_count, samples| Score {
+            passed,
+            pass_count,
+            samples,
+            ..Default::default()
+        };
+
+        let results = vec![
+            EvalResult {
+                case_id: "b".to_string(),
+                model: "model".to_string(),
+                scores: vec![score(true, 0, 0), score(false, 0, 1)],
+                ..Default::default()
+            },
+            EvalResult {
+                case_id: "a".to_string(),
+                model: "model".to_string(),
+                scores: vec![score(true, 4, 5)],
+                ..Default::default()
+            },
+            EvalResult {
+                case_id: "c".to_string(),
+                model: "model".to_string(),
+                error: Some("timeout".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            Baseline::from_results(&results).entries,
+            vec![entry("a", 4, 5), entry("b", 1, 2), errored("c")]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+
+        let saved = baseline(vec![entry("a", 3, 4)]);
+        saved.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), saved);
+
+        fs::write(&path, r#"{ "version": 99, "entries": [] }"#).unwrap();
+        assert!(matches!(
+            Baseline::load(&path),
+            Err(BaselineError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn test_compare() {
+        let comparisons = compare(
+            &baseline(vec![
+                entry("flaky", 5, 5),
+                entry("regressed", 20, 20),
+                entry("improved", 2, 20),
+                entry("removed", 1, 1),
+            ]),
+            &baseline(vec![
+                entry("flaky", 4, 5),
+                entry("regressed", 8, 20),
+                entry("improved", 18, 20),
+                entry("added", 1, 1),
+            ]),
+        );
+
+        let statuses: Vec<_> = comparisons
+            .iter()
+            .map(|c| (c.case_id.as_str(), c.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("added", ComparisonStatus::New),
+                ("flaky", ComparisonStatus::Unchanged),
+                ("improved", ComparisonStatus::Improved),
+                ("regressed", ComparisonStatus::Regressed),
+                ("removed", ComparisonStatus::Missing),
+            ]
+        );
+
+        let regressed = &comparisons[3];
+        assert!((regressed.delta().unwrap() + 0.6).abs() < 1e-9);
+        assert!(regressed.fails(0.5));
+        assert!(!regressed.fails(0.7));
+        assert!(!comparisons[1].fails(0.0));
+    }
+
+    #[test]
+    fn test_compare_errors() {
+        let comparisons = compare(
+            &baseline(vec![
+                entry("broke", 5, 5),
+                errored("still_broken"),
+                errored("fixed"),
+            ]),
+            &baseline(vec![
+                errored("broke"),
+                errored("still_broken"),
+                entry("fixed", 5, 5),
+            ]),
+        );
+
+        let statuses: Vec<_> = comparisons
+            .iter()
+            .map(|c| (c.case_id.as_str(), c.status, c.fails(1.0)))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("broke", ComparisonStatus::Errored, true),
+                ("fixed", ComparisonStatus::Improved, false),
+                ("still_broken", ComparisonStatus::Unchanged, false),
+            ]
+        );
+        assert!(comparisons[0].current.is_none());
+    }
+}
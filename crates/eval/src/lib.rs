@@ -1,15 +1,18 @@
 //! # hypr-eval
 //!
-//! LLM evaluation framework for Rust.
+//! LLM and speech-to-text evaluation framework for Rust.
 //!
 //! ## Features
 //!
 //! - Parallel execution of evaluation cases
 //! - Multiple grading strategies (function-based, LLM-based)
 //! - Statistical analysis with confidence intervals
+//! - Baseline comparison to catch regressions
 //! - Response caching for reproducibility
 //! - Progress tracking
 //! - OpenRouter API integration
+//! - Local GGUF models through `hypr-llama` (`local` feature)
+//! - WER/CER and DER scoring for speech-to-text
 //!
 //! ## Quick Start
 //!
@@ -37,13 +40,17 @@
 //! let results = executor.execute(&[case], &["gpt-4".to_string()]);
 //! ```
 
+mod baseline;
 mod cache;
 mod client;
 mod config;
 mod format;
+#[cfg(feature = "local")]
+mod local;
 mod models;
 mod rubric;
 mod stats;
+mod stt;
 mod submission;
 mod testing;
 
@@ -53,6 +60,9 @@ pub mod constants;
 pub use testing::*;
 
 // Re-export core types at root for convenience
+pub use baseline::{
+    Baseline, BaselineEntry, BaselineError, CaseComparison, ComparisonStatus, compare,
+};
 pub use client::{
     ChatCompleter, ChatCompletionRequest, ChatCompletionResponse, ChatMessage, ClientError,
     GraderResponse, OpenRouterClient, Usage, UsageResolver, generate_chat_multi_with_generation_id,
@@ -66,12 +76,19 @@ pub use format::{
     CheckResult, GradeResult, Rule, count_list_items_in_section, extract_text, find_headings,
     find_list_items, find_lists, first_inline_child, grade, split_by_headings,
 };
+#[cfg(feature = "local")]
+pub use local::LlamaClient;
 pub use models::{fetch_openrouter_models, filter_models};
 pub use rubric::{Score, grade_with_func, grade_with_llm, is_non_empty};
 pub use stats::{
     AggregatedGraderResponse, ConfidenceInterval, PassStats, aggregate_grader_responses,
     calc_pass_stats,
 };
+pub use stt::{
+    DiarizationErrors, ErrorCounts, HypothesisWord, ReferenceWord, SpeakerTurn, char_error_rate,
+    diarization_error_rate, edit_distance, hypothesis_turns, normalize_words, parse_diarization,
+    parse_transcription, word_error_rate,
+};
 pub use submission::{
     EvalCase, EvalResult, Executor, ExecutorProgress, ExecutorProgressCallback, GraderSpec,
     RubricSpec, ValidationError, ValidatorFn, ValidatorFnWithMeta,
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use futures_util::StreamExt;
+use hypr_gbnf::Grammar;
+use hypr_llama::{Llama, LlamaMessage, LlamaRequest, ModelName, Response};
+
+use crate::client::{ChatChoice, ChatChoiceMessage};
+use crate::{ChatCompleter, ChatCompletionRequest, ChatCompletionResponse, ClientError};
+
+/// Runs cases against a GGUF model on this machine, the way the desktop app
+/// does through local-llm, so quantizations and prompts can be compared
+/// without a network.
+pub struct LlamaClient {
+    name: String,
+    model: Llama,
+    grammar: Option<Grammar>,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl LlamaClient {
+    pub fn new(model_path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let model_path = model_path.as_ref();
+        let model = Llama::new(model_path).map_err(|e| {
+            ClientError::model_error(model_path.display().to_string(), e.to_string())
+        })?;
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+
+        // Reported as the model in results and baselines, so quantizations of
+        // the same model stay apart.
+        let name = model_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| model_path.display().to_string());
+
+        Ok(Self {
+            name,
+            model,
+            grammar: None,
+            runtime,
+        })
+    }
+
+    /// Constrains generations the way the app does for the same task, e.g.
+    /// `Grammar::Enhance` for note cases.
+    pub fn with_grammar(mut self, grammar: Grammar) -> Self {
+        self.grammar = Some(grammar);
+        self
+    }
+
+    pub fn model_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_hypr_llm(&self) -> bool {
+        self.model.name == ModelName::HyprLLM
+    }
+
+    // Mirrors local-llm's server: structured requests (graders) get their
+    // schema, and HyprLLM is trained on the enhance format so it only needs a
+    // grammar when specific sections are asked for.
+    fn grammar_for(&self, request: &ChatCompletionRequest) -> Result<Option<String>, ClientError> {
+        if let Some(schema) = request
+            .response_format
+            .as_ref()
+            .and_then(|f| f.json_schema.as_ref())
+        {
+            return Grammar::from_json_schema(schema.schema.clone())
+                .map(Some)
+                .map_err(|e| ClientError::model_error(&request.model, e.to_string()));
+        }
+
+        Ok(match &self.grammar {
+            Some(Grammar::Enhance { sections: None }) if self.is_hypr_llm() => None,
+            Some(grammar) => Some(
+                grammar
+                    .build()
+                    .map_err(|e| ClientError::model_error(&request.model, e.to_string()))?,
+            ),
+            None => None,
+        })
+    }
+
+    fn generate(&self, request: &ChatCompletionRequest) -> Result<String, ClientError> {
+        let llama_request = LlamaRequest {
+            grammar: self.grammar_for(request)?,
+            messages: request
+                .messages
+                .iter()
+                .map(|m| LlamaMessage {
+                    role: m.role.clone(),
+                    content: m.content.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let stream = self
+            .model
+            .generate_stream(llama_request)
+            .map_err(|e| ClientError::model_error(&request.model, e.to_string()))?;
+
+        let content = self.runtime.block_on(async {
+            futures_util::pin_mut!(stream);
+
+            let mut content = String::new();
+            while let Some(response) = stream.next().await {
+                // Reasoning is the model thinking out loud, not its answer.
+                if let Response::TextDelta(delta) = response {
+                    content.push_str(&delta);
+                }
+            }
+            content
+        });
+
+        // The model drops the stream without a word when prefill fails.
+        if content.trim().is_empty() {
+            return Err(ClientError::model_error(
+                &request.model,
+                "generation produced no output",
+            ));
+        }
+
+        Ok(content)
+    }
+}
+
+impl ChatCompleter for LlamaClient {
+    fn create_chat_completion(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, ClientError> {
+        let n = request.n.unwrap_or(1).max(1);
+
+        let choices = (0..n)
+            .map(|_| {
+                Ok(ChatChoice {
+                    message: ChatChoiceMessage {
+                        content: self.generate(request)?,
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, ClientError>>()?;
+
+        // No generation id: there's no usage to look up for local runs.
+        Ok(ChatCompletionResponse {
+            id: String::new(),
+            choices,
+        })
+    }
+}
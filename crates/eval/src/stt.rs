@@ -0,0 +1,383 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Frame size used to compare speaker turns.
+const DER_FRAME_MS: u64 = 10;
+
+/// A word from a ground-truth transcription, as in `transcription.json`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReferenceWord {
+    pub start: u64,
+    pub end: u64,
+    pub text: String,
+}
+
+/// A stretch of time attributed to one speaker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerTurn {
+    pub start: u64,
+    pub end: u64,
+    pub speaker: String,
+}
+
+/// A word as transcribed by the backend under test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HypothesisWord {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speaker: Option<String>,
+}
+
+/// Edit operations between a reference and a hypothesis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ErrorCounts {
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    pub reference_len: usize,
+}
+
+impl ErrorCounts {
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.deletions + self.insertions
+    }
+
+    /// Errors per reference unit. Can exceed 1.0 when the hypothesis adds a
+    /// lot of text.
+    pub fn rate(&self) -> f64 {
+        if self.reference_len == 0 {
+            return if self.insertions == 0 { 0.0 } else { 1.0 };
+        }
+        self.errors() as f64 / self.reference_len as f64
+    }
+
+    pub fn merge(&mut self, other: &ErrorCounts) {
+        self.substitutions += other.substitutions;
+        self.deletions += other.deletions;
+        self.insertions += other.insertions;
+        self.reference_len += other.reference_len;
+    }
+}
+
+/// Diarization error, in milliseconds of reference speech.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DiarizationErrors {
+    pub missed_ms: u64,
+    pub false_alarm_ms: u64,
+    pub confusion_ms: u64,
+    pub total_ms: u64,
+}
+
+impl DiarizationErrors {
+    pub fn rate(&self) -> f64 {
+        if self.total_ms == 0 {
+            return 0.0;
+        }
+        (self.missed_ms + self.false_alarm_ms + self.confusion_ms) as f64 / self.total_ms as f64
+    }
+
+    pub fn merge(&mut self, other: &DiarizationErrors) {
+        self.missed_ms += other.missed_ms;
+        self.false_alarm_ms += other.false_alarm_ms;
+        self.confusion_ms += other.confusion_ms;
+        self.total_ms += other.total_ms;
+    }
+}
+
+pub fn parse_transcription(json: &str) -> Result<Vec<ReferenceWord>, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Parses `diarization.json`, merging the short fixed-size segments it's made
+/// of into one turn per uninterrupted stretch of a speaker.
+pub fn parse_diarization(json: &str) -> Result<Vec<SpeakerTurn>, serde_json::Error> {
+    let segments: Vec<SpeakerTurn> = serde_json::from_str(json)?;
+    Ok(merge_turns(segments))
+}
+
+fn merge_turns(segments: impl IntoIterator<Item = SpeakerTurn>) -> Vec<SpeakerTurn> {
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+
+    for segment in segments {
+        match turns.last_mut() {
+            Some(last) if last.speaker == segment.speaker && segment.start <= last.end => {
+                last.end = last.end.max(segment.end);
+            }
+            _ => turns.push(segment),
+        }
+    }
+
+    turns
+}
+
+/// Speaker turns implied by consecutive words with the same speaker. Words
+/// without a speaker are left out, and count as missed speech in DER.
+pub fn hypothesis_turns(words: &[HypothesisWord]) -> Vec<SpeakerTurn> {
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+
+    for word in words {
+        let Some(speaker) = &word.speaker else {
+            continue;
+        };
+
+        match turns.last_mut() {
+            Some(last) if &last.speaker == speaker => {
+                last.end = last.end.max(word.end_ms);
+            }
+            _ => turns.push(SpeakerTurn {
+                start: word.start_ms,
+                end: word.end_ms,
+                speaker: speaker.clone(),
+            }),
+        }
+    }
+
+    turns
+}
+
+/// Lowercased words with punctuation stripped, so scores reflect recognition
+/// rather than formatting.
+pub fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> ErrorCounts {
+    edit_distance(&normalize_words(reference), &normalize_words(hypothesis))
+}
+
+/// Character error rate, ignoring whitespace. More meaningful than WER for
+/// languages like Korean, where spacing varies between transcribers.
+pub fn char_error_rate(reference: &str, hypothesis: &str) -> ErrorCounts {
+    let chars = |text: &str| -> Vec<char> { normalize_words(text).concat().chars().collect() };
+    edit_distance(&chars(reference), &chars(hypothesis))
+}
+
+/// Levenshtein alignment, broken down by operation.
+pub fn edit_distance<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> ErrorCounts {
+    // Each cell holds (substitutions, deletions, insertions) for the best
+    // alignment of the prefixes; ties prefer substitutions.
+    let mut prev: Vec<(usize, usize, usize)> = (0..=hypothesis.len()).map(|j| (0, 0, j)).collect();
+
+    for (i, r) in reference.iter().enumerate() {
+        let mut row = Vec::with_capacity(hypothesis.len() + 1);
+        row.push((0, i + 1, 0));
+
+        for (j, h) in hypothesis.iter().enumerate() {
+            let diagonal = {
+                let (s, d, n) = prev[j];
+                if r == h { (s, d, n) } else { (s + 1, d, n) }
+            };
+            let deletion = {
+                let (s, d, n) = prev[j + 1];
+                (s, d + 1, n)
+            };
+            let insertion = {
+                let (s, d, n) = row[j];
+                (s, d, n + 1)
+            };
+
+            let cost = |(s, d, n): (usize, usize, usize)| s + d + n;
+            let best = [diagonal, deletion, insertion]
+                .into_iter()
+                .min_by_key(|&ops| cost(ops))
+                .unwrap();
+            row.push(best);
+        }
+
+        prev = row;
+    }
+
+    let (substitutions, deletions, insertions) = prev[hypothesis.len()];
+    ErrorCounts {
+        substitutions,
+        deletions,
+        insertions,
+        reference_len: reference.len(),
+    }
+}
+
+fn frames(turns: &[SpeakerTurn]) -> HashMap<u64, BTreeSet<&str>> {
+    let mut frames: HashMap<u64, BTreeSet<&str>> = HashMap::new();
+    for turn in turns {
+        for frame in turn.start / DER_FRAME_MS..turn.end.div_ceil(DER_FRAME_MS) {
+            frames.entry(frame).or_default().insert(&turn.speaker);
+        }
+    }
+    frames
+}
+
+/// Diarization error rate without a forgiveness collar. Hypothesis speakers
+/// are mapped to reference speakers greedily by overlap, which matches the
+/// optimal mapping whenever one pairing clearly dominates, as it does for
+/// the corpus's two- and three-speaker recordings.
+pub fn diarization_error_rate(
+    reference: &[SpeakerTurn],
+    hypothesis: &[SpeakerTurn],
+) -> DiarizationErrors {
+    let reference = frames(reference);
+    let hypothesis = frames(hypothesis);
+
+    let mut overlap: HashMap<(&str, &str), u64> = HashMap::new();
+    for (frame, speakers) in &reference {
+        if let Some(hyp_speakers) = hypothesis.get(frame) {
+            for r in speakers {
+                for h in hyp_speakers {
+                    *overlap.entry((*r, *h)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let mut pairs: Vec<_> = overlap.into_iter().collect();
+    pairs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut mapping: HashMap<&str, &str> = HashMap::new();
+    let mut taken: BTreeSet<&str> = BTreeSet::new();
+    for ((r, h), _) in pairs {
+        if !mapping.contains_key(h) && !taken.contains(r) {
+            mapping.insert(h, r);
+            taken.insert(r);
+        }
+    }
+
+    let mut errors = DiarizationErrors::default();
+    let all_frames: BTreeSet<u64> = reference.keys().chain(hypothesis.keys()).copied().collect();
+    let empty = BTreeSet::new();
+
+    for frame in all_frames {
+        let r = reference.get(&frame).unwrap_or(&empty);
+        let h = hypothesis.get(&frame).unwrap_or(&empty);
+        let correct = h
+            .iter()
+            .filter(|speaker| mapping.get(*speaker).is_some_and(|m| r.contains(m)))
+            .count();
+
+        errors.total_ms += r.len() as u64 * DER_FRAME_MS;
+        errors.missed_ms += r.len().saturating_sub(h.len()) as u64 * DER_FRAME_MS;
+        errors.false_alarm_ms += h.len().saturating_sub(r.len()) as u64 * DER_FRAME_MS;
+        errors.confusion_ms += (r.len().min(h.len()) - correct) as u64 * DER_FRAME_MS;
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(start: u64, end: u64, speaker: &str) -> SpeakerTurn {
+        SpeakerTurn {
+            start,
+            end,
+            speaker: speaker.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_words() {
+        assert_eq!(
+            normalize_words(" Maybe this is, me   talking. It's"),
+            vec!["maybe", "this", "is", "me", "talking", "it's"]
+        );
+        assert!(normalize_words("-- ...").is_empty());
+    }
+
+    #[test]
+    fn test_word_error_rate() {
+        let counts = word_error_rate("the cat sat on the mat", "The cat sat on a mat today");
+        assert_eq!(counts.substitutions, 1);
+        assert_eq!(counts.deletions, 0);
+        assert_eq!(counts.insertions, 1);
+        assert_eq!(counts.reference_len, 6);
+        assert!((counts.rate() - 2.0 / 6.0).abs() < 1e-9);
+
+        let counts = word_error_rate("one two three", "one three");
+        assert_eq!((counts.deletions, counts.errors()), (1, 1));
+
+        assert_eq!(word_error_rate("", "").rate(), 0.0);
+        assert_eq!(word_error_rate("", "hello").rate(), 1.0);
+    }
+
+    #[test]
+    fn test_char_error_rate() {
+        let counts = char_error_rate("안녕하세요 여러분", "안녕 하세요 여러분");
+        assert_eq!(counts.errors(), 0);
+
+        let counts = char_error_rate("abc", "abd");
+        assert_eq!((counts.substitutions, counts.reference_len), (1, 3));
+    }
+
+    #[test]
+    fn test_parse_diarization() {
+        let turns = parse_diarization(
+            r#"[
+              { "start": 0, "end": 500, "speaker": "speaker0" },
+              { "start": 500, "end": 1000, "speaker": "speaker0" },
+              { "start": 1000, "end": 1500, "speaker": "speaker1" },
+              { "start": 2000, "end": 2500, "speaker": "speaker1" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            turns,
+            vec![
+                turn(0, 1000, "speaker0"),
+                turn(1000, 1500, "speaker1"),
+                turn(2000, 2500, "speaker1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hypothesis_turns() {
+        let word = |start_ms, speaker: Option<&str>| HypothesisWord {
+            text: "word".to_string(),
+            start_ms,
+            end_ms: start_ms + 100,
+            speaker: speaker.map(str::to_string),
+        };
+
+        let turns = hypothesis_turns(&[
+            word(0, Some("0")),
+            word(100, Some("0")),
+            word(200, None),
+            word(300, Some("1")),
+        ]);
+        assert_eq!(turns, vec![turn(0, 200, "0"), turn(300, 400, "1")]);
+    }
+
+    #[test]
+    fn test_diarization_error_rate() {
+        let reference = vec![turn(0, 1000, "alice"), turn(1000, 2000, "bob")];
+
+        // Same boundaries under different labels is a perfect diarization.
+        let perfect =
+            diarization_error_rate(&reference, &[turn(0, 1000, "1"), turn(1000, 2000, "0")]);
+        assert_eq!(perfect.rate(), 0.0);
+        assert_eq!(perfect.total_ms, 2000);
+
+        let errors = diarization_error_rate(
+            &reference,
+            &[
+                turn(0, 1500, "1"),
+                turn(1500, 1800, "0"),
+                turn(2000, 2200, "0"),
+            ],
+        );
+        assert_eq!(errors.confusion_ms, 500);
+        assert_eq!(errors.missed_ms, 200);
+        assert_eq!(errors.false_alarm_ms, 200);
+        assert!((errors.rate() - 0.45).abs() < 1e-9);
+    }
+}
@@ -38,6 +38,15 @@ pub enum ValidationError {
 }
 
 impl EvalCase {
+    /// Drops rubrics graded by an LLM, for runs that must stay deterministic
+    /// and offline. Function graders (such as the `format::grade` rules) are
+    /// kept.
+    pub fn without_llm_rubrics(mut self) -> Self {
+        self.rubrics
+            .retain(|rubric| !matches!(rubric.grader, GraderSpec::Llm { .. }));
+        self
+    }
+
     /// Validates the evaluation case and returns any validation errors.
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
@@ -166,6 +175,7 @@ fn compute_expected_evals(case: &EvalCase) -> i32 {
 
 pub struct Executor {
     client: Arc<dyn ChatCompleter>,
+    grader_client: Option<Arc<dyn ChatCompleter>>,
     grader_model: String,
     concurrency: usize,
     on_progress: Option<ExecutorProgressCallback>,
@@ -176,6 +186,7 @@ impl Executor {
         let cfg = parse_config();
         Self {
             client,
+            grader_client: None,
             grader_model: DEFAULT_GRADER_MODEL.to_string(),
             concurrency: cfg.concurrency,
             on_progress: None,
@@ -187,6 +198,13 @@ impl Executor {
         self
     }
 
+    /// Grades LLM rubrics with a different client than the one generating,
+    /// e.g. a remote grader for a local model's outputs.
+    pub fn with_grader_client(mut self, client: Arc<dyn ChatCompleter>) -> Self {
+        self.grader_client = Some(client);
+        self
+    }
+
     pub fn with_concurrency(mut self, concurrency: usize) -> Self {
         self.concurrency = concurrency;
         self
@@ -502,7 +520,7 @@ impl Executor {
                     .and_then(|v| v.as_object())
                     .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
                 crate::grade_with_llm(
-                    self.client.as_ref(),
+                    self.grader_client.as_ref().unwrap_or(&self.client).as_ref(),
                     &self.grader_model,
                     &rubric.name,
                     &rubric.description,
@@ -515,3 +533,56 @@ impl Executor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockClient, MockResponse, is_non_empty};
+
+    fn case() -> EvalCase {
+        EvalCase {
+            case_id: "case".to_string(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Write a note".to_string(),
+            }],
+            rubrics: vec![
+                RubricSpec {
+                    name: "non_empty".to_string(),
+                    description: "Output is non-empty".to_string(),
+                    grader: GraderSpec::Func(is_non_empty),
+                },
+                RubricSpec {
+                    name: "concise".to_string(),
+                    description: "Output is concise".to_string(),
+                    grader: GraderSpec::Llm { samples: 1 },
+                },
+            ],
+            samples: 1,
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn test_grader_client() {
+        let client = MockClient::with_response(MockResponse::new("# Note"));
+        let grader = MockClient::with_response(MockResponse::grader_pass("Short enough"));
+
+        let results = Executor::new(Arc::new(client.clone()))
+            .with_grader_client(Arc::new(grader.clone()))
+            .with_concurrency(1)
+            .execute(&[case()], &["local".to_string()]);
+
+        assert!(results[0].all_passed());
+        assert_eq!(client.call_count(), 1);
+        assert_eq!(grader.call_count(), 1);
+        assert_eq!(grader.requests()[0].model, DEFAULT_GRADER_MODEL);
+    }
+
+    #[test]
+    fn test_without_llm_rubrics() {
+        let case = case().without_llm_rubrics();
+        let names: Vec<_> = case.rubrics.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["non_empty"]);
+    }
+}
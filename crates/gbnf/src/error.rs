@@ -0,0 +1,13 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid schema at '{0}'")]
+    InvalidSchema(String),
+    #[error("unsupported schema at '{0}': {1}")]
+    Unsupported(String, String),
+    #[error("unresolved reference '{0}'")]
+    UnresolvedRef(String),
+    #[error("invalid pattern '{0}': {1}")]
+    InvalidPattern(String, String),
+    #[error("serde_json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
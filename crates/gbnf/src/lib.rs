@@ -1,5 +1,13 @@
 // https://github.com/ggml-org/llama.cpp/blob/master/grammars/README.md
 
+mod error;
+mod pattern;
+mod schema;
+mod tool;
+
+pub use error::*;
+pub use tool::*;
+
 #[derive(specta::Type, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "task")]
 pub enum Grammar {
@@ -11,19 +19,49 @@ pub enum Grammar {
     Tags,
     #[serde(rename = "email-to-name")]
     EmailToName,
+    #[serde(rename = "json-schema")]
+    JsonSchema { schema: serde_json::Value },
 }
 
 impl Grammar {
-    pub fn build(&self) -> String {
-        match self {
+    /// Only `JsonSchema` can fail, when its schema doesn't compile.
+    pub fn build(&self) -> Result<String, Error> {
+        Ok(match self {
             Grammar::Enhance { sections } => build_enhance_other_grammar(sections),
             Grammar::Title => build_title_grammar(),
             Grammar::Tags => build_tags_grammar(),
             Grammar::EmailToName => build_email_to_name_grammar(),
+            Grammar::JsonSchema { schema } => Self::from_json_schema(schema.clone())?,
+        })
+    }
+
+    /// Compiles a JSON Schema (objects, arrays, enums, `oneOf`/`anyOf`, string
+    /// formats and patterns, numbers, local `$ref`s) into a grammar.
+    pub fn from_json_schema(schema: impl Into<serde_json::Value>) -> Result<String, Error> {
+        schema::compile(&schema.into())
+    }
+
+    /// [`Grammar::from_json_schema`] for a schema generated by `schemars`.
+    pub fn from_schemars(schema: &schemars::schema::RootSchema) -> Result<String, Error> {
+        Self::from_json_schema(serde_json::to_value(schema)?)
+    }
+
+    /// Grammar for the JSON form of `T`.
+    pub fn for_type<T: schemars::JsonSchema>() -> Self {
+        let schema = schemars::schema_for!(T);
+        Grammar::JsonSchema {
+            schema: serde_json::to_value(schema).unwrap_or_default(),
         }
     }
 }
 
+/// Any JSON object, for `response_format: json_object`.
+pub fn build_json_grammar() -> String {
+    let mut rules = vec![r##"root ::= object"##];
+    rules.extend(JSON_RULES);
+    rules.join("\n")
+}
+
 fn build_known_sections_grammar(sections: &[String]) -> String {
     let mut rules = vec![];
 
@@ -167,6 +205,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_json_schema_grammar() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+
+        let grammar = Grammar::JsonSchema {
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": { "first_name": { "type": "string" } },
+                "required": ["first_name"]
+            }),
+        }
+        .build()
+        .unwrap();
+        assert!(
+            gbnf.validate(&grammar, r#"{"first_name": "John"}"#)
+                .unwrap()
+        );
+        assert!(!gbnf.validate(&grammar, r#"{"name": "John"}"#).unwrap());
+
+        let grammar = Grammar::JsonSchema {
+            schema: serde_json::json!({ "$ref": "#/definitions/Missing" }),
+        }
+        .build();
+        assert!(matches!(grammar, Err(Error::UnresolvedRef(_))));
+    }
+
+    #[test]
+    fn test_schemars_grammar() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Name {
+            first_name: String,
+        }
+
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        let grammar = Grammar::from_schemars(&schemars::schema_for!(Name)).unwrap();
+        assert!(
+            gbnf.validate(&grammar, r#"{"first_name": "John"}"#)
+                .unwrap()
+        );
+        assert!(!gbnf.validate(&grammar, r#"{"name": "John"}"#).unwrap());
+    }
+
     #[test]
     fn test_enhance_grammar() {
         let input_1 = "<headers>\n- Objective\n- Key Takeaways\n- Importance of Complementary Skills\n- Benefits of Using Online Resources\n- Advice for Undergrad Students\n</headers># Objective\n\n- **Search is the Best Way to Find Answers**: The speaker emphasizes the importance of utilizing online resources like Google to find answers to questions.\n- **Value in Complementary Skills**: The speaker highlights the need to acquire complementary skills to traditional research methods.\n\n# Key Takeaways\n\n- **Complementary skills include both traditional research and online resource utilization**: The speaker suggests that skills like using a blank sheet of paper with no Internet and effective Google searching are essential.\n- **Online resources can help find pre-solved problems**: The speaker advises investing time in finding existing resources and communities that have already solved problems.\n\n# Importance of Complementary Skills\n\n- **Traditional research is just the starting point**: The speaker suggests that traditional research methods are just the beginning and should be complemented with other skills.\n- **Effective use of online resources can save time and effort**: The speaker highlights the benefits of utilizing online resources in research and problem-solving.\n\n# Benefits of Using Online Resources\n\n- **Access to knowledge from experts and communities**: The speaker suggests that online resources provide access to knowledge and expertise from experienced individuals.\n- **Time-saving and efficient**: The speaker emphasizes the benefits of finding pre-solved problems through online resources.\n\n# Advice for Undergrad Students\n\n- **Start by searching online**: The speaker advises undergrad students to start by searching online for answers to questions and exploring different resources.\n- **Be open to finding existing solutions**: The speaker emphasizes the importance of being open to finding pre-solved problems and leveraging existing resources.\n\n";
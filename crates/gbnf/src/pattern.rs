@@ -0,0 +1,344 @@
+use crate::Error;
+
+// Characters a JSON string can only contain escaped.
+const ESCAPED: &[(u32, u32)] = &[(0x00, 0x1F), (0x22, 0x22), (0x5C, 0x5C), (0x7F, 0x7F)];
+
+/// Translates a JSON Schema `pattern` into a GBNF expression for the content of
+/// a JSON string.
+///
+/// Supports literals, `.`, character classes, `\d` `\w` `\s`, groups,
+/// alternation and the usual quantifiers. The pattern is treated as anchored
+/// on both ends, which is how it is almost always meant in schemas. Quotes,
+/// backslashes and control characters match their JSON escapes.
+pub(crate) fn pattern_to_gbnf(pattern: &str, char_rule: &str) -> Result<String, Error> {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    let pattern = match pattern.strip_suffix('$') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => pattern,
+    };
+
+    let mut parser = Parser {
+        chars: pattern.chars().collect(),
+        pos: 0,
+        pattern,
+        char_rule,
+    };
+
+    let expr = parser.alternation()?;
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unbalanced ')'"));
+    }
+    Ok(expr)
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    pattern: &'a str,
+    char_rule: &'a str,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        Error::InvalidPattern(self.pattern.to_string(), message.to_string())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn alternation(&mut self) -> Result<String, Error> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.sequence()?);
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            format!("( {} )", alternatives.join(" | "))
+        })
+    }
+
+    fn sequence(&mut self) -> Result<String, Error> {
+        let mut items: Vec<String> = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+
+            let atom = self.atom()?;
+            match self.quantifier()? {
+                Some(quantifier) => items.push(format!("{}{}", atom, quantifier)),
+                None => items.push(atom),
+            }
+        }
+
+        Ok(if items.is_empty() {
+            "\"\"".to_string()
+        } else {
+            items.join(" ")
+        })
+    }
+
+    fn atom(&mut self) -> Result<String, Error> {
+        match self.next() {
+            Some('(') => {
+                if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                }
+                let inner = self.alternation()?;
+                if self.next() != Some(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                Ok(format!("( {} )", inner))
+            }
+            Some('[') => self.class(),
+            Some('.') => Ok(self.char_rule.to_string()),
+            Some('\\') => match self.next() {
+                Some(c @ ('d' | 'w' | 's')) => Ok(render_class(false, shorthand(c))),
+                Some(c) if c.is_ascii_alphanumeric() => {
+                    Err(self.error(&format!("unsupported escape '\\{}'", c)))
+                }
+                Some(c) => Ok(crate::tool::literal(&json_escaped(c))),
+                None => Err(self.error("trailing '\\'")),
+            },
+            Some(c @ ('*' | '+' | '?' | '{')) => {
+                Err(self.error(&format!("nothing to repeat before '{}'", c)))
+            }
+            Some(c) => Ok(crate::tool::literal(&json_escaped(c))),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn class(&mut self) -> Result<String, Error> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let lo = match self.next() {
+                None => return Err(self.error("unclosed character class")),
+                Some(']') if !first => break,
+                Some('\\') => match self.next() {
+                    Some(c @ ('d' | 'w' | 's')) => {
+                        ranges.extend(shorthand(c));
+                        first = false;
+                        continue;
+                    }
+                    Some(c) => c,
+                    None => return Err(self.error("trailing '\\'")),
+                },
+                Some(c) => c,
+            };
+            first = false;
+
+            let is_range = self.peek() == Some('-')
+                && !matches!(self.chars.get(self.pos + 1), None | Some(']'));
+            if !is_range {
+                ranges.push((lo, lo));
+                continue;
+            }
+
+            self.pos += 1;
+            let hi = match self.next() {
+                Some('\\') => self.next().ok_or_else(|| self.error("trailing '\\'"))?,
+                Some(c) => c,
+                None => return Err(self.error("unclosed character class")),
+            };
+            if hi < lo {
+                return Err(self.error(&format!("invalid range '{}-{}'", lo, hi)));
+            }
+            ranges.push((lo, hi));
+        }
+
+        Ok(render_class(negated, ranges))
+    }
+
+    fn quantifier(&mut self) -> Result<Option<String>, Error> {
+        match self.peek() {
+            Some(c @ ('*' | '+' | '?')) => {
+                self.pos += 1;
+                Ok(Some(c.to_string()))
+            }
+            Some('{') => {
+                let start = self.pos;
+                let end = self.chars[start..]
+                    .iter()
+                    .position(|&c| c == '}')
+                    .map(|offset| start + offset)
+                    .ok_or_else(|| self.error("unclosed '{'"))?;
+
+                let body: String = self.chars[start + 1..end].iter().collect();
+                let valid = !body.is_empty()
+                    && body.split(',').count() <= 2
+                    && body
+                        .split(',')
+                        .enumerate()
+                        .all(|(i, n)| n.parse::<u32>().is_ok() || (i == 1 && n.is_empty()));
+                if !valid {
+                    return Err(self.error(&format!("invalid repetition '{{{}}}'", body)));
+                }
+
+                self.pos = end + 1;
+                Ok(Some(format!("{{{}}}", body)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn shorthand(c: char) -> Vec<(char, char)> {
+    match c {
+        'd' => vec![('0', '9')],
+        'w' => vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+        _ => vec![(' ', ' '), ('\t', '\t')],
+    }
+}
+
+/// How `c` is written inside a JSON string.
+fn json_escaped(c: char) -> String {
+    match c {
+        '"' => "\\\"".to_string(),
+        '\\' => "\\\\".to_string(),
+        '\n' => "\\n".to_string(),
+        '\r' => "\\r".to_string(),
+        '\t' => "\\t".to_string(),
+        c if c.is_ascii_control() => format!("\\u{:04x}", c as u32),
+        c => c.to_string(),
+    }
+}
+
+/// A GBNF character class over `ranges`, minus the characters JSON requires
+/// escaped. A negated class never matches those; a plain one matches their
+/// escapes instead.
+fn render_class(negated: bool, ranges: Vec<(char, char)>) -> String {
+    let mut body = String::new();
+    for (lo, hi) in ranges.iter().flat_map(|&(lo, hi)| without_escaped(lo, hi)) {
+        body.push_str(&class_char(lo));
+        if hi != lo {
+            body.push('-');
+            body.push_str(&class_char(hi));
+        }
+    }
+
+    if negated {
+        for &(lo, hi) in ESCAPED {
+            body.push_str(&format!("\\x{:02X}", lo));
+            if hi != lo {
+                body.push_str(&format!("-\\x{:02X}", hi));
+            }
+        }
+        return format!("[^{}]", body);
+    }
+
+    let mut alternatives: Vec<String> = Vec::new();
+    if !body.is_empty() {
+        alternatives.push(format!("[{}]", body));
+    }
+    alternatives.extend(
+        ESCAPED
+            .iter()
+            .flat_map(|&(lo, hi)| lo..=hi)
+            .filter_map(char::from_u32)
+            .filter(|&c| ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi))
+            .map(|c| crate::tool::literal(&json_escaped(c))),
+    );
+
+    match alternatives.len() {
+        1 => alternatives.remove(0),
+        _ => format!("( {} )", alternatives.join(" | ")),
+    }
+}
+
+/// Splits `lo..=hi` around the characters in `ESCAPED`.
+fn without_escaped(lo: char, hi: char) -> Vec<(char, char)> {
+    let (mut start, end) = (lo as u32, hi as u32);
+    let mut out = Vec::new();
+
+    for &(e_lo, e_hi) in ESCAPED {
+        if e_hi < start {
+            continue;
+        }
+        if e_lo > end {
+            break;
+        }
+        if e_lo > start {
+            out.push((start, e_lo - 1));
+        }
+        start = e_hi + 1;
+    }
+    if start <= end {
+        out.push((start, end));
+    }
+
+    out.into_iter()
+        .filter_map(|(lo, hi)| Some((char::from_u32(lo)?, char::from_u32(hi)?)))
+        .collect()
+}
+
+/// `c` as written inside a GBNF character class.
+fn class_char(c: char) -> String {
+    match c {
+        '[' | ']' | '-' | '^' => format!("\\x{:02X}", c as u32),
+        c => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_to_gbnf() {
+        for (pattern, expected) in [
+            ("^[A-Z]{2,3}-\\d+$", "[A-Z]{2,3} \"-\" [0-9]+"),
+            ("ab?", "\"a\" \"b\"?"),
+            ("(?:yes|no)", "( ( \"y\" \"e\" \"s\" | \"n\" \"o\" ) )"),
+            ("[^\"]*", "[^\\x00-\\x1F\\x22\\x5C\\x7F]*"),
+            (".+@.+", "char+ \"@\" char+"),
+        ] {
+            assert_eq!(pattern_to_gbnf(pattern, "char").unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_json_escapes() {
+        for (pattern, expected) in [
+            ("a\"b", "\"a\" \"\\\\\\\"\" \"b\""),
+            ("\\\\", "\"\\\\\\\\\""),
+            ("[\"a-c]", "( [a-c] | \"\\\\\\\"\" )"),
+            (
+                "[ -~]",
+                "( [ -!#-\\x5B\\x5D-~] | \"\\\\\\\"\" | \"\\\\\\\\\" )",
+            ),
+            ("[^a-z]", "[^a-z\\x00-\\x1F\\x22\\x5C\\x7F]"),
+            ("\\s", "( [ ] | \"\\\\t\" )"),
+        ] {
+            assert_eq!(
+                pattern_to_gbnf(pattern, "char").unwrap(),
+                expected,
+                "{}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        for pattern in ["(abc", "[abc", "*a", "a{x}", "\\p{L}", "[z-a]"] {
+            assert!(pattern_to_gbnf(pattern, "char").is_err(), "{}", pattern);
+        }
+    }
+}
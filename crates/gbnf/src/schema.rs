@@ -0,0 +1,563 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    Error,
+    pattern::pattern_to_gbnf,
+    tool::{JSON_RULES, literal},
+};
+
+// Rules other than the shared JSON ones that generated rules may refer to.
+const EXTRA_RULES: &[(&str, &str)] = &[
+    (
+        "char",
+        r##"[^"\\\x7F\x00-\x1F] | "\\" ( ["\\bfnrt/] | "u" [0-9a-fA-F]{4} )"##,
+    ),
+    ("integer", r##""-"? ( "0" | [1-9] [0-9]{0,15} )"##),
+    ("unsigned", r##""0" | [1-9] [0-9]{0,15}"##),
+    ("boolean", r##""true" | "false""##),
+    (
+        "date",
+        r##"[0-9]{4} "-" ( "0" [1-9] | "1" [0-2] ) "-" ( "0" [1-9] | [1-2] [0-9] | "3" [0-1] )"##,
+    ),
+    (
+        "time",
+        r##"( [01] [0-9] | "2" [0-3] ) ":" [0-5] [0-9] ":" [0-5] [0-9] ( "." [0-9]{1,6} )? ( "Z" | [+-] ( [01] [0-9] | "2" [0-3] ) ":" [0-5] [0-9] )"##,
+    ),
+    (
+        "uuid",
+        r##"[0-9a-fA-F]{8} "-" [0-9a-fA-F]{4} "-" [0-9a-fA-F]{4} "-" [0-9a-fA-F]{4} "-" [0-9a-fA-F]{12}"##,
+    ),
+];
+
+/// Compiles a JSON Schema into a GBNF grammar whose `root` matches the JSON
+/// documents it describes.
+///
+/// Object properties come out in `propertyOrdering` order if the schema has
+/// one, otherwise in declaration order. Properties that aren't declared are
+/// never generated.
+pub(crate) fn compile(schema: &Value) -> Result<String, Error> {
+    let mut compiler = Compiler {
+        root: schema,
+        rules: Vec::new(),
+        refs: HashMap::new(),
+    };
+
+    let root = compiler.visit(schema, "root")?;
+    if root != "root" {
+        compiler.rules.insert(0, ("root".to_string(), root));
+    }
+
+    let mut out = compiler
+        .rules
+        .into_iter()
+        .map(|(name, body)| format!("{} ::= {}", name, body))
+        .collect::<Vec<_>>();
+    out.extend(
+        EXTRA_RULES
+            .iter()
+            .map(|(name, body)| format!("{} ::= {}", name, body)),
+    );
+    out.extend(JSON_RULES.iter().map(|rule| rule.to_string()));
+
+    Ok(out.join("\n"))
+}
+
+struct Compiler<'a> {
+    root: &'a Value,
+    rules: Vec<(String, String)>,
+    // Rule names of `$ref`s that exist or are being built, so recursive
+    // schemas end. Keyed on the full reference.
+    refs: HashMap<String, String>,
+}
+
+impl Compiler<'_> {
+    fn add_rule(&mut self, name: &str, body: String) -> String {
+        let mut candidate = name.to_string();
+        let mut suffix = 1;
+
+        loop {
+            match self.rules.iter().find(|(n, _)| *n == candidate) {
+                Some((_, existing)) if *existing == body => return candidate,
+                Some(_) => {
+                    candidate = format!("{}{}", name, suffix);
+                    suffix += 1;
+                }
+                None => break,
+            }
+        }
+
+        self.rules.push((candidate.clone(), body));
+        candidate
+    }
+
+    /// Returns the name of a rule matching `schema`, adding rules named after
+    /// `name` as needed.
+    fn visit(&mut self, schema: &Value, name: &str) -> Result<String, Error> {
+        let schema = match schema {
+            Value::Bool(true) => return Ok("value".to_string()),
+            Value::Object(schema) => schema,
+            _ => return Err(Error::InvalidSchema(name.to_string())),
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.visit_ref(reference);
+        }
+
+        if let Some(value) = schema.get("const") {
+            return Ok(self.add_rule(name, json_literal(value)));
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            let body = values.iter().map(json_literal).collect::<Vec<_>>();
+            return Ok(self.add_rule(name, body.join(" | ")));
+        }
+
+        for key in ["oneOf", "anyOf"] {
+            if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+                let alternatives = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, variant)| self.visit(variant, &format!("{}-{}", name, i)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(self.add_rule(name, alternatives.join(" | ")));
+            }
+        }
+
+        if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+            // schemars wraps a documented `$ref` in a single-element `allOf`.
+            return match schemas.as_slice() {
+                [only] => self.visit(only, name),
+                _ => Err(Error::Unsupported(
+                    name.to_string(),
+                    "allOf with more than one schema".to_string(),
+                )),
+            };
+        }
+
+        match schema.get("type") {
+            Some(Value::String(ty)) => self.visit_type(ty, schema, name),
+            Some(Value::Array(types)) => {
+                let alternatives = types
+                    .iter()
+                    .map(|ty| {
+                        let ty = ty
+                            .as_str()
+                            .ok_or_else(|| Error::InvalidSchema(name.to_string()))?;
+                        self.visit_type(ty, schema, &format!("{}-{}", name, ty))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(self.add_rule(name, alternatives.join(" | ")))
+            }
+            Some(_) => Err(Error::InvalidSchema(name.to_string())),
+            None if schema.contains_key("properties") => self.visit_type("object", schema, name),
+            None if schema.contains_key("items") => self.visit_type("array", schema, name),
+            None => Ok("value".to_string()),
+        }
+    }
+
+    fn visit_ref(&mut self, reference: &str) -> Result<String, Error> {
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| self.root.pointer(pointer))
+            .ok_or_else(|| Error::UnresolvedRef(reference.to_string()))?;
+
+        if let Some(name) = self.refs.get(reference) {
+            return Ok(name.clone());
+        }
+
+        // Distinct pointers can sanitize to the same name, e.g. `a_b` and `a-b`.
+        let base = format!("ref-{}", sanitize(reference.trim_start_matches(['#', '/'])));
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while self.refs.values().any(|n| *n == name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.refs.insert(reference.to_string(), name.clone());
+
+        let rule = self.visit(target, &name)?;
+        if rule != name {
+            self.rules.push((name.clone(), rule));
+        }
+        Ok(name)
+    }
+
+    fn visit_type(
+        &mut self,
+        ty: &str,
+        schema: &Map<String, Value>,
+        name: &str,
+    ) -> Result<String, Error> {
+        match ty {
+            "object" => self.visit_object(schema, name),
+            "array" => self.visit_array(schema, name),
+            "string" => self.visit_string(schema, name),
+            "integer" => {
+                let non_negative = schema
+                    .get("minimum")
+                    .and_then(Value::as_f64)
+                    .is_some_and(|min| min >= 0.0);
+                Ok(if non_negative { "unsigned" } else { "integer" }.to_string())
+            }
+            "number" => Ok("number".to_string()),
+            "boolean" => Ok("boolean".to_string()),
+            "null" => Ok(self.add_rule(name, "\"null\"".to_string())),
+            other => Err(Error::Unsupported(
+                name.to_string(),
+                format!("type '{}'", other),
+            )),
+        }
+    }
+
+    fn visit_object(&mut self, schema: &Map<String, Value>, name: &str) -> Result<String, Error> {
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return match schema.get("additionalProperties") {
+                Some(value @ Value::Object(_)) => {
+                    let value = self.visit(value, &format!("{}-value", name))?;
+                    let kv = format!("string ws \":\" ws {}", value);
+                    Ok(self.add_rule(
+                        name,
+                        format!("\"{{\" ws ( {kv} ws ( \",\" ws {kv} ws )* )? \"}}\""),
+                    ))
+                }
+                _ => Ok("object".to_string()),
+            };
+        };
+
+        let mut keys: Vec<&String> = properties.keys().collect();
+        if let Some(order) = schema.get("propertyOrdering").and_then(Value::as_array) {
+            let order: Vec<&str> = order.iter().filter_map(Value::as_str).collect();
+            keys.sort_by_key(|key| order.iter().position(|o| o == key).unwrap_or(usize::MAX));
+        }
+
+        let required: HashSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut props = Vec::with_capacity(keys.len());
+        for key in keys {
+            let value = self.visit(&properties[key], &format!("{}-{}", name, sanitize(key)))?;
+            let kv = format!(
+                "{} ws \":\" ws {}",
+                literal(&serde_json::to_string(key).unwrap_or_default()),
+                value
+            );
+            props.push((kv, required.contains(key.as_str())));
+        }
+
+        // The first property present has no leading comma, so there is one
+        // alternative per property that can come first: each optional one up
+        // to and including the first required one.
+        let tail = |from: usize| {
+            props[from..]
+                .iter()
+                .map(|(kv, required)| {
+                    if *required {
+                        format!("\",\" ws {}", kv)
+                    } else {
+                        format!("( \",\" ws {} )?", kv)
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut alternatives = Vec::new();
+        let mut nullable = true;
+        for (i, (kv, required)) in props.iter().enumerate() {
+            let mut seq = vec![kv.clone()];
+            seq.extend(tail(i + 1));
+            alternatives.push(seq.join(" "));
+
+            if *required {
+                nullable = false;
+                break;
+            }
+        }
+
+        let body = match (alternatives.is_empty(), nullable) {
+            (true, _) => "\"{\" ws \"}\"".to_string(),
+            (false, false) => format!("\"{{\" ws ( {} ) ws \"}}\"", alternatives.join(" | ")),
+            (false, true) => format!("\"{{\" ws ( {} )? ws \"}}\"", alternatives.join(" | ")),
+        };
+        Ok(self.add_rule(name, body))
+    }
+
+    fn visit_array(&mut self, schema: &Map<String, Value>, name: &str) -> Result<String, Error> {
+        // Draft-07 tuples, which is what schemars emits for them.
+        if let Some(items) = schema.get("items").and_then(Value::as_array) {
+            let items = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| self.visit(item, &format!("{}-{}", name, i)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let body = format!("\"[\" ws {} ws \"]\"", items.join(" \",\" ws "));
+            return Ok(self.add_rule(name, body));
+        }
+
+        let item = match schema.get("items") {
+            Some(item) => self.visit(item, &format!("{}-item", name))?,
+            None => "value".to_string(),
+        };
+
+        let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+        let max = schema.get("maxItems").and_then(Value::as_u64);
+
+        let rest = match (min.saturating_sub(1), max.map(|max| max.saturating_sub(1))) {
+            (0, None) => "*".to_string(),
+            (min, None) => format!("{{{},}}", min),
+            (min, Some(max)) => format!("{{{},{}}}", min, max),
+        };
+        let items = format!("{} ( \",\" ws {} ){}", item, item, rest);
+
+        let body = match (min, max) {
+            (_, Some(0)) => "\"[\" ws \"]\"".to_string(),
+            (0, _) => format!("\"[\" ws ( {} )? ws \"]\"", items),
+            _ => format!("\"[\" ws {} ws \"]\"", items),
+        };
+        Ok(self.add_rule(name, body))
+    }
+
+    fn visit_string(&mut self, schema: &Map<String, Value>, name: &str) -> Result<String, Error> {
+        let content = if let Some(format) = schema.get("format").and_then(Value::as_str) {
+            match format {
+                "date" => "date".to_string(),
+                "time" => "time".to_string(),
+                "date-time" => "date \"T\" time".to_string(),
+                "uuid" => "uuid".to_string(),
+                _ => "char*".to_string(),
+            }
+        } else if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            pattern_to_gbnf(pattern, "char")?
+        } else {
+            let min = schema.get("minLength").and_then(Value::as_u64);
+            let max = schema.get("maxLength").and_then(Value::as_u64);
+            match (min, max) {
+                (None, None) => return Ok("string".to_string()),
+                (min, None) => format!("char{{{},}}", min.unwrap_or(0)),
+                (min, Some(max)) => format!("char{{{},{}}}", min.unwrap_or(0), max),
+            }
+        };
+
+        Ok(self.add_rule(name, format!("\"\\\"\" {} \"\\\"\"", content)))
+    }
+}
+
+fn json_literal(value: &Value) -> String {
+    literal(&value.to_string())
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(schema: Value, cases: &[(Value, bool)]) {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        let grammar = compile(&schema).unwrap();
+
+        for (input, expected) in cases {
+            let input = input.to_string();
+            let result = gbnf.validate(&grammar, &input).unwrap();
+            assert_eq!(result, *expected, "failed: {}\n{}", input, grammar);
+        }
+    }
+
+    #[test]
+    fn test_object_properties() {
+        validate(
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "count": { "type": "integer", "minimum": 0 },
+                    "done": { "type": "boolean" }
+                },
+                "required": ["title", "done"],
+                "propertyOrdering": ["done", "title", "count"]
+            }),
+            &[
+                (
+                    serde_json::json!({ "done": true, "title": "a", "count": 2 }),
+                    true,
+                ),
+                (serde_json::json!({ "done": false, "title": "a" }), true),
+                (serde_json::json!({ "title": "a", "done": true }), false),
+                (
+                    serde_json::json!({ "done": true, "title": "a", "count": -1 }),
+                    false,
+                ),
+                (serde_json::json!({ "done": true }), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_optional_only_object() {
+        validate(
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "number" },
+                    "b": { "type": "number" }
+                }
+            }),
+            &[
+                (serde_json::json!({}), true),
+                (serde_json::json!({ "b": 1 }), true),
+                (serde_json::json!({ "a": 1, "b": 2.5 }), true),
+                (serde_json::json!({ "c": 1 }), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_arrays_enums_and_one_of() {
+        validate(
+            serde_json::json!({
+                "type": "array",
+                "items": {
+                    "oneOf": [
+                        { "enum": ["low", "high"] },
+                        { "type": "null" }
+                    ]
+                },
+                "minItems": 1,
+                "maxItems": 2
+            }),
+            &[
+                (serde_json::json!(["low"]), true),
+                (serde_json::json!(["high", null]), true),
+                (serde_json::json!([]), false),
+                (serde_json::json!(["low", "low", "low"]), false),
+                (serde_json::json!(["medium"]), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_string_formats_and_patterns() {
+        validate(
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "due": { "type": ["string", "null"], "format": "date" },
+                    "code": { "type": "string", "pattern": "^[A-Z]{3}-\\d+$" }
+                },
+                "required": ["due", "code"]
+            }),
+            &[
+                (
+                    serde_json::json!({ "due": "2025-03-14", "code": "ABC-12" }),
+                    true,
+                ),
+                (serde_json::json!({ "due": null, "code": "XYZ-1" }), true),
+                (
+                    serde_json::json!({ "due": "next week", "code": "ABC-12" }),
+                    false,
+                ),
+                (serde_json::json!({ "due": null, "code": "abc-12" }), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_refs() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Item {
+            /// Who owns it.
+            owner: Option<Owner>,
+            tags: Vec<String>,
+        }
+
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Owner {
+            name: String,
+        }
+
+        let schema = serde_json::to_value(schemars::schema_for!(Item)).unwrap();
+        validate(
+            schema,
+            &[
+                (
+                    serde_json::json!({ "owner": { "name": "Ann" }, "tags": [] }),
+                    true,
+                ),
+                (
+                    serde_json::json!({ "owner": null, "tags": ["x", "y"] }),
+                    true,
+                ),
+                (serde_json::json!({ "owner": "Ann", "tags": [] }), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_recursive_ref() {
+        let schema = serde_json::json!({
+            "$ref": "#/definitions/Node",
+            "definitions": {
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } }
+                    },
+                    "required": ["children"]
+                }
+            }
+        });
+
+        validate(
+            schema,
+            &[
+                (
+                    serde_json::json!({ "children": [{ "children": [] }] }),
+                    true,
+                ),
+                (serde_json::json!({ "children": [{}] }), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_refs_with_same_last_segment() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "a": { "$ref": "#/definitions/a/Item" },
+                "b": { "$ref": "#/definitions/b/Item" }
+            },
+            "required": ["a", "b"],
+            "definitions": {
+                "a": { "Item": { "type": "integer" } },
+                "b": { "Item": { "type": "boolean" } }
+            }
+        });
+
+        validate(
+            schema,
+            &[
+                (serde_json::json!({ "a": 1, "b": true }), true),
+                (serde_json::json!({ "a": 1, "b": 2 }), false),
+                (serde_json::json!({ "a": true, "b": true }), false),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(compile(&serde_json::json!({ "$ref": "#/definitions/Missing" })).is_err());
+        assert!(compile(&serde_json::json!({ "allOf": [{}, {}] })).is_err());
+        assert!(compile(&serde_json::json!({ "type": "string", "pattern": "(" })).is_err());
+    }
+}
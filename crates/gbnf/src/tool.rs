@@ -0,0 +1,102 @@
+// Hermes-style tool calls, which is what Qwen and most ChatML templates ask for:
+// <tool_call>
+// {"name": "greet", "arguments": {"text": "Hi"}}
+// </tool_call>
+pub const TOOL_CALL_TRIGGER: &str = "<tool_call>";
+
+// https://github.com/ggml-org/llama.cpp/blob/master/grammars/json.gbnf
+pub(crate) const JSON_RULES: &[&str] = &[
+    r##"value ::= object | array | string | number | ("true" | "false" | "null")"##,
+    r##"object ::= "{" ws ( string ws ":" ws value ws ( "," ws string ws ":" ws value ws )* )? "}""##,
+    r##"array ::= "[" ws ( value ws ( "," ws value ws )* )? "]""##,
+    r##"string ::= "\"" ( [^"\\\x7F\x00-\x1F] | "\\" ( ["\\bfnrt/] | "u" [0-9a-fA-F]{4} ) )* "\"""##,
+    r##"number ::= "-"? ( [0-9] | [1-9] [0-9]{0,15} ) ( "." [0-9]+ )? ( [eE] [-+]? [0-9]+ )?"##,
+    r##"ws ::= [ \t\n]{0,20}"##,
+];
+
+/// Grammar for one or more tool calls to any of `tool_names`, with the
+/// arguments being any JSON object.
+///
+/// Meant to be applied lazily from [`TOOL_CALL_TRIGGER`] on, so the model can
+/// still think and answer in plain text when it doesn't call a tool.
+pub fn build_tool_call_grammar(tool_names: &[String]) -> String {
+    let names = tool_names
+        .iter()
+        .map(|name| literal(&format!("\"{}\"", escape_json(name))))
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    let mut rules = vec![
+        r##"root ::= tool-call ( "\n" tool-call )*"##.to_string(),
+        format!(
+            r##"tool-call ::= {} "\n{{\"name\": " tool-name ", \"arguments\": " object "}}\n</tool_call>""##,
+            literal(TOOL_CALL_TRIGGER)
+        ),
+        format!("tool-name ::= {}", names),
+    ];
+    rules.extend(JSON_RULES.iter().map(|rule| rule.to_string()));
+
+    rules.join("\n")
+}
+
+fn escape_json(s: &str) -> String {
+    let quoted = serde_json::Value::String(s.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Quotes `s` as a GBNF string literal.
+pub(crate) fn literal(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(literal(r#"say "hi"\n"#), r#""say \"hi\"\\n""#);
+        assert_eq!(literal("a\nb"), r#""a\nb""#);
+    }
+
+    #[test]
+    fn test_tool_call_grammar() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        let grammar = build_tool_call_grammar(&["greet".to_string(), "search".to_string()]);
+
+        for (input, expected) in vec![
+            (
+                "<tool_call>\n{\"name\": \"greet\", \"arguments\": {\"text\": \"Hello!\"}}\n</tool_call>",
+                true,
+            ),
+            (
+                "<tool_call>\n{\"name\": \"search\", \"arguments\": {\"query\": \"budget\", \"limit\": 3}}\n</tool_call>\n<tool_call>\n{\"name\": \"greet\", \"arguments\": {}}\n</tool_call>",
+                true,
+            ),
+            (
+                "<tool_call>\n{\"name\": \"delete\", \"arguments\": {}}\n</tool_call>",
+                false,
+            ),
+            (
+                "<tool_call>\n{\"name\": \"greet\", \"arguments\": \"Hello!\"}\n</tool_call>",
+                false,
+            ),
+        ] {
+            let result = gbnf.validate(&grammar, input).unwrap();
+            assert_eq!(result, expected, "failed: {}", input);
+        }
+    }
+}
@@ -83,3 +83,17 @@ impl AsRef<str> for ChatTemplate {
         }
     }
 }
+
+// ChatML with Hermes-style tool calls, the same shape Qwen's own templates use.
+const CHATML_TEMPLATE: &str = include_str!("../assets/chatml.jinja");
+
+impl ChatTemplate {
+    /// Jinja source to render prompts with. Registry entries are only names
+    /// llama.cpp resolves internally, so they are rendered as ChatML.
+    pub fn jinja_source(&self) -> &str {
+        match self {
+            Self::TemplateKey(_) => CHATML_TEMPLATE,
+            Self::TemplateValue(v) => v,
+        }
+    }
+}
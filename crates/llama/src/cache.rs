@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use llama_cpp_2::token::LlamaToken;
+
+use crate::progress::ProgressData;
+
+/// A context kept alive after its task, with the tokens its KV cache holds.
+pub(crate) struct CachedContext<C> {
+    // Declared before `progress`: the context's eval callback points into it,
+    // so the context has to be dropped first.
+    pub(crate) ctx: C,
+    pub(crate) progress: Box<ProgressData>,
+    /// Tokens whose KV entries are in `ctx`, in position order.
+    pub(crate) tokens: Vec<LlamaToken>,
+    n_ctx: u32,
+    last_used: u64,
+}
+
+impl<C> CachedContext<C> {
+    pub(crate) fn new(ctx: C, progress: Box<ProgressData>, n_ctx: u32) -> Self {
+        Self {
+            ctx,
+            progress,
+            tokens: Vec::new(),
+            n_ctx,
+            last_used: 0,
+        }
+    }
+}
+
+/// Contexts keyed by a hash of the prompt prefix they were built for (e.g. the
+/// system prompt carrying a meeting transcript), so follow-up requests only
+/// have to evaluate what changed since the last one.
+pub(crate) struct PromptCache<C> {
+    entries: HashMap<u64, CachedContext<C>>,
+    budget_bytes: usize,
+    bytes_per_token: usize,
+    clock: u64,
+}
+
+impl<C> PromptCache<C> {
+    pub(crate) fn new(budget_bytes: usize, bytes_per_token: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            budget_bytes,
+            bytes_per_token: bytes_per_token.max(1),
+            clock: 0,
+        }
+    }
+
+    /// How many leading `tokens` the context cached under `key` already holds.
+    /// `None` if there is no context for `key` that fits `n_ctx` tokens; a
+    /// cached one that is too small is dropped.
+    pub(crate) fn lookup(&mut self, key: u64, tokens: &[LlamaToken], n_ctx: u32) -> Option<usize> {
+        let entry = self.entries.get(&key)?;
+
+        if entry.n_ctx < n_ctx {
+            self.entries.remove(&key);
+            return None;
+        }
+
+        Some(common_prefix_len(&entry.tokens, tokens))
+    }
+
+    /// Evicts least recently used contexts until one of `n_ctx` tokens fits
+    /// in the budget. Always leaves room for at least that one.
+    pub(crate) fn make_room(&mut self, n_ctx: u32) {
+        let needed = n_ctx as usize * self.bytes_per_token;
+
+        while !self.entries.is_empty() && self.used_bytes() + needed > self.budget_bytes {
+            let Some(&oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key)
+            else {
+                break;
+            };
+
+            tracing::debug!(key = oldest, "prompt_cache_evicted");
+            self.entries.remove(&oldest);
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: u64, entry: CachedContext<C>) {
+        self.entries.insert(key, entry);
+    }
+
+    pub(crate) fn get_mut(&mut self, key: u64) -> Option<&mut CachedContext<C>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(&key)?;
+        entry.last_used = self.clock;
+        Some(entry)
+    }
+
+    fn used_bytes(&self) -> usize {
+        self.entries
+            .values()
+            .map(|entry| entry.n_ctx as usize * self.bytes_per_token)
+            .sum()
+    }
+}
+
+fn common_prefix_len(a: &[LlamaToken], b: &[LlamaToken]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(ids: &[i32]) -> Vec<LlamaToken> {
+        ids.iter().copied().map(LlamaToken::new).collect()
+    }
+
+    fn entry(n_ctx: u32, ids: &[i32]) -> CachedContext<()> {
+        let mut entry = CachedContext::new((), ProgressData::new(), n_ctx);
+        entry.tokens = tokens(ids);
+        entry
+    }
+
+    #[test]
+    fn test_lookup_reports_shared_prefix() {
+        let mut cache = PromptCache::new(1000, 1);
+        cache.insert(1, entry(100, &[1, 2, 3, 4]));
+
+        assert_eq!(cache.lookup(1, &tokens(&[1, 2, 3, 9, 9]), 50), Some(3));
+        assert_eq!(cache.lookup(1, &tokens(&[7]), 50), Some(0));
+        assert_eq!(cache.lookup(2, &tokens(&[1, 2]), 50), None);
+    }
+
+    #[test]
+    fn test_lookup_drops_too_small_context() {
+        let mut cache = PromptCache::new(1000, 1);
+        cache.insert(1, entry(100, &[1, 2, 3]));
+
+        assert_eq!(cache.lookup(1, &tokens(&[1, 2, 3]), 200), None);
+        assert!(cache.get_mut(1).is_none());
+    }
+
+    #[test]
+    fn test_make_room_evicts_least_recently_used() {
+        let mut cache = PromptCache::new(300, 1);
+        cache.insert(1, entry(100, &[]));
+        cache.insert(2, entry(100, &[]));
+        cache.insert(3, entry(100, &[]));
+
+        cache.get_mut(1);
+        cache.get_mut(3);
+        cache.get_mut(2);
+        cache.get_mut(1);
+
+        cache.make_room(150);
+
+        assert!(cache.get_mut(3).is_none());
+        assert!(cache.get_mut(2).is_none());
+        assert!(cache.get_mut(1).is_some());
+    }
+
+    #[test]
+    fn test_make_room_always_fits_one() {
+        let mut cache = PromptCache::new(100, 1);
+        cache.insert(1, entry(100, &[]));
+
+        cache.make_room(500);
+
+        assert!(cache.get_mut(1).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+use llama_cpp_2::{
+    context::params::LlamaContextParams,
+    llama_batch::LlamaBatch,
+    model::{AddBos, LlamaModel},
+};
+
+use crate::Llama;
+
+// Embedding models are trained on short passages; longer input is cut off.
+const MAX_EMBEDDING_TOKENS: u32 = 512;
+
+struct EmbedTask {
+    texts: Vec<String>,
+    response_sender: tokio::sync::oneshot::Sender<Result<Vec<Vec<f32>>, crate::Error>>,
+}
+
+/// Sentence embeddings from a GGUF embedding model (bge, nomic-embed, ...).
+///
+/// Like [`Llama`], the model lives on a thread of its own and requests are
+/// handled one at a time.
+pub struct LlamaEmbedder {
+    dim: usize,
+    task_sender: tokio::sync::mpsc::UnboundedSender<EmbedTask>,
+}
+
+impl LlamaEmbedder {
+    pub fn new(model_path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
+        Llama::setup_log();
+
+        let backend = Llama::get_backend();
+        let model = Llama::load_model(model_path)?;
+        let dim = model.n_embd().max(0) as usize;
+
+        let (task_sender, mut task_receiver) = tokio::sync::mpsc::unbounded_channel::<EmbedTask>();
+
+        std::thread::spawn(move || {
+            let n_ctx = model.n_ctx_train().clamp(1, MAX_EMBEDDING_TOKENS);
+
+            // Non-causal models need the whole input in a single ubatch.
+            let ctx = model.new_context(
+                &backend,
+                LlamaContextParams::default()
+                    .with_n_ctx(std::num::NonZeroU32::new(n_ctx))
+                    .with_n_batch(n_ctx)
+                    .with_n_ubatch(n_ctx)
+                    .with_embeddings(true),
+            );
+
+            let mut ctx = match ctx {
+                Ok(ctx) => ctx,
+                Err(e) => {
+                    tracing::error!(error = ?e, "embedding_context_failed");
+                    return;
+                }
+            };
+
+            while let Some(task) = task_receiver.blocking_recv() {
+                let result = task
+                    .texts
+                    .iter()
+                    .map(|text| Self::embed_one(&model, &mut ctx, text, n_ctx))
+                    .collect();
+                let _ = task.response_sender.send(result);
+            }
+        });
+
+        Ok(Self { dim, task_sender })
+    }
+
+    fn embed_one(
+        model: &LlamaModel,
+        ctx: &mut llama_cpp_2::context::LlamaContext,
+        text: &str,
+        n_ctx: u32,
+    ) -> Result<Vec<f32>, crate::Error> {
+        let mut tokens = model.str_to_token(text, AddBos::Always)?;
+        tokens.truncate(n_ctx as usize);
+
+        let mut batch = LlamaBatch::new(n_ctx as usize, 1);
+        batch.add_sequence(&tokens, 0, false)?;
+
+        ctx.clear_kv_cache();
+        ctx.decode(&mut batch)?;
+
+        Ok(normalize(ctx.embeddings_seq_ith(0)?))
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// One unit-length vector per text, so a dot product is cosine similarity.
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, crate::Error> {
+        let (response_sender, response_receiver) = tokio::sync::oneshot::channel();
+
+        self.task_sender
+            .send(EmbedTask {
+                texts,
+                response_sender,
+            })
+            .map_err(|_| crate::Error::EmbedderStopped)?;
+
+        response_receiver
+            .await
+            .map_err(|_| crate::Error::EmbedderStopped)?
+    }
+}
+
+fn normalize(embedding: &[f32]) -> Vec<f32> {
+    let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return embedding.to_vec();
+    }
+    embedding.iter().map(|v| v / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(&[3.0, 4.0]), vec![0.6, 0.8]);
+        assert_eq!(normalize(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    #[ignore]
+    #[tokio::test]
+    async fn test_embed() {
+        let model_path = dirs::data_dir()
+            .unwrap()
+            .join("hyprnote")
+            .join("models/embedding/bge-small-en-v1.5.gguf");
+        let embedder = LlamaEmbedder::new(model_path).unwrap();
+
+        let vectors = embedder
+            .embed(vec![
+                "We agreed to raise prices next quarter.".into(),
+                "Pricing goes up in Q3.".into(),
+                "The office plants need watering.".into(),
+            ])
+            .await
+            .unwrap();
+
+        let similarity = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        assert_eq!(vectors[0].len(), embedder.dim());
+        assert!(similarity(&vectors[0], &vectors[1]) > similarity(&vectors[0], &vectors[2]));
+    }
+}
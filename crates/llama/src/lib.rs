@@ -1,9 +1,9 @@
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex, OnceLock};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, OnceLock};
 
 use llama_cpp_2::{
     LogOptions,
-    context::params::LlamaContextParams,
+    context::{LlamaContext, params::LlamaContextParams},
     llama_backend::LlamaBackend,
     llama_batch::LlamaBatch,
     model::{AddBos, LlamaModel, Special, params::LlamaModelParams},
@@ -15,16 +15,28 @@ use tokio_util::sync::CancellationToken;
 
 use hypr_gguf::GgufExt;
 
+mod cache;
+mod embedding;
 mod error;
 mod parser;
+mod progress;
 mod types;
 
+pub use embedding::LlamaEmbedder;
 pub use error::*;
 pub use parser::{Response, StreamingParser};
 pub use types::*;
 
-const DEFAULT_MAX_INPUT_TOKENS: u32 = 1024 * 16;
+use cache::{CachedContext, PromptCache};
+use progress::{ProgressData, cb_eval_fn};
+
+pub const DEFAULT_MAX_INPUT_TOKENS: u32 = 1024 * 16;
 const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 1024 * 2;
+// Room left in a new context for the next turn of the same conversation
+// (previous answer plus a follow-up question) so it can be reused.
+const FOLLOW_UP_HEADROOM_TOKENS: u32 = 1024 * 2;
+// KV memory all cached contexts may hold together.
+const DEFAULT_CACHE_BUDGET_BYTES: usize = 1024 * 1024 * 1024;
 
 static LLAMA_BACKEND: OnceLock<Arc<LlamaBackend>> = OnceLock::new();
 
@@ -48,15 +60,6 @@ pub enum Task {
     },
 }
 
-struct ProgressData {
-    total: usize,
-    processed: AtomicUsize,
-    enabled: AtomicBool,
-    callback: Mutex<Box<dyn FnMut(f64) + Send + 'static>>,
-    last_reported: Mutex<i32>,
-    cancellation_token: CancellationToken,
-}
-
 impl Llama {
     fn get_backend() -> Arc<LlamaBackend> {
         LLAMA_BACKEND
@@ -84,10 +87,10 @@ impl Llama {
         }
     }
 
-    fn get_sampler(model: &LlamaModel, grammar: Option<&str>) -> LlamaSampler {
+    fn get_sampler(model: &LlamaModel, request: &LlamaRequest) -> LlamaSampler {
         let mut samplers = Vec::new();
 
-        if let Some(grammar) = grammar {
+        if let Some(grammar) = request.grammar.as_deref() {
             if let Some(grammar_sampler) = LlamaSampler::grammar(model, grammar, "root") {
                 samplers.push(grammar_sampler);
             }
@@ -95,6 +98,25 @@ impl Llama {
             if cfg!(debug_assertions) {
                 println!("---\n{:?}\n---", grammar);
             }
+        } else if let Some(tools) = request.tools.as_ref().filter(|tools| !tools.is_empty()) {
+            let names = tools
+                .iter()
+                .map(|tool| tool.function.name.clone())
+                .collect::<Vec<_>>();
+            let grammar = hypr_gbnf::build_tool_call_grammar(&names);
+
+            // Lazy, so the model can still think and answer in plain text;
+            // only a tool call it starts has to be well-formed.
+            match LlamaSampler::grammar_lazy(
+                model,
+                &grammar,
+                "root",
+                [hypr_gbnf::TOOL_CALL_TRIGGER],
+                &[],
+            ) {
+                Some(grammar_sampler) => samplers.push(grammar_sampler),
+                None => tracing::warn!("tool_call_grammar_rejected"),
+            }
         }
 
         {
@@ -111,164 +133,166 @@ impl Llama {
         LlamaSampler::chain_simple(samplers)
     }
 
-    fn process_prefill<'a>(
+    fn render_prompt(template: &str, request: &LlamaRequest) -> String {
+        let mut env = minijinja::Environment::new();
+        env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
+
+        env.add_template("chat", template).unwrap();
+        env.get_template("chat")
+            .unwrap()
+            // https://huggingface.co/unsloth/Qwen3-1.7B/blob/main/chat_template.jinja
+            .render(serde_json::json!({
+                "messages": request.messages,
+                "tools": request.tools,
+                "add_generation_prompt": true,
+                "enable_thinking": true
+            }))
+            .unwrap()
+    }
+
+    /// Requests that share a first message (for chat, the system prompt with
+    /// the meeting in it) and tools land on the same cached context.
+    fn prefix_key(request: &LlamaRequest) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        if let Some(first) = request.messages.first() {
+            first.role.hash(&mut hasher);
+            first.content.hash(&mut hasher);
+        }
+        if let Some(tools) = &request.tools {
+            serde_json::to_string(tools)
+                .unwrap_or_default()
+                .hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn kv_bytes_per_token(model: &LlamaModel) -> usize {
+        // K and V in f16 for every layer. Uses the full embedding width, which
+        // overestimates for grouped-query attention; that only makes the
+        // budget conservative.
+        2 * model.n_layer() as usize * model.n_embd().max(0) as usize * 2
+    }
+
+    fn new_cached_context<'a>(
         model: &'a LlamaModel,
         backend: &LlamaBackend,
+        n_ctx: u32,
+    ) -> Result<CachedContext<LlamaContext<'a>>, crate::Error> {
+        let progress = ProgressData::new();
+
+        let ctx = model.new_context(
+            backend,
+            LlamaContextParams::default()
+                .with_n_ctx(std::num::NonZeroU32::new(n_ctx))
+                .with_n_batch(n_ctx)
+                .with_embeddings(false)
+                .with_swa_full(false)
+                // https://github.com/ggml-org/llama.cpp/blob/f505bd8/include/llama.h#L182
+                .with_flash_attention_policy(0)
+                .with_cb_eval_user_data(progress.as_user_data())
+                .with_cb_eval(Some(cb_eval_fn)),
+        )?;
+
+        Ok(CachedContext::new(ctx, progress, n_ctx))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn process_prefill<'a, 'c>(
+        model: &'a LlamaModel,
+        backend: &LlamaBackend,
+        cache: &'c mut PromptCache<LlamaContext<'a>>,
         template: &str,
         request: &LlamaRequest,
         callback: Box<dyn FnMut(f64) + Send + 'static>,
         cancellation_token: CancellationToken,
-    ) -> Result<
-        (
-            llama_cpp_2::context::LlamaContext<'a>,
-            LlamaBatch,
-            i32,
-            *mut std::ffi::c_void,
-            u32,
-        ),
-        crate::Error,
-    > {
-        let prompt = {
-            let mut env = minijinja::Environment::new();
-            env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
-
-            env.add_template("chat", template).unwrap();
-            env.get_template("chat")
-                .unwrap()
-                // https://huggingface.co/unsloth/Qwen3-1.7B/blob/main/chat_template.jinja
-                .render(serde_json::json!({
-                    "messages": request.messages,
-                    "tools": request.tools,
-                    "add_generation_prompt": true,
-                    "enable_thinking": true
-                }))
-                .unwrap()
-        };
+    ) -> Result<(&'c mut CachedContext<LlamaContext<'a>>, LlamaBatch, u32), crate::Error> {
+        let prompt = Self::render_prompt(template, request);
 
-        let mut tokens_list = model.str_to_token(&prompt, AddBos::Always).unwrap();
-        tokens_list.truncate(DEFAULT_MAX_INPUT_TOKENS as usize);
+        let mut tokens_list = model.str_to_token(&prompt, AddBos::Always)?;
+        truncate_middle(&mut tokens_list, DEFAULT_MAX_INPUT_TOKENS as usize);
         let input_tokens_len = tokens_list.len() as u32;
         let max_output_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS);
 
-        let progress_data = Box::new(ProgressData {
-            total: input_tokens_len as usize,
-            processed: AtomicUsize::new(0),
-            enabled: AtomicBool::new(true),
-            callback: Mutex::new(callback),
-            last_reported: Mutex::new(-1),
-            cancellation_token,
-        });
-        let progress_data_ptr = Box::into_raw(progress_data) as *mut std::ffi::c_void;
-
-        extern "C" fn cb_eval_fn(
-            _t: *mut llama_cpp_sys_2::ggml_tensor,
-            _ask: bool,
-            user_data: *mut std::ffi::c_void,
-        ) -> bool {
-            if user_data.is_null() {
-                return false;
-            }
-
-            unsafe {
-                let progress_data = &*(user_data as *mut ProgressData);
-
-                if progress_data.cancellation_token.is_cancelled() {
-                    return true;
-                }
-
-                if progress_data.enabled.load(Ordering::Relaxed) {
-                    let count = progress_data.processed.fetch_add(1, Ordering::Relaxed) + 1;
-
-                    let mut progress = (count as f64) / ((progress_data.total * 2) as f64);
-                    if progress > 1.0 {
-                        progress = 1.0;
-                    }
-
-                    let rounded_progress_int = (progress * 100.0).round() as i32;
-
-                    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        if let Ok(mut last_reported) = progress_data.last_reported.lock()
-                            && *last_reported != rounded_progress_int
-                        {
-                            *last_reported = rounded_progress_int;
-                            let rounded_progress = rounded_progress_int as f64 / 100.0;
+        let key = Self::prefix_key(request);
+        let n_ctx_needed = input_tokens_len + max_output_tokens;
 
-                            if let Ok(mut cb) = progress_data.callback.lock() {
-                                (cb)(rounded_progress);
-                            }
-                        }
-                    }));
-                }
-            }
-
-            false
+        let reusable = cache.lookup(key, &tokens_list, n_ctx_needed);
+        if reusable.is_none() {
+            let n_ctx = (n_ctx_needed + FOLLOW_UP_HEADROOM_TOKENS)
+                .min(DEFAULT_MAX_INPUT_TOKENS + DEFAULT_MAX_OUTPUT_TOKENS)
+                .max(n_ctx_needed);
+            cache.make_room(n_ctx);
+            cache.insert(key, Self::new_cached_context(model, backend, n_ctx)?);
         }
 
-        let mut ctx = model
-            .new_context(
-                backend,
-                LlamaContextParams::default()
-                    .with_n_ctx(std::num::NonZeroU32::new(
-                        input_tokens_len + max_output_tokens,
-                    ))
-                    .with_n_batch(input_tokens_len)
-                    .with_embeddings(false)
-                    .with_swa_full(false)
-                    // https://github.com/ggml-org/llama.cpp/blob/f505bd8/include/llama.h#L182
-                    .with_flash_attention_policy(0)
-                    .with_cb_eval_user_data(progress_data_ptr)
-                    .with_cb_eval(Some(cb_eval_fn)),
-            )
-            .unwrap();
-
-        let batch_size = tokens_list.len().max(512);
-        let mut batch = LlamaBatch::new(batch_size, 1);
-
-        let last_index = (tokens_list.len() - 1) as i32;
-        for (i, token) in (0_i32..).zip(tokens_list.into_iter()) {
-            let is_last = i == last_index;
-            batch.add(token, i, &[0], is_last).unwrap();
+        let entry = cache.get_mut(key).expect("context was just cached");
+
+        // At least the last prompt token has to be decoded to get logits.
+        let mut reused = reusable
+            .unwrap_or(0)
+            .min(tokens_list.len().saturating_sub(1));
+        if reused > 0
+            && !entry
+                .ctx
+                .clear_kv_cache_seq(Some(0), Some(reused as u32), None)
+                .unwrap_or(false)
+        {
+            reused = 0;
+        }
+        if reused == 0 {
+            entry.ctx.clear_kv_cache();
+        }
+        entry.tokens.truncate(reused);
+
+        tracing::info!(
+            input_tokens = input_tokens_len,
+            reused_tokens = reused,
+            "prompt_prefill"
+        );
+
+        let pending = &tokens_list[reused..];
+        entry
+            .progress
+            .start(pending.len(), callback, cancellation_token);
+
+        let mut batch = LlamaBatch::new(pending.len().max(512), 1);
+        let last_index = pending.len() - 1;
+        for (i, token) in pending.iter().enumerate() {
+            batch.add(*token, (reused + i) as i32, &[0], i == last_index)?;
         }
 
-        ctx.decode(&mut batch).unwrap();
-
-        unsafe {
-            let progress_data = &*(progress_data_ptr as *mut ProgressData);
-            progress_data.enabled.store(false, Ordering::Relaxed);
-
-            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                if let Ok(mut cb) = progress_data.callback.lock() {
-                    (cb)(1.0);
-                }
-            }));
+        if let Err(e) = entry.ctx.decode(&mut batch) {
+            entry.tokens.clear();
+            entry.progress.release();
+            return Err(e.into());
         }
 
-        Ok((ctx, batch, last_index, progress_data_ptr, max_output_tokens))
+        entry.tokens.extend_from_slice(pending);
+        entry.progress.finish();
+
+        Ok((entry, batch, max_output_tokens))
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn process_generation<'a>(
+    fn process_generation(
         model: &LlamaModel,
-        mut ctx: llama_cpp_2::context::LlamaContext<'a>,
+        entry: &mut CachedContext<LlamaContext<'_>>,
         mut batch: LlamaBatch,
-        last_index: i32,
         request: &LlamaRequest,
         response_sender: tokio::sync::mpsc::UnboundedSender<Response>,
-        progress_data_ptr: *mut std::ffi::c_void,
         cancellation_token: CancellationToken,
         max_output_tokens: u32,
     ) {
-        let mut n_cur = batch.n_tokens();
+        let prompt_len = entry.tokens.len();
         let mut decoder = encoding_rs::UTF_8.new_decoder();
-        let mut sampler = Self::get_sampler(model, request.grammar.as_deref());
+        let mut sampler = Self::get_sampler(model, request);
         let mut parser = StreamingParser::new();
 
-        'generation: while n_cur <= last_index + max_output_tokens as i32 {
+        'generation: while entry.tokens.len() < prompt_len + max_output_tokens as usize {
             if cancellation_token.is_cancelled() || response_sender.is_closed() {
                 break;
             }
 
-            let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+            let token = sampler.sample(&entry.ctx, batch.n_tokens() - 1);
 
             if model.is_eog_token(token) {
                 break;
@@ -292,20 +316,29 @@ impl Llama {
             }
 
             batch.clear();
-            batch.add(token, n_cur, &[0], true).unwrap();
+            batch
+                .add(token, entry.tokens.len() as i32, &[0], true)
+                .unwrap();
 
-            n_cur += 1;
             if cancellation_token.is_cancelled() || response_sender.is_closed() {
                 break;
             }
-            ctx.decode(&mut batch).unwrap();
+            if let Err(e) = entry.ctx.decode(&mut batch) {
+                tracing::error!("Decode failed: {:?}", e);
+                entry.tokens.clear();
+                break;
+            }
+            entry.tokens.push(token);
         }
 
-        drop(response_sender);
-
-        unsafe {
-            let _ = Box::from_raw(progress_data_ptr as *mut ProgressData);
+        for response in parser.finish() {
+            if response_sender.send(response).is_err() {
+                break;
+            }
         }
+
+        drop(response_sender);
+        entry.progress.release();
     }
 
     fn setup_log() {
@@ -329,6 +362,9 @@ impl Llama {
 
         std::thread::spawn({
             move || {
+                let mut cache =
+                    PromptCache::new(DEFAULT_CACHE_BUDGET_BYTES, Self::kv_bytes_per_token(&model));
+
                 while let Some(task) = task_receiver.blocking_recv() {
                     match task {
                         Task::Generate {
@@ -340,26 +376,19 @@ impl Llama {
                             match Self::process_prefill(
                                 &model,
                                 &backend,
-                                template.as_ref(),
+                                &mut cache,
+                                template.jinja_source(),
                                 &request,
                                 callback,
                                 cancellation_token.clone(),
                             ) {
-                                Ok((
-                                    ctx,
-                                    batch,
-                                    last_index,
-                                    progress_data_ptr,
-                                    max_output_tokens,
-                                )) => {
+                                Ok((entry, batch, max_output_tokens)) => {
                                     Self::process_generation(
                                         &model,
-                                        ctx,
+                                        entry,
                                         batch,
-                                        last_index,
                                         &request,
                                         response_sender,
-                                        progress_data_ptr,
                                         cancellation_token,
                                         max_output_tokens,
                                     );
@@ -417,6 +446,27 @@ impl Llama {
     }
 }
 
+/// Callers are expected to keep prompts within [`DEFAULT_MAX_INPUT_TOKENS`]
+/// (e.g. by summarizing long transcripts in chunks). If one still doesn't fit,
+/// drop the middle: the head carries the instructions and the tail the
+/// generation prompt, and losing either derails the model.
+fn truncate_middle<T>(tokens: &mut Vec<T>, max: usize) {
+    if tokens.len() <= max {
+        return;
+    }
+
+    tracing::warn!(
+        input_tokens = tokens.len(),
+        max_input_tokens = max,
+        "prompt_truncated"
+    );
+
+    let tail = max / 4;
+    let head = max - tail;
+    let tail_start = tokens.len() - tail;
+    tokens.drain(head..tail_start);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -451,21 +501,90 @@ mod tests {
 
     fn get_request() -> LlamaRequest {
         LlamaRequest {
-            grammar: Some(hypr_gbnf::Grammar::Enhance { sections: None }.build()),
+            grammar: Some(
+                hypr_gbnf::Grammar::Enhance { sections: None }
+                    .build()
+                    .unwrap(),
+            ),
             messages: vec![
                 LlamaMessage {
                     role: "system".into(),
                     content: "Summarize the text the user gives you.".into(),
+                    ..Default::default()
                 },
                 LlamaMessage {
                     role: "user".into(),
                     content: hypr_data::english_3::WORDS_JSON.repeat(1),
+                    ..Default::default()
                 },
             ],
             ..Default::default()
         }
     }
 
+    #[test]
+    fn test_truncate_middle() {
+        let mut tokens: Vec<u32> = (0..20).collect();
+        truncate_middle(&mut tokens, 8);
+        assert_eq!(tokens, vec![0, 1, 2, 3, 4, 5, 18, 19]);
+
+        let mut tokens: Vec<u32> = (0..4).collect();
+        truncate_middle(&mut tokens, 8);
+        assert_eq!(tokens, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_render_tool_round_trip() {
+        let template = hypr_gguf::ChatTemplate::TemplateKey(hypr_gguf::LlamaCppRegistry::ChatML);
+        let request = LlamaRequest {
+            messages: vec![
+                LlamaMessage {
+                    role: "user".into(),
+                    content: "Say hi".into(),
+                    ..Default::default()
+                },
+                LlamaMessage {
+                    role: "assistant".into(),
+                    content: "".into(),
+                    tool_calls: vec![LlamaToolCall {
+                        id: "call_1".into(),
+                        r#type: "function".into(),
+                        function: LlamaFunctionCall {
+                            name: "greet".into(),
+                            arguments: serde_json::json!({ "text": "Hi" }),
+                        },
+                    }],
+                    tool_call_id: None,
+                },
+                LlamaMessage {
+                    role: "tool".into(),
+                    content: "done".into(),
+                    tool_call_id: Some("call_1".into()),
+                    ..Default::default()
+                },
+            ],
+            tools: Some(vec![async_openai::types::ChatCompletionTool {
+                r#type: async_openai::types::ChatCompletionToolType::Function,
+                function: async_openai::types::FunctionObject {
+                    name: "greet".into(),
+                    description: None,
+                    strict: None,
+                    parameters: None,
+                },
+            }]),
+            ..Default::default()
+        };
+
+        let prompt = Llama::render_prompt(template.jinja_source(), &request);
+
+        assert!(prompt.contains("<tools>\n{") && prompt.contains(r#""name":"greet""#));
+        assert!(prompt.contains(
+            "<|im_start|>assistant\n<tool_call>\n{\"name\": \"greet\", \"arguments\": {\"text\":\"Hi\"}}\n</tool_call><|im_end|>"
+        ));
+        assert!(prompt.contains("<tool_response>\ndone\n</tool_response>"));
+        assert!(prompt.ends_with("<|im_start|>assistant\n"));
+    }
+
     // cargo test test_tool -p llama -- --nocapture --ignored
     #[ignore]
     #[tokio::test]
@@ -475,6 +594,7 @@ mod tests {
             messages: vec![LlamaMessage {
                 role: "user".into(),
                 content: "response, and say hi".into(),
+                ..Default::default()
             }],
             tools: Some(vec![
                 async_openai::types::ChatCompletionTool {
@@ -522,10 +642,12 @@ mod tests {
                 LlamaMessage {
                     role: "system".into(),
                     content: "You are helpful assistamt.".into(),
+                    ..Default::default()
                 },
                 LlamaMessage {
                     role: "user".into(),
                     content: "hello".into(),
+                    ..Default::default()
                 },
             ],
             max_tokens: Some(5),
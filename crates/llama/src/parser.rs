@@ -19,6 +19,9 @@ pub enum Response {
 
 pub struct StreamingParser {
     buffer: String,
+    // Whitespace after a block belongs to it, even when it arrives in a
+    // later chunk.
+    after_block: bool,
 }
 
 impl Default for StreamingParser {
@@ -27,10 +30,14 @@ impl Default for StreamingParser {
     }
 }
 
+const THINK_START: &str = "<think>";
+const TOOL_CALL_START: &str = "<tool_call>";
+
 impl StreamingParser {
     pub fn new() -> Self {
         Self {
             buffer: String::new(),
+            after_block: false,
         }
     }
 
@@ -43,26 +50,61 @@ impl StreamingParser {
         }
 
         if !self.buffer.is_empty() && !self.looks_like_block_start() {
-            let text = self.buffer.clone();
-            self.buffer.clear();
-            responses.push(Response::TextDelta(text));
+            // A tool call tag can arrive split over several tokens, so hold
+            // back a tail that could still turn into one.
+            let split = self.buffer.len() - partial_tag_len(&self.buffer);
+            if split > 0 {
+                let text = self.buffer[..split].to_string();
+                self.buffer.drain(..split);
+                responses.push(Response::TextDelta(text));
+            }
+        }
+
+        responses
+    }
+
+    /// Flushes what is still buffered once generation has stopped, e.g. a
+    /// block cut off by the token limit.
+    pub fn finish(&mut self) -> Vec<Response> {
+        let mut responses = self.process_chunk("");
+
+        let rest = std::mem::take(&mut self.buffer);
+        if let Some(reasoning) = rest.trim_start().strip_prefix(THINK_START) {
+            responses.push(Response::Reasoning(reasoning.trim().to_string()));
+        } else if !rest.is_empty() {
+            responses.push(Response::TextDelta(rest));
         }
 
         responses
     }
 
     fn try_parse_next(&mut self) -> Option<Response> {
+        if self.after_block {
+            let trimmed = self.buffer.trim_start();
+            self.after_block = trimmed.is_empty();
+            self.buffer = trimmed.to_string();
+        }
+
         if let Ok((remaining, content)) = parse_think_block(&self.buffer) {
             self.buffer = remaining.to_string();
+            self.after_block = true;
             return Some(Response::Reasoning(content));
         }
 
-        if let Ok((remaining, (name, arguments))) = parse_tool_call_block(&self.buffer) {
-            let arguments: HashMap<String, serde_json::Value> =
-                serde_json::from_str(&arguments).unwrap_or_default();
+        if let Ok((remaining, json)) = parse_tool_call_block(&self.buffer) {
+            let response = match parse_tool_call(json) {
+                Some((name, arguments)) => Response::ToolCall { name, arguments },
+                None => {
+                    tracing::warn!(json, "invalid_tool_call");
+                    Response::TextDelta(
+                        self.buffer[..self.buffer.len() - remaining.len()].to_string(),
+                    )
+                }
+            };
 
             self.buffer = remaining.to_string();
-            return Some(Response::ToolCall { name, arguments });
+            self.after_block = true;
+            return Some(response);
         }
 
         if let Some(pos) = self.find_next_block_start()
@@ -77,13 +119,13 @@ impl StreamingParser {
     }
 
     fn looks_like_block_start(&self) -> bool {
-        self.buffer.trim_start().starts_with("<think>")
-            || self.buffer.trim_start().starts_with("<tool_call>")
+        self.buffer.trim_start().starts_with(THINK_START)
+            || self.buffer.trim_start().starts_with(TOOL_CALL_START)
     }
 
     fn find_next_block_start(&self) -> Option<usize> {
-        let think_pos = self.buffer.find("<think>");
-        let tool_pos = self.buffer.find("<tool_call>");
+        let think_pos = self.buffer.find(THINK_START);
+        let tool_pos = self.buffer.find(TOOL_CALL_START);
 
         match (think_pos, tool_pos) {
             (Some(t), Some(tc)) => Some(t.min(tc)),
@@ -94,6 +136,15 @@ impl StreamingParser {
     }
 }
 
+/// Length of the longest suffix of `buffer` that is the start of a tool call
+/// tag. Think tags are left alone and stream as text when split.
+fn partial_tag_len(buffer: &str) -> usize {
+    (1..TOOL_CALL_START.len())
+        .rev()
+        .find(|&len| buffer.ends_with(&TOOL_CALL_START[..len]))
+        .unwrap_or(0)
+}
+
 fn parse_think_block(input: &str) -> IResult<&str, String> {
     let mut parser = map(
         terminated(
@@ -105,25 +156,29 @@ fn parse_think_block(input: &str) -> IResult<&str, String> {
     parser.parse(input)
 }
 
-fn parse_tool_call_block(input: &str) -> IResult<&str, (String, String)> {
-    let (input, _) = tag("<tool_call>")(input)?;
+fn parse_tool_call_block(input: &str) -> IResult<&str, &str> {
     let (input, _) = multispace0(input)?;
+    let (input, _) = tag("<tool_call>")(input)?;
     let (input, json_content) = take_until("</tool_call>")(input)?;
     let (input, _) = tag("</tool_call>")(input)?;
     let (input, _) = multispace0(input)?;
 
-    let json_trimmed = json_content.trim();
-    let parsed: serde_json::Value = serde_json::from_str(json_trimmed)
-        .map_err(|_| nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)))?;
+    Ok((input, json_content.trim()))
+}
 
-    let name = parsed["name"]
-        .as_str()
-        .ok_or_else(|| nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)))?
-        .to_string();
+fn parse_tool_call(json: &str) -> Option<(String, HashMap<String, serde_json::Value>)> {
+    let parsed: serde_json::Value = serde_json::from_str(json).ok()?;
+    let name = parsed["name"].as_str()?.to_string();
 
-    let arguments = parsed["arguments"].to_string();
+    // Some models emit the arguments as a JSON-encoded string.
+    let arguments = match &parsed["arguments"] {
+        serde_json::Value::Object(map) => map.clone().into_iter().collect(),
+        serde_json::Value::String(s) => serde_json::from_str(s).ok()?,
+        serde_json::Value::Null => HashMap::new(),
+        _ => return None,
+    };
 
-    Ok((input, (name, arguments)))
+    Some((name, arguments))
 }
 
 #[cfg(test)]
@@ -231,6 +286,127 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tool_call_with_split_tags() {
+        let mut parser = StreamingParser::new();
+
+        let items = {
+            let mut items = vec![];
+            items.extend(parser.process_chunk("Let me check. <"));
+            items.extend(parser.process_chunk("tool_"));
+            items.extend(parser.process_chunk("call>\n"));
+            items.extend(
+                parser.process_chunk(r#"{"name": "search", "arguments": "{\"q\": \"budget\"}"}"#),
+            );
+            items.extend(parser.process_chunk("\n</tool_call>\n<tool_call>\n"));
+            items.extend(parser.process_chunk(r#"{"name": "greet", "arguments": {}}"#));
+            items.extend(parser.process_chunk("\n</tool_call>"));
+            items.extend(parser.finish());
+            items
+        };
+
+        assert_eq!(
+            items,
+            [
+                Response::TextDelta("Let me check. ".to_string()),
+                Response::ToolCall {
+                    name: "search".to_string(),
+                    arguments: HashMap::from([(
+                        "q".to_string(),
+                        serde_json::Value::String("budget".to_string())
+                    )])
+                },
+                Response::ToolCall {
+                    name: "greet".to_string(),
+                    arguments: HashMap::new()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_tool_call_is_text() {
+        let mut parser = StreamingParser::new();
+
+        let items = {
+            let mut items = vec![];
+            items.extend(parser.process_chunk("<tool_call>\n{\"name\": "));
+            items.extend(parser.process_chunk("\n</tool_call>"));
+            items
+        };
+
+        assert_eq!(
+            items,
+            [Response::TextDelta(
+                "<tool_call>\n{\"name\": \n</tool_call>".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_finish_flushes_unclosed_blocks() {
+        let mut parser = StreamingParser::new();
+        assert_eq!(parser.process_chunk("<think>\nStill thinking"), []);
+        assert_eq!(
+            parser.finish(),
+            [Response::Reasoning("Still thinking".to_string())]
+        );
+
+        let mut parser = StreamingParser::new();
+        assert_eq!(
+            parser.process_chunk("a < b <"),
+            [Response::TextDelta("a < b ".to_string())]
+        );
+        assert_eq!(parser.finish(), [Response::TextDelta("<".to_string())]);
+    }
+
+    #[test]
+    fn test_tool_call_in_random_chunks() {
+        use rand::Rng;
+
+        let text = r#"Let me look that up.
+<tool_call>
+{"name": "search", "arguments": {"q": "budget"}}
+</tool_call>"#;
+
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let chars: Vec<char> = text.chars().collect();
+            let mut parser = StreamingParser::new();
+            let mut items = vec![];
+
+            let mut pos = 0;
+            while pos < chars.len() {
+                let end = std::cmp::min(pos + rng.random_range(1..8), chars.len());
+                let chunk: String = chars[pos..end].iter().collect();
+                items.extend(parser.process_chunk(&chunk));
+                pos = end;
+            }
+            items.extend(parser.finish());
+
+            let (tool_call, text_deltas) = items.split_last().unwrap();
+            assert_eq!(
+                *tool_call,
+                Response::ToolCall {
+                    name: "search".to_string(),
+                    arguments: HashMap::from([(
+                        "q".to_string(),
+                        serde_json::Value::String("budget".to_string())
+                    )])
+                }
+            );
+
+            let restored = text_deltas
+                .iter()
+                .map(|item| match item {
+                    Response::TextDelta(text) => text.clone(),
+                    other => panic!("unexpected {:?}", other),
+                })
+                .collect::<String>();
+            assert_eq!(restored, "Let me look that up.\n");
+        }
+    }
+
     #[test]
     fn test_summary() {
         let reasoning = r###"
@@ -0,0 +1,126 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use tokio_util::sync::CancellationToken;
+
+type Callback = Box<dyn FnMut(f64) + Send + 'static>;
+
+/// Prefill progress for the task currently running on a context. Lives as
+/// long as the context, whose eval callback holds a pointer to it, and is
+/// re-armed for every task.
+pub(crate) struct ProgressData {
+    total: AtomicUsize,
+    processed: AtomicUsize,
+    enabled: AtomicBool,
+    callback: Mutex<Callback>,
+    last_reported: Mutex<i32>,
+    cancellation_token: Mutex<CancellationToken>,
+}
+
+impl ProgressData {
+    pub(crate) fn new() -> Box<Self> {
+        Box::new(Self {
+            total: AtomicUsize::new(0),
+            processed: AtomicUsize::new(0),
+            enabled: AtomicBool::new(false),
+            callback: Mutex::new(Box::new(|_| {})),
+            last_reported: Mutex::new(-1),
+            cancellation_token: Mutex::new(CancellationToken::new()),
+        })
+    }
+
+    pub(crate) fn as_user_data(&self) -> *mut std::ffi::c_void {
+        self as *const Self as *mut std::ffi::c_void
+    }
+
+    pub(crate) fn start(
+        &self,
+        total: usize,
+        callback: Callback,
+        cancellation_token: CancellationToken,
+    ) {
+        self.total.store(total.max(1), Ordering::Relaxed);
+        self.processed.store(0, Ordering::Relaxed);
+        if let Ok(mut cb) = self.callback.lock() {
+            *cb = callback;
+        }
+        if let Ok(mut last_reported) = self.last_reported.lock() {
+            *last_reported = -1;
+        }
+        if let Ok(mut token) = self.cancellation_token.lock() {
+            *token = cancellation_token;
+        }
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn finish(&self) {
+        self.enabled.store(false, Ordering::Relaxed);
+
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if let Ok(mut cb) = self.callback.lock() {
+                (cb)(1.0);
+            }
+        }));
+    }
+
+    /// Drops the task's callback so whatever it captured is released while the
+    /// context stays cached.
+    pub(crate) fn release(&self) {
+        self.enabled.store(false, Ordering::Relaxed);
+        if let Ok(mut cb) = self.callback.lock() {
+            *cb = Box::new(|_| {});
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation_token
+            .lock()
+            .map(|token| token.is_cancelled())
+            .unwrap_or(false)
+    }
+}
+
+pub(crate) extern "C" fn cb_eval_fn(
+    _t: *mut llama_cpp_sys_2::ggml_tensor,
+    _ask: bool,
+    user_data: *mut std::ffi::c_void,
+) -> bool {
+    if user_data.is_null() {
+        return false;
+    }
+
+    unsafe {
+        let progress_data = &*(user_data as *const ProgressData);
+
+        if progress_data.is_cancelled() {
+            return true;
+        }
+
+        if progress_data.enabled.load(Ordering::Relaxed) {
+            let count = progress_data.processed.fetch_add(1, Ordering::Relaxed) + 1;
+            let total = progress_data.total.load(Ordering::Relaxed);
+
+            let mut progress = (count as f64) / ((total * 2) as f64);
+            if progress > 1.0 {
+                progress = 1.0;
+            }
+
+            let rounded_progress_int = (progress * 100.0).round() as i32;
+
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                if let Ok(mut last_reported) = progress_data.last_reported.lock()
+                    && *last_reported != rounded_progress_int
+                {
+                    *last_reported = rounded_progress_int;
+                    let rounded_progress = rounded_progress_int as f64 / 100.0;
+
+                    if let Ok(mut cb) = progress_data.callback.lock() {
+                        (cb)(rounded_progress);
+                    }
+                }
+            }));
+        }
+    }
+
+    false
+}
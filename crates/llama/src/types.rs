@@ -1,7 +1,10 @@
 use async_openai::types::{
-    ChatCompletionRequestAssistantMessageContent, ChatCompletionRequestMessage,
-    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestToolMessageContent,
-    ChatCompletionRequestUserMessageContent, ChatCompletionTool,
+    ChatCompletionRequestAssistantMessageContent, ChatCompletionRequestAssistantMessageContentPart,
+    ChatCompletionRequestDeveloperMessageContent, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestSystemMessageContentPart,
+    ChatCompletionRequestToolMessageContent, ChatCompletionRequestToolMessageContentPart,
+    ChatCompletionRequestUserMessageContent, ChatCompletionRequestUserMessageContentPart,
+    ChatCompletionTool,
 };
 
 pub use llama_cpp_2::model::LlamaChatMessage;
@@ -14,94 +17,207 @@ pub struct LlamaRequest {
     pub max_tokens: Option<u32>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct LlamaMessage {
     pub role: String,
     pub content: String,
+    /// Calls an earlier assistant turn made, rendered back by the chat template.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<LlamaToolCall>,
+    /// For `role: tool`, the call this message is the result of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+// Same shape as OpenAI's, which is what chat templates expect.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LlamaToolCall {
+    pub id: String,
+    pub r#type: String,
+    pub function: LlamaFunctionCall,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LlamaFunctionCall {
+    pub name: String,
+    /// Parsed back into JSON so templates can `tojson` it; kept as a string if
+    /// the client sent something that isn't valid JSON.
+    pub arguments: serde_json::Value,
 }
 
 pub trait FromOpenAI {
     fn from_openai(message: &ChatCompletionRequestMessage) -> Self;
 }
 
+fn join_text(parts: impl Iterator<Item = Option<String>>) -> String {
+    parts.flatten().collect::<Vec<_>>().join("\n")
+}
+
 impl FromOpenAI for LlamaMessage {
     fn from_openai(message: &ChatCompletionRequestMessage) -> Self {
         match message {
             ChatCompletionRequestMessage::System(system) => {
                 let content = match &system.content {
-                    ChatCompletionRequestSystemMessageContent::Text(text) => text,
-                    _ => todo!(),
+                    ChatCompletionRequestSystemMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestSystemMessageContent::Array(parts) => {
+                        join_text(parts.iter().map(|part| match part {
+                            ChatCompletionRequestSystemMessageContentPart::Text(text) => {
+                                Some(text.text.clone())
+                            }
+                        }))
+                    }
                 };
 
                 LlamaMessage {
                     role: "system".into(),
-                    content: content.clone(),
+                    content,
+                    ..Default::default()
+                }
+            }
+            ChatCompletionRequestMessage::Developer(developer) => {
+                let content = match &developer.content {
+                    ChatCompletionRequestDeveloperMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestDeveloperMessageContent::Array(parts) => {
+                        join_text(parts.iter().map(|part| Some(part.text.clone())))
+                    }
+                };
+
+                LlamaMessage {
+                    role: "system".into(),
+                    content,
+                    ..Default::default()
                 }
             }
             ChatCompletionRequestMessage::Assistant(assistant) => {
                 let content = match &assistant.content {
-                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text,
-                    _ => todo!(),
+                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text.clone(),
+                    Some(ChatCompletionRequestAssistantMessageContent::Array(parts)) => {
+                        join_text(parts.iter().map(|part| match part {
+                            ChatCompletionRequestAssistantMessageContentPart::Text(text) => {
+                                Some(text.text.clone())
+                            }
+                            ChatCompletionRequestAssistantMessageContentPart::Refusal(refusal) => {
+                                Some(refusal.refusal.clone())
+                            }
+                        }))
+                    }
+                    None => String::new(),
                 };
+
+                let tool_calls = assistant
+                    .tool_calls
+                    .iter()
+                    .flatten()
+                    .map(|call| LlamaToolCall {
+                        id: call.id.clone(),
+                        r#type: "function".into(),
+                        function: LlamaFunctionCall {
+                            name: call.function.name.clone(),
+                            arguments: serde_json::from_str(&call.function.arguments).unwrap_or(
+                                serde_json::Value::String(call.function.arguments.clone()),
+                            ),
+                        },
+                    })
+                    .collect();
+
                 LlamaMessage {
                     role: "assistant".into(),
-                    content: content.clone(),
+                    content,
+                    tool_calls,
+                    tool_call_id: None,
                 }
             }
             ChatCompletionRequestMessage::User(user) => {
+                // Local models are text-only; other parts are dropped.
                 let content = match &user.content {
-                    ChatCompletionRequestUserMessageContent::Text(text) => text,
-                    _ => todo!(),
+                    ChatCompletionRequestUserMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestUserMessageContent::Array(parts) => {
+                        join_text(parts.iter().map(|part| match part {
+                            ChatCompletionRequestUserMessageContentPart::Text(text) => {
+                                Some(text.text.clone())
+                            }
+                            _ => None,
+                        }))
+                    }
                 };
 
                 LlamaMessage {
                     role: "user".into(),
-                    content: content.clone(),
+                    content,
+                    ..Default::default()
                 }
             }
             ChatCompletionRequestMessage::Tool(tool) => {
                 let content = match &tool.content {
-                    ChatCompletionRequestToolMessageContent::Text(text) => text,
-                    _ => todo!(),
+                    ChatCompletionRequestToolMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestToolMessageContent::Array(parts) => {
+                        join_text(parts.iter().map(|part| match part {
+                            ChatCompletionRequestToolMessageContentPart::Text(text) => {
+                                Some(text.text.clone())
+                            }
+                        }))
+                    }
                 };
 
                 LlamaMessage {
                     role: "tool".into(),
-                    content: content.clone(),
+                    content,
+                    tool_calls: vec![],
+                    tool_call_id: Some(tool.tool_call_id.clone()),
                 }
             }
-            _ => todo!(),
+            ChatCompletionRequestMessage::Function(function) => LlamaMessage {
+                role: "tool".into(),
+                content: function.content.clone().unwrap_or_default(),
+                ..Default::default()
+            },
         }
     }
 }
 
 impl FromOpenAI for LlamaChatMessage {
     fn from_openai(message: &ChatCompletionRequestMessage) -> Self {
-        match message {
-            ChatCompletionRequestMessage::System(system) => {
-                let content = match &system.content {
-                    ChatCompletionRequestSystemMessageContent::Text(text) => text,
-                    _ => todo!(),
-                };
+        let message = LlamaMessage::from_openai(message);
+        LlamaChatMessage::new(message.role, message.content).unwrap()
+    }
+}
 
-                LlamaChatMessage::new("system".into(), content.into()).unwrap()
-            }
-            ChatCompletionRequestMessage::Assistant(assistant) => {
-                let content = match &assistant.content {
-                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text,
-                    _ => todo!(),
-                };
-                LlamaChatMessage::new("assistant".into(), content.into()).unwrap()
-            }
-            ChatCompletionRequestMessage::User(user) => {
-                let content = match &user.content {
-                    ChatCompletionRequestUserMessageContent::Text(text) => text,
-                    _ => todo!(),
-                };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                LlamaChatMessage::new("user".into(), content.into()).unwrap()
-            }
-            _ => todo!(),
-        }
+    #[test]
+    fn test_tool_round_trip_messages() {
+        let messages: Vec<ChatCompletionRequestMessage> =
+            serde_json::from_value(serde_json::json!([
+                { "role": "user", "content": [{ "type": "text", "text": "Say hi" }] },
+                {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "greet", "arguments": "{\"text\":\"Hi\"}" }
+                    }]
+                },
+                { "role": "tool", "tool_call_id": "call_1", "content": "done" }
+            ]))
+            .unwrap();
+
+        let messages: Vec<LlamaMessage> = messages.iter().map(FromOpenAI::from_openai).collect();
+
+        assert_eq!(messages[0].content, "Say hi");
+
+        assert_eq!(messages[1].role, "assistant");
+        assert_eq!(messages[1].content, "");
+        assert_eq!(messages[1].tool_calls[0].function.name, "greet");
+        assert_eq!(
+            messages[1].tool_calls[0].function.arguments,
+            serde_json::json!({ "text": "Hi" })
+        );
+
+        assert_eq!(messages[2].role, "tool");
+        assert_eq!(messages[2].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(messages[2].content, "done");
     }
 }
@@ -2,10 +2,13 @@ use hypr_analytics::{AnalyticsClient, AnalyticsPayload};
 use reqwest::Client;
 use serde::Deserialize;
 
-use crate::types::OPENROUTER_URL;
+use crate::cache::CacheEvent;
 
 #[derive(Debug, Clone)]
 pub struct GenerationEvent {
+    /// [`crate::Backend::name`] of the backend that served the request.
+    pub provider: String,
+    pub base_url: String,
     pub generation_id: String,
     pub model: String,
     pub input_tokens: u32,
@@ -20,6 +23,14 @@ pub trait AnalyticsReporter: Send + Sync {
         &self,
         event: GenerationEvent,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + '_>>;
+
+    /// Called on every response cache lookup, when the cache is enabled.
+    fn report_cache(
+        &self,
+        _event: CacheEvent,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + '_>> {
+        Box::pin(async {})
+    }
 }
 
 impl AnalyticsReporter for AnalyticsClient {
@@ -29,14 +40,14 @@ impl AnalyticsReporter for AnalyticsClient {
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + '_>> {
         Box::pin(async move {
             let payload = AnalyticsPayload::builder("$ai_generation")
-                .with("$ai_provider", "openrouter")
+                .with("$ai_provider", event.provider.clone())
                 .with("$ai_model", event.model.clone())
                 .with("$ai_input_tokens", event.input_tokens)
                 .with("$ai_output_tokens", event.output_tokens)
                 .with("$ai_latency", event.latency)
                 .with("$ai_trace_id", event.generation_id.clone())
                 .with("$ai_http_status", event.http_status)
-                .with("$ai_base_url", OPENROUTER_URL);
+                .with("$ai_base_url", event.base_url.clone());
 
             let payload = if let Some(cost) = event.total_cost {
                 payload.with("$ai_total_cost_usd", cost)
@@ -47,6 +58,21 @@ impl AnalyticsReporter for AnalyticsClient {
             let _ = self.event(event.generation_id, payload.build()).await;
         })
     }
+
+    fn report_cache(
+        &self,
+        event: CacheEvent,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            let payload = AnalyticsPayload::builder("llm_cache_lookup")
+                .with("hit", event.hit)
+                .with("hits", event.stats.hits)
+                .with("misses", event.stats.misses)
+                .build();
+
+            let _ = self.event(event.key, payload).await;
+        })
+    }
 }
 
 pub async fn fetch_generation_metadata(
@@ -0,0 +1,345 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::sync::watch;
+
+use crate::routing::Attempt;
+use crate::types::ChatCompletionRequest;
+
+const CACHE_KEY_VERSION: u32 = 1;
+
+/// Bounds for the in-memory response cache.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub ttl: Duration,
+    pub max_entries: usize,
+    /// Total size of cached bodies. Bodies larger than this are never cached.
+    pub max_bytes: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60 * 60),
+            max_entries: 1_000,
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+}
+
+/// Counters since the proxy started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEvent {
+    pub key: String,
+    pub hit: bool,
+    pub stats: CacheStats,
+}
+
+struct Entry {
+    body: Bytes,
+    inserted_at: Instant,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    // Oldest first, for eviction.
+    order: VecDeque<String>,
+    bytes: usize,
+    // Dropping the sender wakes everyone waiting on the same request.
+    inflight: HashMap<String, watch::Sender<()>>,
+}
+
+pub(crate) struct ResponseCache {
+    config: CacheConfig,
+    state: Mutex<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+pub(crate) enum Lookup {
+    Hit(Bytes),
+    /// The caller is the only one sending this request upstream, and should
+    /// hand the response to [`Fill::complete`].
+    Miss(Fill),
+}
+
+/// Keeps identical requests waiting while one of them is in flight. Dropping
+/// it without completing lets the next waiter try upstream instead.
+pub(crate) struct Fill {
+    cache: Arc<ResponseCache>,
+    key: String,
+}
+
+impl Fill {
+    pub(crate) fn complete(self, body: Bytes) {
+        self.cache.insert(&self.key, body);
+    }
+}
+
+impl Drop for Fill {
+    fn drop(&mut self) {
+        let mut state = self.cache.state.lock().unwrap();
+        state.inflight.remove(&self.key);
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) async fn lookup(self: &Arc<Self>, key: &str) -> Lookup {
+        loop {
+            let mut waiting = {
+                let mut state = self.state.lock().unwrap();
+
+                if let Some(body) = self.get(&mut state, key) {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Lookup::Hit(body);
+                }
+
+                match state.inflight.get(key) {
+                    Some(sender) => sender.subscribe(),
+                    None => {
+                        state.inflight.insert(key.to_string(), watch::channel(()).0);
+                        self.misses.fetch_add(1, Ordering::Relaxed);
+                        return Lookup::Miss(Fill {
+                            cache: self.clone(),
+                            key: key.to_string(),
+                        });
+                    }
+                }
+            };
+
+            // Only ever errors, once the request in flight is done.
+            let _ = waiting.changed().await;
+        }
+    }
+
+    fn get(&self, state: &mut CacheState, key: &str) -> Option<Bytes> {
+        let entry = state.entries.get(key)?;
+        if entry.inserted_at.elapsed() <= self.config.ttl {
+            return Some(entry.body.clone());
+        }
+
+        Self::remove(state, key);
+        None
+    }
+
+    fn insert(&self, key: &str, body: Bytes) {
+        if body.len() > self.config.max_bytes || self.config.max_entries == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        Self::remove(&mut state, key);
+
+        while state.entries.len() >= self.config.max_entries
+            || state.bytes + body.len() > self.config.max_bytes
+        {
+            let Some(oldest) = state.order.front().cloned() else {
+                break;
+            };
+            Self::remove(&mut state, &oldest);
+        }
+
+        state.bytes += body.len();
+        state.order.push_back(key.to_string());
+        state.entries.insert(
+            key.to_string(),
+            Entry {
+                body,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    fn remove(state: &mut CacheState, key: &str) {
+        if let Some(entry) = state.entries.remove(key) {
+            state.bytes -= entry.body.len();
+            state.order.retain(|k| k != key);
+        }
+    }
+}
+
+/// Hash of everything that decides what upstream answers: the request as
+/// sent, and which backends and models it would go to.
+pub(crate) fn cache_key(request: &ChatCompletionRequest, attempts: &[Attempt]) -> String {
+    let targets: Vec<Value> = attempts
+        .iter()
+        .map(|a| serde_json::json!([a.backend.name, a.backend.url, a.models]))
+        .collect();
+
+    let key_input = serde_json::json!({
+        "v": CACHE_KEY_VERSION,
+        "model": request.model,
+        "messages": request.messages,
+        "tools": request.tools,
+        "tool_choice": request.tool_choice,
+        "temperature": request.temperature,
+        "max_tokens": request.max_tokens,
+        "stream": request.is_stream(),
+        "extra": request.extra,
+        "targets": targets,
+    });
+
+    let canonical = serde_json::to_string(&canonical(key_input)).unwrap_or_default();
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+// Object keys sorted, whether or not serde_json preserves insertion order.
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonical(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonical).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(value: Value) -> ChatCompletionRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let a = request(serde_json::json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "temperature": 0.2,
+            "top_p": 0.9,
+            "seed": 1
+        }));
+        let b = request(serde_json::json!({
+            "seed": 1,
+            "top_p": 0.9,
+            "temperature": 0.2,
+            "messages": [{"content": "Hi", "role": "user"}]
+        }));
+        let c = request(serde_json::json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "temperature": 0.3,
+            "top_p": 0.9,
+            "seed": 1
+        }));
+
+        assert_eq!(cache_key(&a, &[]), cache_key(&b, &[]));
+        assert_ne!(cache_key(&a, &[]), cache_key(&c, &[]));
+    }
+
+    #[tokio::test]
+    async fn test_lookup() {
+        let cache = Arc::new(ResponseCache::new(CacheConfig::default()));
+
+        let Lookup::Miss(fill) = cache.lookup("a").await else {
+            panic!("expected a miss");
+        };
+
+        // A second identical request waits for the first one.
+        let waiter = tokio::spawn({
+            let cache = cache.clone();
+            async move {
+                match cache.lookup("a").await {
+                    Lookup::Hit(body) => body,
+                    Lookup::Miss(_) => panic!("expected a hit"),
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        fill.complete(Bytes::from_static(b"response"));
+
+        assert_eq!(waiter.await.unwrap(), Bytes::from_static(b"response"));
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+
+        // Abandoned requests let the next caller through.
+        let Lookup::Miss(fill) = cache.lookup("b").await else {
+            panic!("expected a miss");
+        };
+        drop(fill);
+        assert!(matches!(cache.lookup("b").await, Lookup::Miss(_)));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let cache = Arc::new(ResponseCache::new(
+            CacheConfig::default()
+                .with_max_entries(2)
+                .with_max_bytes(10),
+        ));
+
+        cache.insert("a", Bytes::from_static(b"1234"));
+        cache.insert("b", Bytes::from_static(b"1234"));
+        cache.insert("c", Bytes::from_static(b"1234"));
+        cache.insert("huge", Bytes::from_static(b"12345678901"));
+
+        let mut state = cache.state.lock().unwrap();
+        assert!(cache.get(&mut state, "a").is_none());
+        assert!(cache.get(&mut state, "b").is_some());
+        assert!(cache.get(&mut state, "c").is_some());
+        assert!(cache.get(&mut state, "huge").is_none());
+        assert_eq!(state.bytes, 8);
+    }
+
+    #[test]
+    fn test_ttl() {
+        let cache = ResponseCache::new(CacheConfig::default().with_ttl(Duration::ZERO));
+        cache.insert("a", Bytes::from_static(b"1234"));
+
+        std::thread::sleep(Duration::from_millis(1));
+        let mut state = cache.state.lock().unwrap();
+        assert!(cache.get(&mut state, "a").is_none());
+        assert_eq!(state.bytes, 0);
+    }
+}
@@ -2,6 +2,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::analytics::AnalyticsReporter;
+use crate::cache::CacheConfig;
+use crate::provider::Backend;
+use crate::routing::{ModelRoute, RoutingConfig};
 use crate::types::OPENROUTER_URL;
 
 const DEFAULT_TIMEOUT_MS: u64 = 120_000;
@@ -14,6 +17,10 @@ pub struct LlmProxyConfig {
     pub models_default: Vec<String>,
     pub analytics: Option<Arc<dyn AnalyticsReporter>>,
     pub base_url: String,
+    pub backends: Vec<Backend>,
+    pub routes: Vec<ModelRoute>,
+    /// Responses are only cached when this is set.
+    pub cache: Option<CacheConfig>,
 }
 
 impl LlmProxyConfig {
@@ -32,6 +39,9 @@ impl LlmProxyConfig {
             ],
             analytics: None,
             base_url: OPENROUTER_URL.to_string(),
+            backends: vec![],
+            routes: vec![],
+            cache: None,
         }
     }
 
@@ -59,4 +69,26 @@ impl LlmProxyConfig {
         self.base_url = base_url.into();
         self
     }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
+    /// Routes are matched in the order they were added.
+    pub fn with_route(mut self, route: ModelRoute) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    pub fn with_routing(mut self, routing: RoutingConfig) -> Self {
+        self.backends.extend(routing.backends);
+        self.routes.extend(routing.routes);
+        self
+    }
+
+    pub fn with_cache(mut self, cache: CacheConfig) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 }
@@ -9,36 +9,43 @@ use std::time::Instant;
 
 use axum::{
     Json, Router,
+    body::Body,
     extract::State,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
     routing::post,
 };
 use reqwest::Client;
 
 use crate::analytics::{AnalyticsReporter, GenerationEvent, fetch_generation_metadata};
+use crate::cache::{CacheEvent, Fill, Lookup, ResponseCache, cache_key};
 use crate::config::LlmProxyConfig;
-use crate::types::{ChatCompletionRequest, OpenRouterRequest, Provider, ToolChoice};
+use crate::provider::{Backend, ProviderKind};
+use crate::routing::Attempt;
+use crate::types::{ChatCompletionRequest, ToolChoice};
 
 async fn report_with_cost(
     analytics: &dyn AnalyticsReporter,
     client: &Client,
-    api_key: &str,
+    backend: &Backend,
     mut event: GenerationEvent,
 ) {
-    event.total_cost = fetch_generation_metadata(client, api_key, &event.generation_id).await;
+    // Only OpenRouter reports what a generation cost.
+    if let (ProviderKind::OpenRouter, Some(api_key)) = (backend.kind, &backend.api_key) {
+        event.total_cost = fetch_generation_metadata(client, api_key, &event.generation_id).await;
+    }
     analytics.report_generation(event).await;
 }
 
 pub(super) fn spawn_analytics_report(
     analytics: Option<Arc<dyn AnalyticsReporter>>,
     client: Client,
-    api_key: String,
+    backend: Backend,
     event: GenerationEvent,
 ) {
     if let Some(analytics) = analytics {
         tokio::spawn(async move {
-            report_with_cost(&*analytics, &client, &api_key, event).await;
+            report_with_cost(&*analytics, &client, &backend, event).await;
         });
     }
 }
@@ -92,13 +99,26 @@ impl IntoResponse for ProxyError {
 pub(crate) struct AppState {
     pub(crate) config: LlmProxyConfig,
     pub(crate) client: Client,
+    pub(crate) cache: Option<Arc<ResponseCache>>,
+}
+
+impl AppState {
+    fn new(config: LlmProxyConfig) -> Self {
+        let cache = config
+            .cache
+            .clone()
+            .map(|cache| Arc::new(ResponseCache::new(cache)));
+
+        Self {
+            config,
+            client: Client::new(),
+            cache,
+        }
+    }
 }
 
 pub fn router(config: LlmProxyConfig) -> Router {
-    let state = AppState {
-        config,
-        client: Client::new(),
-    };
+    let state = AppState::new(config);
 
     Router::new()
         .route("/", post(completions_handler))
@@ -107,10 +127,7 @@ pub fn router(config: LlmProxyConfig) -> Router {
 }
 
 pub fn chat_completions_router(config: LlmProxyConfig) -> Router {
-    let state = AppState {
-        config,
-        client: Client::new(),
-    };
+    let state = AppState::new(config);
 
     Router::new()
         .route("/chat/completions", post(completions_handler))
@@ -119,6 +136,7 @@ pub fn chat_completions_router(config: LlmProxyConfig) -> Router {
 
 async fn completions_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<ChatCompletionRequest>,
 ) -> Response {
     let start_time = Instant::now();
@@ -126,55 +144,145 @@ async fn completions_handler(
     let needs_tool_calling = request.tools.as_ref().is_some_and(|t| !t.is_empty())
         && !matches!(&request.tool_choice, Some(ToolChoice::String(s)) if s == "none");
 
-    let models = if needs_tool_calling {
-        state.config.models_tool_calling.clone()
-    } else {
-        state.config.models_default.clone()
-    };
-
-    let stream = request.stream.unwrap_or(false);
+    let stream = request.is_stream();
+    let attempts = state
+        .config
+        .attempts(request.model.as_deref(), needs_tool_calling);
 
     tracing::info!(
         stream = %stream,
         has_tools = %needs_tool_calling,
         message_count = %request.messages.len(),
-        model_count = %models.len(),
+        backend_count = %attempts.len(),
         "llm_completion_request_received"
     );
 
-    let openrouter_request = OpenRouterRequest {
-        messages: request.messages,
-        tools: request.tools,
-        tool_choice: request.tool_choice,
-        temperature: request.temperature,
-        max_tokens: request.max_tokens,
-        stream,
-        models,
-        provider: Provider::default(),
-        extra: request.extra,
+    let fill = match &state.cache {
+        Some(cache) if !bypasses_cache(&headers) => {
+            let key = cache_key(&request, &attempts);
+            let lookup = cache.lookup(&key).await;
+            let hit = matches!(lookup, Lookup::Hit(_));
+
+            tracing::info!(hit = %hit, "llm_cache_lookup");
+            if let Some(analytics) = state.config.analytics.clone() {
+                let event = CacheEvent {
+                    key,
+                    hit,
+                    stats: cache.stats(),
+                };
+                tokio::spawn(async move { analytics.report_cache(event).await });
+            }
+
+            match lookup {
+                Lookup::Hit(body) => return cached_response(body, stream),
+                Lookup::Miss(fill) => Some(fill),
+            }
+        }
+        _ => None,
     };
 
-    let result = tokio::time::timeout(state.config.timeout, async {
-        state
-            .client
-            .post(&state.config.base_url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", state.config.api_key))
-            .json(&openrouter_request)
-            .send()
-            .await
-    })
+    let (last, fallbacks) = attempts.split_last().expect("attempts is never empty");
+
+    for attempt in fallbacks {
+        match send(&state, attempt, &request).await {
+            Ok(response) if !response.status().is_server_error() => {
+                return respond(state, &attempt.backend, response, stream, start_time, fill).await;
+            }
+            Ok(response) => tracing::warn!(
+                backend = %attempt.backend.name,
+                http_status = %response.status().as_u16(),
+                "llm_backend_fallback"
+            ),
+            Err(e) => tracing::warn!(
+                backend = %attempt.backend.name,
+                is_timeout = %matches!(e, ProxyError::Timeout),
+                "llm_backend_fallback"
+            ),
+        }
+    }
+
+    match send(&state, last, &request).await {
+        Ok(response) => respond(state, &last.backend, response, stream, start_time, fill).await,
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn send(
+    state: &AppState,
+    attempt: &Attempt,
+    request: &ChatCompletionRequest,
+) -> Result<reqwest::Response, ProxyError> {
+    let result = tokio::time::timeout(
+        state.config.timeout,
+        attempt
+            .backend
+            .request(&state.client, request, &attempt.models)
+            .send(),
+    )
     .await;
 
-    let response = match result {
-        Ok(Ok(resp)) => resp,
-        Ok(Err(e)) => return ProxyError::UpstreamRequest(e).into_response(),
-        Err(_) => return ProxyError::Timeout.into_response(),
-    };
+    match result {
+        Ok(Ok(resp)) => Ok(resp),
+        Ok(Err(e)) => Err(ProxyError::UpstreamRequest(e)),
+        Err(_) => Err(ProxyError::Timeout),
+    }
+}
 
-    if stream {
-        handle_stream_response(state, response, start_time).await
+async fn respond(
+    state: AppState,
+    backend: &Backend,
+    response: reqwest::Response,
+    stream: bool,
+    start_time: Instant,
+    fill: Option<Fill>,
+) -> Response {
+    let backend = backend.clone();
+
+    // Only successful responses are worth replaying.
+    let fill = fill.filter(|_| response.status().is_success());
+
+    // Errors come back as plain JSON even for streaming requests.
+    if stream && response.status().is_success() {
+        handle_stream_response(state, backend, response, start_time, fill).await
     } else {
-        handle_non_stream_response(state, response, start_time).await
+        handle_non_stream_response(state, backend, response, start_time, fill).await
     }
 }
+
+fn bypasses_cache(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|directive| matches!(directive.trim(), "no-cache" | "no-store"))
+}
+
+fn cached_response(body: bytes::Bytes, stream: bool) -> Response {
+    let builder = Response::builder()
+        .status(StatusCode::OK)
+        .header("X-Cache", "HIT");
+
+    if !stream {
+        return builder
+            .header("Content-Type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+    }
+
+    // Replayed an event at a time, like a live stream.
+    let mut events = Vec::new();
+    let mut rest = body;
+    while let Some(end) = rest.windows(2).position(|w| w == b"\n\n") {
+        events.push(Ok::<_, std::io::Error>(rest.split_to(end + 2)));
+    }
+    if !rest.is_empty() {
+        events.push(Ok(rest));
+    }
+
+    builder
+        .header("Content-Type", "text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .body(Body::from_stream(futures_util::stream::iter(events)))
+        .unwrap()
+}
@@ -6,20 +6,25 @@ use axum::{
 };
 
 use crate::analytics::GenerationEvent;
+use crate::cache::Fill;
+use crate::provider::Backend;
 use crate::types::OpenRouterResponse;
 
 use super::{AppState, ProxyError, spawn_analytics_report};
 
 pub(super) async fn handle_non_stream_response(
     state: AppState,
+    backend: Backend,
     response: reqwest::Response,
     start_time: Instant,
+    fill: Option<Fill>,
 ) -> Response {
     let status = response.status();
     let http_status = status.as_u16();
     let latency_ms = start_time.elapsed().as_millis();
 
     tracing::info!(
+        backend = %backend.name,
         http_status = %http_status,
         streaming = false,
         latency_ms = %latency_ms,
@@ -27,12 +32,18 @@ pub(super) async fn handle_non_stream_response(
     );
 
     let body_bytes = match response.bytes().await {
-        Ok(b) => b,
+        Ok(b) => backend.response_body(b),
         Err(e) => return ProxyError::BodyRead(e).into_response(),
     };
 
+    if let Some(fill) = fill {
+        fill.complete(body_bytes.clone());
+    }
+
     if let Ok(parsed) = serde_json::from_slice::<OpenRouterResponse>(&body_bytes) {
         let event = GenerationEvent {
+            provider: backend.name.clone(),
+            base_url: backend.url.clone(),
             generation_id: parsed.id,
             model: parsed.model.unwrap_or_default(),
             input_tokens: parsed.usage.as_ref().map(|u| u.input_tokens()).unwrap_or(0),
@@ -49,7 +60,7 @@ pub(super) async fn handle_non_stream_response(
         spawn_analytics_report(
             state.config.analytics.clone(),
             state.client.clone(),
-            state.config.api_key.clone(),
+            backend,
             event,
         );
     }
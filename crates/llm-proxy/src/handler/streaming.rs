@@ -5,6 +5,8 @@ use axum::{body::Body, response::Response};
 use futures_util::StreamExt;
 
 use crate::analytics::GenerationEvent;
+use crate::cache::Fill;
+use crate::provider::{AnthropicStream, Backend, ProviderKind};
 use crate::types::UsageInfo;
 
 use super::{AppState, report_with_cost};
@@ -65,8 +67,15 @@ impl StreamAccumulator {
         }
     }
 
-    fn into_event(self, start_time: Instant, http_status: u16) -> Option<GenerationEvent> {
+    fn into_event(
+        self,
+        backend: &Backend,
+        start_time: Instant,
+        http_status: u16,
+    ) -> Option<GenerationEvent> {
         Some(GenerationEvent {
+            provider: backend.name.clone(),
+            base_url: backend.url.clone(),
             generation_id: self.generation_id?,
             model: self.model.unwrap_or_default(),
             input_tokens: self.input_tokens,
@@ -80,17 +89,19 @@ impl StreamAccumulator {
 
 pub(super) async fn handle_stream_response(
     state: AppState,
+    backend: Backend,
     response: reqwest::Response,
     start_time: Instant,
+    fill: Option<Fill>,
 ) -> Response {
     let status = response.status();
     let http_status = status.as_u16();
     let latency_ms = start_time.elapsed().as_millis();
     let analytics = state.config.analytics.clone();
-    let api_key = state.config.api_key.clone();
     let client = state.client.clone();
 
     tracing::info!(
+        backend = %backend.name,
         http_status = %http_status,
         streaming = true,
         latency_ms = %latency_ms,
@@ -101,27 +112,45 @@ pub(super) async fn handle_stream_response(
 
     let output_stream = stream! {
         let mut accumulator = StreamAccumulator::new();
+        let mut anthropic = (backend.kind == ProviderKind::Anthropic).then(AnthropicStream::default);
+        let mut fill = fill;
+        let mut recorded = Vec::new();
 
         futures_util::pin_mut!(upstream);
 
         while let Some(chunk_result) = upstream.next().await {
             match chunk_result {
                 Ok(chunk) => {
+                    let chunk = match anthropic.as_mut() {
+                        Some(translator) => translator.push(&chunk),
+                        None => chunk,
+                    };
+                    if chunk.is_empty() {
+                        continue;
+                    }
                     if analytics.is_some() {
                         accumulator.process_chunk(&chunk);
                     }
+                    if fill.is_some() {
+                        recorded.extend_from_slice(&chunk);
+                    }
                     yield Ok::<_, std::io::Error>(chunk);
                 }
                 Err(e) => {
+                    fill = None;
                     yield Err(std::io::Error::new(std::io::ErrorKind::Other, e));
                     break;
                 }
             }
         }
 
+        if let Some(fill) = fill {
+            fill.complete(recorded.into());
+        }
+
         if let Some(analytics) = analytics {
-            if let Some(event) = accumulator.into_event(start_time, http_status) {
-                report_with_cost(&*analytics, &client, &api_key, event).await;
+            if let Some(event) = accumulator.into_event(&backend, start_time, http_status) {
+                report_with_cost(&*analytics, &client, &backend, event).await;
             }
         }
     };
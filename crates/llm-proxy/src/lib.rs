@@ -1,8 +1,14 @@
 mod analytics;
+mod cache;
 mod config;
 mod handler;
+mod provider;
+mod routing;
 mod types;
 
 pub use analytics::{AnalyticsReporter, GenerationEvent};
+pub use cache::{CacheConfig, CacheEvent, CacheStats};
 pub use config::*;
 pub use handler::{chat_completions_router, router};
+pub use provider::{ANTHROPIC_URL, Backend, OLLAMA_URL, OPENAI_URL, ProviderKind};
+pub use routing::{DEFAULT_BACKEND, ModelRoute, RouteTarget, RoutingConfig};
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use serde_json::{Value, json};
+
+use crate::types::{ChatCompletionRequest, Role, ToolChoice};
+
+pub(super) const API_VERSION: &str = "2023-06-01";
+
+// Required by the Messages API, optional in chat completions.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+pub(super) fn body(request: &ChatCompletionRequest, model: Option<&String>) -> Value {
+    let mut system = Vec::new();
+    let mut messages = Vec::new();
+
+    for message in &request.messages {
+        let text = message.content.clone().unwrap_or_default();
+
+        match message.role {
+            Role::System => system.push(text),
+            Role::User => messages.push(json!({ "role": "user", "content": text })),
+            Role::Assistant => {
+                let mut content = Vec::new();
+                if !text.is_empty() {
+                    content.push(json!({ "type": "text", "text": text }));
+                }
+
+                let tool_calls = message.extra.get("tool_calls").and_then(Value::as_array);
+                for call in tool_calls.into_iter().flatten() {
+                    let function = &call["function"];
+                    let input = function["arguments"]
+                        .as_str()
+                        .and_then(|arguments| serde_json::from_str::<Value>(arguments).ok())
+                        .unwrap_or_else(|| json!({}));
+
+                    content.push(json!({
+                        "type": "tool_use",
+                        "id": call["id"],
+                        "name": function["name"],
+                        "input": input,
+                    }));
+                }
+
+                messages.push(json!({ "role": "assistant", "content": content }));
+            }
+            Role::Tool => messages.push(json!({
+                "role": "user",
+                "content": [{
+                    "type": "tool_result",
+                    "tool_use_id": message.extra.get("tool_call_id"),
+                    "content": text,
+                }],
+            })),
+        }
+    }
+
+    let mut body = json!({
+        "model": model.or(request.model.as_ref()),
+        "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+        "messages": messages,
+        "stream": request.is_stream(),
+    });
+
+    if !system.is_empty() {
+        body["system"] = json!(system.join("\n\n"));
+    }
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if let Some(top_p) = request.extra.get("top_p") {
+        body["top_p"] = top_p.clone();
+    }
+    match request.extra.get("stop") {
+        Some(Value::String(stop)) => body["stop_sequences"] = json!([stop]),
+        Some(stop @ Value::Array(_)) => body["stop_sequences"] = stop.clone(),
+        _ => {}
+    }
+
+    if let Some(tools) = request.tools.as_ref().filter(|tools| !tools.is_empty()) {
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|tool| {
+                let function = &tool["function"];
+                let mut tool = json!({
+                    "name": function["name"],
+                    "input_schema": function
+                        .get("parameters")
+                        .cloned()
+                        .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+                });
+                if let Some(description) = function.get("description") {
+                    tool["description"] = description.clone();
+                }
+                tool
+            })
+            .collect();
+        body["tools"] = json!(tools);
+
+        if let Some(tool_choice) = &request.tool_choice {
+            body["tool_choice"] = match tool_choice {
+                ToolChoice::String(s) if s == "required" => json!({ "type": "any" }),
+                ToolChoice::String(s) if s == "none" => json!({ "type": "none" }),
+                ToolChoice::String(_) => json!({ "type": "auto" }),
+                ToolChoice::Object { function, .. } => {
+                    json!({ "type": "tool", "name": function["name"] })
+                }
+            };
+        }
+    }
+
+    body
+}
+
+fn finish_reason(stop_reason: &str) -> &'static str {
+    match stop_reason {
+        "max_tokens" => "length",
+        "tool_use" => "tool_calls",
+        "refusal" => "content_filter",
+        _ => "stop",
+    }
+}
+
+fn usage(input_tokens: u64, output_tokens: u64) -> Value {
+    json!({
+        "prompt_tokens": input_tokens,
+        "completion_tokens": output_tokens,
+        "total_tokens": input_tokens + output_tokens,
+    })
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn error(error: &Value) -> Value {
+    json!({
+        "error": {
+            "message": error["message"],
+            "type": error["type"],
+        }
+    })
+}
+
+/// Maps a Messages API response, or error, to a chat completion.
+pub(super) fn response_body(body: &[u8]) -> Option<Bytes> {
+    let message: Value = serde_json::from_slice(body).ok()?;
+
+    let response = match message["type"].as_str()? {
+        "error" => error(&message["error"]),
+        "message" => {
+            let mut text = String::new();
+            let mut tool_calls = Vec::new();
+
+            for block in message["content"].as_array().into_iter().flatten() {
+                match block["type"].as_str() {
+                    Some("text") => text.push_str(block["text"].as_str().unwrap_or_default()),
+                    Some("tool_use") => tool_calls.push(json!({
+                        "id": block["id"],
+                        "type": "function",
+                        "function": {
+                            "name": block["name"],
+                            "arguments": block["input"].to_string(),
+                        },
+                    })),
+                    _ => {}
+                }
+            }
+
+            let mut reply = json!({
+                "role": "assistant",
+                "content": (!text.is_empty()).then_some(text),
+            });
+            if !tool_calls.is_empty() {
+                reply["tool_calls"] = json!(tool_calls);
+            }
+
+            json!({
+                "id": message["id"],
+                "object": "chat.completion",
+                "created": now(),
+                "model": message["model"],
+                "choices": [{
+                    "index": 0,
+                    "message": reply,
+                    "finish_reason": finish_reason(message["stop_reason"].as_str().unwrap_or_default()),
+                }],
+                "usage": usage(
+                    message["usage"]["input_tokens"].as_u64().unwrap_or_default(),
+                    message["usage"]["output_tokens"].as_u64().unwrap_or_default(),
+                ),
+            })
+        }
+        _ => return None,
+    };
+
+    Some(Bytes::from(response.to_string()))
+}
+
+/// Turns Messages API server-sent events into chat completion chunks.
+#[derive(Default)]
+pub(crate) struct AnthropicStream {
+    buffer: Vec<u8>,
+    id: Value,
+    model: Value,
+    created: u64,
+    input_tokens: u64,
+    // Content block index to tool call index.
+    tool_calls: HashMap<u64, usize>,
+}
+
+impl AnthropicStream {
+    /// Feeds upstream bytes, which may split events anywhere, and returns the
+    /// complete chunks they finish.
+    pub(crate) fn push(&mut self, bytes: &[u8]) -> Bytes {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut out = String::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let Ok(event) = serde_json::from_str::<Value>(data.trim()) else {
+                continue;
+            };
+
+            if let Some(data) = self.translate(&event) {
+                out.push_str("data: ");
+                out.push_str(&data);
+                out.push_str("\n\n");
+            }
+        }
+
+        Bytes::from(out)
+    }
+
+    fn chunk(&self, delta: Value, finish_reason: Option<&str>) -> Value {
+        json!({
+            "id": self.id,
+            "object": "chat.completion.chunk",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        })
+    }
+
+    fn translate(&mut self, event: &Value) -> Option<String> {
+        let chunk = match event["type"].as_str()? {
+            "message_start" => {
+                let message = &event["message"];
+                self.id = message["id"].clone();
+                self.model = message["model"].clone();
+                self.created = now();
+                self.input_tokens = message["usage"]["input_tokens"]
+                    .as_u64()
+                    .unwrap_or_default();
+                self.chunk(json!({ "role": "assistant", "content": "" }), None)
+            }
+            "content_block_start" => {
+                let block = &event["content_block"];
+                if block["type"] != "tool_use" {
+                    return None;
+                }
+
+                let index = self.tool_calls.len();
+                self.tool_calls
+                    .insert(event["index"].as_u64().unwrap_or_default(), index);
+                self.chunk(
+                    json!({
+                        "tool_calls": [{
+                            "index": index,
+                            "id": block["id"],
+                            "type": "function",
+                            "function": { "name": block["name"], "arguments": "" },
+                        }]
+                    }),
+                    None,
+                )
+            }
+            "content_block_delta" => {
+                let delta = &event["delta"];
+                match delta["type"].as_str()? {
+                    "text_delta" => self.chunk(json!({ "content": delta["text"] }), None),
+                    "input_json_delta" => {
+                        let block = event["index"].as_u64().unwrap_or_default();
+                        let index = *self.tool_calls.get(&block)?;
+                        self.chunk(
+                            json!({
+                                "tool_calls": [{
+                                    "index": index,
+                                    "function": { "arguments": delta["partial_json"] },
+                                }]
+                            }),
+                            None,
+                        )
+                    }
+                    _ => return None,
+                }
+            }
+            "message_delta" => {
+                let stop_reason = event["delta"]["stop_reason"].as_str().unwrap_or_default();
+                let mut chunk = self.chunk(json!({}), Some(finish_reason(stop_reason)));
+                chunk["usage"] = usage(
+                    self.input_tokens,
+                    event["usage"]["output_tokens"].as_u64().unwrap_or_default(),
+                );
+                chunk
+            }
+            "message_stop" => return Some("[DONE]".to_string()),
+            "error" => error(&event["error"]),
+            _ => return None,
+        };
+
+        Some(chunk.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body() {
+        let request: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": "claude-sonnet-4-5",
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Weather in Seoul?"},
+                {"role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "weather", "arguments": "{\"city\":\"Seoul\"}"}
+                }]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "Sunny"}
+            ],
+            "tools": [{"type": "function", "function": {
+                "name": "weather",
+                "parameters": {"type": "object", "properties": {"city": {"type": "string"}}}
+            }}],
+            "tool_choice": "required",
+            "stop": "END"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            body(&request, None),
+            json!({
+                "model": "claude-sonnet-4-5",
+                "max_tokens": 4096,
+                "stream": false,
+                "system": "Be brief.",
+                "stop_sequences": ["END"],
+                "messages": [
+                    {"role": "user", "content": "Weather in Seoul?"},
+                    {"role": "assistant", "content": [
+                        {"type": "tool_use", "id": "call_1", "name": "weather", "input": {"city": "Seoul"}}
+                    ]},
+                    {"role": "user", "content": [
+                        {"type": "tool_result", "tool_use_id": "call_1", "content": "Sunny"}
+                    ]}
+                ],
+                "tools": [{
+                    "name": "weather",
+                    "input_schema": {"type": "object", "properties": {"city": {"type": "string"}}}
+                }],
+                "tool_choice": {"type": "any"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_response_body() {
+        let body = response_body(
+            json!({
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-sonnet-4-5",
+                "content": [
+                    {"type": "text", "text": "Checking."},
+                    {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "Seoul"}}
+                ],
+                "stop_reason": "tool_use",
+                "usage": {"input_tokens": 12, "output_tokens": 5}
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["id"], "msg_1");
+        assert_eq!(body["choices"][0]["message"]["content"], "Checking.");
+        assert_eq!(
+            body["choices"][0]["message"]["tool_calls"][0]["function"],
+            json!({"name": "weather", "arguments": "{\"city\":\"Seoul\"}"})
+        );
+        assert_eq!(body["choices"][0]["finish_reason"], "tool_calls");
+        assert_eq!(
+            body["usage"],
+            json!({"prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17})
+        );
+    }
+
+    #[test]
+    fn test_stream() {
+        let upstream = [
+            r#"event: message_start
+data: {"type":"message_start","message":{"id":"msg_1","model":"claude-sonnet-4-5","usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#,
+            r#"event: content_block_start
+data: {"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"weather","input":{}}}"#,
+            r#"event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"city\":"}}"#,
+            r#"event: message_delta
+data: {"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":9}}"#,
+            r#"event: message_stop
+data: {"type":"message_stop"}"#,
+        ]
+        .join("\n\n")
+            + "\n\n";
+
+        // Split mid-event to make sure partial lines are held back.
+        let mut stream = AnthropicStream::default();
+        let (head, tail) = upstream.as_bytes().split_at(150);
+        let mut out = String::from_utf8(stream.push(head).to_vec()).unwrap();
+        out.push_str(std::str::from_utf8(&stream.push(tail)).unwrap());
+
+        let chunks: Vec<&str> = out
+            .split("\n\n")
+            .filter_map(|line| line.strip_prefix("data: "))
+            .collect();
+        assert_eq!(chunks.len(), 6);
+        assert_eq!(chunks[5], "[DONE]");
+
+        let chunks: Vec<Value> = chunks[..5]
+            .iter()
+            .map(|c| serde_json::from_str(c).unwrap())
+            .collect();
+        assert_eq!(chunks[0]["id"], "msg_1");
+        assert_eq!(chunks[1]["choices"][0]["delta"]["content"], "Hi");
+        assert_eq!(
+            chunks[2]["choices"][0]["delta"]["tool_calls"][0]["function"]["name"],
+            "weather"
+        );
+        assert_eq!(
+            chunks[3]["choices"][0]["delta"]["tool_calls"][0]["function"]["arguments"],
+            "{\"city\":"
+        );
+        assert_eq!(chunks[4]["choices"][0]["finish_reason"], "tool_calls");
+        assert_eq!(chunks[4]["usage"]["prompt_tokens"], 12);
+        assert_eq!(chunks[4]["usage"]["completion_tokens"], 9);
+    }
+}
@@ -0,0 +1,146 @@
+mod anthropic;
+mod openai;
+
+pub(crate) use anthropic::AnthropicStream;
+
+use bytes::Bytes;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::types::{ChatCompletionRequest, OPENROUTER_URL};
+
+pub const OPENAI_URL: &str = "https://api.openai.com/v1/chat/completions";
+pub const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const OLLAMA_URL: &str = "http://localhost:11434/v1/chat/completions";
+
+/// Wire format spoken by an upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// OpenRouter, which takes a list of models and falls back across them itself.
+    OpenRouter,
+    /// Anything speaking OpenAI's chat completions API: OpenAI, Ollama, vLLM, llama.cpp.
+    OpenAI,
+    /// Anthropic's Messages API.
+    Anthropic,
+}
+
+/// An upstream the proxy can send completions to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Backend {
+    /// Referenced by [`crate::RouteTarget::backend`], and reported to analytics.
+    pub name: String,
+    pub kind: ProviderKind,
+    /// Full endpoint URL, e.g. `.../v1/chat/completions`.
+    pub url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+impl Backend {
+    pub fn openrouter(api_key: impl Into<String>) -> Self {
+        Self {
+            name: "openrouter".into(),
+            kind: ProviderKind::OpenRouter,
+            url: OPENROUTER_URL.into(),
+            api_key: Some(api_key.into()),
+        }
+    }
+
+    pub fn openai(api_key: impl Into<String>) -> Self {
+        Self {
+            name: "openai".into(),
+            kind: ProviderKind::OpenAI,
+            url: OPENAI_URL.into(),
+            api_key: Some(api_key.into()),
+        }
+    }
+
+    pub fn anthropic(api_key: impl Into<String>) -> Self {
+        Self {
+            name: "anthropic".into(),
+            kind: ProviderKind::Anthropic,
+            url: ANTHROPIC_URL.into(),
+            api_key: Some(api_key.into()),
+        }
+    }
+
+    pub fn ollama() -> Self {
+        Self {
+            name: "ollama".into(),
+            kind: ProviderKind::OpenAI,
+            url: OLLAMA_URL.into(),
+            api_key: None,
+        }
+    }
+
+    /// A self-hosted OpenAI-compatible server, like vLLM or the desktop app's local LLM.
+    pub fn openai_compatible(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: ProviderKind::OpenAI,
+            url: url.into(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub(crate) fn request(
+        &self,
+        client: &Client,
+        request: &ChatCompletionRequest,
+        models: &[String],
+    ) -> reqwest::RequestBuilder {
+        let builder = client
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+
+        match self.kind {
+            ProviderKind::OpenRouter => {
+                let builder = match &self.api_key {
+                    Some(key) => builder.header("Authorization", format!("Bearer {}", key)),
+                    None => builder,
+                };
+                builder.json(&openai::openrouter_body(request, models))
+            }
+            ProviderKind::OpenAI => {
+                let builder = match &self.api_key {
+                    Some(key) => builder.header("Authorization", format!("Bearer {}", key)),
+                    None => builder,
+                };
+                builder.json(&openai::body(request, models.first()))
+            }
+            ProviderKind::Anthropic => {
+                let builder = match &self.api_key {
+                    Some(key) => builder.header("x-api-key", key),
+                    None => builder,
+                };
+                builder
+                    .header("anthropic-version", anthropic::API_VERSION)
+                    .json(&anthropic::body(request, models.first()))
+            }
+        }
+    }
+
+    /// Rewrites a non-streaming response body into OpenAI's format.
+    pub(crate) fn response_body(&self, body: Bytes) -> Bytes {
+        match self.kind {
+            ProviderKind::OpenRouter | ProviderKind::OpenAI => body,
+            ProviderKind::Anthropic => anthropic::response_body(&body).unwrap_or(body),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+use crate::types::{ChatCompletionRequest, ChatMessage, OpenRouterRequest, Provider, ToolChoice};
+
+#[derive(Serialize)]
+pub(super) struct OpenAIRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    messages: &'a [ChatMessage],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<&'a Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<&'a ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
+    #[serde(flatten)]
+    extra: &'a serde_json::Map<String, serde_json::Value>,
+}
+
+pub(super) fn openrouter_body(
+    request: &ChatCompletionRequest,
+    models: &[String],
+) -> OpenRouterRequest {
+    OpenRouterRequest {
+        messages: request.messages.clone(),
+        tools: request.tools.clone(),
+        tool_choice: request.tool_choice.clone(),
+        temperature: request.temperature,
+        max_tokens: request.max_tokens,
+        stream: request.is_stream(),
+        models: models.to_vec(),
+        provider: Provider::default(),
+        extra: request.extra.clone(),
+    }
+}
+
+pub(super) fn body<'a>(
+    request: &'a ChatCompletionRequest,
+    model: Option<&'a String>,
+) -> OpenAIRequest<'a> {
+    let stream = request.is_stream();
+
+    // Usage is only sent at the end of a stream when asked for, and analytics
+    // needs it.
+    let stream_options = (stream && !request.extra.contains_key("stream_options"))
+        .then(|| serde_json::json!({ "include_usage": true }));
+
+    OpenAIRequest {
+        model: model.or(request.model.as_ref()).map(String::as_str),
+        messages: &request.messages,
+        tools: request.tools.as_ref(),
+        tool_choice: request.tool_choice.as_ref(),
+        temperature: request.temperature,
+        max_tokens: request.max_tokens,
+        stream,
+        stream_options,
+        extra: &request.extra,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body() {
+        let request: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "llama3.2",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "stream": true,
+            "top_p": 0.9
+        }))
+        .unwrap();
+
+        let value = serde_json::to_value(body(&request, Some(&"qwen3:8b".to_string()))).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "model": "qwen3:8b",
+                "messages": [{"role": "user", "content": "Hi"}],
+                "stream": true,
+                "stream_options": {"include_usage": true},
+                "top_p": 0.9
+            })
+        );
+    }
+}
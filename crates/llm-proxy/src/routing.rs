@@ -0,0 +1,200 @@
+use serde::Deserialize;
+
+use crate::config::LlmProxyConfig;
+use crate::provider::{Backend, ProviderKind};
+
+/// Name of the backend built from [`LlmProxyConfig::api_key`] and
+/// [`LlmProxyConfig::base_url`], usable in routes without declaring it.
+pub const DEFAULT_BACKEND: &str = "openrouter";
+
+/// Sends requests for matching models to an ordered list of backends, moving
+/// on to the next one when a backend times out or answers with a 5xx.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelRoute {
+    /// Exact model name, or a prefix ending in `*`. A lone `*` matches every
+    /// request, including ones without a model.
+    pub model: String,
+    pub targets: Vec<RouteTarget>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteTarget {
+    /// A [`Backend::name`].
+    pub backend: String,
+    /// Model to ask this backend for. Defaults to the requested one.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Backends and routes, as read from e.g. a JSON env var.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RoutingConfig {
+    #[serde(default)]
+    pub backends: Vec<Backend>,
+    #[serde(default)]
+    pub routes: Vec<ModelRoute>,
+}
+
+impl ModelRoute {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            targets: vec![],
+        }
+    }
+
+    pub fn with_target(mut self, backend: impl Into<String>, model: Option<&str>) -> Self {
+        self.targets.push(RouteTarget {
+            backend: backend.into(),
+            model: model.map(String::from),
+        });
+        self
+    }
+
+    fn matches(&self, model: Option<&str>) -> bool {
+        match self.model.strip_suffix('*') {
+            Some(prefix) => prefix.is_empty() || model.is_some_and(|m| m.starts_with(prefix)),
+            None => model == Some(self.model.as_str()),
+        }
+    }
+}
+
+pub(crate) struct Attempt {
+    pub(crate) backend: Backend,
+    pub(crate) models: Vec<String>,
+}
+
+impl LlmProxyConfig {
+    fn default_backend(&self) -> Backend {
+        Backend::openrouter(&self.api_key).with_url(&self.base_url)
+    }
+
+    fn backend(&self, name: &str) -> Option<Backend> {
+        self.backends
+            .iter()
+            .find(|b| b.name == name)
+            .cloned()
+            .or_else(|| (name == DEFAULT_BACKEND).then(|| self.default_backend()))
+    }
+
+    /// Backends to try for a request, in order. Never empty.
+    pub(crate) fn attempts(&self, model: Option<&str>, needs_tool_calling: bool) -> Vec<Attempt> {
+        let targets = self
+            .routes
+            .iter()
+            .find(|route| route.matches(model))
+            .map(|route| route.targets.as_slice())
+            .unwrap_or_default();
+
+        let attempts: Vec<Attempt> = targets
+            .iter()
+            .filter_map(|target| {
+                let Some(backend) = self.backend(&target.backend) else {
+                    tracing::warn!(backend = %target.backend, "llm_route_backend_unknown");
+                    return None;
+                };
+
+                let model = target.model.as_deref().or(model);
+                let models = match (model, backend.kind) {
+                    (Some(model), _) => vec![model.to_string()],
+                    // OpenRouter can still pick from our own lists.
+                    (None, ProviderKind::OpenRouter) => self.default_models(needs_tool_calling),
+                    (None, _) => vec![],
+                };
+
+                Some(Attempt { backend, models })
+            })
+            .collect();
+
+        if !attempts.is_empty() {
+            return attempts;
+        }
+
+        vec![Attempt {
+            backend: self.default_backend(),
+            models: self.default_models(needs_tool_calling),
+        }]
+    }
+
+    fn default_models(&self, needs_tool_calling: bool) -> Vec<String> {
+        if needs_tool_calling {
+            self.models_tool_calling.clone()
+        } else {
+            self.models_default.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LlmProxyConfig {
+        LlmProxyConfig::new("key")
+            .with_backend(Backend::anthropic("anthropic-key"))
+            .with_backend(Backend::ollama())
+            .with_route(
+                ModelRoute::new("claude-*")
+                    .with_target("anthropic", None)
+                    .with_target("openrouter", Some("anthropic/claude-haiku-4.5")),
+            )
+            .with_route(ModelRoute::new("llama3.2").with_target("ollama", Some("llama3.2:3b")))
+    }
+
+    fn summary(attempts: Vec<Attempt>) -> Vec<(String, Vec<String>)> {
+        attempts
+            .into_iter()
+            .map(|a| (a.backend.name, a.models))
+            .collect()
+    }
+
+    #[test]
+    fn test_attempts() {
+        let config = config();
+
+        assert_eq!(
+            summary(config.attempts(Some("claude-haiku-4-5"), false)),
+            vec![
+                ("anthropic".into(), vec!["claude-haiku-4-5".into()]),
+                (
+                    "openrouter".into(),
+                    vec!["anthropic/claude-haiku-4.5".into()]
+                ),
+            ]
+        );
+        assert_eq!(
+            summary(config.attempts(Some("llama3.2"), false)),
+            vec![("ollama".into(), vec!["llama3.2:3b".into()])]
+        );
+        assert_eq!(
+            summary(config.attempts(None, true)),
+            vec![("openrouter".into(), config.models_tool_calling.clone())]
+        );
+        assert_eq!(
+            summary(config.attempts(Some("gpt-5"), false)),
+            vec![("openrouter".into(), config.models_default.clone())]
+        );
+    }
+
+    #[test]
+    fn test_routing_config() {
+        let routing: RoutingConfig = serde_json::from_value(serde_json::json!({
+            "backends": [
+                {"name": "local", "kind": "openai", "url": "http://localhost:8080/v1/chat/completions"}
+            ],
+            "routes": [
+                {"model": "*", "targets": [{"backend": "local"}, {"backend": "openrouter"}]}
+            ]
+        }))
+        .unwrap();
+        let config = LlmProxyConfig::new("key").with_routing(routing);
+
+        assert_eq!(
+            summary(config.attempts(None, false)),
+            vec![
+                ("local".into(), vec![]),
+                ("openrouter".into(), config.models_default.clone()),
+            ]
+        );
+    }
+}
@@ -11,13 +11,17 @@ pub enum Role {
     Tool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: Role,
-    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// `tool_calls`, `tool_call_id` and `name`, passed through as-is.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum ToolChoice {
     String(String),
@@ -28,10 +32,9 @@ pub enum ToolChoice {
     },
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ChatCompletionRequest {
     #[serde(default)]
-    #[allow(dead_code)]
     pub model: Option<String>,
     pub messages: Vec<ChatMessage>,
     #[serde(default)]
@@ -48,6 +51,12 @@ pub struct ChatCompletionRequest {
     pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
+impl ChatCompletionRequest {
+    pub fn is_stream(&self) -> bool {
+        self.stream.unwrap_or(false)
+    }
+}
+
 #[derive(Serialize)]
 pub struct OpenRouterRequest {
     pub messages: Vec<ChatMessage>,
@@ -84,9 +93,12 @@ pub struct OpenRouterResponse {
     pub usage: Option<UsageInfo>,
 }
 
+/// Token usage, in OpenAI's shape or Anthropic's.
 #[derive(Debug, Deserialize)]
 pub struct UsageInfo {
+    #[serde(alias = "input_tokens")]
     pub prompt_tokens: Option<u32>,
+    #[serde(alias = "output_tokens")]
     pub completion_tokens: Option<u32>,
 }
 
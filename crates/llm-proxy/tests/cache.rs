@@ -0,0 +1,186 @@
+#[allow(dead_code)]
+mod utils;
+use utils::*;
+
+use axum::http::StatusCode;
+use llm_proxy::{CacheConfig, CacheStats, router};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn non_streaming_hit() {
+    let harness = TestHarness::new().await;
+    harness
+        .mount_json_response(completion_response(
+            "gen-cached",
+            "openai/gpt-4.1-nano",
+            "hello",
+        ))
+        .await;
+
+    let app = router(harness.config().with_cache(CacheConfig::default()));
+
+    let first = app
+        .clone()
+        .oneshot(build_request(simple_message("Hello")))
+        .await
+        .unwrap();
+    assert_eq!(first.status(), StatusCode::OK);
+    assert!(first.headers().get("X-Cache").is_none());
+    let first = response_to_json(first).await;
+
+    let second = app
+        .oneshot(build_request(simple_message("Hello")))
+        .await
+        .unwrap();
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(second.headers().get("X-Cache").unwrap(), "HIT");
+    assert_eq!(response_to_json(second).await, first);
+
+    // Only the miss reached upstream, and produced a generation.
+    harness.analytics.get_single_event().await;
+    let lookups: Vec<_> = harness
+        .analytics
+        .captured_cache_events()
+        .into_iter()
+        .map(|e| e.hit)
+        .collect();
+    assert_eq!(lookups, vec![false, true]);
+    assert_eq!(
+        harness.analytics.captured_cache_events()[1].stats,
+        CacheStats { hits: 1, misses: 1 }
+    );
+}
+
+#[tokio::test]
+async fn streaming_replay() {
+    let harness = TestHarness::new().await;
+    harness
+        .mount_stream_response(&stream_chunks("gen-stream-cached"))
+        .await;
+
+    let app = router(
+        harness
+            .config_no_analytics()
+            .with_cache(CacheConfig::default()),
+    );
+
+    let first = app
+        .clone()
+        .oneshot(build_request(stream_request("Hello")))
+        .await
+        .unwrap();
+    let first = response_to_string(first).await;
+
+    let second = app
+        .oneshot(build_request(stream_request("Hello")))
+        .await
+        .unwrap();
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(
+        second.headers().get("Content-Type").unwrap(),
+        "text/event-stream"
+    );
+    assert_eq!(second.headers().get("X-Cache").unwrap(), "HIT");
+    assert_eq!(response_to_string(second).await, first);
+}
+
+#[tokio::test]
+async fn concurrent_requests_are_deduplicated() {
+    let harness = TestHarness::new().await;
+    harness
+        .mount_json_response(completion_response(
+            "gen-dedup",
+            "openai/gpt-4.1-nano",
+            "hello",
+        ))
+        .await;
+
+    let app = router(
+        harness
+            .config_no_analytics()
+            .with_cache(CacheConfig::default()),
+    );
+
+    let (a, b) = tokio::join!(
+        app.clone().oneshot(build_request(simple_message("Hello"))),
+        app.oneshot(build_request(simple_message("Hello"))),
+    );
+
+    assert_eq!(response_to_json(a.unwrap()).await["id"], "gen-dedup");
+    assert_eq!(response_to_json(b.unwrap()).await["id"], "gen-dedup");
+}
+
+#[tokio::test]
+async fn errors_are_not_cached() {
+    let harness = TestHarness::new().await;
+    harness
+        .mount_error_response(500, serde_json::json!({"error": {"message": "boom"}}))
+        .await;
+
+    let app = router(
+        harness
+            .config_no_analytics()
+            .with_cache(CacheConfig::default()),
+    );
+    let response = app
+        .clone()
+        .oneshot(build_request(simple_message("Hello")))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 500);
+
+    harness.mock_server.reset().await;
+    harness
+        .mount_json_response(completion_response(
+            "gen-after-error",
+            "openai/gpt-4.1-nano",
+            "hello",
+        ))
+        .await;
+
+    let response = app
+        .oneshot(build_request(simple_message("Hello")))
+        .await
+        .unwrap();
+    assert_eq!(response_to_json(response).await["id"], "gen-after-error");
+}
+
+#[tokio::test]
+async fn no_cache_header_bypasses() {
+    let harness = TestHarness::new().await;
+    harness
+        .mount_json_response(completion_response(
+            "gen-first",
+            "openai/gpt-4.1-nano",
+            "hello",
+        ))
+        .await;
+
+    let app = router(
+        harness
+            .config_no_analytics()
+            .with_cache(CacheConfig::default()),
+    );
+    app.clone()
+        .oneshot(build_request(simple_message("Hello")))
+        .await
+        .unwrap();
+
+    harness.mock_server.reset().await;
+    harness
+        .mount_json_response(completion_response(
+            "gen-fresh",
+            "openai/gpt-4.1-nano",
+            "hello",
+        ))
+        .await;
+
+    let mut request = build_request(simple_message("Hello"));
+    request
+        .headers_mut()
+        .insert("Cache-Control", "no-cache".parse().unwrap());
+
+    let response = app.oneshot(request).await.unwrap();
+    assert!(response.headers().get("X-Cache").is_none());
+    assert_eq!(response_to_json(response).await["id"], "gen-fresh");
+}
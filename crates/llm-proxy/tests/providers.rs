@@ -0,0 +1,284 @@
+#[allow(dead_code)]
+mod utils;
+use utils::*;
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use llm_proxy::{Backend, ModelRoute, router};
+use tower::ServiceExt;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+fn model_request(model: &str, stream: bool) -> serde_json::Value {
+    serde_json::json!({
+        "model": model,
+        "messages": [
+            {"role": "system", "content": "Be brief."},
+            {"role": "user", "content": "Hello"}
+        ],
+        "stream": stream,
+        "max_tokens": 10
+    })
+}
+
+mod fallback {
+    use super::*;
+
+    #[tokio::test]
+    async fn server_error_falls_back() {
+        let harness = TestHarness::new().await;
+        Mock::given(method("POST"))
+            .and(path("/primary"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&harness.mock_server)
+            .await;
+        harness
+            .mount_with_body_matcher(
+                serde_json::json!({"models": ["openai/gpt-4.1-mini"]}),
+                completion_response("gen-fallback", "openai/gpt-4.1-mini", "hello"),
+            )
+            .await;
+
+        let config = harness
+            .config()
+            .with_backend(
+                Backend::openai("primary-key")
+                    .with_url(format!("{}/primary", harness.mock_server.uri())),
+            )
+            .with_route(
+                ModelRoute::new("gpt-4.1-mini")
+                    .with_target("openai", None)
+                    .with_target("openrouter", Some("openai/gpt-4.1-mini")),
+            );
+
+        let response = router(config)
+            .oneshot(build_request(model_request("gpt-4.1-mini", false)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_to_json(response).await["id"], "gen-fallback");
+
+        let event = harness.analytics.get_single_event().await;
+        assert_eq!(event.provider, "openrouter");
+        assert_eq!(event.base_url, harness.mock_server.uri());
+    }
+
+    #[tokio::test]
+    async fn timeout_falls_back() {
+        let harness = TestHarness::new().await;
+        Mock::given(method("POST"))
+            .and(path("/slow"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(completion_response("gen-slow", "slow", "late"))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&harness.mock_server)
+            .await;
+        harness
+            .mount_json_response(completion_response(
+                "gen-fast",
+                "openai/gpt-4.1-nano",
+                "hello",
+            ))
+            .await;
+
+        let config = harness
+            .config_no_analytics()
+            .with_timeout(Duration::from_millis(200))
+            .with_backend(Backend::openai_compatible(
+                "slow",
+                format!("{}/slow", harness.mock_server.uri()),
+            ))
+            .with_route(
+                ModelRoute::new("*")
+                    .with_target("slow", None)
+                    .with_target("openrouter", None),
+            );
+
+        let response = router(config)
+            .oneshot(build_request(simple_message("Hello")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_to_json(response).await["id"], "gen-fast");
+    }
+
+    #[tokio::test]
+    async fn last_error_propagates() {
+        let harness = TestHarness::new().await;
+        harness
+            .mount_error_response(
+                500,
+                serde_json::json!({"error": {"message": "Internal error"}}),
+            )
+            .await;
+
+        let config = harness
+            .config_no_analytics()
+            .with_route(ModelRoute::new("*").with_target("openrouter", None));
+
+        let response = router(config)
+            .oneshot(build_request(simple_message("Hello")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 500);
+    }
+}
+
+mod anthropic {
+    use super::*;
+
+    fn config(harness: &TestHarness) -> llm_proxy::LlmProxyConfig {
+        harness
+            .config()
+            .with_backend(
+                Backend::anthropic("anthropic-key")
+                    .with_url(format!("{}/v1/messages", harness.mock_server.uri())),
+            )
+            .with_route(ModelRoute::new("claude-*").with_target("anthropic", None))
+    }
+
+    #[tokio::test]
+    async fn non_streaming() {
+        let harness = TestHarness::new().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "anthropic-key"))
+            .and(header("anthropic-version", "2023-06-01"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "claude-haiku-4-5",
+                "system": "Be brief.",
+                "messages": [{"role": "user", "content": "Hello"}],
+                "max_tokens": 10
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_123",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-haiku-4-5",
+                "content": [{"type": "text", "text": "Hi!"}],
+                "stop_reason": "end_turn",
+                "usage": {"input_tokens": 9, "output_tokens": 3}
+            })))
+            .expect(1)
+            .mount(&harness.mock_server)
+            .await;
+
+        let response = router(config(&harness))
+            .oneshot(build_request(model_request("claude-haiku-4-5", false)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_to_json(response).await;
+        assert_eq!(body["object"], "chat.completion");
+        assert_eq!(body["choices"][0]["message"]["content"], "Hi!");
+        assert_eq!(body["choices"][0]["finish_reason"], "stop");
+        assert_eq!(body["usage"]["total_tokens"], 12);
+
+        let event = harness.analytics.get_single_event().await;
+        assert_eq!(event.provider, "anthropic");
+        assert_eq!(event.generation_id, "msg_123");
+        assert_eq!(event.input_tokens, 9);
+        assert_eq!(event.output_tokens, 3);
+        assert_eq!(event.total_cost, None);
+    }
+
+    #[tokio::test]
+    async fn streaming() {
+        let events = [
+            r#"event: message_start
+data: {"type":"message_start","message":{"id":"msg_456","model":"claude-haiku-4-5","usage":{"input_tokens":9,"output_tokens":1}}}"#,
+            r#"event: content_block_start
+data: {"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi!"}}"#,
+            r#"event: message_delta
+data: {"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":3}}"#,
+            r#"event: message_stop
+data: {"type":"message_stop"}"#,
+        ]
+        .join("\n\n")
+            + "\n\n";
+
+        let harness = TestHarness::new().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({"stream": true})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(events)
+                    .insert_header("Content-Type", "text/event-stream"),
+            )
+            .expect(1)
+            .mount(&harness.mock_server)
+            .await;
+
+        let response = router(config(&harness))
+            .oneshot(build_request(model_request("claude-haiku-4-5", true)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_to_string(response).await;
+        assert!(body.contains(r#""object":"chat.completion.chunk""#));
+        assert!(body.contains(r#""content":"Hi!""#));
+        assert!(body.contains(r#""finish_reason":"stop""#));
+        assert!(body.ends_with("data: [DONE]\n\n"));
+
+        let event = harness.analytics.get_single_event().await;
+        assert_eq!(event.provider, "anthropic");
+        assert_eq!(event.generation_id, "msg_456");
+        assert_eq!(event.input_tokens, 9);
+        assert_eq!(event.output_tokens, 3);
+    }
+}
+
+mod openai_compatible {
+    use super::*;
+
+    #[tokio::test]
+    async fn model_rewrite() {
+        let harness = TestHarness::new().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "llama3.2:3b",
+                "stream": false
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(completion_response(
+                    "chatcmpl-1",
+                    "llama3.2:3b",
+                    "hello",
+                )),
+            )
+            .expect(1)
+            .mount(&harness.mock_server)
+            .await;
+
+        let config = harness
+            .config_no_analytics()
+            .with_backend(
+                Backend::ollama()
+                    .with_url(format!("{}/v1/chat/completions", harness.mock_server.uri())),
+            )
+            .with_route(ModelRoute::new("llama3.2").with_target("ollama", Some("llama3.2:3b")));
+
+        let response = router(config)
+            .oneshot(build_request(model_request("llama3.2", false)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_to_json(response).await["model"], "llama3.2:3b");
+    }
+}
@@ -2,13 +2,14 @@ use std::sync::{Arc, Mutex};
 
 use axum::body::Body;
 use axum::http::Request;
-use llm_proxy::{AnalyticsReporter, GenerationEvent, LlmProxyConfig};
+use llm_proxy::{AnalyticsReporter, CacheEvent, GenerationEvent, LlmProxyConfig};
 use wiremock::matchers::{body_partial_json, header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[derive(Default, Clone)]
 pub struct MockAnalytics {
     events: Arc<Mutex<Vec<GenerationEvent>>>,
+    cache_events: Arc<Mutex<Vec<CacheEvent>>>,
 }
 
 impl AnalyticsReporter for MockAnalytics {
@@ -21,6 +22,16 @@ impl AnalyticsReporter for MockAnalytics {
             events.lock().unwrap().push(event);
         })
     }
+
+    fn report_cache(
+        &self,
+        event: CacheEvent,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + '_>> {
+        let cache_events = self.cache_events.clone();
+        Box::pin(async move {
+            cache_events.lock().unwrap().push(event);
+        })
+    }
 }
 
 impl MockAnalytics {
@@ -28,6 +39,11 @@ impl MockAnalytics {
         self.events.lock().unwrap().clone()
     }
 
+    #[allow(dead_code)]
+    pub fn captured_cache_events(&self) -> Vec<CacheEvent> {
+        self.cache_events.lock().unwrap().clone()
+    }
+
     pub async fn get_single_event(&self) -> GenerationEvent {
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         let events = self.captured_events();
@@ -133,7 +149,7 @@ impl TestHarness {
 pub fn build_request(body: serde_json::Value) -> Request<Body> {
     Request::builder()
         .method("POST")
-        .uri("/completions")
+        .uri("/chat/completions")
         .header("Content-Type", "application/json")
         .body(Body::from(serde_json::to_string(&body).unwrap()))
         .unwrap()
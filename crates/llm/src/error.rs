@@ -7,9 +7,15 @@ pub enum Error {
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
+    HyprGbnfError(#[from] hypr_gbnf::Error),
+    #[error(transparent)]
     HyprLlamaError(#[from] hypr_llama::Error),
     #[error(transparent)]
     HyprLlmInterfaceError(#[from] hypr_llm_interface::Error),
+    #[error(transparent)]
+    HyprTemplateAppError(#[from] hypr_template_app::Error),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
     #[error("Model not downloaded")]
     ModelNotDownloaded,
 }
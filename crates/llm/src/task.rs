@@ -2,6 +2,7 @@ use futures_util::StreamExt;
 
 use hypr_gbnf::Grammar;
 use hypr_llm_interface::ModelManager;
+use hypr_template_app::{ActionItems, ActionItemsSystem, ActionItemsUser, action_items_schema};
 use hypr_template_app_legacy::{Template, render};
 
 pub async fn generate_title(
@@ -15,14 +16,16 @@ pub async fn generate_title(
             hypr_llama::LlamaMessage {
                 role: "system".into(),
                 content: render(Template::TitleSystem, &ctx).unwrap(),
+                ..Default::default()
             },
             hypr_llama::LlamaMessage {
                 role: "user".into(),
                 content: render(Template::TitleUser, &ctx).unwrap(),
+                ..Default::default()
             },
         ],
         max_tokens: Some(30),
-        grammar: Some(Grammar::Title.build()),
+        grammar: Some(Grammar::Title.build()?),
         ..Default::default()
     })?;
 
@@ -39,3 +42,48 @@ pub async fn generate_title(
 
     Ok(text)
 }
+
+pub async fn extract_action_items(
+    provider: &ModelManager,
+    system: ActionItemsSystem,
+    user: ActionItemsUser,
+) -> Result<ActionItems, crate::Error> {
+    let model = provider.get_model().await?;
+    let grammar = Grammar::JsonSchema {
+        schema: action_items_schema(&user.participants),
+    };
+
+    let system = hypr_template_app::render(hypr_template_app::Template::ActionItemsSystem(system))?;
+    let prompt =
+        hypr_template_app::render(hypr_template_app::Template::ActionItemsUser(user.clone()))?;
+
+    let stream = model.generate_stream(hypr_llama::LlamaRequest {
+        messages: vec![
+            hypr_llama::LlamaMessage {
+                role: "system".into(),
+                content: system,
+                ..Default::default()
+            },
+            hypr_llama::LlamaMessage {
+                role: "user".into(),
+                content: prompt,
+                ..Default::default()
+            },
+        ],
+        grammar: Some(grammar.build()?),
+        ..Default::default()
+    })?;
+
+    let text = stream
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .filter_map(|r| match r {
+            hypr_llama::Response::TextDelta(content) => Some(content),
+            _ => None,
+        })
+        .collect::<String>();
+
+    let items: ActionItems = serde_json::from_str(&text)?;
+    Ok(items.normalize(&user))
+}
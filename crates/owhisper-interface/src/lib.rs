@@ -28,6 +28,9 @@ common_derives! {
         pub confidence: Option<f32>,
         pub start_ms: Option<u64>,
         pub end_ms: Option<u64>,
+        /// Audio channel the word was heard on, when known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub channel: Option<u8>,
     }
 }
 
@@ -41,6 +44,7 @@ impl From<stream::Word> for Word2 {
             confidence: Some(word.confidence as f32),
             start_ms: Some((word.start * 1000.0) as u64),
             end_ms: Some((word.end * 1000.0) as u64),
+            channel: None,
         }
     }
 }
@@ -55,6 +59,7 @@ impl From<batch::Word> for Word2 {
             confidence: Some(word.confidence as f32),
             start_ms: Some((word.start * 1000.0) as u64),
             end_ms: Some((word.end * 1000.0) as u64),
+            channel: None,
         }
     }
 }
@@ -0,0 +1,256 @@
+use crate::{Participant, Segment, Session, Transcript, common_derives, filters};
+
+common_derives! {
+    #[derive(askama::Template)]
+    #[template(path = "action_items.system.md.jinja")]
+    pub struct ActionItemsSystem {
+        pub language: Option<String>,
+    }
+}
+
+common_derives! {
+    #[derive(askama::Template)]
+    #[template(path = "action_items.user.md.jinja", escape = "none")]
+    pub struct ActionItemsUser {
+        pub session: Session,
+        pub participants: Vec<Participant>,
+        pub transcripts: Vec<Transcript>,
+    }
+}
+
+impl ActionItemsUser {
+    /// Segments in the order they are numbered in the prompt, which is what
+    /// [`SourceSpan`] indexes into.
+    pub fn segments(&self) -> Vec<&Segment> {
+        self.transcripts
+            .iter()
+            .flat_map(|t| t.segments.iter())
+            .collect()
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    specta::Type,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionItems {
+    pub action_items: Vec<ActionItem>,
+    pub decisions: Vec<Decision>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    specta::Type,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionItem {
+    pub text: String,
+    /// Participant name, exactly as given in the prompt.
+    pub owner: Option<String>,
+    /// `YYYY-MM-DD`.
+    #[schemars(schema_with = "due_date_schema")]
+    pub due_date: Option<String>,
+    pub source: SourceSpan,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    specta::Type,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Decision {
+    pub text: String,
+    pub source: SourceSpan,
+}
+
+/// Inclusive range of transcript segments, see [`ActionItemsUser::segments`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    specta::Type,
+    schemars::JsonSchema,
+)]
+pub struct SourceSpan {
+    pub start: u32,
+    pub end: u32,
+}
+
+fn due_date_schema(generator: &mut schemars::r#gen::SchemaGenerator) -> schemars::schema::Schema {
+    let mut schema = generator.subschema_for::<Option<String>>().into_object();
+    schema.format = Some("date".to_string());
+    schema.into()
+}
+
+/// JSON Schema for [`ActionItems`], with owners limited to `participants`.
+///
+/// Compiled into a grammar for local models, and passed as the structured
+/// output schema to cloud ones.
+pub fn action_items_schema(participants: &[Participant]) -> serde_json::Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(ActionItems)).unwrap_or_default();
+
+    if !participants.is_empty() {
+        let names: Vec<_> = participants.iter().map(|p| p.name.clone()).collect();
+        if let Some(owner) = schema.pointer_mut("/definitions/ActionItem/properties/owner") {
+            *owner = serde_json::json!({ "anyOf": [{ "enum": names }, { "type": "null" }] });
+        }
+    }
+
+    schema
+}
+
+impl ActionItems {
+    /// Drops what the schema can't rule out: owners who aren't participants,
+    /// and spans outside the transcript.
+    pub fn normalize(mut self, user: &ActionItemsUser) -> Self {
+        let last = user.segments().len().saturating_sub(1) as u32;
+        let clamp = |span: &mut SourceSpan| {
+            span.end = span.end.min(last);
+            span.start = span.start.min(span.end);
+        };
+
+        for item in &mut self.action_items {
+            if let Some(owner) = &item.owner {
+                let owner = owner.trim();
+                item.owner = user
+                    .participants
+                    .iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(owner))
+                    .map(|p| p.name.clone());
+            }
+            clamp(&mut item.source);
+        }
+        for decision in &mut self.decisions {
+            clamp(&mut decision.source);
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use askama_utils::{tpl_assert, tpl_snapshot};
+
+    fn user() -> ActionItemsUser {
+        ActionItemsUser {
+            session: Session {
+                title: Some("Launch Sync".to_string()),
+                started_at: None,
+                ended_at: None,
+                event: None,
+            },
+            participants: vec![
+                Participant {
+                    name: "Alice".to_string(),
+                    job_title: None,
+                },
+                Participant {
+                    name: "Bob".to_string(),
+                    job_title: None,
+                },
+            ],
+            transcripts: vec![
+                Transcript {
+                    segments: vec![Segment {
+                        text: "We ship on Friday.".to_string(),
+                        speaker: "Alice".to_string(),
+                    }],
+                    started_at: None,
+                    ended_at: None,
+                },
+                Transcript {
+                    segments: vec![Segment {
+                        text: "I'll update the changelog by Thursday.".to_string(),
+                        speaker: "Bob".to_string(),
+                    }],
+                    started_at: None,
+                    ended_at: None,
+                },
+            ],
+        }
+    }
+
+    tpl_assert!(
+        test_language_as_specified,
+        ActionItemsSystem {
+            language: Some("ko".to_string()),
+        },
+        |v| v.contains("Korean")
+    );
+
+    tpl_snapshot!(
+        test_action_items_user,
+        user(),
+        @"
+    # Context
+
+
+    Session: Launch Sync
+    Participants:
+    - Alice
+      - Bob
+      
+
+    # Transcript
+
+    [0] Alice: We ship on Friday.
+    [1] Bob: I'll update the changelog by Thursday.
+    "
+    );
+
+    #[test]
+    fn test_action_items_schema() {
+        let schema = action_items_schema(&user().participants);
+
+        assert_eq!(
+            schema.pointer("/definitions/ActionItem/properties/owner/anyOf/0/enum"),
+            Some(&serde_json::json!(["Alice", "Bob"]))
+        );
+        assert_eq!(
+            schema.pointer("/definitions/ActionItem/properties/dueDate/format"),
+            Some(&serde_json::json!("date"))
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let items: ActionItems = serde_json::from_value(serde_json::json!({
+            "actionItems": [
+                { "text": "Update the changelog", "owner": "bob", "dueDate": "2025-01-02", "source": { "start": 1, "end": 1 } },
+                { "text": "Book a room", "owner": "Carol", "dueDate": null, "source": { "start": 3, "end": 9 } }
+            ],
+            "decisions": [{ "text": "Ship on Friday", "source": { "start": 0, "end": 0 } }]
+        }))
+        .unwrap();
+
+        let items = items.normalize(&user());
+        assert_eq!(items.action_items[0].owner.as_deref(), Some("Bob"));
+        assert_eq!(items.action_items[1].owner, None);
+        assert_eq!(
+            items.action_items[1].source,
+            SourceSpan { start: 1, end: 1 }
+        );
+        assert_eq!(items.decisions.len(), 1);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{Participant, Session, Transcript, common_derives, filters};
+
+common_derives! {
+    #[derive(askama::Template)]
+    #[template(path = "chunk.system.md.jinja")]
+    pub struct ChunkSystem {
+        pub language: Option<String>,
+    }
+}
+
+common_derives! {
+    #[derive(askama::Template)]
+    #[template(path = "chunk.user.md.jinja")]
+    pub struct ChunkUser {
+        pub session: Session,
+        pub participants: Vec<Participant>,
+        pub transcripts: Vec<Transcript>,
+        pub part: usize,
+        pub total: usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Segment;
+    use askama_utils::{tpl_assert, tpl_snapshot};
+
+    tpl_assert!(
+        test_language_as_specified,
+        ChunkSystem {
+            language: Some("ko".to_string()),
+        },
+        |v| v.contains("Korean")
+    );
+
+    tpl_snapshot!(
+        test_chunk_user_formatting,
+        ChunkUser {
+            session: Session {
+                title: Some("Meeting".to_string()),
+                started_at: None,
+                ended_at: None,
+                event: None,
+            },
+            participants: vec![],
+            transcripts: vec![Transcript {
+                segments: vec![Segment {
+                    text: "Ship it on Friday".to_string(),
+                    speaker: "John Doe".to_string(),
+                }],
+                started_at: None,
+                ended_at: None,
+            }],
+            part: 2,
+            total: 3,
+        }, @"
+    # Context
+
+
+    Session: Meeting
+
+    # Transcript (part 2 of 3)
+
+
+    John Doe: Ship it on Friday
+    ");
+}
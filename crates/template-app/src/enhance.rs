@@ -1,4 +1,6 @@
-use crate::{EnhanceTemplate, Participant, Session, Transcript, common_derives, filters};
+use crate::{
+    EnhanceTemplate, Participant, Session, TemplateVariables, Transcript, common_derives, filters,
+};
 
 common_derives! {
     #[derive(askama::Template)]
@@ -17,6 +19,11 @@ common_derives! {
         pub participants: Vec<Participant>,
         pub template: Option<EnhanceTemplate>,
         pub transcripts: Vec<Transcript>,
+        /// Notes from a chunked first pass; when present they replace the transcript.
+        pub partial_summaries: Vec<String>,
+        /// Resolved into `template` when rendering, see [`EnhanceUser::resolved`].
+        #[serde(default)]
+        pub variables: TemplateVariables,
     }
 }
 
@@ -102,10 +109,12 @@ mod tests {
                     TemplateSection {
                         title: "Section 1".to_string(),
                         description: Some("Section 1 description".to_string()),
+                        ..Default::default()
                     },
                     TemplateSection {
                         title: "Section 2".to_string(),
                         description: Some("Section 2 description".to_string()),
+                        ..Default::default()
                     },
                 ],
             }),
@@ -117,6 +126,8 @@ mod tests {
                 started_at: Some(1719859200),
                 ended_at: Some(1719862800),
             }],
+            partial_summaries: vec![],
+            variables: TemplateVariables::default(),
         }, @"
     # Context
 
@@ -143,4 +154,47 @@ mod tests {
     1. Section 1 - Section 1 description
     2. Section 2 - Section 2 description
     ");
+
+    tpl_snapshot!(
+        test_enhance_user_with_partial_summaries,
+        EnhanceUser {
+            session: Session {
+                title: Some("Meeting".to_string()),
+                started_at: None,
+                ended_at: None,
+                event: None,
+            },
+            participants: vec![],
+            template: None,
+            transcripts: vec![],
+            partial_summaries: vec![
+                "- Kickoff".to_string(),
+                "- Ship on Friday".to_string(),
+            ],
+            variables: TemplateVariables::default(),
+        }, @"
+    # Context
+
+
+    Session: Meeting
+
+    # Notes
+
+    The transcript was too long to read at once, so it was split into consecutive parts. These are the notes taken on each part, in order.
+
+    ## Part 1
+
+    - Kickoff
+
+    ## Part 2
+
+    - Ship on Friday
+
+    # Output Template
+
+    # Instructions
+
+    1. Analyze the content and decide the sections to use.
+    2. Generate a well-formatted markdown summary.
+    ");
 }
@@ -1,14 +1,24 @@
+mod action_items;
 mod chat;
+mod chunk;
 mod enhance;
 mod filters;
+mod meetings_chat;
+mod plan;
 mod title;
 mod types;
+mod variables;
 
+pub use action_items::*;
 pub use chat::*;
+pub use chunk::*;
 pub use enhance::*;
 pub use filters::*;
+pub use meetings_chat::*;
+pub use plan::*;
 pub use title::*;
 pub use types::*;
+pub use variables::*;
 
 #[macro_export]
 macro_rules! common_derives {
@@ -23,9 +33,14 @@ common_derives! {
     pub enum Template {
         EnhanceSystem(EnhanceSystem),
         EnhanceUser(EnhanceUser),
+        ChunkSystem(ChunkSystem),
+        ChunkUser(ChunkUser),
         TitleSystem(TitleSystem),
         TitleUser(TitleUser),
         ChatSystem(ChatSystem),
+        MeetingsChatSystem(MeetingsChatSystem),
+        ActionItemsSystem(ActionItemsSystem),
+        ActionItemsUser(ActionItemsUser),
     }
 }
 
@@ -38,10 +53,15 @@ pub enum Error {
 pub fn render(t: Template) -> Result<String, Error> {
     let value = match t {
         Template::EnhanceSystem(t) => askama::Template::render(&t),
-        Template::EnhanceUser(t) => askama::Template::render(&t),
+        Template::EnhanceUser(t) => askama::Template::render(&t.resolved()),
+        Template::ChunkSystem(t) => askama::Template::render(&t),
+        Template::ChunkUser(t) => askama::Template::render(&t),
         Template::TitleSystem(t) => askama::Template::render(&t),
         Template::TitleUser(t) => askama::Template::render(&t),
         Template::ChatSystem(t) => askama::Template::render(&t),
+        Template::MeetingsChatSystem(t) => askama::Template::render(&t),
+        Template::ActionItemsSystem(t) => askama::Template::render(&t),
+        Template::ActionItemsUser(t) => askama::Template::render(&t),
     }?;
 
     Ok(value)
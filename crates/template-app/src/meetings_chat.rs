@@ -0,0 +1,216 @@
+use crate::{common_derives, filters};
+
+common_derives! {
+    /// A passage retrieved from one of the user's meetings.
+    pub struct MeetingPassage {
+        pub session_id: String,
+        pub title: Option<String>,
+        pub date: Option<String>,
+        /// Milliseconds from the start of the recording, when the passage
+        /// comes from the transcript.
+        pub start_ms: Option<u64>,
+        pub text: String,
+    }
+}
+
+impl MeetingPassage {
+    /// `m:ss`, or `h:mm:ss` past the first hour.
+    pub fn timestamp(&self) -> Option<String> {
+        let secs = self.start_ms? / 1000;
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+        Some(if h > 0 {
+            format!("{h}:{m:02}:{s:02}")
+        } else {
+            format!("{m}:{s:02}")
+        })
+    }
+}
+
+common_derives! {
+    #[derive(askama::Template)]
+    #[template(path = "meetings_chat.system.md.jinja", escape = "none")]
+    pub struct MeetingsChatSystem {
+        pub language: Option<String>,
+        /// Numbered from 1 in the prompt, which is what the answer cites.
+        pub passages: Vec<MeetingPassage>,
+    }
+}
+
+common_derives! {
+    #[derive(Debug, PartialEq)]
+    pub struct Citation {
+        /// The `[n]` marker used in the answer.
+        pub marker: u32,
+        pub session_id: String,
+        pub start_ms: Option<u64>,
+    }
+}
+
+/// Citations in the order they first appear in `answer`. Markers that don't
+/// match a passage are ignored, since models occasionally make them up.
+pub fn collect_citations(answer: &str, passages: &[MeetingPassage]) -> Vec<Citation> {
+    let mut citations: Vec<Citation> = Vec::new();
+
+    for marker in citation_markers(answer) {
+        if citations.iter().any(|c| c.marker == marker) {
+            continue;
+        }
+
+        let Some(passage) = passages.get((marker as usize).wrapping_sub(1)) else {
+            continue;
+        };
+
+        citations.push(Citation {
+            marker,
+            session_id: passage.session_id.clone(),
+            start_ms: passage.start_ms,
+        });
+    }
+
+    citations
+}
+
+// `[1]`, and the `[1, 3]` models write when several passages agree.
+fn citation_markers(answer: &str) -> Vec<u32> {
+    let mut markers = Vec::new();
+    let mut rest = answer;
+
+    while let Some(open) = rest.find('[') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+
+        let inner: Option<Vec<u32>> = rest[..close]
+            .split(',')
+            .map(|n| n.trim().parse().ok())
+            .collect();
+        if let Some(inner) = inner {
+            markers.extend(inner);
+            rest = &rest[close + 1..];
+        }
+    }
+
+    markers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use askama_utils::tpl_snapshot_with_assert;
+
+    fn passage(session_id: &str, start_ms: Option<u64>, text: &str) -> MeetingPassage {
+        MeetingPassage {
+            session_id: session_id.to_string(),
+            title: Some("Pricing review".to_string()),
+            date: Some("2025-01-10".to_string()),
+            start_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(passage("a", None, "").timestamp(), None);
+        assert_eq!(
+            passage("a", Some(65_400), "").timestamp().as_deref(),
+            Some("1:05")
+        );
+        assert_eq!(
+            passage("a", Some(3_723_000), "").timestamp().as_deref(),
+            Some("1:02:03")
+        );
+    }
+
+    #[test]
+    fn test_collect_citations() {
+        let passages = vec![
+            passage("a", Some(1_000), "We raise prices in Q3."),
+            passage("b", None, "Pricing stays flat for enterprise."),
+        ];
+
+        let citations = collect_citations(
+            "Prices go up in Q3 [1], except for enterprise [2, 1]. See also [7] and [link].",
+            &passages,
+        );
+
+        assert_eq!(
+            citations,
+            vec![
+                Citation {
+                    marker: 1,
+                    session_id: "a".to_string(),
+                    start_ms: Some(1_000),
+                },
+                Citation {
+                    marker: 2,
+                    session_id: "b".to_string(),
+                    start_ms: None,
+                },
+            ]
+        );
+        assert!(collect_citations("[0]", &passages).is_empty());
+    }
+
+    tpl_snapshot_with_assert!(
+        test_meetings_chat_system,
+        MeetingsChatSystem {
+            language: None,
+            passages: vec![
+                passage("a", Some(754_000), "Speaker 1: Let's raise prices in Q3."),
+                passage("b", None, "Enterprise pricing stays flat this year."),
+            ],
+        },
+        |v| v.contains("[1] Pricing review (2025-01-10, at 12:34)"),
+        @r#"
+    # General Instructions
+
+    Current date: 2025-01-01
+
+    - You are a helpful AI meeting assistant in Hyprnote. The user is asking a question about their past meetings, and you answer it from the passages below, which were retrieved from those meetings.
+    - Always respond in English, unless the user explicitly asks for a different language.
+    - Keep your answer concise and directly relevant to the question.
+
+    # Citations
+
+    - Cite every claim with the number of the passage it comes from, in square brackets, like [1] or [2, 3].
+    - Only cite passages listed below, and only for what they actually say.
+    - If the passages don't answer the question, say so instead of guessing.
+
+    # Passages
+
+    [1] Pricing review (2025-01-10, at 12:34)
+    Speaker 1: Let's raise prices in Q3.
+
+    [2] Pricing review (2025-01-10)
+    Enterprise pricing stays flat this year.
+    "#);
+
+    tpl_snapshot_with_assert!(
+        test_meetings_chat_system_without_passages,
+        MeetingsChatSystem {
+            language: Some("ko".to_string()),
+            passages: vec![],
+        },
+        |v| v.contains("No passages matched"),
+        @r#"
+    # General Instructions
+
+    Current date: 2025-01-01
+
+    - You are a helpful AI meeting assistant in Hyprnote. The user is asking a question about their past meetings, and you answer it from the passages below, which were retrieved from those meetings.
+    - Always respond in Korean, unless the user explicitly asks for a different language.
+    - Keep your answer concise and directly relevant to the question.
+
+    # Citations
+
+    - Cite every claim with the number of the passage it comes from, in square brackets, like [1] or [2, 3].
+    - Only cite passages listed below, and only for what they actually say.
+    - If the passages don't answer the question, say so instead of guessing.
+
+    # Passages
+
+    No passages matched the question. Tell the user you couldn't find it in their meetings.
+    "#);
+}
@@ -0,0 +1,232 @@
+use crate::{Segment, Transcript};
+
+// Rough average for English and most Latin-script text; CJK runs denser, which
+// only makes the plan more conservative.
+const CHARS_PER_TOKEN: usize = 4;
+// "Speaker: " prefix and line break the templates add around each segment.
+const SEGMENT_OVERHEAD_TOKENS: usize = 4;
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+fn segment_tokens(segment: &Segment) -> usize {
+    estimate_tokens(&segment.speaker) + estimate_tokens(&segment.text) + SEGMENT_OVERHEAD_TOKENS
+}
+
+pub fn transcripts_tokens(transcripts: &[Transcript]) -> usize {
+    transcripts
+        .iter()
+        .flat_map(|t| t.segments.iter())
+        .map(segment_tokens)
+        .sum()
+}
+
+/// Splits `transcripts` into consecutive chunks of at most `max_tokens` each.
+///
+/// Cuts fall between speaker turns. A new recording (transcript) starts a new
+/// chunk once the current one is half full, so chunks follow the timeline
+/// instead of straddling a break. A single turn longer than the budget is split
+/// on word boundaries.
+pub fn plan_chunks(transcripts: &[Transcript], max_tokens: usize) -> Vec<Vec<Transcript>> {
+    let max_tokens = max_tokens.max(SEGMENT_OVERHEAD_TOKENS * 2);
+
+    let mut chunks: Vec<Vec<Transcript>> = Vec::new();
+    let mut current: Vec<Transcript> = Vec::new();
+    let mut current_tokens = 0;
+
+    for transcript in transcripts {
+        if current_tokens >= max_tokens / 2 {
+            chunks.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+
+        for segment in transcript
+            .segments
+            .iter()
+            .flat_map(|s| split_segment(s, max_tokens))
+        {
+            let tokens = segment_tokens(&segment);
+
+            if current_tokens > 0 && current_tokens + tokens > max_tokens {
+                chunks.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+
+            match current.last_mut() {
+                Some(last)
+                    if last.started_at == transcript.started_at
+                        && last.ended_at == transcript.ended_at =>
+                {
+                    last.segments.push(segment)
+                }
+                _ => current.push(Transcript {
+                    segments: vec![segment],
+                    started_at: transcript.started_at,
+                    ended_at: transcript.ended_at,
+                }),
+            }
+            current_tokens += tokens;
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn split_segment(segment: &Segment, max_tokens: usize) -> Vec<Segment> {
+    if max_tokens == 0 || segment_tokens(segment) <= max_tokens {
+        return vec![segment.clone()];
+    }
+
+    // A speaker name that alone exceeds the budget still gets one word per piece.
+    let budget = max_tokens
+        .saturating_sub(estimate_tokens(&segment.speaker))
+        .saturating_sub(SEGMENT_OVERHEAD_TOKENS);
+    let max_chars = budget.max(1) * CHARS_PER_TOKEN;
+
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    for word in segment.text.split_whitespace() {
+        if !piece.is_empty() && piece.chars().count() + 1 + word.chars().count() > max_chars {
+            pieces.push(std::mem::take(&mut piece));
+        }
+        if !piece.is_empty() {
+            piece.push(' ');
+        }
+        piece.push_str(word);
+    }
+    if !piece.is_empty() {
+        pieces.push(piece);
+    }
+
+    pieces
+        .into_iter()
+        .map(|text| Segment {
+            text,
+            speaker: segment.speaker.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(started_at: u64, turns: &[(&str, &str)]) -> Transcript {
+        Transcript {
+            segments: turns
+                .iter()
+                .map(|(speaker, text)| Segment {
+                    speaker: speaker.to_string(),
+                    text: text.to_string(),
+                })
+                .collect(),
+            started_at: Some(started_at),
+            ended_at: Some(started_at + 60),
+        }
+    }
+
+    fn texts(chunk: &[Transcript]) -> Vec<String> {
+        chunk
+            .iter()
+            .flat_map(|t| t.segments.iter().map(|s| s.text.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fits_in_one_chunk() {
+        let transcripts = vec![transcript(0, &[("Alice", "Hello"), ("Bob", "Hi there")])];
+        let chunks = plan_chunks(&transcripts, 1000);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(texts(&chunks[0]), vec!["Hello", "Hi there"]);
+    }
+
+    #[test]
+    fn test_cuts_between_turns_in_order() {
+        let turn = "word ".repeat(20);
+        let turns: Vec<(&str, &str)> = (0..10)
+            .map(|i| (if i % 2 == 0 { "Alice" } else { "Bob" }, turn.as_str()))
+            .collect();
+        let transcripts = vec![transcript(0, &turns)];
+
+        let max_tokens = 100;
+        let chunks = plan_chunks(&transcripts, max_tokens);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(transcripts_tokens(chunk) <= max_tokens);
+        }
+
+        let rejoined: Vec<String> = chunks.iter().flat_map(|c| texts(c)).collect();
+        assert_eq!(rejoined, texts(&transcripts));
+    }
+
+    #[test]
+    fn test_keeps_recording_boundaries_and_times() {
+        let turn = "word ".repeat(20);
+        let transcripts = vec![
+            transcript(0, &[("Alice", turn.as_str()), ("Bob", turn.as_str())]),
+            transcript(3600, &[("Alice", turn.as_str())]),
+        ];
+
+        let chunks = plan_chunks(&transcripts, 100);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0][0].started_at, Some(0));
+        assert_eq!(chunks[1][0].started_at, Some(3600));
+    }
+
+    #[test]
+    fn test_splits_oversized_turn() {
+        let long = "word ".repeat(500);
+        let transcripts = vec![transcript(0, &[("Alice", long.trim())])];
+
+        let chunks = plan_chunks(&transcripts, 100);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(transcripts_tokens(chunk) <= 100);
+            assert!(chunk[0].segments.iter().all(|s| s.speaker == "Alice"));
+        }
+        let words: usize = chunks
+            .iter()
+            .flat_map(|c| texts(c))
+            .map(|t| t.split_whitespace().count())
+            .sum();
+        assert_eq!(words, 500);
+    }
+
+    #[test]
+    fn test_split_segment_edge_budgets() {
+        let segment = Segment {
+            speaker: "A very long speaker name ".repeat(10),
+            text: "one two three".to_string(),
+        };
+
+        let pieces = split_segment(&segment, 10);
+        assert_eq!(
+            pieces.iter().map(|p| p.text.as_str()).collect::<Vec<_>>(),
+            vec!["one", "two", "three"]
+        );
+
+        let whole = split_segment(&segment, 0);
+        assert_eq!(whole.len(), 1);
+        assert_eq!(whole[0].text, segment.text);
+
+        let chunks = plan_chunks(
+            &[transcript(0, &[(segment.speaker.as_str(), "one two")])],
+            0,
+        );
+        let words: usize = chunks
+            .iter()
+            .flat_map(|c| texts(c))
+            .map(|t| t.split_whitespace().count())
+            .sum();
+        assert_eq!(words, 2);
+    }
+}
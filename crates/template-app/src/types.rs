@@ -1,4 +1,4 @@
-use crate::common_derives;
+use crate::{SectionCondition, SectionFormat, common_derives};
 
 common_derives! {
     pub struct Transcript {
@@ -18,6 +18,8 @@ common_derives! {
 common_derives! {
     pub struct Event {
         pub name: String,
+        pub started_at: Option<String>,
+        pub ended_at: Option<String>,
     }
 }
 
@@ -38,9 +40,17 @@ common_derives! {
 }
 
 common_derives! {
+    #[derive(Default)]
     pub struct TemplateSection {
         pub title: String,
         pub description: Option<String>,
+        /// Extra guidance for the model, on top of the description.
+        #[serde(default)]
+        pub instructions: Option<String>,
+        #[serde(default)]
+        pub format: Option<SectionFormat>,
+        #[serde(default)]
+        pub condition: Option<SectionCondition>,
     }
 }
 
@@ -140,7 +150,11 @@ mod tests {
                 title: Some("Team Sync".to_string()),
                 started_at: Some("2025-01-01 10:00".to_string()),
                 ended_at: Some("2025-01-01 11:00".to_string()),
-                event: Some(Event { name: "Calendar Event".to_string() }),
+                event: Some(Event {
+                    name: "Calendar Event".to_string(),
+                    started_at: Some("2025-01-01 10:00".to_string()),
+                    ended_at: Some("2025-01-01 11:00".to_string()),
+                }),
             }),
         },
         @"
@@ -215,8 +229,8 @@ mod tests {
                 title: "Meeting Notes".to_string(),
                 description: Some("Standard meeting format".to_string()),
                 sections: vec![
-                    TemplateSection { title: "Summary".to_string(), description: Some("Brief overview".to_string()) },
-                    TemplateSection { title: "Action Items".to_string(), description: None },
+                    TemplateSection { title: "Summary".to_string(), description: Some("Brief overview".to_string()), ..Default::default() },
+                    TemplateSection { title: "Action Items".to_string(), description: None, ..Default::default() },
                 ],
             }),
         },
@@ -234,6 +248,34 @@ mod tests {
     "
     );
 
+    tpl_snapshot!(
+        test_macro_template_numbered_instructions,
+        TestTemplateNumbered {
+            template: Some(EnhanceTemplate {
+                title: "Customer Call".to_string(),
+                description: None,
+                sections: vec![
+                    TemplateSection { title: "Requests".to_string(), description: None, instructions: Some("Quote the customer where possible".to_string()), format: Some(crate::SectionFormat::Table), condition: None },
+                    TemplateSection { title: "Follow-ups".to_string(), description: None, format: Some(crate::SectionFormat::Checklist), ..Default::default() },
+                ],
+            }),
+        },
+        @"
+
+
+    # Summary Template
+
+    Name: Customer Call
+
+    Sections:
+    1. Requests
+       Instructions: Quote the customer where possible
+       Format: write this section as a markdown table.
+    2. Follow-ups
+       Format: write this section as a checklist of `- [ ]` items.
+    "
+    );
+
     tpl_snapshot!(
         test_macro_template_numbered_none,
         TestTemplateNumbered {
@@ -0,0 +1,431 @@
+use crate::{EnhanceTemplate, EnhanceUser, TemplateSection, common_derives};
+
+/// Variables user templates can reference as `{{ name }}`.
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "participants",
+    "participants.count",
+    "external_participants",
+    "calendar.title",
+    "calendar.start",
+    "calendar.end",
+    "previous_meeting.title",
+    "previous_meeting.date",
+    "previous_meeting.summary",
+];
+
+const NOT_AVAILABLE: &str = "(not available)";
+
+common_derives! {
+    pub struct PreviousMeeting {
+        pub title: Option<String>,
+        pub date: Option<String>,
+        /// The enhanced note of that meeting.
+        pub summary: String,
+    }
+}
+
+common_derives! {
+    /// What user templates can see beyond the session and its participants.
+    #[derive(Default)]
+    pub struct TemplateVariables {
+        /// Names of participants from outside the user's organization.
+        pub external_participants: Vec<String>,
+        /// The last meeting of the same calendar series, if any.
+        pub previous_meeting: Option<PreviousMeeting>,
+    }
+}
+
+common_derives! {
+    #[derive(Debug, Copy, PartialEq)]
+    pub enum SectionFormat {
+        Paragraphs,
+        Bullets,
+        Table,
+        Checklist,
+    }
+}
+
+impl SectionFormat {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Paragraphs => "short paragraphs",
+            Self::Bullets => "a bulleted list",
+            Self::Table => "a markdown table",
+            Self::Checklist => "a checklist of `- [ ]` items",
+        }
+    }
+}
+
+common_derives! {
+    /// When a section is included; sections without one always are.
+    #[derive(Debug, PartialEq)]
+    #[serde(tag = "type")]
+    pub enum SectionCondition {
+        /// Someone from outside the user's organization attended.
+        ExternalParticipants,
+        /// `variable` resolves to a value, e.g. there was a previous meeting.
+        HasValue { variable: String },
+        /// A participant with this name attended.
+        ParticipantPresent { name: String },
+    }
+}
+
+common_derives! {
+    #[derive(Debug, PartialEq)]
+    pub struct TemplateIssue {
+        /// Index of the offending section, or `None` for the template itself.
+        pub section: Option<u32>,
+        pub message: String,
+    }
+}
+
+enum Placeholder<'a> {
+    Variable(&'a str),
+    Invalid(String),
+}
+
+// `{{ name }}` placeholders in user text. Anything richer than a variable
+// name is reported rather than interpreted, since the text comes from users.
+fn placeholders(text: &str) -> Vec<Placeholder<'_>> {
+    let mut found = Vec::new();
+    let mut rest = text;
+
+    if text.contains("{%") {
+        found.push(Placeholder::Invalid(
+            "`{% %}` blocks are not supported; use a section condition instead".to_string(),
+        ));
+    }
+
+    while let Some(open) = rest.find("{{") {
+        rest = &rest[open + 2..];
+        let Some(close) = rest.find("}}") else {
+            found.push(Placeholder::Invalid("`{{` is never closed".to_string()));
+            break;
+        };
+
+        let name = rest[..close].trim();
+        let is_path = !name.is_empty()
+            && name.split('.').all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            });
+
+        found.push(if is_path {
+            Placeholder::Variable(name)
+        } else {
+            Placeholder::Invalid(format!(
+                "`{{{{ {name} }}}}` is not a variable name, like `{{{{ participants }}}}`"
+            ))
+        });
+        rest = &rest[close + 2..];
+    }
+
+    found
+}
+
+fn check_text(text: &str, section: Option<u32>, issues: &mut Vec<TemplateIssue>) {
+    for placeholder in placeholders(text) {
+        let message = match placeholder {
+            Placeholder::Variable(name) if TEMPLATE_VARIABLES.contains(&name) => continue,
+            Placeholder::Variable(name) => format!(
+                "Unknown variable `{name}`. Available: {}",
+                TEMPLATE_VARIABLES.join(", ")
+            ),
+            Placeholder::Invalid(message) => message,
+        };
+        issues.push(TemplateIssue { section, message });
+    }
+}
+
+/// Everything that would make a user template render differently than its
+/// author expects. Meant to be shown while editing, long before it's used.
+pub fn validate_template(template: &EnhanceTemplate) -> Vec<TemplateIssue> {
+    let mut issues = Vec::new();
+
+    check_text(&template.title, None, &mut issues);
+    if let Some(description) = &template.description {
+        check_text(description, None, &mut issues);
+    }
+
+    for (index, section) in template.sections.iter().enumerate() {
+        let index = Some(index as u32);
+
+        if section.title.trim().is_empty() {
+            issues.push(TemplateIssue {
+                section: index,
+                message: "Section title is empty".to_string(),
+            });
+        }
+
+        for text in [
+            Some(&section.title),
+            section.description.as_ref(),
+            section.instructions.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            check_text(text, index, &mut issues);
+        }
+
+        match &section.condition {
+            Some(SectionCondition::HasValue { variable })
+                if !TEMPLATE_VARIABLES.contains(&variable.as_str()) =>
+            {
+                issues.push(TemplateIssue {
+                    section: index,
+                    message: format!("Condition uses unknown variable `{variable}`"),
+                });
+            }
+            Some(SectionCondition::ParticipantPresent { name }) if name.trim().is_empty() => {
+                issues.push(TemplateIssue {
+                    section: index,
+                    message: "Condition needs a participant name".to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    issues
+}
+
+impl EnhanceUser {
+    fn variable(&self, name: &str) -> Option<String> {
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let event = self.session.event.as_ref();
+        let previous = self.variables.previous_meeting.as_ref();
+
+        match name {
+            "participants" => non_empty(
+                &self
+                    .participants
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            "participants.count" => Some(self.participants.len().to_string()),
+            "external_participants" => non_empty(&self.variables.external_participants.join(", ")),
+            "calendar.title" => non_empty(&event?.name),
+            "calendar.start" => event?.started_at.clone(),
+            "calendar.end" => event?.ended_at.clone(),
+            "previous_meeting.title" => previous?.title.clone(),
+            "previous_meeting.date" => previous?.date.clone(),
+            "previous_meeting.summary" => non_empty(previous?.summary.trim()),
+            _ => None,
+        }
+    }
+
+    fn interpolate(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(open) = rest.find("{{") {
+            let Some(close) = rest[open..].find("}}").map(|i| open + i) else {
+                break;
+            };
+
+            let name = rest[open + 2..close].trim();
+            out.push_str(&rest[..open]);
+            if TEMPLATE_VARIABLES.contains(&name) {
+                out.push_str(
+                    &self
+                        .variable(name)
+                        .unwrap_or_else(|| NOT_AVAILABLE.to_string()),
+                );
+            } else {
+                // Left as written; `validate_template` already told the author.
+                out.push_str(&rest[open..close + 2]);
+            }
+            rest = &rest[close + 2..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    fn includes(&self, section: &TemplateSection) -> bool {
+        match &section.condition {
+            None => true,
+            Some(SectionCondition::ExternalParticipants) => {
+                !self.variables.external_participants.is_empty()
+            }
+            Some(SectionCondition::HasValue { variable }) => self.variable(variable).is_some(),
+            Some(SectionCondition::ParticipantPresent { name }) => self
+                .participants
+                .iter()
+                .any(|p| p.name.trim().eq_ignore_ascii_case(name.trim())),
+        }
+    }
+
+    /// The template as the model should see it: sections whose condition
+    /// doesn't hold are dropped, and variables are filled in.
+    pub fn resolved(&self) -> Self {
+        let template = self.template.as_ref().map(|template| EnhanceTemplate {
+            title: self.interpolate(&template.title),
+            description: template.description.as_deref().map(|d| self.interpolate(d)),
+            sections: template
+                .sections
+                .iter()
+                .filter(|section| self.includes(section))
+                .map(|section| TemplateSection {
+                    title: self.interpolate(&section.title),
+                    description: section.description.as_deref().map(|d| self.interpolate(d)),
+                    instructions: section.instructions.as_deref().map(|i| self.interpolate(i)),
+                    format: section.format,
+                    condition: None,
+                })
+                .collect(),
+        });
+
+        Self {
+            template,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, Participant, Session};
+
+    fn section(title: &str) -> TemplateSection {
+        TemplateSection {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn user(template: EnhanceTemplate, variables: TemplateVariables) -> EnhanceUser {
+        EnhanceUser {
+            session: Session {
+                title: Some("Pricing follow-up".to_string()),
+                started_at: Some("2025-01-15 10:04".to_string()),
+                ended_at: None,
+                event: Some(Event {
+                    name: "Acme sync".to_string(),
+                    started_at: Some("2025-01-15 10:00".to_string()),
+                    ended_at: Some("2025-01-15 10:30".to_string()),
+                }),
+            },
+            participants: vec![
+                Participant {
+                    name: "John".to_string(),
+                    job_title: None,
+                },
+                Participant {
+                    name: "Mia".to_string(),
+                    job_title: None,
+                },
+            ],
+            template: Some(template),
+            transcripts: vec![],
+            partial_summaries: vec![],
+            variables,
+        }
+    }
+
+    #[test]
+    fn test_validate_template() {
+        let template = EnhanceTemplate {
+            title: "Sync with {{ calendar.title }}".to_string(),
+            description: Some("{% if vip %}VIP{% endif %}".to_string()),
+            sections: vec![
+                TemplateSection {
+                    instructions: Some("Compare with {{ previous_meeting.summary }}".to_string()),
+                    ..section("Recap")
+                },
+                TemplateSection {
+                    description: Some("{{ attendees }} and {{ participants | upper }}".to_string()),
+                    condition: Some(SectionCondition::HasValue {
+                        variable: "budget".to_string(),
+                    }),
+                    ..section("")
+                },
+                section("Next steps {{ calendar.title"),
+            ],
+        };
+
+        let issues = validate_template(&template);
+        let sections: Vec<_> = issues.iter().map(|i| i.section).collect();
+        assert_eq!(
+            sections,
+            vec![None, Some(1), Some(1), Some(1), Some(1), Some(2)]
+        );
+        assert!(
+            issues[2]
+                .message
+                .starts_with("Unknown variable `attendees`")
+        );
+        assert!(issues[3].message.contains("participants | upper"));
+    }
+
+    #[test]
+    fn test_resolved() {
+        let template = EnhanceTemplate {
+            title: "{{ calendar.title }} notes, {{ calendar.start }}".to_string(),
+            description: None,
+            sections: vec![
+                TemplateSection {
+                    description: Some(
+                        "With {{ participants }} ({{ participants.count }})".to_string(),
+                    ),
+                    format: Some(SectionFormat::Bullets),
+                    ..section("Discussion")
+                },
+                TemplateSection {
+                    instructions: Some(
+                        "What changed since: {{ previous_meeting.summary }}".to_string(),
+                    ),
+                    condition: Some(SectionCondition::HasValue {
+                        variable: "previous_meeting.summary".to_string(),
+                    }),
+                    ..section("Since last time")
+                },
+                TemplateSection {
+                    condition: Some(SectionCondition::ExternalParticipants),
+                    ..section("Customer asks from {{ external_participants }}")
+                },
+                TemplateSection {
+                    condition: Some(SectionCondition::ParticipantPresent {
+                        name: "mia".to_string(),
+                    }),
+                    ..section("Design review {{ unknown }}")
+                },
+            ],
+        };
+
+        let resolved = user(template.clone(), TemplateVariables::default()).resolved();
+        let resolved = resolved.template.unwrap();
+        assert_eq!(resolved.title, "Acme sync notes, 2025-01-15 10:00");
+        assert_eq!(
+            resolved
+                .sections
+                .iter()
+                .map(|s| s.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Discussion", "Design review {{ unknown }}"]
+        );
+        assert_eq!(
+            resolved.sections[0].description.as_deref(),
+            Some("With John, Mia (2)")
+        );
+
+        let variables = TemplateVariables {
+            external_participants: vec!["Mia".to_string()],
+            previous_meeting: Some(PreviousMeeting {
+                title: None,
+                date: None,
+                summary: "- Pricing draft\n".to_string(),
+            }),
+        };
+        let resolved = user(template, variables).resolved().template.unwrap();
+        assert_eq!(resolved.sections.len(), 4);
+        assert_eq!(
+            resolved.sections[1].instructions.as_deref(),
+            Some("What changed since: - Pricing draft")
+        );
+        assert_eq!(resolved.sections[2].title, "Customer asks from Mia");
+    }
+}
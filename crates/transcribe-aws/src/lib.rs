@@ -164,6 +164,7 @@ impl TranscribeService {
                                             confidence: None,
                                             start_ms: Some((result.start_time * 1000.0) as u64),
                                             end_ms: Some((result.end_time * 1000.0) as u64),
+                                            channel: None,
                                         });
                                     }
 
@@ -129,6 +129,7 @@ impl TranscribeService {
                                         confidence: Some(word.confidence as f32),
                                         start_ms: Some((word.start * 1000.0) as u64),
                                         end_ms: Some((word.end * 1000.0) as u64),
+                                        channel: None,
                                     });
                                 }
                             } else if !first_alt.transcript.is_empty() {
@@ -139,6 +140,7 @@ impl TranscribeService {
                                         confidence: Some(first_alt.confidence as f32),
                                         start_ms: None,
                                         end_ms: None,
+                                        channel: None,
                                     });
                                 }
                             }
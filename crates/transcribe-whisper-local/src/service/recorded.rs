@@ -47,6 +47,7 @@ pub fn process_recorded(
                 confidence: Some(whisper_segment.confidence()),
                 start_ms: Some(start_ms),
                 end_ms: Some(end_ms),
+                channel: None,
             };
 
             // TODO
@@ -1,11 +1,13 @@
 mod continuous;
 mod continuous2;
 mod error;
+mod mask;
 mod streaming;
 
 pub use continuous::*;
 pub use continuous2::*;
 pub use error::*;
+pub use mask::*;
 pub use streaming::*;
 
 #[cfg(test)]
@@ -0,0 +1,48 @@
+use hypr_audio_utils::AudioProcessor;
+
+use crate::{StreamingVad, VadConfig};
+
+/// Zeroes out frames the streaming VAD classifies as non-speech.
+#[derive(Default)]
+pub struct VadMask {
+    vad: Option<StreamingVad>,
+    cfg: VadConfig,
+}
+
+impl VadMask {
+    pub fn new(cfg: VadConfig) -> Self {
+        Self { vad: None, cfg }
+    }
+}
+
+impl AudioProcessor for VadMask {
+    fn name(&self) -> &'static str {
+        "vad_mask"
+    }
+
+    fn process(
+        &mut self,
+        frame: &mut [f32],
+        _reference: Option<&[f32]>,
+    ) -> Result<(), hypr_audio_utils::Error> {
+        if frame.is_empty() {
+            return Ok(());
+        }
+
+        let vad = self
+            .vad
+            .get_or_insert_with(|| StreamingVad::with_config(frame.len(), self.cfg.clone()));
+
+        vad.process_in_place(frame, |chunk, is_speech| {
+            if !is_speech {
+                chunk.fill(0.0);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.vad = None;
+    }
+}
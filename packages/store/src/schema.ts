@@ -99,9 +99,18 @@ export const mappingTagSessionSchema = z.object({
   session_id: z.string(),
 });
 
+const sectionConditionSchema = z.discriminatedUnion("type", [
+  z.object({ type: z.literal("externalParticipants") }),
+  z.object({ type: z.literal("hasValue"), variable: z.string() }),
+  z.object({ type: z.literal("participantPresent"), name: z.string() }),
+]);
+
 export const templateSectionSchema = z.object({
   title: z.string(),
   description: z.string(),
+  instructions: z.string().optional(),
+  format: z.enum(["paragraphs", "bullets", "table", "checklist"]).optional(),
+  condition: sectionConditionSchema.optional(),
 });
 
 export const templateSchema = z.object({
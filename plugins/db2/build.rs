@@ -1,4 +1,4 @@
-const COMMANDS: &[&str] = &["execute_local", "execute_cloud"];
+const COMMANDS: &[&str] = &["execute_local", "execute_cloud", "store_action_items"];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
@@ -21,6 +21,14 @@ async executeCloud(sql: string, args: string[]) : Promise<Result<JsonValue[], st
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async storeActionItems(sessionId: string, userId: string, participants: Human[], user: ActionItemsUser, extracted: ActionItems) : Promise<Result<ActionItems, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:db2|store_action_items", { sessionId, userId, participants, user, extracted }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
@@ -34,7 +42,29 @@ async executeCloud(sql: string, args: string[]) : Promise<Result<JsonValue[], st
 
 /** user-defined types **/
 
+export type ActionItem = { text: string; 
+/**
+ * Participant name, exactly as given in the prompt.
+ */
+owner: string | null; 
+/**
+ * `YYYY-MM-DD`.
+ */
+dueDate: string | null; source: SourceSpan }
+export type ActionItems = { actionItems: ActionItem[]; decisions: Decision[] }
+export type ActionItemsUser = { session: Session; participants: Participant[]; transcripts: Transcript[] }
+export type Decision = { text: string; source: SourceSpan }
+export type Event = { name: string; startedAt: string | null; endedAt: string | null }
+export type Human = { id: string; organization_id: string | null; is_user: boolean; full_name: string | null; email: string | null; job_title: string | null; linkedin_username: string | null }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+export type Participant = { name: string; jobTitle: string | null }
+export type Segment = { text: string; speaker: string }
+export type Session = { title: string | null; startedAt: string | null; endedAt: string | null; event: Event | null }
+/**
+ * Inclusive range of transcript segments, see [`ActionItemsUser::segments`].
+ */
+export type SourceSpan = { start: number; end: number }
+export type Transcript = { segments: Segment[]; startedAt: number | null; endedAt: number | null }
 
 /** tauri-specta globals **/
 
@@ -0,0 +1,101 @@
+use hypr_db_user::{ActionItem, Decision, GetSessionFilter, Human, Session, UserDatabase};
+use hypr_template_app::ActionItems;
+
+/// Stores an extraction for the session in place of the previous one.
+///
+/// Sessions and people live in the frontend store, but the user database
+/// keys action items to its own rows, so any that are missing are created
+/// first. Existing ones are left as they are. Items whose owner isn't one of
+/// `participants` are kept without one.
+pub async fn store(
+    db: &UserDatabase,
+    session_id: &str,
+    user_id: &str,
+    title: Option<String>,
+    participants: &[Human],
+    extracted: &ActionItems,
+) -> Result<(), crate::Error> {
+    for human in participants {
+        if db.get_human(&human.id).await?.is_none() {
+            db.upsert_human(human.clone()).await?;
+        }
+    }
+
+    // The session row needs its owner even when the user isn't a participant.
+    if db.get_human(user_id).await?.is_none() {
+        db.upsert_human(Human {
+            id: user_id.to_string(),
+            is_user: true,
+            ..Default::default()
+        })
+        .await?;
+    }
+
+    if db
+        .get_session(GetSessionFilter::Id(session_id.to_string()))
+        .await?
+        .is_none()
+    {
+        let now = chrono::Utc::now();
+        db.upsert_session(Session {
+            id: session_id.to_string(),
+            created_at: now,
+            visited_at: now,
+            user_id: user_id.to_string(),
+            calendar_event_id: None,
+            title: title.unwrap_or_default(),
+            raw_memo_html: "".to_string(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+            words: vec![],
+            record_start: None,
+            record_end: None,
+            pre_meeting_memo_html: None,
+        })
+        .await?;
+    }
+
+    let now = chrono::Utc::now();
+
+    let items = extracted
+        .action_items
+        .iter()
+        .map(|item| {
+            let owner = item
+                .owner
+                .as_deref()
+                .and_then(|name| hypr_db_user::match_participant(name, participants));
+
+            ActionItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                session_id: session_id.to_string(),
+                text: item.text.clone(),
+                owner_id: owner.map(|h| h.id.clone()),
+                owner_name: item.owner.clone(),
+                due_date: item.due_date.clone(),
+                source_start: item.source.start,
+                source_end: item.source.end,
+                completed: false,
+                external_url: None,
+                created_at: now,
+            }
+        })
+        .collect();
+    db.replace_session_action_items(session_id, items).await?;
+
+    let decisions = extracted
+        .decisions
+        .iter()
+        .map(|decision| Decision {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            text: decision.text.clone(),
+            source_start: decision.source.start,
+            source_end: decision.source.end,
+            created_at: now,
+        })
+        .collect();
+    db.replace_session_decisions(session_id, decisions).await?;
+
+    Ok(())
+}
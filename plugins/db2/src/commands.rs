@@ -25,3 +25,19 @@ pub(crate) async fn execute_cloud<R: tauri::Runtime>(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn store_action_items<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    session_id: String,
+    user_id: String,
+    participants: Vec<hypr_db_user::Human>,
+    user: hypr_template_app::ActionItemsUser,
+    extracted: hypr_template_app::ActionItems,
+) -> Result<hypr_template_app::ActionItems, String> {
+    app.db2()
+        .store_action_items(session_id, user_id, participants, user, extracted)
+        .await
+        .map_err(|e| e.to_string())
+}
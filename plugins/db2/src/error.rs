@@ -7,9 +7,13 @@ pub enum Error {
     #[error(transparent)]
     HyprDbError(#[from] hypr_db_core::Error),
     #[error(transparent)]
+    HyprDbUserError(#[from] hypr_db_user::Error),
+    #[error(transparent)]
     TauriError(#[from] tauri::Error),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+    #[error("Database not ready")]
+    DatabaseNotReady,
 }
 
 impl Serialize for Error {
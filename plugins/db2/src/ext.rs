@@ -4,7 +4,9 @@ pub struct Database2<'a, R: tauri::Runtime, M: tauri::Manager<R>> {
 }
 
 impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Database2<'a, R, M> {
-    pub async fn init_local(&self) -> Result<(), crate::Error> {
+    /// With `encryption_key`, the database file is encrypted at rest, and an
+    /// existing plain one is converted on the way.
+    pub async fn init_local(&self, encryption_key: Option<Vec<u8>>) -> Result<(), crate::Error> {
         let db = {
             if cfg!(debug_assertions) {
                 hypr_db_core::DatabaseBuilder::default()
@@ -21,21 +23,65 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Database2<'a, R, M> {
                 std::fs::create_dir_all(&dir_path)?;
                 let file_path = dir_path.join("db.sqlite");
 
-                hypr_db_core::DatabaseBuilder::default()
-                    .local(file_path)
-                    .build()
-                    .await
-                    .unwrap()
+                let mut builder = hypr_db_core::DatabaseBuilder::default().local(&file_path);
+                if let Some(key) = encryption_key {
+                    hypr_db_core::encrypt_in_place(&file_path, &key).await?;
+                    builder = builder.encryption_key(key);
+                }
+                builder.build().await.unwrap()
             }
         };
         {
             let state = self.manager.state::<crate::ManagedState>();
             let mut guard = state.lock().await;
             guard.local_db = Some(db);
+            guard.user_db = None;
         }
         Ok(())
     }
 
+    /// The local database with the user schema applied, for data kept outside
+    /// the frontend store, like extracted action items. `None` until
+    /// `init_local` has run.
+    pub async fn user_db(&self) -> Result<Option<hypr_db_user::UserDatabase>, crate::Error> {
+        let state = self.manager.state::<crate::ManagedState>();
+        let mut guard = state.lock().await;
+
+        if guard.user_db.is_none()
+            && let Some(db) = &guard.local_db
+        {
+            let user_db = hypr_db_user::UserDatabase::from(db.clone());
+            hypr_db_user::migrate(&user_db).await?;
+            guard.user_db = Some(user_db);
+        }
+
+        Ok(guard.user_db.clone())
+    }
+
+    /// Stores action items extracted for a session, replacing the previous
+    /// extraction. `extracted` is normalized against `user` first, since it
+    /// may come straight from a model.
+    pub async fn store_action_items(
+        &self,
+        session_id: String,
+        user_id: String,
+        participants: Vec<hypr_db_user::Human>,
+        user: hypr_template_app::ActionItemsUser,
+        extracted: hypr_template_app::ActionItems,
+    ) -> Result<hypr_template_app::ActionItems, crate::Error> {
+        let db = self
+            .user_db()
+            .await?
+            .ok_or(crate::Error::DatabaseNotReady)?;
+
+        let title = user.session.title.clone();
+        let extracted = extracted.normalize(&user);
+        crate::action_items::store(&db, &session_id, &user_id, title, &participants, &extracted)
+            .await?;
+
+        Ok(extracted)
+    }
+
     pub async fn init_cloud(&self, connection_str: &str) -> Result<(), crate::Error> {
         let (client, connection) =
             tokio_postgres::connect(connection_str, tokio_postgres::NoTls).await?;
@@ -1,5 +1,6 @@
 use tokio::sync::Mutex;
 
+mod action_items;
 mod commands;
 mod error;
 mod ext;
@@ -15,6 +16,7 @@ pub type ManagedState = Mutex<State>;
 #[derive(Default)]
 pub struct State {
     pub local_db: Option<hypr_db_core::Database>,
+    pub user_db: Option<hypr_db_user::UserDatabase>,
     pub cloud_db: Option<tokio_postgres::Client>,
 }
 
@@ -24,6 +26,7 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
         .commands(tauri_specta::collect_commands![
             commands::execute_local::<tauri::Wry>,
             commands::execute_cloud::<tauri::Wry>,
+            commands::store_action_items::<tauri::Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
@@ -3,6 +3,9 @@ const COMMANDS: &[&str] = &[
     "write_json_batch",
     "write_document_batch",
     "read_document_batch",
+    "read_document",
+    "list_note_revisions",
+    "read_note_revision",
     "list_folders",
     "move_session",
     "create_folder",
@@ -15,9 +18,15 @@ const COMMANDS: &[&str] = &[
     "audio_path",
     "session_dir",
     "delete_session_folder",
+    "restore_session_folder",
+    "purge_trash",
+    "list_trash",
     "scan_and_read",
     "chat_dir",
     "entity_dir",
+    "encryption_status",
+    "enable_encryption",
+    "unlock_encryption",
 ];
 
 fn main() {
@@ -38,6 +38,30 @@ async readDocumentBatch(dirPath: string) : Promise<Result<Partial<{ [key in stri
     else return { status: "error", error: e  as any };
 }
 },
+async readDocument(path: string) : Promise<Result<ParsedDocument, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|read_document", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listNoteRevisions(path: string) : Promise<Result<NoteRevision[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|list_note_revisions", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readNoteRevision(path: string, id: string) : Promise<Result<ParsedDocument, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|read_note_revision", { path, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async listFolders() : Promise<Result<ListFoldersResult, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|list_folders") };
@@ -134,6 +158,30 @@ async deleteSessionFolder(sessionId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async restoreSessionFolder(sessionId: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|restore_session_folder", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async purgeTrash(olderThanDays: number) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|purge_trash", { olderThanDays }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listTrash() : Promise<Result<TrashedSession[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|list_trash") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async scanAndRead(scanDir: string, filePatterns: string[], recursive: boolean, pathFilter: string | null) : Promise<Result<ScanResult, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|scan_and_read", { scanDir, filePatterns, recursive, pathFilter }) };
@@ -157,12 +205,41 @@ async entityDir(dirName: string) : Promise<Result<string, string>> {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async encryptionStatus() : Promise<Result<EncryptionStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|encryption_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async enableEncryption(passphrase: string | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|enable_encryption", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unlockEncryption(passphrase: string | null) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:fs-sync|unlock_encryption", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
 /** user-defined events **/
 
 
+export const events = __makeEvents__<{
+encryptionUnlocked: EncryptionUnlocked
+}>({
+encryptionUnlocked: "plugin:fs-sync:encryption-unlocked"
+})
 
 /** user-defined constants **/
 
@@ -171,11 +248,28 @@ async entityDir(dirName: string) : Promise<Result<string, string>> {
 /** user-defined types **/
 
 export type CleanupTarget = { type: "files"; subdir: string; extension: string } | { type: "dirs"; subdir: string; marker_file: string } | { type: "filesRecursive"; subdir: string; marker_file: string; extension: string }
+export type EncryptionStatus = { enabled: boolean; unlocked: boolean; keySource: KeySource | null }
+/**
+ * Emitted once a passphrase-derived key has been unlocked, so whatever was
+ * waiting on it, like the local database, can start.
+ */
+export type EncryptionUnlocked = null
 export type FolderInfo = { name: string; parent_folder_id: string | null }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+export type KeySource = "keyring" | "passphrase"
 export type ListFoldersResult = { folders: Partial<{ [key in string]: FolderInfo }>; session_folder_map: Partial<{ [key in string]: string }> }
+export type NoteRevision = { id: string; 
+/**
+ * Milliseconds since the Unix epoch.
+ */
+createdAt: number }
 export type ParsedDocument = { frontmatter: Partial<{ [key in string]: JsonValue }>; content: string }
 export type ScanResult = { files: Partial<{ [key in string]: string }>; dirs: string[] }
+export type TrashedSession = { sessionId: string; title: string; 
+/**
+ * Milliseconds since the Unix epoch.
+ */
+trashedAt: number }
 
 /** tauri-specta globals **/
 
@@ -38,9 +38,49 @@ pub fn path(session_dir: &Path) -> Option<PathBuf> {
         .find(|path| path.exists())
 }
 
+/// A path the recording can be read from as a plain file. An encrypted one is
+/// decrypted into `cache_dir`; the player streams it from memory instead.
+pub fn playable_path(
+    session_dir: &Path,
+    cache_dir: &Path,
+    key: Option<&hypr_crypto::Key>,
+) -> std::io::Result<Option<PathBuf>> {
+    let Some(path) = path(session_dir) else {
+        return Ok(None);
+    };
+    if !hypr_crypto::is_encrypted_file(&path)? {
+        return Ok(Some(path));
+    }
+
+    let key = key
+        .ok_or_else(|| std::io::Error::new(ErrorKind::PermissionDenied, "encrypted_data_locked"))?;
+    let session_id = session_dir.file_name().unwrap_or_default();
+    let mut name = session_id.to_os_string();
+    name.push(".");
+    name.push(path.extension().unwrap_or_default());
+
+    std::fs::create_dir_all(cache_dir)?;
+    let target = cache_dir.join(name);
+    hypr_crypto::decrypt_file(&path, &target, key)?;
+    Ok(Some(target))
+}
+
+/// Overwrites and removes every decrypted copy left in `cache_dir`.
+pub fn clear_cache(cache_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if let Err(e) = hypr_crypto::remove_securely(&entry.path()) {
+            tracing::warn!("audio_cache_remove_failed: {}", e);
+        }
+    }
+}
+
 pub fn import_to_session(
     session_dir: &Path,
     source_path: &Path,
+    key: Option<&hypr_crypto::Key>,
 ) -> Result<PathBuf, AudioImportError> {
     std::fs::create_dir_all(session_dir)?;
 
@@ -52,7 +92,12 @@ pub fn import_to_session(
     }
 
     match import_audio(source_path, &tmp_path, &target_path) {
-        Ok(final_path) => Ok(final_path),
+        Ok(final_path) => {
+            if let Some(key) = key {
+                hypr_crypto::encrypt_in_place(&final_path, key)?;
+            }
+            Ok(final_path)
+        }
         Err(error) => {
             if tmp_path.exists() {
                 let _ = std::fs::remove_file(&tmp_path);
@@ -175,4 +220,28 @@ mod tests {
         test_import_aiff: hypr_data::english_1::AUDIO_AIFF_PATH,
         test_import_caf: hypr_data::english_1::AUDIO_CAF_PATH,
     }
+
+    #[test]
+    fn test_playable_path() {
+        let temp = TempDir::new().unwrap();
+        let session_dir = temp.path().join("sessions").join("s1");
+        let cache_dir = temp.path().join("cache");
+        std::fs::create_dir_all(&session_dir).unwrap();
+        std::fs::write(session_dir.join("audio.ogg"), b"OggS").unwrap();
+
+        assert_eq!(
+            playable_path(&session_dir, &cache_dir, None).unwrap(),
+            Some(session_dir.join("audio.ogg"))
+        );
+
+        let key = hypr_crypto::Key::generate();
+        hypr_crypto::encrypt_in_place(&session_dir.join("audio.ogg"), &key).unwrap();
+        assert!(playable_path(&session_dir, &cache_dir, None).is_err());
+
+        let playable = playable_path(&session_dir, &cache_dir, Some(&key))
+            .unwrap()
+            .unwrap();
+        assert_eq!(playable, cache_dir.join("s1.ogg"));
+        assert_eq!(std::fs::read(playable).unwrap(), b"OggS");
+    }
 }
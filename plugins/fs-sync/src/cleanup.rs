@@ -77,10 +77,14 @@ fn for_each_entity_dir(
     }
 }
 
+/// Removes entity folders whose id isn't in `valid_ids`. With `trash_base`,
+/// they are moved into its trash instead, where they can be restored from
+/// until purged.
 pub fn cleanup_dirs_recursive(
     base_dir: &Path,
     marker_file: &str,
     valid_ids: &HashSet<String>,
+    trash_base: Option<&Path>,
 ) -> std::io::Result<u32> {
     if !base_dir.exists() {
         return Ok(0);
@@ -90,7 +94,11 @@ pub fn cleanup_dirs_recursive(
     for_each_entity_dir(base_dir, base_dir, marker_file, &mut |path, name| {
         if !valid_ids.contains(name) {
             let relative_path = to_relative_path(path, base_dir);
-            if let Err(e) = std::fs::remove_dir_all(path) {
+            let result = match trash_base {
+                Some(trash_base) => crate::session::delete_session_dir(trash_base, path),
+                None => std::fs::remove_dir_all(path),
+            };
+            if let Err(e) = result {
                 tracing::warn!(path = %relative_path, error = %e, "failed to remove orphan directory");
             } else {
                 tracing::info!(path = %relative_path, "orphan directory removed");
@@ -204,7 +212,7 @@ mod tests {
             .build();
 
         let valid: HashSet<String> = [UUID_1.to_string()].into();
-        let removed = cleanup_dirs_recursive(env.path(), "_meta.json", &valid).unwrap();
+        let removed = cleanup_dirs_recursive(env.path(), "_meta.json", &valid, None).unwrap();
 
         assert_eq!(removed, 1);
         env.child(UUID_1).assert(predicate::path::exists());
@@ -223,7 +231,7 @@ mod tests {
             .build();
 
         let valid: HashSet<String> = [UUID_1.to_string()].into();
-        let removed = cleanup_dirs_recursive(env.path(), "_meta.json", &valid).unwrap();
+        let removed = cleanup_dirs_recursive(env.path(), "_meta.json", &valid, None).unwrap();
 
         assert_eq!(removed, 1);
         env.child("work")
@@ -234,6 +242,35 @@ mod tests {
             .assert(predicate::path::missing());
     }
 
+    #[test]
+    fn cleanup_dirs_moves_orphans_to_trash() {
+        let env = TestEnv::new()
+            .folder("sessions")
+            .session(UUID_1)
+            .done_folder()
+            .session(UUID_2)
+            .done_folder()
+            .done()
+            .build();
+
+        let valid: HashSet<String> = [UUID_1.to_string()].into();
+        let removed = cleanup_dirs_recursive(
+            &env.path().join("sessions"),
+            "_meta.json",
+            &valid,
+            Some(env.path()),
+        )
+        .unwrap();
+
+        assert_eq!(removed, 1);
+        env.child("sessions")
+            .child(UUID_2)
+            .assert(predicate::path::missing());
+        env.child(".trash/sessions")
+            .child(UUID_2)
+            .assert(predicate::path::is_dir());
+    }
+
     #[test]
     fn cleanup_files_recursive_removes_orphan_notes() {
         let env = TestEnv::new()
@@ -5,11 +5,15 @@ use rayon::prelude::*;
 use serde_json::Value;
 use tauri_plugin_notify::NotifyPluginExt;
 use tauri_plugin_settings::SettingsPluginExt;
+use tauri_specta::Event;
 
 use crate::FsSyncPluginExt;
+use crate::events::EncryptionUnlocked;
 use crate::frontmatter::ParsedDocument;
 use crate::session::find_session_dir;
-use crate::types::{CleanupTarget, ListFoldersResult, ScanResult};
+use crate::types::{
+    CleanupTarget, EncryptionStatus, ListFoldersResult, NoteRevision, ScanResult, TrashedSession,
+};
 
 macro_rules! spawn_blocking {
     ($body:expr) => {
@@ -83,6 +87,8 @@ pub(crate) async fn write_document_batch<R: tauri::Runtime>(
         })
         .collect();
 
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
+
     app.notify().mark_own_writes(&relative_paths);
 
     spawn_blocking!({
@@ -91,23 +97,31 @@ pub(crate) async fn write_document_batch<R: tauri::Runtime>(
             if let Some(parent) = path.parent() {
                 std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
             }
+            // Memos and enhanced notes keep a history; other documents don't.
+            let is_note = doc.frontmatter.contains_key("session_id");
             let content = crate::frontmatter::serialize(doc).map_err(|e| e.to_string())?;
-            std::fs::write(path, content).map_err(|e| e.to_string())
+            if is_note {
+                crate::revisions::record(path, &content, key.as_ref())
+                    .map_err(|e| e.to_string())?;
+            }
+            hypr_crypto::write(path, content, key.as_ref()).map_err(|e| e.to_string())
         })
     })
 }
 
 #[tauri::command]
 #[specta::specta]
-pub(crate) async fn read_document_batch(
+pub(crate) async fn read_document_batch<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
     dir_path: String,
 ) -> Result<HashMap<String, ParsedDocument>, String> {
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
     spawn_blocking!({
         let files = crate::session::list_uuid_files(&PathBuf::from(&dir_path), "md");
         let results: HashMap<_, _> = files
             .into_par_iter()
             .filter_map(|(id, path)| {
-                let content = std::fs::read_to_string(&path).ok()?;
+                let content = hypr_crypto::read_to_string(&path, key.as_ref()).ok()?;
                 let doc = crate::frontmatter::deserialize(&content).ok()?;
                 Some((id, doc))
             })
@@ -116,6 +130,41 @@ pub(crate) async fn read_document_batch(
     })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn read_document<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    path: String,
+) -> Result<ParsedDocument, String> {
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
+    spawn_blocking!({
+        let content = hypr_crypto::read_to_string(std::path::Path::new(&path), key.as_ref())
+            .map_err(|e| e.to_string())?;
+        crate::frontmatter::deserialize(&content).map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn list_note_revisions(path: String) -> Result<Vec<NoteRevision>, String> {
+    spawn_blocking!({ Ok(crate::revisions::list(std::path::Path::new(&path))) })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn read_note_revision<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    path: String,
+    id: String,
+) -> Result<ParsedDocument, String> {
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
+    spawn_blocking!({
+        let content = crate::revisions::read(std::path::Path::new(&path), &id, key.as_ref())
+            .map_err(|e| e.to_string())?;
+        crate::frontmatter::deserialize(&content).map_err(|e| e.to_string())
+    })
+}
+
 #[tauri::command]
 #[specta::specta]
 pub(crate) async fn list_folders<R: tauri::Runtime>(
@@ -210,7 +259,8 @@ pub(crate) async fn audio_import<R: tauri::Runtime>(
     source_path: String,
 ) -> Result<String, String> {
     let session_dir = resolve_session_dir(&app, &session_id)?;
-    crate::audio::import_to_session(&session_dir, &PathBuf::from(&source_path))
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
+    crate::audio::import_to_session(&session_dir, &PathBuf::from(&source_path), key.as_ref())
         .map(|path| path.to_string_lossy().to_string())
         .map_err(|e| e.to_string())
 }
@@ -222,9 +272,20 @@ pub(crate) async fn audio_path<R: tauri::Runtime>(
     session_id: String,
 ) -> Result<String, String> {
     let session_dir = resolve_session_dir(&app, &session_id)?;
-    crate::audio::path(&session_dir)
-        .map(|p| p.to_string_lossy().to_string())
-        .ok_or_else(|| "audio_path_not_found".to_string())
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
+    let cache_dir = crate::audio_cache_dir(&app).map_err(|e| e.to_string())?;
+    let path = crate::audio::playable_path(&session_dir, &cache_dir, key.as_ref())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "audio_path_not_found".to_string())?;
+
+    if path.starts_with(&cache_dir) {
+        let path = path.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(crate::AUDIO_CACHE_TTL).await;
+            let _ = hypr_crypto::remove_securely(&path);
+        });
+    }
+    Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
@@ -242,8 +303,47 @@ pub(crate) async fn delete_session_folder<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     session_id: String,
 ) -> Result<(), String> {
-    let session_dir = resolve_session_dir(&app, &session_id)?;
-    crate::session::delete_session_dir(&session_dir).map_err(|e| e.to_string())
+    let base = app.settings().settings_base().map_err(|e| e.to_string())?;
+    let session_dir = find_session_dir(&base.join("sessions"), &session_id);
+    crate::session::delete_session_dir(&base, &session_dir).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn restore_session_folder<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    session_id: String,
+) -> Result<Option<String>, String> {
+    let base = app.settings().settings_base().map_err(|e| e.to_string())?;
+    crate::session::restore_session_dir(&base, &session_id)
+        .map(|path| path.map(|p| p.to_string_lossy().to_string()))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn purge_trash<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    older_than_days: u32,
+) -> Result<u32, String> {
+    let base = app.settings().settings_base().map_err(|e| e.to_string())?;
+    let older_than = std::time::Duration::from_secs(u64::from(older_than_days) * 24 * 60 * 60);
+    spawn_blocking!({
+        crate::session::purge_trash(&base, older_than)
+            .map(|purged| purged as u32)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn list_trash<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Vec<TrashedSession>, String> {
+    let base = app.settings().settings_base().map_err(|e| e.to_string())?;
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
+    let trashed = spawn_blocking!(crate::session::list_trash(&base, key.as_ref()));
+    Ok(trashed)
 }
 
 #[tauri::command]
@@ -256,6 +356,7 @@ pub(crate) async fn scan_and_read<R: tauri::Runtime>(
     path_filter: Option<String>,
 ) -> Result<ScanResult, String> {
     let base = app.settings().settings_base().map_err(|e| e.to_string())?;
+    let key = app.fs_sync().encryption_key().map_err(|e| e.to_string())?;
     spawn_blocking!({
         Ok(crate::scan::scan_and_read(
             &PathBuf::from(&scan_dir),
@@ -263,6 +364,7 @@ pub(crate) async fn scan_and_read<R: tauri::Runtime>(
             &file_patterns,
             recursive,
             path_filter.as_deref(),
+            key.as_ref(),
         ))
     })
 }
@@ -290,3 +392,43 @@ pub(crate) async fn entity_dir<R: tauri::Runtime>(
     let base = app.settings().settings_base().map_err(|e| e.to_string())?;
     Ok(base.join(&dir_name).to_string_lossy().to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn encryption_status<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<EncryptionStatus, String> {
+    app.fs_sync().encryption_status().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn enable_encryption<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    passphrase: Option<String>,
+) -> Result<u32, String> {
+    spawn_blocking!({
+        app.fs_sync()
+            .enable_encryption(passphrase.as_deref())
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn unlock_encryption<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    passphrase: Option<String>,
+) -> Result<bool, String> {
+    let handle = app.clone();
+    let unlocked = spawn_blocking!({
+        app.fs_sync()
+            .unlock_encryption(passphrase.as_deref())
+            .map_err(|e| e.to_string())
+    })?;
+
+    if unlocked {
+        let _ = EncryptionUnlocked.emit(&handle);
+    }
+    Ok(unlocked)
+}
@@ -0,0 +1,254 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use hypr_crypto::{Key, Keystore};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{EncryptionStatus, KeySource};
+
+pub const CONFIG_FILE: &str = "encryption.json";
+
+const KEYRING_SERVICE: &str = "hyprnote";
+const KEYRING_USER: &str = "local-data-key";
+
+// Sealed with the key, so a wrong passphrase is caught before anything is
+// written with the key derived from it.
+const CHECK: &[u8] = b"hyprnote";
+
+#[derive(Default)]
+pub struct EncryptionState {
+    key: Mutex<Option<Key>>,
+}
+
+impl EncryptionState {
+    pub fn key(&self) -> Option<Key> {
+        self.key.lock().unwrap().clone()
+    }
+
+    pub fn set_key(&self, key: Key) {
+        *self.key.lock().unwrap() = Some(key);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Config {
+    key_source: KeySource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    check: String,
+}
+
+fn load_config(base: &Path) -> crate::Result<Option<Config>> {
+    match std::fs::read_to_string(base.join(CONFIG_FILE)) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| crate::Error::Path(e.to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn keystore() -> Keystore {
+    Keystore::new(KEYRING_SERVICE, KEYRING_USER)
+}
+
+fn verify(config: &Config, key: Key) -> crate::Result<Key> {
+    let check = hypr_crypto::from_hex(&config.check).ok_or(crate::Error::WrongPassphrase)?;
+    match hypr_crypto::decrypt(&check, &key) {
+        Ok(check) if check == CHECK => Ok(key),
+        _ => Err(crate::Error::WrongPassphrase),
+    }
+}
+
+pub fn status(base: &Path, unlocked: bool) -> crate::Result<EncryptionStatus> {
+    let config = load_config(base)?;
+    Ok(EncryptionStatus {
+        enabled: config.is_some(),
+        unlocked: config.is_some() && unlocked,
+        key_source: config.map(|c| c.key_source),
+    })
+}
+
+/// The key the data under `base` is encrypted with, or `None` if it isn't.
+/// A key kept in the OS keyring is loaded as is; one derived from a
+/// passphrase needs the passphrase.
+pub fn unlock(base: &Path, passphrase: Option<&str>) -> crate::Result<Option<Key>> {
+    let Some(config) = load_config(base)? else {
+        return Ok(None);
+    };
+
+    let key = match config.key_source {
+        KeySource::Keyring => keystore().load()?.ok_or(crate::Error::Locked)?,
+        KeySource::Passphrase => {
+            let passphrase = passphrase.ok_or(crate::Error::PassphraseRequired)?;
+            let salt = config
+                .salt
+                .as_deref()
+                .and_then(hypr_crypto::from_hex)
+                .ok_or(crate::Error::WrongPassphrase)?;
+            Key::from_passphrase(passphrase, &salt)?
+        }
+    };
+    verify(&config, key).map(Some)
+}
+
+/// Turns encryption on for the data under `base` and returns its key. Without
+/// a passphrase, a random key is kept in the OS keyring; where there is none
+/// this fails and the caller should ask for a passphrase instead.
+///
+/// Only records the key. Existing files are converted by [`encrypt_tree`].
+pub fn enable(base: &Path, passphrase: Option<&str>) -> crate::Result<Key> {
+    if load_config(base)?.is_some() {
+        return unlock(base, passphrase)?.ok_or(crate::Error::Locked);
+    }
+
+    let (key, config) = match passphrase {
+        None => (
+            keystore().load_or_create()?,
+            Config {
+                key_source: KeySource::Keyring,
+                salt: None,
+                check: String::new(),
+            },
+        ),
+        Some(passphrase) => {
+            let salt = hypr_crypto::generate_salt();
+            (
+                Key::from_passphrase(passphrase, &salt)?,
+                Config {
+                    key_source: KeySource::Passphrase,
+                    salt: Some(hypr_crypto::to_hex(&salt)),
+                    check: String::new(),
+                },
+            )
+        }
+    };
+
+    let config = Config {
+        check: hypr_crypto::to_hex(&hypr_crypto::encrypt(CHECK, &key)?),
+        ..config
+    };
+    let content =
+        serde_json::to_string_pretty(&config).map_err(|e| crate::Error::Path(e.to_string()))?;
+    std::fs::write(base.join(CONFIG_FILE), content)?;
+
+    Ok(key)
+}
+
+// Notes in the entity and session folders, and recordings only inside session
+// folders (trashed ones included). Files at the top, like `AGENTS.md`, are
+// read by other tools and stay plain.
+fn should_encrypt(base: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(base) else {
+        return false;
+    };
+    let mut components = relative.components();
+    let Some(top) = components.next().map(|c| c.as_os_str()) else {
+        return false;
+    };
+    if components.next().is_none() {
+        return false;
+    }
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("md") => true,
+        Some("ogg" | "wav") => top == "sessions" || top == crate::session::TRASH_DIR,
+        _ => false,
+    }
+}
+
+/// Encrypts the notes and recordings under `base` that are still plain,
+/// one file at a time and each replaced only once its encrypted copy is
+/// complete. Safe to run again after an interruption. Returns how many
+/// files were converted.
+pub fn encrypt_tree(base: &Path, key: &Key) -> std::io::Result<usize> {
+    let mut converted = 0;
+    let mut pending = vec![base.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file()
+                && should_encrypt(base, &path)
+                && hypr_crypto::encrypt_in_place(&path, key)?
+            {
+                converted += 1;
+            }
+        }
+    }
+
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_passphrase_unlock() {
+        let temp = TempDir::new().unwrap();
+
+        assert!(unlock(temp.path(), None).unwrap().is_none());
+        assert!(!status(temp.path(), false).unwrap().enabled);
+
+        let key = enable(temp.path(), Some("hunter2")).unwrap();
+        let status = status(temp.path(), false).unwrap();
+        assert!(status.enabled);
+        assert_eq!(status.key_source, Some(KeySource::Passphrase));
+
+        assert!(matches!(
+            unlock(temp.path(), None),
+            Err(crate::Error::PassphraseRequired)
+        ));
+        assert!(matches!(
+            unlock(temp.path(), Some("hunter3")),
+            Err(crate::Error::WrongPassphrase)
+        ));
+        assert_eq!(
+            unlock(temp.path(), Some("hunter2")).unwrap(),
+            Some(key.clone())
+        );
+        assert_eq!(enable(temp.path(), Some("hunter2")).unwrap(), key);
+    }
+
+    #[test]
+    fn test_encrypt_tree() {
+        let temp = TempDir::new().unwrap();
+        let session = temp.child("sessions/work/a0000000-0000-0000-0000-000000000001");
+        session.child("_meta.json").write_str("{}").unwrap();
+        session.child("_memo.md").write_str("# Memo").unwrap();
+        session.child("audio.ogg").write_binary(b"OggS").unwrap();
+        temp.child("humans/b.md")
+            .write_str("---\nname: B\n---\n")
+            .unwrap();
+        temp.child("settings.json").write_str("{}").unwrap();
+        temp.child("AGENTS.md").write_str("# Agents").unwrap();
+        temp.child("imports/call.wav")
+            .write_binary(b"RIFF")
+            .unwrap();
+
+        let key = enable(temp.path(), Some("hunter2")).unwrap();
+        assert_eq!(encrypt_tree(temp.path(), &key).unwrap(), 3);
+        assert_eq!(encrypt_tree(temp.path(), &key).unwrap(), 0);
+
+        for encrypted in ["_memo.md", "audio.ogg"] {
+            assert!(hypr_crypto::is_encrypted_file(session.child(encrypted).path()).unwrap());
+        }
+        assert_eq!(
+            hypr_crypto::read_to_string(session.child("_memo.md").path(), Some(&key)).unwrap(),
+            "# Memo"
+        );
+        session.child("_meta.json").assert("{}");
+        temp.child("settings.json").assert("{}");
+        temp.child("AGENTS.md").assert("# Agents");
+        temp.child("imports/call.wav").assert("RIFF");
+        assert!(load_config(temp.path()).unwrap().is_some());
+    }
+}
@@ -14,6 +14,14 @@ pub enum Error {
     Frontmatter(#[from] hypr_frontmatter::Error),
     #[error("Markdown error: {0}")]
     Markdown(String),
+    #[error(transparent)]
+    Crypto(#[from] hypr_crypto::Error),
+    #[error("encryption_locked")]
+    Locked,
+    #[error("encryption_passphrase_required")]
+    PassphraseRequired,
+    #[error("encryption_wrong_passphrase")]
+    WrongPassphrase,
 }
 
 impl Serialize for Error {
@@ -0,0 +1,4 @@
+/// Emitted once a passphrase-derived key has been unlocked, so whatever was
+/// waiting on it, like the local database, can start.
+#[derive(serde::Serialize, serde::Deserialize, Clone, specta::Type, tauri_specta::Event)]
+pub struct EncryptionUnlocked;
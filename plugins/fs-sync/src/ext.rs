@@ -4,10 +4,12 @@ use std::path::PathBuf;
 use tauri_plugin_settings::SettingsPluginExt;
 
 use crate::cleanup::{cleanup_dirs_recursive, cleanup_files_in_dir, cleanup_files_recursive};
+use crate::encryption::EncryptionState;
 use crate::folder::scan_directory_recursive;
 use crate::path::is_uuid;
 use crate::session::find_session_dir;
 use crate::types::CleanupTarget;
+use crate::types::EncryptionStatus;
 use crate::types::ListFoldersResult;
 
 pub struct FsSync<'a, R: tauri::Runtime, M: tauri::Manager<R>> {
@@ -167,6 +169,48 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> FsSync<'a, R, M> {
         Ok(false)
     }
 
+    /// The key to read and write notes and recordings with: `None` while
+    /// encryption is off, and an error while it's on but still locked, so
+    /// nothing gets written in plain text by mistake.
+    pub fn encryption_key(&self) -> Result<Option<hypr_crypto::Key>, crate::Error> {
+        let state = self.manager.state::<EncryptionState>();
+        match state.key() {
+            Some(key) => Ok(Some(key)),
+            None if crate::encryption::status(&self.base_dir()?, false)?.enabled => {
+                Err(crate::Error::Locked)
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn encryption_status(&self) -> Result<EncryptionStatus, crate::Error> {
+        let state = self.manager.state::<EncryptionState>();
+        crate::encryption::status(&self.base_dir()?, state.key().is_some())
+    }
+
+    /// Turns encryption on and converts the existing notes and recordings.
+    /// Returns how many files were converted. The database follows on the
+    /// next start, when it's opened with the key.
+    pub fn enable_encryption(&self, passphrase: Option<&str>) -> Result<u32, crate::Error> {
+        let base = self.base_dir()?;
+        let key = crate::encryption::enable(&base, passphrase)?;
+        self.manager.state::<EncryptionState>().set_key(key.clone());
+
+        let converted = crate::encryption::encrypt_tree(&base, &key)?;
+        tracing::info!("Encrypted {} files", converted);
+        Ok(converted as u32)
+    }
+
+    pub fn unlock_encryption(&self, passphrase: Option<&str>) -> Result<bool, crate::Error> {
+        match crate::encryption::unlock(&self.base_dir()?, passphrase)? {
+            Some(key) => {
+                self.manager.state::<EncryptionState>().set_key(key);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     pub fn cleanup_orphan(
         &self,
         target: CleanupTarget,
@@ -183,8 +227,16 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> FsSync<'a, R, M> {
                 subdir,
                 marker_file,
             } => {
-                let dir = self.base_dir()?.join(&subdir);
-                Ok(cleanup_dirs_recursive(&dir, &marker_file, &valid_set)?)
+                let base = self.base_dir()?;
+                let dir = base.join(&subdir);
+                // Deleted sessions go to the trash, to be restored or purged.
+                let trash_base = (subdir == "sessions").then_some(base.as_path());
+                Ok(cleanup_dirs_recursive(
+                    &dir,
+                    &marker_file,
+                    &valid_set,
+                    trash_base,
+                )?)
             }
             CleanupTarget::FilesRecursive {
                 subdir,
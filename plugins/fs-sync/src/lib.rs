@@ -4,13 +4,17 @@ mod test_fixtures;
 mod audio;
 mod cleanup;
 mod commands;
+mod encryption;
 mod error;
+mod events;
 mod ext;
 mod folder;
 mod frontmatter;
 mod json;
 mod migrations;
 mod path;
+mod protocol;
+mod revisions;
 mod scan;
 mod session;
 mod types;
@@ -18,12 +22,28 @@ mod types;
 pub use types::*;
 
 pub use error::{Error, Result};
+pub use events::*;
 pub use ext::*;
 pub use path::is_uuid;
 pub use session::find_session_dir;
 
 const PLUGIN_NAME: &str = "fs-sync";
 
+// Where `audio_path` decrypts encrypted recordings to, for callers that need
+// a file. Copies are removed after `AUDIO_CACHE_TTL` and on every start.
+pub(crate) const AUDIO_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+pub(crate) fn audio_cache_dir<R: tauri::Runtime>(
+    app: &impl tauri::Manager<R>,
+) -> tauri::Result<std::path::PathBuf> {
+    Ok(app.path().app_cache_dir()?.join("decrypted-audio"))
+}
+
+// Trashed sessions can be restored for this long, checked on start and then
+// once a day.
+const TRASH_RETENTION: std::time::Duration = std::time::Duration::from_secs(30 * 24 * 60 * 60);
+const TRASH_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
 fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
     tauri_specta::Builder::<R>::new()
         .plugin_name(PLUGIN_NAME)
@@ -31,7 +51,10 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::deserialize,
             commands::write_json_batch::<tauri::Wry>,
             commands::write_document_batch::<tauri::Wry>,
-            commands::read_document_batch,
+            commands::read_document_batch::<tauri::Wry>,
+            commands::read_document::<tauri::Wry>,
+            commands::list_note_revisions,
+            commands::read_note_revision::<tauri::Wry>,
             commands::list_folders::<tauri::Wry>,
             commands::move_session::<tauri::Wry>,
             commands::create_folder::<tauri::Wry>,
@@ -44,10 +67,17 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::audio_path::<tauri::Wry>,
             commands::session_dir::<tauri::Wry>,
             commands::delete_session_folder::<tauri::Wry>,
+            commands::restore_session_folder::<tauri::Wry>,
+            commands::purge_trash::<tauri::Wry>,
+            commands::list_trash::<tauri::Wry>,
             commands::scan_and_read::<tauri::Wry>,
             commands::chat_dir::<tauri::Wry>,
             commands::entity_dir::<tauri::Wry>,
+            commands::encryption_status::<tauri::Wry>,
+            commands::enable_encryption::<tauri::Wry>,
+            commands::unlock_encryption::<tauri::Wry>,
         ])
+        .events(tauri_specta::collect_events![EncryptionUnlocked])
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
 
@@ -56,12 +86,54 @@ pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
 
     tauri::plugin::Builder::new(PLUGIN_NAME)
         .invoke_handler(specta_builder.invoke_handler())
-        .setup(|app, _api| {
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(protocol::handle(&app, &request));
+            });
+        })
+        .setup(move |app, _api| {
+            use tauri::Manager;
             use tauri_plugin_settings::SettingsPluginExt;
+
+            specta_builder.mount_events(app);
+
+            app.manage(encryption::EncryptionState::default());
+            app.manage(protocol::AudioCache::default());
+            if let Ok(cache_dir) = audio_cache_dir(app) {
+                audio::clear_cache(&cache_dir);
+            }
+
             if let Ok(base_dir) = app.settings().settings_base() {
                 migrations::move_uuid_folders_to_sessions(&base_dir)?;
                 migrations::rename_transcript(&base_dir)?;
+
+                // A passphrase-derived key stays locked until `unlock_encryption`.
+                match encryption::unlock(&base_dir, None) {
+                    Ok(Some(key)) => app.state::<encryption::EncryptionState>().set_key(key),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("encryption_locked: {}", e),
+                }
             }
+
+            let app_handle = app.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    if let Ok(base_dir) = app_handle.settings().settings_base() {
+                        let purged = tokio::task::spawn_blocking(move || {
+                            session::purge_trash(&base_dir, TRASH_RETENTION)
+                        })
+                        .await;
+                        match purged {
+                            Ok(Ok(0)) => {}
+                            Ok(Ok(purged)) => tracing::info!(purged, "trash_purged"),
+                            Ok(Err(e)) => tracing::warn!("trash_purge_failed: {}", e),
+                            Err(e) => tracing::warn!("trash_purge_failed: {}", e),
+                        }
+                    }
+                    tokio::time::sleep(TRASH_PURGE_INTERVAL).await;
+                }
+            });
             Ok(())
         })
         .build()
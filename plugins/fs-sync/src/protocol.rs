@@ -0,0 +1,153 @@
+//! Serves recordings to the player at `hypr-audio://localhost/<session_id>`.
+//! Encrypted ones are decrypted in memory, so playing them back leaves no
+//! plain copy on disk.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use tauri::http::{Request, Response, StatusCode, header};
+use tauri_plugin_settings::SettingsPluginExt;
+
+use crate::FsSyncPluginExt;
+use crate::session::find_session_dir;
+
+pub const SCHEME: &str = "hypr-audio";
+
+// The player asks for a recording in many ranges while seeking; decrypting
+// all of it for each one would be wasteful.
+#[derive(Default)]
+pub struct AudioCache(Mutex<Option<(PathBuf, SystemTime, Arc<Vec<u8>>)>>);
+
+impl AudioCache {
+    fn get(&self, path: &PathBuf, modified: SystemTime) -> Option<Arc<Vec<u8>>> {
+        match &*self.0.lock().unwrap() {
+            Some((p, m, bytes)) if p == path && *m == modified => Some(bytes.clone()),
+            _ => None,
+        }
+    }
+
+    fn set(&self, path: PathBuf, modified: SystemTime, bytes: Arc<Vec<u8>>) {
+        *self.0.lock().unwrap() = Some((path, modified, bytes));
+    }
+}
+
+pub fn handle<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    request: &Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    respond(app, request)
+        .unwrap_or_else(|status| Response::builder().status(status).body(Vec::new()).unwrap())
+}
+
+fn respond<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    request: &Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, StatusCode> {
+    use tauri::Manager;
+
+    let session_id = request.uri().path().trim_start_matches('/');
+    if !crate::is_uuid(session_id) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let base = app
+        .settings()
+        .settings_base()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let session_dir = find_session_dir(&base.join("sessions"), session_id);
+    let path = crate::audio::path(&session_dir).ok_or(StatusCode::NOT_FOUND)?;
+    let modified = std::fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let cache = app.state::<AudioCache>();
+    let bytes = match cache.get(&path, modified) {
+        Some(bytes) => bytes,
+        None => {
+            let key = app
+                .fs_sync()
+                .encryption_key()
+                .map_err(|_| StatusCode::FORBIDDEN)?;
+            let bytes = Arc::new(
+                hypr_crypto::read(&path, key.as_ref())
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+            );
+            cache.set(path.clone(), modified, bytes.clone());
+            bytes
+        }
+    };
+
+    let content_type = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        _ => "application/octet-stream",
+    };
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let Some(range) = request.headers().get(header::RANGE) else {
+        return builder
+            .status(StatusCode::OK)
+            .body(bytes.to_vec())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    match range
+        .to_str()
+        .ok()
+        .and_then(|range| parse_range(range, bytes.len()))
+    {
+        Some((start, end)) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, bytes.len()),
+            )
+            .body(bytes[start..=end].to_vec()),
+        None => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", bytes.len()))
+            .body(Vec::new()),
+    }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// The first range of a `bytes=` header, as inclusive offsets within `len`.
+fn parse_range(header: &str, len: usize) -> Option<(usize, usize)> {
+    let spec = header.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let last = len.checked_sub(1)?;
+
+    let (start, end) = if start.is_empty() {
+        let suffix: usize = end.parse().ok()?;
+        (len.saturating_sub(suffix), last)
+    } else {
+        let start: usize = start.parse().ok()?;
+        let end = match end {
+            "" => last,
+            end => end.parse::<usize>().ok()?.min(last),
+        };
+        (start, end)
+    };
+
+    (start <= end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-", 100), Some((0, 99)));
+        assert_eq!(parse_range("bytes=10-19", 100), Some((10, 19)));
+        assert_eq!(parse_range("bytes=90-200", 100), Some((90, 99)));
+        assert_eq!(parse_range("bytes=-10", 100), Some((90, 99)));
+        assert_eq!(parse_range("bytes=0-1, 5-6", 100), Some((0, 1)));
+        assert_eq!(parse_range("bytes=100-", 100), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("items=0-1", 100), None);
+    }
+}
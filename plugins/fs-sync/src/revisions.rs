@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use similar::TextDiff;
+
+use crate::types::NoteRevision;
+
+// Next to the notes, so a session's history is trashed, restored and
+// encrypted along with it.
+const HISTORY_DIR: &str = ".history";
+// The editor saves every few seconds while typing, so saves this close to
+// the last revision are folded into it.
+const COALESCE_WINDOW: Duration = Duration::from_secs(5 * 60);
+// Below this, a save rewrote the note (a re-run enhancement, a restore)
+// rather than continued editing it, and always starts a new revision.
+const COALESCE_MIN_SIMILARITY: f32 = 0.5;
+
+fn history_dir(note_path: &Path) -> Option<PathBuf> {
+    let name = note_path.file_name()?;
+    Some(note_path.parent()?.join(HISTORY_DIR).join(name))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Revisions of the note at `note_path`, newest first.
+pub fn list(note_path: &Path) -> Vec<NoteRevision> {
+    let Some(Ok(entries)) = history_dir(note_path).map(std::fs::read_dir) else {
+        return Vec::new();
+    };
+
+    let mut revisions: Vec<NoteRevision> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "md" {
+                return None;
+            }
+            let created_at: u64 = path.file_stem()?.to_str()?.parse().ok()?;
+            Some(NoteRevision {
+                id: created_at.to_string(),
+                created_at,
+            })
+        })
+        .collect();
+    revisions.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+    revisions
+}
+
+/// The note as it was saved in the revision `id`. Restoring it is writing it
+/// back, which records it as a new revision.
+pub fn read(note_path: &Path, id: &str, key: Option<&hypr_crypto::Key>) -> std::io::Result<String> {
+    if id.parse::<u64>().is_err() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("no revision {id}"),
+        ));
+    }
+    let dir = history_dir(note_path).ok_or(std::io::ErrorKind::InvalidInput)?;
+    hypr_crypto::read_to_string(&dir.join(format!("{id}.md")), key)
+}
+
+/// Records `content` as the note's newest revision unless nothing changed.
+/// Called before the note itself is written, so its history is never behind
+/// the file.
+pub fn record(
+    note_path: &Path,
+    content: &str,
+    key: Option<&hypr_crypto::Key>,
+) -> std::io::Result<()> {
+    let dir = history_dir(note_path).ok_or(std::io::ErrorKind::InvalidInput)?;
+    let now = now_ms();
+
+    if let Some(latest) = list(note_path).into_iter().next() {
+        let latest_path = dir.join(format!("{}.md", latest.id));
+        let latest_content = hypr_crypto::read_to_string(&latest_path, key)?;
+        if latest_content == content {
+            return Ok(());
+        }
+
+        let recent = now.saturating_sub(latest.created_at) < COALESCE_WINDOW.as_millis() as u64;
+        if recent
+            && TextDiff::from_words(latest_content.as_str(), content).ratio()
+                >= COALESCE_MIN_SIMILARITY
+        {
+            std::fs::remove_file(&latest_path)?;
+        }
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    hypr_crypto::write(&dir.join(format!("{now}.md")), content, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{TestEnv, UUID_1};
+
+    #[test]
+    fn record_skips_unchanged_and_folds_quick_edits() {
+        let env = TestEnv::new().session(UUID_1).done().build();
+        let note = env.session_path(UUID_1).join("_memo.md");
+
+        record(&note, "ship on friday", None).unwrap();
+        record(&note, "ship on friday", None).unwrap();
+        assert_eq!(list(&note).len(), 1);
+
+        record(&note, "ship on friday, demo monday", None).unwrap();
+        let revisions = list(&note);
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(
+            read(&note, &revisions[0].id, None).unwrap(),
+            "ship on friday, demo monday"
+        );
+
+        std::thread::sleep(Duration::from_millis(2));
+        record(&note, "# Summary\n\n- Launch slips a week", None).unwrap();
+        let revisions = list(&note);
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(
+            read(&note, &revisions[1].id, None).unwrap(),
+            "ship on friday, demo monday"
+        );
+        assert!(read(&note, "../_meta", None).is_err());
+    }
+}
@@ -13,6 +13,7 @@ pub fn scan_and_read(
     file_patterns: &[String],
     recursive: bool,
     path_filter: Option<&str>,
+    key: Option<&hypr_crypto::Key>,
 ) -> ScanResult {
     if !scan_dir.exists() {
         return ScanResult {
@@ -46,7 +47,7 @@ pub fn scan_and_read(
                 .unwrap_or(true)
         })
         .filter_map(|(rel_path, abs_path)| {
-            std::fs::read_to_string(&abs_path)
+            hypr_crypto::read_to_string(&abs_path, key)
                 .ok()
                 .map(|content| (rel_path, content))
         })
@@ -75,6 +76,10 @@ fn scan_directory_for_files(
         };
 
         if path.is_dir() {
+            // Note history and the trash aren't folders of their own.
+            if name.starts_with('.') {
+                continue;
+            }
             let rel_path = to_relative_path(&path, base_path);
 
             if !is_uuid(name) {
@@ -103,7 +108,14 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let nonexistent = temp.path().join("does_not_exist");
 
-        let result = scan_and_read(&nonexistent, &nonexistent, &["*.txt".into()], true, None);
+        let result = scan_and_read(
+            &nonexistent,
+            &nonexistent,
+            &["*.txt".into()],
+            true,
+            None,
+            None,
+        );
 
         assert!(result.files.is_empty());
         assert!(result.dirs.is_empty());
@@ -116,7 +128,7 @@ mod tests {
             .file("data.json", "{}")
             .build();
 
-        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], false, None);
+        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], false, None, None);
 
         assert_eq!(result.files.len(), 1);
         assert_eq!(result.files.get("note.txt"), Some(&"hello".into()));
@@ -131,7 +143,7 @@ mod tests {
             .done()
             .build();
 
-        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], true, None);
+        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], true, None, None);
 
         assert_eq!(result.files.len(), 2);
         assert_eq!(result.files.get("root.txt"), Some(&"root".into()));
@@ -147,7 +159,7 @@ mod tests {
             .done()
             .build();
 
-        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], false, None);
+        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], false, None, None);
 
         assert_eq!(result.files.len(), 1);
         assert_eq!(result.files.get("root.txt"), Some(&"root".into()));
@@ -162,7 +174,7 @@ mod tests {
             .done()
             .build();
 
-        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], true, None);
+        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], true, None, None);
 
         assert!(result.dirs.contains(&"work".into()));
         assert!(result.dirs.contains(&"personal".into()));
@@ -176,7 +188,7 @@ mod tests {
             .done()
             .build();
 
-        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], false, None);
+        let result = scan_and_read(env.path(), env.path(), &["*.txt".into()], false, None, None);
 
         assert!(!result.dirs.iter().any(|d| d.contains(UUID_1)));
         assert_eq!(
@@ -194,7 +206,7 @@ mod tests {
             .build();
 
         let scan_dir = env.path().join("sessions").join(UUID_1);
-        let result = scan_and_read(&scan_dir, env.path(), &["*.json".into()], false, None);
+        let result = scan_and_read(&scan_dir, env.path(), &["*.json".into()], false, None, None);
 
         assert_eq!(result.files.len(), 1);
         assert_eq!(
@@ -34,13 +34,153 @@ fn find_session_dir_recursive(dir: &Path, session_id: &str) -> Option<PathBuf> {
     None
 }
 
-pub fn delete_session_dir(session_dir: &Path) -> std::io::Result<()> {
-    if session_dir.exists() {
-        std::fs::remove_dir_all(session_dir)?;
+pub(crate) const TRASH_DIR: &str = ".trash";
+// Created when a folder is trashed, so its mtime says when that happened.
+const TRASHED_MARKER: &str = ".trashed";
+const SESSION_META_FILE: &str = "_meta.json";
+
+pub fn trash_dir(base: &Path) -> PathBuf {
+    base.join(TRASH_DIR)
+}
+
+/// Moves the session folder into `<base>/.trash` at the same place in the
+/// folder tree, so it can be restored where it was.
+pub fn delete_session_dir(base: &Path, session_dir: &Path) -> std::io::Result<()> {
+    if !session_dir.exists() {
+        return Ok(());
+    }
+
+    let relative = match session_dir.strip_prefix(base) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => PathBuf::from(session_dir.file_name().unwrap_or_default()),
+    };
+    let target = trash_dir(base).join(relative);
+
+    if target.exists() {
+        std::fs::remove_dir_all(&target)?;
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+
+    std::fs::rename(session_dir, &target)?;
+    std::fs::File::create(target.join(TRASHED_MARKER))?;
     Ok(())
 }
 
+/// Moves a trashed session folder back to where it was deleted from and
+/// returns its path, or `None` if it isn't in the trash.
+pub fn restore_session_dir(base: &Path, session_id: &str) -> std::io::Result<Option<PathBuf>> {
+    let trash = trash_dir(base);
+    let Some(trashed) = find_session_dir_recursive(&trash, session_id) else {
+        return Ok(None);
+    };
+
+    let relative = trashed.strip_prefix(&trash).unwrap_or(&trashed);
+    let target = base.join(relative);
+    if target.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::remove_file(trashed.join(TRASHED_MARKER)).ok();
+    std::fs::rename(&trashed, &target)?;
+    Ok(Some(target))
+}
+
+/// Permanently removes session folders trashed longer than `older_than` ago,
+/// returning how many went.
+pub fn purge_trash(base: &Path, older_than: std::time::Duration) -> std::io::Result<usize> {
+    fn purge(dir: &Path, older_than: std::time::Duration) -> std::io::Result<usize> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(0);
+        };
+
+        let mut purged = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let is_session = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(is_uuid);
+            if !is_session {
+                purged += purge(&path, older_than)?;
+                continue;
+            }
+
+            // Folders without a marker were trashed by hand; their own mtime
+            // is the best guess.
+            let trashed_at = std::fs::metadata(path.join(TRASHED_MARKER))
+                .or_else(|_| std::fs::metadata(&path))?
+                .modified()?;
+            if trashed_at.elapsed().unwrap_or_default() >= older_than {
+                std::fs::remove_dir_all(&path)?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    purge(&trash_dir(base), older_than)
+}
+
+/// Session folders in the trash, most recently trashed first.
+pub fn list_trash(base: &Path, key: Option<&hypr_crypto::Key>) -> Vec<crate::TrashedSession> {
+    fn collect(dir: &Path, key: Option<&hypr_crypto::Key>, out: &mut Vec<crate::TrashedSession>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !is_uuid(name) {
+                collect(&path, key, out);
+                continue;
+            }
+
+            let trashed_at = std::fs::metadata(path.join(TRASHED_MARKER))
+                .or_else(|_| std::fs::metadata(&path))
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default();
+            let title = hypr_crypto::read_to_string(&path.join(SESSION_META_FILE), key)
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .and_then(|meta| Some(meta.get("title")?.as_str()?.to_string()))
+                .unwrap_or_default();
+
+            out.push(crate::TrashedSession {
+                session_id: name.to_string(),
+                title,
+                trashed_at,
+            });
+        }
+    }
+
+    let mut trashed = Vec::new();
+    collect(&trash_dir(base), key, &mut trashed);
+    trashed.sort_by(|a, b| b.trashed_at.cmp(&a.trashed_at));
+    trashed
+}
+
 pub fn list_uuid_files(dir: &Path, ext: &str) -> Vec<(String, PathBuf)> {
     let Ok(entries) = std::fs::read_dir(dir) else {
         return Vec::new();
@@ -117,11 +257,14 @@ mod tests {
     }
 
     #[test]
-    fn delete_session_dir_removes_directory() {
+    fn delete_session_dir_moves_directory_to_trash() {
         let env = TestEnv::new().session(UUID_1).done().build();
 
-        delete_session_dir(&env.session_path(UUID_1)).unwrap();
+        delete_session_dir(env.path(), &env.session_path(UUID_1)).unwrap();
         env.child(UUID_1).assert(predicate::path::missing());
+        env.child(".trash")
+            .child(UUID_1)
+            .assert(predicate::path::is_dir());
     }
 
     #[test]
@@ -129,10 +272,83 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let missing = temp.path().join(UUID_1);
 
-        let result = delete_session_dir(&missing);
+        let result = delete_session_dir(temp.path(), &missing);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn delete_session_dir_moves_to_trash_and_restores() {
+        let env = TestEnv::new()
+            .folder("sessions/work")
+            .session(UUID_1)
+            .done_folder()
+            .done()
+            .build();
+        let session_dir = env.folder_session_path("sessions/work", UUID_1);
+
+        delete_session_dir(env.path(), &session_dir).unwrap();
+        env.child(".trash/sessions/work")
+            .child(UUID_1)
+            .assert(predicate::path::is_dir());
+        assert_eq!(
+            find_session_dir(&env.path().join("sessions"), UUID_1),
+            env.path().join("sessions").join(UUID_1)
+        );
+
+        let restored = restore_session_dir(env.path(), UUID_1).unwrap();
+        assert_eq!(restored, Some(session_dir.clone()));
+        assert!(!session_dir.join(TRASHED_MARKER).exists());
+        assert_eq!(restore_session_dir(env.path(), UUID_1).unwrap(), None);
+    }
+
+    #[test]
+    fn purge_trash_removes_old_sessions() {
+        let env = TestEnv::new()
+            .session(UUID_1)
+            .done()
+            .session(UUID_2)
+            .done()
+            .build();
+
+        delete_session_dir(env.path(), &env.session_path(UUID_1)).unwrap();
+        assert_eq!(
+            purge_trash(env.path(), std::time::Duration::from_secs(3600)).unwrap(),
+            0
+        );
+        assert_eq!(
+            purge_trash(env.path(), std::time::Duration::ZERO).unwrap(),
+            1
+        );
+
+        env.child(".trash")
+            .child(UUID_1)
+            .assert(predicate::path::missing());
+        env.child(UUID_2).assert(predicate::path::is_dir());
+    }
+
+    #[test]
+    fn list_trash_returns_trashed_sessions() {
+        let env = TestEnv::new()
+            .session(UUID_1)
+            .done()
+            .session(UUID_2)
+            .done()
+            .build();
+        env.child(UUID_1)
+            .child(SESSION_META_FILE)
+            .write_str(r#"{"title":"Standup"}"#)
+            .unwrap();
+
+        assert!(list_trash(env.path(), None).is_empty());
+
+        delete_session_dir(env.path(), &env.session_path(UUID_1)).unwrap();
+        let trashed = list_trash(env.path(), None);
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].session_id, UUID_1);
+        assert_eq!(trashed[0].title, "Standup");
+        assert!(trashed[0].trashed_at > 0);
+    }
+
     #[test]
     fn list_uuid_files_nonexistent_dir_returns_empty() {
         let temp = TempDir::new().unwrap();
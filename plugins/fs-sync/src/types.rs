@@ -38,3 +38,35 @@ pub enum CleanupTarget {
         extension: String,
     },
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum KeySource {
+    Keyring,
+    Passphrase,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub key_source: Option<KeySource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedSession {
+    pub session_id: String,
+    pub title: String,
+    /// Milliseconds since the Unix epoch.
+    pub trashed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteRevision {
+    pub id: String,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+}
@@ -26,6 +26,26 @@ async runEventHooks(event: HookEvent) : Promise<Result<HookResult[], string>> {
 
 /** user-defined types **/
 
+/**
+ * Arguments passed to hooks triggered after action items and decisions are extracted from a session.
+ */
+export type AfterActionItemsExtractedArgs = { 
+/**
+ * Path to the resource directory.
+ */
+resource_dir: string; 
+/**
+ * Application-specific Hyprnote data.
+ */
+app_hyprnote: string; 
+/**
+ * ID of the session the items were extracted from.
+ */
+session_id: string; 
+/**
+ * Extracted action items and decisions, as JSON.
+ */
+action_items: string }
 /**
  * Arguments passed to hooks triggered after listening stops.
  */
@@ -66,7 +86,7 @@ export type HookDefinition = {
  * Shell command to execute when the hook is triggered.
  */
 command: string }
-export type HookEvent = { afterListeningStopped: { args: AfterListeningStoppedArgs } } | { beforeListeningStarted: { args: BeforeListeningStartedArgs } }
+export type HookEvent = { afterActionItemsExtracted: { args: AfterActionItemsExtractedArgs } } | { afterListeningStopped: { args: AfterListeningStoppedArgs } } | { beforeListeningStarted: { args: BeforeListeningStartedArgs } }
 export type HookResult = { command: string; success: boolean; exit_code: number | null; stdout: string; stderr: string }
 /**
  * Configuration for hook execution.
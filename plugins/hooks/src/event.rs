@@ -3,6 +3,9 @@ use std::ffi::OsString;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum HookEvent {
+    #[serde(rename = "afterActionItemsExtracted")]
+    #[specta(rename = "afterActionItemsExtracted")]
+    AfterActionItemsExtracted { args: AfterActionItemsExtractedArgs },
     #[serde(rename = "afterListeningStopped")]
     #[specta(rename = "afterListeningStopped")]
     AfterListeningStopped { args: AfterListeningStoppedArgs },
@@ -14,6 +17,7 @@ pub enum HookEvent {
 impl HookEvent {
     pub fn condition_key(&self) -> &'static str {
         match self {
+            HookEvent::AfterActionItemsExtracted { .. } => "afterActionItemsExtracted",
             HookEvent::AfterListeningStopped { .. } => "afterListeningStopped",
             HookEvent::BeforeListeningStarted { .. } => "beforeListeningStarted",
         }
@@ -21,6 +25,7 @@ impl HookEvent {
 
     pub fn cli_args(&self) -> Vec<OsString> {
         match self {
+            HookEvent::AfterActionItemsExtracted { args } => args.to_cli_args(),
             HookEvent::AfterListeningStopped { args } => args.to_cli_args(),
             HookEvent::BeforeListeningStarted { args } => args.to_cli_args(),
         }
@@ -87,3 +92,28 @@ impl HookArgs for BeforeListeningStartedArgs {
         args
     }
 }
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
+/// Arguments passed to hooks triggered after action items and decisions are extracted from a session.
+pub struct AfterActionItemsExtractedArgs {
+    /// Path to the resource directory.
+    pub resource_dir: String,
+    /// Application-specific Hyprnote data.
+    pub app_hyprnote: String,
+    /// ID of the session the items were extracted from.
+    pub session_id: String,
+    /// Extracted action items and decisions, as JSON.
+    pub action_items: String,
+}
+
+impl HookArgs for AfterActionItemsExtractedArgs {
+    fn to_cli_args(&self) -> Vec<OsString> {
+        let mut args = Vec::with_capacity(8);
+        push_cli_arg(&mut args, stringify!(resource_dir), &self.resource_dir);
+        push_cli_arg(&mut args, stringify!(app_hyprnote), &self.app_hyprnote);
+        push_cli_arg(&mut args, stringify!(session_id), &self.session_id);
+        push_cli_arg(&mut args, stringify!(action_items), &self.action_items);
+
+        args
+    }
+}
@@ -11,19 +11,69 @@ use transforms::{session_to_imported_note, session_to_imported_transcript};
 pub async fn import_all_from_path(path: &Path) -> Result<ImportResult, crate::Error> {
     let db = hypr_db_core::DatabaseBuilder::default()
         .local(path)
+        .read_only()
         .build()
         .await?;
     let db = UserDatabase::from(db);
 
-    // Older Hyprnote DBs can have `sessions.words` as NULL/empty, but db-user's
-    // `Session::from_row` expects a non-null JSON string.
+    // The source is only read. Where it differs from what db-user's ops
+    // expect, temporary views, which shadow the tables of the same name on
+    // this connection, make up for it: older Hyprnote DBs can have
+    // `sessions.words` as NULL/empty where `Session::from_row` expects a JSON
+    // string, and lack the `deleted_at` columns the list ops filter on.
     let conn = db.conn()?;
-    conn.execute(
-        "UPDATE sessions SET words = '[]' WHERE words IS NULL OR words = ''",
-        (),
-    )
-    .await
-    .map_err(hypr_db_user::Error::from)?;
+    for table in ["sessions", "humans", "templates"] {
+        let mut rows = conn
+            .query(
+                "SELECT name FROM pragma_table_info(?) ORDER BY cid",
+                vec![table],
+            )
+            .await
+            .map_err(hypr_db_user::Error::from)?;
+
+        let mut columns = Vec::new();
+        while let Some(row) = rows.next().await.map_err(hypr_db_user::Error::from)? {
+            columns.push(row.get::<String>(0).map_err(hypr_db_user::Error::from)?);
+        }
+
+        let has_deleted_at = columns.iter().any(|c| c == "deleted_at");
+        let mut select = columns
+            .into_iter()
+            .map(|c| match (table, c.as_str()) {
+                ("sessions", "words") => "COALESCE(NULLIF(words, ''), '[]') AS words".to_string(),
+                _ => format!("\"{}\"", c),
+            })
+            .collect::<Vec<_>>();
+        if !has_deleted_at {
+            select.push("NULL AS deleted_at".to_string());
+        }
+
+        conn.execute(
+            &format!(
+                "CREATE TEMP VIEW {} AS SELECT {} FROM main.{}",
+                table,
+                select.join(", "),
+                table
+            ),
+            (),
+        )
+        .await
+        .map_err(hypr_db_user::Error::from)?;
+    }
+
+    // Databases from after the transcript moved out of `sessions.words` keep
+    // it in `transcript_words`, with the JSON column left empty.
+    let has_transcript_words = conn
+        .query(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'transcript_words'",
+            (),
+        )
+        .await
+        .map_err(hypr_db_user::Error::from)?
+        .next()
+        .await
+        .map_err(hypr_db_user::Error::from)?
+        .is_some();
 
     let sessions = db.list_sessions(None).await?;
 
@@ -31,7 +81,11 @@ pub async fn import_all_from_path(path: &Path) -> Result<ImportResult, crate::Er
     let mut transcripts = Vec::new();
     let mut participants = Vec::new();
 
-    for session in sessions {
+    for mut session in sessions {
+        if session.words.is_empty() && has_transcript_words {
+            session.words = db.get_words(&session.id).await?;
+        }
+
         let session_participants = db.session_list_participants(&session.id).await?;
         for human in session_participants {
             participants.push(ImportedSessionParticipant {
@@ -15,12 +15,7 @@ pub const SAMPLE_RATE: u32 = 16 * 1000;
 #[cfg(not(target_os = "macos"))]
 pub const SAMPLE_RATE: u32 = 16 * 1000;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ChannelMode {
-    MicOnly,
-    SpeakerOnly,
-    MicAndSpeaker,
-}
+pub use hypr_audio_pipeline::ChannelMode;
 
 #[cfg(target_os = "macos")]
 #[derive(Debug, Clone, Copy)]
@@ -57,68 +52,58 @@ fn determine_from_state(onboarding: bool, state: DeviceState) -> ChannelMode {
     ChannelMode::MicAndSpeaker
 }
 
-impl ChannelMode {
-    #[cfg(target_os = "macos")]
-    pub fn determine(onboarding: bool) -> Self {
-        use hypr_audio_device::macos::{
-            is_default_input_external, is_default_output_external,
-            is_headphone_from_default_output_device,
-        };
-
-        fn is_builtin_display_foldable() -> bool {
-            hypr_mac::ModelIdentifier::current()
-                .ok()
-                .flatten()
-                .map(|model| model.has_foldable_display())
-                .unwrap_or(false)
-        }
-
-        fn has_builtin_mic() -> bool {
-            hypr_mac::ModelIdentifier::current()
-                .ok()
-                .flatten()
-                .map(|model| model.has_builtin_mic())
-                .unwrap_or(false)
-        }
-
-        determine_from_state(
-            onboarding,
-            DeviceState {
-                is_headphone: is_headphone_from_default_output_device(),
-                is_foldable: is_builtin_display_foldable(),
-                is_display_inactive: hypr_mac::is_builtin_display_inactive(),
-                has_builtin_mic: has_builtin_mic(),
-                is_input_external: is_default_input_external(),
-                is_output_external: is_default_output_external(),
-            },
-        )
+#[cfg(target_os = "macos")]
+pub fn determine_channel_mode(onboarding: bool) -> ChannelMode {
+    use hypr_audio_device::macos::{
+        is_default_input_external, is_default_output_external,
+        is_headphone_from_default_output_device,
+    };
+
+    fn is_builtin_display_foldable() -> bool {
+        hypr_mac::ModelIdentifier::current()
+            .ok()
+            .flatten()
+            .map(|model| model.has_foldable_display())
+            .unwrap_or(false)
     }
 
-    #[cfg(target_os = "linux")]
-    pub fn determine(onboarding: bool) -> Self {
-        if onboarding {
-            return ChannelMode::SpeakerOnly;
-        }
+    fn has_builtin_mic() -> bool {
+        hypr_mac::ModelIdentifier::current()
+            .ok()
+            .flatten()
+            .map(|model| model.has_builtin_mic())
+            .unwrap_or(false)
+    }
 
-        if hypr_audio_device::linux::is_headphone_from_default_output_device() == Some(true) {
-            return ChannelMode::MicAndSpeaker;
-        }
+    determine_from_state(
+        onboarding,
+        DeviceState {
+            is_headphone: is_headphone_from_default_output_device(),
+            is_foldable: is_builtin_display_foldable(),
+            is_display_inactive: hypr_mac::is_builtin_display_inactive(),
+            has_builtin_mic: has_builtin_mic(),
+            is_input_external: is_default_input_external(),
+            is_output_external: is_default_output_external(),
+        },
+    )
+}
 
-        ChannelMode::MicAndSpeaker
+#[cfg(target_os = "linux")]
+pub fn determine_channel_mode(onboarding: bool) -> ChannelMode {
+    if onboarding {
+        return ChannelMode::SpeakerOnly;
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-    pub fn determine(_onboarding: bool) -> Self {
-        ChannelMode::MicAndSpeaker
+    if hypr_audio_device::linux::is_headphone_from_default_output_device() == Some(true) {
+        return ChannelMode::MicAndSpeaker;
     }
 
-    pub fn uses_mic(self) -> bool {
-        matches!(self, ChannelMode::MicOnly | ChannelMode::MicAndSpeaker)
-    }
+    ChannelMode::MicAndSpeaker
+}
 
-    pub fn uses_speaker(self) -> bool {
-        matches!(self, ChannelMode::SpeakerOnly | ChannelMode::MicAndSpeaker)
-    }
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn determine_channel_mode(_onboarding: bool) -> ChannelMode {
+    ChannelMode::MicAndSpeaker
 }
 
 #[derive(Clone)]
@@ -1,13 +1,15 @@
 use std::fs::File;
-use std::io::BufWriter;
-use std::path::PathBuf;
+use std::io::{BufWriter, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 
 use hypr_audio_utils::{
-    VorbisEncodeSettings, decode_vorbis_to_mono_wav_file, encode_wav_to_vorbis_file_mono_as_stereo,
-    mix_audio_f32,
+    VorbisEncodeSettings, decode_vorbis_mono_to_pcm, decode_vorbis_to_mono_wav_file,
+    encode_pcm_to_vorbis_mono_as_stereo, encode_wav_to_vorbis_file_mono_as_stereo, mix_audio_f32,
 };
+use hypr_crypto::{EncryptWriter, Key};
 use ractor::{Actor, ActorName, ActorProcessingErr, ActorRef};
 use tauri_plugin_fs_sync::find_session_dir;
 
@@ -21,14 +23,62 @@ pub enum RecMsg {
 pub struct RecArgs {
     pub app_dir: PathBuf,
     pub session_id: String,
+    pub encryption_key: Option<Key>,
+}
+
+// With encryption on, samples go through the cipher as they're written, so
+// nothing plain reaches the disk. The stream can't seek back to fill in a
+// WAV header, so it holds raw little-endian `f32` samples instead.
+enum Sink {
+    Wav(hound::WavWriter<BufWriter<File>>),
+    Sealed(EncryptWriter<BufWriter<File>>),
+}
+
+impl Sink {
+    fn write_samples(&mut self, samples: &[f32]) -> Result<(), hound::Error> {
+        match self {
+            Sink::Wav(writer) => {
+                for s in samples {
+                    writer.write_sample(*s)?;
+                }
+            }
+            Sink::Sealed(writer) => {
+                let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+                writer.write_all(&bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), hound::Error> {
+        match self {
+            Sink::Wav(writer) => writer.flush(),
+            Sink::Sealed(writer) => Ok(writer.flush()?),
+        }
+    }
+
+    fn finalize(self) -> Result<(), hound::Error> {
+        match self {
+            Sink::Wav(mut writer) => {
+                writer.flush()?;
+                writer.finalize()
+            }
+            Sink::Sealed(writer) => {
+                let file = writer.finish()?.into_inner().map_err(|e| e.into_error())?;
+                Ok(file.sync_all()?)
+            }
+        }
+    }
 }
 
 pub struct RecState {
-    writer: Option<hound::WavWriter<BufWriter<File>>>,
+    writer: Option<Sink>,
     writer_mic: Option<hound::WavWriter<BufWriter<File>>>,
     writer_spk: Option<hound::WavWriter<BufWriter<File>>>,
     wav_path: PathBuf,
+    pcm_path: PathBuf,
     ogg_path: PathBuf,
+    encryption_key: Option<Key>,
     last_flush: Instant,
 }
 
@@ -56,13 +106,9 @@ impl Actor for RecorderActor {
 
         let filename_base = "audio".to_string();
         let wav_path = dir.join(format!("{}.wav", filename_base));
+        let pcm_path = dir.join(format!("{}.pcm", filename_base));
         let ogg_path = dir.join(format!("{}.ogg", filename_base));
 
-        if ogg_path.exists() {
-            decode_vorbis_to_mono_wav_file(&ogg_path, &wav_path).map_err(into_actor_err)?;
-            std::fs::remove_file(&ogg_path)?;
-        }
-
         let spec = hound::WavSpec {
             channels: 1,
             sample_rate: super::SAMPLE_RATE,
@@ -70,13 +116,25 @@ impl Actor for RecorderActor {
             sample_format: hound::SampleFormat::Float,
         };
 
-        let writer = if wav_path.exists() {
-            hound::WavWriter::append(&wav_path)?
-        } else {
-            hound::WavWriter::create(&wav_path, spec)?
+        let writer = match &args.encryption_key {
+            Some(key) => Sink::Sealed(open_sealed(&ogg_path, &wav_path, &pcm_path, key)?),
+            None => {
+                if ogg_path.exists() {
+                    decode_vorbis_to_mono_wav_file(&ogg_path, &wav_path).map_err(into_actor_err)?;
+                    std::fs::remove_file(&ogg_path)?;
+                }
+
+                if wav_path.exists() {
+                    Sink::Wav(hound::WavWriter::append(&wav_path)?)
+                } else {
+                    Sink::Wav(hound::WavWriter::create(&wav_path, spec)?)
+                }
+            }
         };
 
-        let (writer_mic, writer_spk) = if is_debug_mode() {
+        // The per-channel debug recordings are plain WAVs, so they're left
+        // out while encryption is on.
+        let (writer_mic, writer_spk) = if is_debug_mode() && args.encryption_key.is_none() {
             let mic_path = dir.join(format!("{}_mic.wav", filename_base));
             let spk_path = dir.join(format!("{}_spk.wav", filename_base));
 
@@ -102,7 +160,9 @@ impl Actor for RecorderActor {
             writer_mic,
             writer_spk,
             wav_path,
+            pcm_path,
             ogg_path,
+            encryption_key: args.encryption_key,
             last_flush: Instant::now(),
         })
     }
@@ -116,18 +176,13 @@ impl Actor for RecorderActor {
         match msg {
             RecMsg::AudioSingle(samples) => {
                 if let Some(ref mut writer) = st.writer {
-                    for s in samples.iter() {
-                        writer.write_sample(*s)?;
-                    }
+                    writer.write_samples(&samples)?;
                 }
                 flush_if_due(st)?;
             }
             RecMsg::AudioDual(mic, spk) => {
                 if let Some(ref mut writer) = st.writer {
-                    let mixed = mix_audio_f32(&mic, &spk);
-                    for sample in mixed {
-                        writer.write_sample(sample)?;
-                    }
+                    writer.write_samples(&mix_audio_f32(&mic, &spk))?;
                 }
 
                 if st.writer_mic.is_some() {
@@ -156,10 +211,19 @@ impl Actor for RecorderActor {
         _myself: ActorRef<Self::Msg>,
         st: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
-        finalize_writer(&mut st.writer)?;
+        if let Some(writer) = st.writer.take() {
+            writer.finalize()?;
+        }
         finalize_writer(&mut st.writer_mic)?;
         finalize_writer(&mut st.writer_spk)?;
 
+        if let Some(key) = &st.encryption_key {
+            if st.pcm_path.exists() {
+                seal_recording(&st.pcm_path, &st.ogg_path, key)?;
+            }
+            return Ok(());
+        }
+
         if st.wav_path.exists() {
             let temp_ogg_path = st.ogg_path.with_extension("ogg.tmp");
 
@@ -188,6 +252,88 @@ fn into_actor_err(err: hypr_audio_utils::Error) -> ActorProcessingErr {
     Box::new(err)
 }
 
+// Continues an earlier recording of the session, whichever form it was left
+// in: finished, cut off while sealed, or a plain WAV from before encryption
+// was turned on. A sealed stream can't be appended to, so what's there is
+// streamed into a new one. An unfinished stream is moved aside first; if the
+// copy is interrupted, that file still holds the whole recording and is used
+// again next time.
+fn open_sealed(
+    ogg_path: &Path,
+    wav_path: &Path,
+    pcm_path: &Path,
+    key: &Key,
+) -> Result<EncryptWriter<BufWriter<File>>, ActorProcessingErr> {
+    let previous_pcm_path = pcm_path.with_extension("pcm.prev");
+    if !ogg_path.exists() && pcm_path.exists() && !previous_pcm_path.exists() {
+        std::fs::rename(pcm_path, &previous_pcm_path)?;
+    }
+
+    let mut writer = EncryptWriter::new(BufWriter::new(File::create(pcm_path)?), key)?;
+    if ogg_path.exists() {
+        decode_vorbis_mono_to_pcm(hypr_crypto::open(ogg_path, Some(key))?, &mut writer)
+            .map_err(into_actor_err)?;
+    } else if previous_pcm_path.exists() {
+        hypr_crypto::copy_partial(&previous_pcm_path, key, &mut writer)?;
+    } else if wav_path.exists() {
+        let mut wav = hound::WavReader::new(hypr_crypto::open(wav_path, Some(key))?)?;
+        for sample in wav.samples::<f32>() {
+            writer.write_all(&sample?.to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+
+    if ogg_path.exists() {
+        std::fs::remove_file(ogg_path)?;
+    }
+    if previous_pcm_path.exists() {
+        std::fs::remove_file(&previous_pcm_path)?;
+    }
+    if wav_path.exists() {
+        hypr_crypto::remove_securely(wav_path)?;
+    }
+    Ok(writer)
+}
+
+// Encodes the sealed samples to Ogg as they're decrypted, and encrypts the
+// Ogg on its way to disk, so the recording is never held in memory. The
+// samples are kept if encoding fails, like the WAV is without encryption.
+fn seal_recording(pcm_path: &Path, ogg_path: &Path, key: &Key) -> Result<(), ActorProcessingErr> {
+    let sample_rate = NonZeroU32::new(super::SAMPLE_RATE).expect("non-zero sample rate");
+    let temp_ogg_path = ogg_path.with_extension("ogg.tmp");
+
+    let encoded = hypr_crypto::open(pcm_path, Some(key))
+        .map_err(hypr_audio_utils::Error::from)
+        .and_then(|pcm| {
+            let ogg = EncryptWriter::new(BufWriter::new(File::create(&temp_ogg_path)?), key)?;
+            encode_pcm_to_vorbis_mono_as_stereo(
+                pcm,
+                ogg,
+                sample_rate,
+                VorbisEncodeSettings::default(),
+            )
+        })
+        .and_then(|ogg| {
+            ogg.finish()?
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_all()?;
+            Ok(())
+        });
+
+    match encoded {
+        Ok(()) => {
+            std::fs::rename(&temp_ogg_path, ogg_path)?;
+            std::fs::remove_file(pcm_path)?;
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "pcm_to_ogg_failed_keeping_pcm");
+            let _ = std::fs::remove_file(&temp_ogg_path);
+        }
+    }
+    Ok(())
+}
+
 fn is_debug_mode() -> bool {
     cfg!(debug_assertions)
         || std::env::var("HYPRNOTE_DEBUG")
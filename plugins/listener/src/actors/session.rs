@@ -6,9 +6,11 @@ use ractor::{Actor, ActorCell, ActorProcessingErr};
 use ractor_supervisor::SupervisorStrategy;
 use ractor_supervisor::core::{ChildBackoffFn, ChildSpec, Restart, SpawnFn};
 use ractor_supervisor::supervisor::{Supervisor, SupervisorArguments, SupervisorOptions};
+use tauri_plugin_fs_sync::FsSyncPluginExt;
 
 use crate::actors::{
-    ChannelMode, ListenerActor, ListenerArgs, RecArgs, RecorderActor, SourceActor, SourceArgs,
+    ListenerActor, ListenerArgs, RecArgs, RecorderActor, SourceActor, SourceArgs,
+    determine_channel_mode,
 };
 
 pub const SESSION_SUPERVISOR_PREFIX: &str = "session_supervisor_";
@@ -97,7 +99,7 @@ pub async fn spawn_session_supervisor(
         spawn_fn: SpawnFn::new(move |supervisor_cell, _id| {
             let ctx = ctx_listener.clone();
             async move {
-                let mode = ChannelMode::determine(ctx.params.onboarding);
+                let mode = determine_channel_mode(ctx.params.onboarding);
 
                 let (actor_ref, _) = Actor::spawn_linked(
                     Some(ListenerActor::name()),
@@ -125,7 +127,18 @@ pub async fn spawn_session_supervisor(
         reset_after: Some(Duration::from_secs(30)),
     });
 
-    if ctx.params.record_enabled {
+    // While encryption is on but locked, a recording could only be written in
+    // plain text, so the session goes on without one.
+    let record_enabled = ctx.params.record_enabled
+        && match ctx.app.fs_sync().encryption_key() {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!("recording_disabled: {}", e);
+                false
+            }
+        };
+
+    if record_enabled {
         let ctx_recorder = ctx.clone();
         child_specs.push(ChildSpec {
             id: RecorderActor::name().to_string(),
@@ -133,12 +146,18 @@ pub async fn spawn_session_supervisor(
             spawn_fn: SpawnFn::new(move |supervisor_cell, _id| {
                 let ctx = ctx_recorder.clone();
                 async move {
+                    let encryption_key = ctx
+                        .app
+                        .fs_sync()
+                        .encryption_key()
+                        .map_err(|e| ractor::SpawnErr::StartupFailed(Box::new(e)))?;
                     let (actor_ref, _) = Actor::spawn_linked(
                         Some(RecorderActor::name()),
                         RecorderActor,
                         RecArgs {
                             app_dir: ctx.app_dir.clone(),
                             session_id: ctx.params.session_id.clone(),
+                            encryption_key,
                         },
                         supervisor_cell,
                     )
@@ -0,0 +1,228 @@
+use std::time::{Duration, Instant};
+
+use ractor::{ActorProcessingErr, ActorRef};
+use tauri_specta::Event;
+
+use crate::{SessionProgressEvent, actors::determine_channel_mode};
+use hypr_audio::AudioInput;
+use hypr_device_monitor::DeviceSwitch;
+
+use super::{
+    SourceMsg, SourceState,
+    stream::{StreamGeneration, start_source_loop},
+};
+
+/// A device switch whose new streams haven't delivered audio yet.
+pub(super) struct PendingSwitch {
+    started: Instant,
+    reason: &'static str,
+    previous_device: Option<String>,
+    /// The run of streams opened for the switch.
+    generation: StreamGeneration,
+}
+
+impl PendingSwitch {
+    /// How long audio was missing, once a chunk of `chunk_len` samples arrives
+    /// at `now`; `None` while the chunk comes from an older run of streams.
+    fn gap(
+        &self,
+        generation: StreamGeneration,
+        chunk_len: usize,
+        now: Instant,
+    ) -> Option<Duration> {
+        if generation != self.generation {
+            return None;
+        }
+
+        // The first chunk already covers its own duration.
+        let chunk_duration =
+            Duration::from_secs_f64(chunk_len as f64 / crate::actors::SAMPLE_RATE as f64);
+        Some(
+            now.saturating_duration_since(self.started)
+                .saturating_sub(chunk_duration),
+        )
+    }
+}
+
+fn reason(switch: &DeviceSwitch) -> &'static str {
+    match switch {
+        DeviceSwitch::DefaultInputChanged => "default_input_changed",
+        DeviceSwitch::DefaultOutputChanged { .. } => "default_output_changed",
+        DeviceSwitch::DeviceListChanged => "device_list_changed",
+    }
+}
+
+/// The pinned mic while it is still connected, otherwise the system default.
+fn resolve_mic_device(pinned: Option<&str>) -> Option<String> {
+    if let Some(pinned) = pinned
+        && AudioInput::list_mic_devices().iter().any(|d| d == pinned)
+    {
+        return Some(pinned.to_string());
+    }
+
+    Some(AudioInput::get_default_device_name())
+}
+
+pub(super) async fn handle_device_switch(
+    myself: &ActorRef<SourceMsg>,
+    st: &mut SourceState,
+    switch: DeviceSwitch,
+) -> Result<(), ActorProcessingErr> {
+    let reason = reason(&switch);
+
+    // The listener's STT connection is opened for a fixed channel layout, so a
+    // mode change still needs the whole session restarted.
+    let new_mode = determine_channel_mode(st.onboarding);
+    if new_mode != st.current_mode {
+        tracing::info!(
+            reason,
+            old_mode = ?st.current_mode,
+            ?new_mode,
+            "channel_mode_changed_restarting_source"
+        );
+        myself.stop(Some("channel_mode_changed".to_string()));
+        return Ok(());
+    }
+
+    let next_mic = resolve_mic_device(st.pinned_mic.as_deref());
+    let mic_changed = next_mic != st.mic_device;
+
+    let should_reopen = match switch {
+        // A new default can share the old one's name (e.g. AirPods reconnecting),
+        // so reopen unless a pinned device is still there.
+        DeviceSwitch::DefaultInputChanged => {
+            st.current_mode.uses_mic() && (mic_changed || st.pinned_mic.is_none())
+        }
+        DeviceSwitch::DefaultOutputChanged { .. } => st.current_mode.uses_speaker(),
+        DeviceSwitch::DeviceListChanged => st.current_mode.uses_mic() && mic_changed,
+    };
+
+    if !should_reopen {
+        tracing::debug!(reason, device = ?st.mic_device, "device_switch_ignored");
+        return Ok(());
+    }
+
+    tracing::info!(
+        reason,
+        from = ?st.mic_device,
+        to = ?next_mic,
+        "device_switch_reopening_streams"
+    );
+
+    // `start_source_loop` opens the new streams as the next generation.
+    let pending = PendingSwitch {
+        started: Instant::now(),
+        reason,
+        previous_device: st.mic_device.clone(),
+        generation: st.stream_generation.next(),
+    };
+
+    st.stop_streams();
+    st.pipeline.drain(st.current_mode);
+    st.mic_device = next_mic;
+    st.pending_switch = Some(pending);
+
+    start_source_loop(myself, st).await
+}
+
+/// Called for every chunk. Returns whether it comes from the current streams;
+/// chunks still queued from streams stopped by a switch are dropped. Once the
+/// new streams produce audio, fills the time they were down with silence and
+/// reports the switch.
+pub(super) fn accept_chunk(
+    st: &mut SourceState,
+    generation: StreamGeneration,
+    chunk_len: usize,
+) -> bool {
+    if generation != st.stream_generation {
+        tracing::debug!(?generation, current = ?st.stream_generation, "stale_chunk_dropped");
+        return false;
+    }
+
+    let Some(gap) = st
+        .pending_switch
+        .as_ref()
+        .and_then(|pending| pending.gap(generation, chunk_len, Instant::now()))
+    else {
+        return true;
+    };
+    let Some(pending) = st.pending_switch.take() else {
+        return true;
+    };
+
+    st.pipeline.insert_gap(gap, st.current_mode);
+
+    tracing::info!(
+        reason = pending.reason,
+        gap_ms = gap.as_millis() as u64,
+        device = ?st.mic_device,
+        "device_switch_completed"
+    );
+
+    if let Err(error) = (SessionProgressEvent::AudioDeviceSwitched {
+        session_id: st.session_id.clone(),
+        reason: pending.reason.to_string(),
+        previous_device: pending.previous_device,
+        device: st.mic_device.clone(),
+        gap_ms: gap.as_millis() as u32,
+    })
+    .emit(&st.app)
+    {
+        tracing::error!(?error, "failed_to_emit_audio_device_switched");
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(started: Instant) -> PendingSwitch {
+        PendingSwitch {
+            started,
+            reason: "default_input_changed",
+            previous_device: Some("Built-in Microphone".to_string()),
+            generation: StreamGeneration::default().next().next(),
+        }
+    }
+
+    #[test]
+    fn test_chunks_from_old_streams_do_not_complete_switch() {
+        let started = Instant::now();
+        let switch = pending(started);
+        let old = StreamGeneration::default().next();
+
+        let now = started + Duration::from_millis(500);
+        assert_eq!(switch.gap(old, 1_600, now), None);
+        assert!(switch.gap(old.next(), 1_600, now).is_some());
+    }
+
+    #[test]
+    fn test_gap_excludes_first_chunk() {
+        let started = Instant::now();
+        let switch = pending(started);
+        let chunk_len = crate::actors::SAMPLE_RATE as usize / 10;
+
+        let gap = switch.gap(
+            switch.generation,
+            chunk_len,
+            started + Duration::from_millis(350),
+        );
+        assert_eq!(gap, Some(Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn test_gap_saturates_when_chunk_outlasts_switch() {
+        let started = Instant::now();
+        let switch = pending(started);
+        let chunk_len = crate::actors::SAMPLE_RATE as usize;
+
+        let gap = switch.gap(
+            switch.generation,
+            chunk_len,
+            started + Duration::from_millis(100),
+        );
+        assert_eq!(gap, Some(Duration::ZERO));
+    }
+}
@@ -1,3 +1,4 @@
+mod device;
 mod pipeline;
 mod stream;
 
@@ -17,10 +18,13 @@ use crate::{
     actors::{AudioChunk, ChannelMode},
 };
 use hypr_audio::AudioInput;
+use hypr_audio_pipeline::ProcessingConfig;
+use tauri_plugin_settings::SettingsPluginExt;
 use tauri_specta::Event;
 
+use device::{PendingSwitch, accept_chunk, handle_device_switch};
 use pipeline::Pipeline;
-use stream::start_source_loop;
+use stream::{StreamGeneration, start_source_loop};
 
 use hypr_device_monitor::{DeviceMonitorHandle, DeviceSwitch, DeviceSwitchMonitor};
 
@@ -28,9 +32,10 @@ pub enum SourceMsg {
     SetMicMute(bool),
     GetMicMute(RpcReplyPort<bool>),
     GetMicDevice(RpcReplyPort<Option<String>>),
-    MicChunk(AudioChunk),
-    SpeakerChunk(AudioChunk),
+    MicChunk(StreamGeneration, AudioChunk),
+    SpeakerChunk(StreamGeneration, AudioChunk),
     StreamFailed(String),
+    DeviceChanged(DeviceSwitch),
 }
 
 pub struct SourceArgs {
@@ -44,10 +49,13 @@ pub struct SourceState {
     pub(super) app: tauri::AppHandle,
     pub(super) session_id: String,
     pub(super) mic_device: Option<String>,
+    pinned_mic: Option<String>,
+    pending_switch: Option<PendingSwitch>,
     pub(super) onboarding: bool,
     pub(super) mic_muted: Arc<AtomicBool>,
     pub(super) run_task: Option<tokio::task::JoinHandle<()>>,
     pub(super) stream_cancel_token: Option<CancellationToken>,
+    pub(super) stream_generation: StreamGeneration,
     pub(super) current_mode: ChannelMode,
     pub(super) pipeline: Pipeline,
     _device_watcher: Option<DeviceChangeWatcher>,
@@ -74,14 +82,10 @@ impl DeviceChangeWatcher {
     }
 
     fn event_loop(event_rx: Receiver<DeviceSwitch>, actor: ActorRef<SourceMsg>) {
-        loop {
-            match event_rx.recv() {
-                Ok(DeviceSwitch::DefaultInputChanged) => {
-                    tracing::info!("default_input_changed_restarting_source");
-                    actor.stop(Some("device_change".to_string()));
-                }
-                Ok(_) => {}
-                Err(_) => break,
+        while let Ok(switch) = event_rx.recv() {
+            tracing::info!(?switch, "device_switch_detected");
+            if actor.cast(SourceMsg::DeviceChanged(switch)).is_err() {
+                break;
             }
         }
     }
@@ -93,6 +97,16 @@ impl SourceActor {
     }
 }
 
+async fn load_processing_config(app: &tauri::AppHandle) -> ProcessingConfig {
+    match app.settings().load().await {
+        Ok(settings) => ProcessingConfig::from_settings(&settings),
+        Err(error) => {
+            tracing::warn!(?error, "failed_to_load_settings_for_audio_processing");
+            ProcessingConfig::default()
+        }
+    }
+}
+
 #[ractor::async_trait]
 impl Actor for SourceActor {
     type Msg = SourceMsg;
@@ -119,21 +133,27 @@ impl Actor for SourceActor {
             let device_watcher = DeviceChangeWatcher::spawn(myself.clone());
 
             let silence_stream_tx = Some(hypr_audio::AudioOutput::silence());
-            let mic_device = args
-                .mic_device
+            let pinned_mic = args.mic_device;
+            let mic_device = pinned_mic
+                .clone()
                 .or_else(|| Some(AudioInput::get_default_device_name()));
             tracing::info!(mic_device = ?mic_device);
 
-            let pipeline = Pipeline::new(args.app.clone(), args.session_id.clone());
+            let processing = load_processing_config(&args.app).await;
+            tracing::info!(?processing, "audio_processing_config");
+            let pipeline = Pipeline::new(args.app.clone(), args.session_id.clone(), &processing);
 
             let mut st = SourceState {
                 app: args.app,
                 session_id: args.session_id,
                 mic_device,
+                pinned_mic,
+                pending_switch: None,
                 onboarding: args.onboarding,
                 mic_muted: Arc::new(AtomicBool::new(false)),
                 run_task: None,
                 stream_cancel_token: None,
+                stream_generation: StreamGeneration::default(),
                 _device_watcher: Some(device_watcher),
                 _silence_stream_tx: silence_stream_tx,
                 current_mode: ChannelMode::MicAndSpeaker,
@@ -154,44 +174,54 @@ impl Actor for SourceActor {
         st: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
         let span = session_span(&st.session_id);
-        let _guard = span.enter();
 
-        match msg {
-            SourceMsg::SetMicMute(muted) => {
-                st.mic_muted.store(muted, Ordering::Relaxed);
-            }
-            SourceMsg::GetMicMute(reply) => {
-                if !reply.is_closed() {
-                    let _ = reply.send(st.mic_muted.load(Ordering::Relaxed));
+        async {
+            match msg {
+                SourceMsg::SetMicMute(muted) => {
+                    st.mic_muted.store(muted, Ordering::Relaxed);
                 }
-            }
-            SourceMsg::GetMicDevice(reply) => {
-                if !reply.is_closed() {
-                    let _ = reply.send(st.mic_device.clone());
+                SourceMsg::GetMicMute(reply) => {
+                    if !reply.is_closed() {
+                        let _ = reply.send(st.mic_muted.load(Ordering::Relaxed));
+                    }
+                }
+                SourceMsg::GetMicDevice(reply) => {
+                    if !reply.is_closed() {
+                        let _ = reply.send(st.mic_device.clone());
+                    }
+                }
+                SourceMsg::MicChunk(generation, chunk) => {
+                    if accept_chunk(st, generation, chunk.data.len()) {
+                        st.pipeline.ingest_mic(chunk);
+                        st.pipeline.flush(st.current_mode);
+                    }
+                }
+                SourceMsg::SpeakerChunk(generation, chunk) => {
+                    if accept_chunk(st, generation, chunk.data.len()) {
+                        st.pipeline.ingest_speaker(chunk);
+                        st.pipeline.flush(st.current_mode);
+                    }
+                }
+                SourceMsg::StreamFailed(reason) => {
+                    tracing::error!(%reason, "source_stream_failed_stopping");
+                    let _ = (SessionErrorEvent::AudioError {
+                        session_id: st.session_id.clone(),
+                        error: reason.clone(),
+                        device: st.mic_device.clone(),
+                        is_fatal: true,
+                    })
+                    .emit(&st.app);
+                    myself.stop(Some(reason));
+                }
+                SourceMsg::DeviceChanged(switch) => {
+                    handle_device_switch(&myself, st, switch).await?;
                 }
             }
-            SourceMsg::MicChunk(chunk) => {
-                st.pipeline.ingest_mic(chunk);
-                st.pipeline.flush(st.current_mode);
-            }
-            SourceMsg::SpeakerChunk(chunk) => {
-                st.pipeline.ingest_speaker(chunk);
-                st.pipeline.flush(st.current_mode);
-            }
-            SourceMsg::StreamFailed(reason) => {
-                tracing::error!(%reason, "source_stream_failed_stopping");
-                let _ = (SessionErrorEvent::AudioError {
-                    session_id: st.session_id.clone(),
-                    error: reason.clone(),
-                    device: st.mic_device.clone(),
-                    is_fatal: true,
-                })
-                .emit(&st.app);
-                myself.stop(Some(reason));
-            }
-        }
 
-        Ok(())
+            Ok(())
+        }
+        .instrument(span)
+        .await
     }
 
     async fn post_stop(
@@ -199,13 +229,18 @@ impl Actor for SourceActor {
         _myself: ActorRef<Self::Msg>,
         st: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
-        if let Some(cancel_token) = st.stream_cancel_token.take() {
+        st.stop_streams();
+        Ok(())
+    }
+}
+
+impl SourceState {
+    pub(super) fn stop_streams(&mut self) {
+        if let Some(cancel_token) = self.stream_cancel_token.take() {
             cancel_token.cancel();
         }
-        if let Some(task) = st.run_task.take() {
+        if let Some(task) = self.run_task.take() {
             task.abort();
         }
-
-        Ok(())
     }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::VecDeque,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -11,18 +11,18 @@ use crate::{
     SessionDataEvent,
     actors::{AudioChunk, ChannelMode, ListenerActor, ListenerMsg, RecMsg, RecorderActor},
 };
-use hypr_aec::AEC;
-use hypr_agc::VadAgc;
-use hypr_audio_utils::f32_to_i16_bytes;
+use hypr_audio_pipeline::{AudioPipeline, ProcessingConfig};
+use hypr_audio_utils::{chunk_size_for_stt, f32_to_i16_bytes};
 
 const AUDIO_AMPLITUDE_THROTTLE: Duration = Duration::from_millis(100);
 const MAX_BUFFER_CHUNKS: usize = 150;
+const METRICS_LOG_INTERVAL: Duration = Duration::from_secs(30);
+// Longer gaps are a stalled device rather than a switch; don't flood the listener.
+const MAX_GAP: Duration = Duration::from_secs(30);
 
 pub(in crate::actors) struct Pipeline {
-    agc_mic: VadAgc,
-    agc_spk: VadAgc,
-    aec: Option<AEC>,
-    joiner: Joiner,
+    core: AudioPipeline,
+    last_metrics_log: Instant,
     amplitude: AmplitudeEmitter,
     audio_buffer: AudioBuffer,
     backlog_quota: f32,
@@ -32,12 +32,14 @@ impl Pipeline {
     const BACKLOG_QUOTA_INCREMENT: f32 = 0.25;
     const MAX_BACKLOG_QUOTA: f32 = 2.0;
 
-    pub(super) fn new(app: tauri::AppHandle, session_id: String) -> Self {
+    pub(super) fn new(
+        app: tauri::AppHandle,
+        session_id: String,
+        config: &ProcessingConfig,
+    ) -> Self {
         Self {
-            agc_mic: VadAgc::default().with_masking(true),
-            agc_spk: VadAgc::default(),
-            aec: None,
-            joiner: Joiner::new(),
+            core: AudioPipeline::new(config, crate::actors::SAMPLE_RATE),
+            last_metrics_log: Instant::now(),
             amplitude: AmplitudeEmitter::new(app, session_id),
             audio_buffer: AudioBuffer::new(MAX_BUFFER_CHUNKS),
             backlog_quota: 0.0,
@@ -45,55 +47,105 @@ impl Pipeline {
     }
 
     pub(super) fn reset(&mut self) {
-        self.joiner.reset();
-        self.agc_mic = VadAgc::default().with_masking(true);
-        self.agc_spk = VadAgc::default();
-        if let Some(aec) = &mut self.aec {
-            aec.reset();
-        }
+        self.log_metrics();
+        self.core.reset();
         self.amplitude.reset();
-        self.audio_buffer.clear();
         self.backlog_quota = 0.0;
     }
 
     pub(super) fn ingest_mic(&mut self, chunk: AudioChunk) {
-        let mut data = chunk.data;
-        self.agc_mic.process(&mut data);
+        let data = self.core.push_mic(chunk.data);
         self.amplitude.observe_mic(&data);
-        let arc = Arc::<[f32]>::from(data);
-        self.joiner.push_mic(arc);
     }
 
     pub(super) fn ingest_speaker(&mut self, chunk: AudioChunk) {
-        let mut data = chunk.data;
-        self.agc_spk.process(&mut data);
-        self.amplitude.observe_spk(&data);
-        let arc = Arc::<[f32]>::from(data);
-        self.joiner.push_spk(arc);
+        for data in self.core.push_speaker(chunk.data) {
+            self.amplitude.observe_spk(&data);
+        }
     }
 
     pub(super) fn flush(&mut self, mode: ChannelMode) {
-        while let Some((mic, spk)) = self.joiner.pop_pair(mode) {
+        while let Some((mic, spk)) = self.core.pop_pair(mode) {
             self.dispatch(mic, spk, mode);
         }
+
+        if self.last_metrics_log.elapsed() >= METRICS_LOG_INTERVAL {
+            self.log_metrics();
+        }
     }
 
-    fn dispatch(&mut self, mic: Arc<[f32]>, spk: Arc<[f32]>, mode: ChannelMode) {
-        let (processed_mic, processed_spk) = if let Some(aec) = &mut self.aec {
-            match aec.process_streaming(&mic, &spk) {
-                Ok(processed) => {
-                    let processed_arc = Arc::<[f32]>::from(processed);
-                    (processed_arc, Arc::clone(&spk))
-                }
-                Err(e) => {
-                    tracing::warn!(error = ?e, "aec_failed");
-                    (mic, spk)
+    /// Pushes out whatever the joiner still holds from streams that are about
+    /// to be closed, padding the missing side with silence.
+    pub(super) fn drain(&mut self, mode: ChannelMode) {
+        self.flush(mode);
+
+        if mode == ChannelMode::MicAndSpeaker {
+            for tail in [ChannelMode::MicOnly, ChannelMode::SpeakerOnly] {
+                while let Some((mic, spk)) = self.core.pop_pair(tail) {
+                    self.dispatch(mic, spk, mode);
                 }
             }
+        }
+    }
+
+    /// Sends `duration` of silence downstream so the recording and transcript
+    /// timelines stay aligned with wall-clock time across a capture gap.
+    pub(super) fn insert_gap(&mut self, duration: Duration, mode: ChannelMode) {
+        let duration = if duration > MAX_GAP {
+            tracing::warn!(
+                gap_ms = duration.as_millis() as u64,
+                "capture_gap_truncated"
+            );
+            MAX_GAP
         } else {
-            (mic, spk)
+            duration
         };
 
+        let chunk_size = chunk_size_for_stt(crate::actors::SAMPLE_RATE);
+        let samples = (duration.as_secs_f64() * crate::actors::SAMPLE_RATE as f64) as usize;
+        let silence = Arc::<[f32]>::from(vec![0.0; chunk_size]);
+
+        for _ in 0..samples.div_ceil(chunk_size) {
+            self.dispatch(Arc::clone(&silence), Arc::clone(&silence), mode);
+        }
+    }
+
+    fn log_metrics(&mut self) {
+        for (channel, node, metrics) in self.core.metrics() {
+            if metrics.frames == 0 {
+                continue;
+            }
+            tracing::info!(
+                channel,
+                node,
+                frames = metrics.frames,
+                errors = metrics.errors,
+                mean_us = metrics.mean().as_micros() as u64,
+                max_us = metrics.max.as_micros() as u64,
+                "audio_processor_metrics"
+            );
+        }
+
+        let drift = self.core.drift_metrics();
+        if drift.converged {
+            tracing::info!(
+                drift_ppm = drift.drift_ppm,
+                ratio = drift.ratio,
+                offset_samples = drift.offset_samples,
+                "audio_drift_metrics"
+            );
+        }
+
+        self.core.reset_metrics();
+        self.last_metrics_log = Instant::now();
+    }
+
+    fn dispatch(
+        &mut self,
+        processed_mic: Arc<[f32]>,
+        processed_spk: Arc<[f32]>,
+        mode: ChannelMode,
+    ) {
         if let Some(cell) = registry::where_is(RecorderActor::name()) {
             let actor: ActorRef<RecMsg> = cell.into();
             let result = match mode {
@@ -207,10 +259,6 @@ impl AudioBuffer {
     fn is_empty(&self) -> bool {
         self.buffer.is_empty()
     }
-
-    fn clear(&mut self) {
-        self.buffer.clear();
-    }
 }
 
 struct AmplitudeEmitter {
@@ -276,91 +324,3 @@ impl AmplitudeEmitter {
             * 100.0) as u16
     }
 }
-
-struct Joiner {
-    mic: VecDeque<Arc<[f32]>>,
-    spk: VecDeque<Arc<[f32]>>,
-    silence_cache: HashMap<usize, Arc<[f32]>>,
-}
-
-impl Joiner {
-    const MAX_LAG: usize = 4;
-    const MAX_QUEUE_SIZE: usize = 30;
-
-    fn new() -> Self {
-        Self {
-            mic: VecDeque::new(),
-            spk: VecDeque::new(),
-            silence_cache: HashMap::new(),
-        }
-    }
-
-    fn reset(&mut self) {
-        self.mic.clear();
-        self.spk.clear();
-    }
-
-    fn get_silence(&mut self, len: usize) -> Arc<[f32]> {
-        self.silence_cache
-            .entry(len)
-            .or_insert_with(|| Arc::from(vec![0.0; len]))
-            .clone()
-    }
-
-    fn push_mic(&mut self, data: Arc<[f32]>) {
-        self.mic.push_back(data);
-        if self.mic.len() > Self::MAX_QUEUE_SIZE {
-            tracing::warn!("mic_queue_overflow");
-            self.mic.pop_front();
-        }
-    }
-
-    fn push_spk(&mut self, data: Arc<[f32]>) {
-        self.spk.push_back(data);
-        if self.spk.len() > Self::MAX_QUEUE_SIZE {
-            tracing::warn!("spk_queue_overflow");
-            self.spk.pop_front();
-        }
-    }
-
-    fn pop_pair(&mut self, mode: ChannelMode) -> Option<(Arc<[f32]>, Arc<[f32]>)> {
-        if self.mic.front().is_some() && self.spk.front().is_some() {
-            return Some((self.mic.pop_front()?, self.spk.pop_front()?));
-        }
-
-        match mode {
-            ChannelMode::MicOnly => {
-                if let Some(mic) = self.mic.pop_front() {
-                    let spk = self.get_silence(mic.len());
-                    return Some((mic, spk));
-                }
-            }
-            ChannelMode::SpeakerOnly => {
-                if let Some(spk) = self.spk.pop_front() {
-                    let mic = self.get_silence(spk.len());
-                    return Some((mic, spk));
-                }
-            }
-            ChannelMode::MicAndSpeaker => {
-                if self.mic.front().is_some()
-                    && self.spk.is_empty()
-                    && self.mic.len() > Self::MAX_LAG
-                {
-                    let mic = self.mic.pop_front()?;
-                    let spk = self.get_silence(mic.len());
-                    return Some((mic, spk));
-                }
-                if self.spk.front().is_some()
-                    && self.mic.is_empty()
-                    && self.spk.len() > Self::MAX_LAG
-                {
-                    let spk = self.spk.pop_front()?;
-                    let mic = self.get_silence(spk.len());
-                    return Some((mic, spk));
-                }
-            }
-        }
-
-        None
-    }
-}
@@ -9,19 +9,30 @@ use tokio_util::sync::CancellationToken;
 
 use crate::{
     SessionProgressEvent,
-    actors::{AudioChunk, ChannelMode},
+    actors::{AudioChunk, ChannelMode, determine_channel_mode},
 };
-use hypr_audio::AudioInput;
+use hypr_audio::{AudioInput, CaptureTarget};
 use hypr_audio_utils::{ResampleExtDynamicNew, chunk_size_for_stt};
 use tauri_specta::Event;
 
 use super::{SourceMsg, SourceState};
 
+/// Identifies one run of the input streams, so chunks still queued from a run
+/// that was stopped (e.g. by a device switch) can be told apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct StreamGeneration(u64);
+
+impl StreamGeneration {
+    pub(super) fn next(self) -> Self {
+        Self(self.0.wrapping_add(1))
+    }
+}
+
 pub(super) async fn start_source_loop(
     myself: &ActorRef<SourceMsg>,
     st: &mut SourceState,
 ) -> Result<(), ActorProcessingErr> {
-    let new_mode = ChannelMode::determine(st.onboarding);
+    let new_mode = determine_channel_mode(st.onboarding);
 
     let mode_changed = st.current_mode != new_mode;
     st.current_mode = new_mode;
@@ -32,7 +43,9 @@ pub(super) async fn start_source_loop(
 
     let result = start_streams(myself, st).await;
 
+    // A hot device switch reports itself once the new streams deliver audio.
     if result.is_ok()
+        && st.pending_switch.is_none()
         && let Err(error) = (SessionProgressEvent::AudioReady {
             session_id: st.session_id.clone(),
             device: st.mic_device.clone(),
@@ -56,10 +69,13 @@ async fn start_streams(
 
     let stream_cancel_token = CancellationToken::new();
     st.stream_cancel_token = Some(stream_cancel_token.clone());
+    st.stream_generation = st.stream_generation.next();
+    let generation = st.stream_generation;
 
     let handle = tokio::spawn(async move {
         let ctx = StreamContext {
             actor: myself2,
+            generation,
             cancel_token: stream_cancel_token,
             mic_muted,
             mic_device,
@@ -74,6 +90,7 @@ async fn start_streams(
 
 struct StreamContext {
     actor: ActorRef<SourceMsg>,
+    generation: StreamGeneration,
     cancel_token: CancellationToken,
     mic_muted: Arc<AtomicBool>,
     mic_device: Option<String>,
@@ -172,7 +189,7 @@ fn setup_mic_stream(
 fn setup_speaker_stream(
     ctx: &StreamContext,
 ) -> Result<impl futures_util::Stream<Item = Result<Vec<f32>, hypr_audio_utils::Error>>, ()> {
-    let mut spk_input = hypr_audio::AudioInput::from_speaker();
+    let mut spk_input = AudioInput::from_speaker_with_target(speaker_capture_target());
     let chunk_size = chunk_size_for_stt(crate::actors::SAMPLE_RATE);
     match spk_input
         .stream()
@@ -187,6 +204,18 @@ fn setup_speaker_stream(
     }
 }
 
+// On Linux, record only the apps that are in a call (they hold the mic) so
+// notification sounds and music stay out of the transcript.
+#[cfg(target_os = "linux")]
+fn speaker_capture_target() -> CaptureTarget {
+    CaptureTarget::MicUsers
+}
+
+#[cfg(not(target_os = "linux"))]
+fn speaker_capture_target() -> CaptureTarget {
+    CaptureTarget::System
+}
+
 fn handle_mic_item(
     ctx: &StreamContext,
     item: Option<Result<Vec<f32>, hypr_audio_utils::Error>>,
@@ -200,7 +229,10 @@ fn handle_mic_item(
             };
             if ctx
                 .actor
-                .cast(SourceMsg::MicChunk(AudioChunk { data: output_data }))
+                .cast(SourceMsg::MicChunk(
+                    ctx.generation,
+                    AudioChunk { data: output_data },
+                ))
                 .is_err()
             {
                 tracing::warn!("failed_to_cast_mic_chunk");
@@ -231,7 +263,7 @@ fn handle_speaker_item(
         Some(Ok(data)) => {
             if ctx
                 .actor
-                .cast(SourceMsg::SpeakerChunk(AudioChunk { data }))
+                .cast(SourceMsg::SpeakerChunk(ctx.generation, AudioChunk { data }))
                 .is_err()
             {
                 tracing::warn!("failed_to_cast_speaker_chunk");
@@ -35,6 +35,14 @@ common_event_derives! {
             session_id: String,
             device: Option<String>,
         },
+        #[serde(rename = "audio_device_switched")]
+        AudioDeviceSwitched {
+            session_id: String,
+            reason: String,
+            previous_device: Option<String>,
+            device: Option<String>,
+            gap_ms: u32,
+        },
         #[serde(rename = "connecting")]
         Connecting { session_id: String },
         #[serde(rename = "connected")]
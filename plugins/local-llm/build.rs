@@ -14,6 +14,7 @@ const COMMANDS: &[&str] = &[
     "list_custom_models",
     "get_current_model_selection",
     "set_current_model_selection",
+    "extract_action_items",
 ];
 
 fn main() {
@@ -120,6 +120,14 @@ async setCurrentModelSelection(model: ModelSelection) : Promise<Result<null, str
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async extractActionItems(sessionId: string, userId: string, participants: Human[], system: ActionItemsSystem, user: ActionItemsUser) : Promise<Result<ActionItems, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:local-llm|extract_action_items", { sessionId, userId, participants, system, user }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
@@ -138,12 +146,35 @@ llmEvent: "plugin:local-llm:llm-event"
 
 /** user-defined types **/
 
+export type ActionItem = { text: string; 
+/**
+ * Participant name, exactly as given in the prompt.
+ */
+owner: string | null; 
+/**
+ * `YYYY-MM-DD`.
+ */
+dueDate: string | null; source: SourceSpan }
+export type ActionItems = { actionItems: ActionItem[]; decisions: Decision[] }
+export type ActionItemsSystem = { language: string | null }
+export type ActionItemsUser = { session: Session; participants: Participant[]; transcripts: Transcript[] }
 export type CustomModelInfo = { path: string; name: string }
+export type Decision = { text: string; source: SourceSpan }
+export type Event = { name: string; startedAt: string | null; endedAt: string | null }
+export type Human = { id: string; organization_id: string | null; is_user: boolean; full_name: string | null; email: string | null; job_title: string | null; linkedin_username: string | null }
 export type LLMEvent = { progress: number }
 export type ModelInfo = { key: SupportedModel; name: string; description: string; size_bytes: number }
 export type ModelSelection = { type: "Predefined"; content: { key: SupportedModel } } | { type: "Custom"; content: { path: string } }
+export type Participant = { name: string; jobTitle: string | null }
+export type Segment = { text: string; speaker: string }
+export type Session = { title: string | null; startedAt: string | null; endedAt: string | null; event: Event | null }
+/**
+ * Inclusive range of transcript segments, see [`ActionItemsUser::segments`].
+ */
+export type SourceSpan = { start: number; end: number }
 export type SupportedModel = "Llama3p2_3bQ4" | "Gemma3_4bQ4" | "HyprLLM"
 export type TAURI_CHANNEL<TSend> = null
+export type Transcript = { segments: Segment[]; startedAt: number | null; endedAt: number | null }
 
 /** tauri-specta globals **/
 
@@ -140,3 +140,18 @@ pub async fn set_current_model_selection<R: tauri::Runtime>(
     app.set_current_model_selection(model)
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_action_items<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    session_id: String,
+    user_id: String,
+    participants: Vec<hypr_db_user::Human>,
+    system: hypr_template_app::ActionItemsSystem,
+    user: hypr_template_app::ActionItemsUser,
+) -> Result<hypr_template_app::ActionItems, String> {
+    app.extract_action_items(session_id, user_id, participants, system, user)
+        .await
+        .map_err(|e| e.to_string())
+}
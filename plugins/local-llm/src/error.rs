@@ -11,6 +11,8 @@ pub enum Error {
     #[error(transparent)]
     HyprLlamaError(#[from] hypr_llama::Error),
     #[error(transparent)]
+    HyprGbnfError(#[from] hypr_gbnf::Error),
+    #[error(transparent)]
     HyprFileError(#[from] hypr_file::Error),
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
@@ -18,6 +20,8 @@ pub enum Error {
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     StoreError(#[from] tauri_plugin_store2::Error),
+    #[error(transparent)]
+    Db2Error(#[from] tauri_plugin_db2::Error),
     #[error("Model not downloaded")]
     ModelNotDownloaded,
     #[error("server already running")]
@@ -1,6 +1,7 @@
 use std::{future::Future, path::PathBuf};
 
 use tauri::{Manager, Runtime, ipc::Channel};
+use tauri_plugin_db2::Database2PluginExt;
 use tauri_plugin_store2::Store2PluginExt;
 use tauri_specta::Event;
 
@@ -14,6 +15,7 @@ pub trait LocalLlmPluginExt<R: Runtime> {
     fn api_base(&self) -> impl Future<Output = Option<String>>;
 
     fn is_server_running(&self) -> impl Future<Output = bool>;
+    fn model_manager(&self) -> impl Future<Output = Result<crate::ModelManager, crate::Error>>;
     fn start_server(&self) -> impl Future<Output = Result<String, crate::Error>>;
     fn stop_server(&self) -> impl Future<Output = Result<(), crate::Error>>;
 
@@ -40,6 +42,15 @@ pub trait LocalLlmPluginExt<R: Runtime> {
         &self,
         model: &crate::SupportedModel,
     ) -> impl Future<Output = Result<bool, crate::Error>>;
+
+    fn extract_action_items(
+        &self,
+        session_id: String,
+        user_id: String,
+        participants: Vec<hypr_db_user::Human>,
+        system: hypr_template_app::ActionItemsSystem,
+        user: hypr_template_app::ActionItemsUser,
+    ) -> impl Future<Output = Result<hypr_template_app::ActionItems, crate::Error>>;
 }
 
 impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
@@ -201,12 +212,7 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
             return Err(crate::Error::ServerAlreadyRunning);
         }
 
-        let current_selection = self.get_current_model_selection()?;
-        let model_path = current_selection.file_path(&self.models_dir());
-
-        let model_manager = crate::ModelManager::builder()
-            .model_path(model_path)
-            .build();
+        let model_manager = self.model_manager().await?;
         let state = self.state::<crate::SharedState>();
 
         let handle = self.app_handle().clone();
@@ -229,6 +235,28 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
         Ok(api_base)
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn model_manager(&self) -> Result<crate::ModelManager, crate::Error> {
+        let model_path = self
+            .get_current_model_selection()?
+            .file_path(&self.models_dir());
+
+        let state = self.state::<crate::SharedState>();
+        let mut s = state.lock().await;
+
+        if let Some((path, manager)) = &s.model_manager
+            && *path == model_path
+        {
+            return Ok(manager.clone());
+        }
+
+        let manager = crate::ModelManager::builder()
+            .model_path(&model_path)
+            .build();
+        s.model_manager = Some((model_path, manager.clone()));
+        Ok(manager)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn stop_server(&self) -> Result<(), crate::Error> {
         let state = self.state::<crate::SharedState>();
@@ -342,4 +370,29 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
         store.set(crate::StoreKey::ModelSelection, model)?;
         Ok(())
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn extract_action_items(
+        &self,
+        session_id: String,
+        user_id: String,
+        participants: Vec<hypr_db_user::Human>,
+        system: hypr_template_app::ActionItemsSystem,
+        user: hypr_template_app::ActionItemsUser,
+    ) -> Result<hypr_template_app::ActionItems, crate::Error> {
+        let model_path = self
+            .get_current_model_selection()?
+            .file_path(&self.models_dir());
+        if !model_path.exists() {
+            return Err(crate::Error::ModelNotDownloaded);
+        }
+
+        let model = self.model_manager().await?;
+        let extracted = hypr_llm::extract_action_items(&model, system, user.clone()).await?;
+
+        Ok(self
+            .db2()
+            .store_action_items(session_id, user_id, participants, user, extracted)
+            .await?)
+    }
 }
@@ -35,6 +35,10 @@ pub struct State {
     pub server: Option<crate::server::ServerHandle>,
     pub download_task: HashMap<SupportedModel, tokio::task::JoinHandle<()>>,
     pub builtin_model: ModelManager,
+    /// The selected model, shared by the server and direct tasks so the GGUF
+    /// is only loaded once. Kept with its path, so a new selection gets its
+    /// own.
+    pub model_manager: Option<(std::path::PathBuf, ModelManager)>,
 }
 
 fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
@@ -57,6 +61,7 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::list_custom_models::<Wry>,
             commands::get_current_model_selection::<Wry>,
             commands::set_current_model_selection::<Wry>,
+            commands::extract_action_items::<Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
@@ -102,6 +107,7 @@ pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
                     server: None,
                     download_task: HashMap::new(),
                     builtin_model: ModelManager::builder().model_path(model_path).build(),
+                    model_manager: None,
                 };
                 app.manage(Arc::new(Mutex::new(state)));
             }
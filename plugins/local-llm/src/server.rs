@@ -4,9 +4,10 @@ use std::sync::{Arc, Mutex};
 
 use async_openai::types::{
     ChatChoice, ChatChoiceStream, ChatCompletionMessageToolCallChunk,
-    ChatCompletionResponseMessage, ChatCompletionStreamResponseDelta, ChatCompletionToolType,
-    CreateChatCompletionRequest, CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
-    FunctionCallStream, Role,
+    ChatCompletionResponseMessage, ChatCompletionStreamResponseDelta,
+    ChatCompletionToolChoiceOption, ChatCompletionToolType, CreateChatCompletionRequest,
+    CreateChatCompletionResponse, CreateChatCompletionStreamResponse, FinishReason,
+    FunctionCallStream, ResponseFormat, Role,
 };
 use axum::{
     Router,
@@ -202,20 +203,43 @@ impl LocalProvider {
             .and_then(|v| serde_json::from_value::<hypr_gbnf::Grammar>(v.clone()).ok());
 
         let grammar = match maybe_grammar {
-            None => None,
+            None => match &request.response_format {
+                Some(ResponseFormat::JsonSchema { json_schema }) => {
+                    Some(match &json_schema.schema {
+                        Some(schema) => hypr_gbnf::Grammar::from_json_schema(schema.clone())?,
+                        None => hypr_gbnf::build_json_grammar(),
+                    })
+                }
+                Some(ResponseFormat::JsonObject) => Some(hypr_gbnf::build_json_grammar()),
+                Some(ResponseFormat::Text) | None => None,
+            },
             Some(g) => {
                 if model.name == hypr_llama::ModelName::HyprLLM {
                     match &g {
                         hypr_gbnf::Grammar::Enhance { sections: None } => None,
-                        _ => Some(g.build()),
+                        _ => Some(g.build()?),
                     }
                 } else {
-                    Some(g.build())
+                    Some(g.build()?)
                 }
             }
         };
 
-        let tools = request.tools.clone();
+        // `required` can't be enforced without also forbidding the model's
+        // thinking, so it is treated like `auto`.
+        let tools = match &request.tool_choice {
+            Some(ChatCompletionToolChoiceOption::None) => None,
+            Some(ChatCompletionToolChoiceOption::Named(named)) => {
+                request.tools.as_ref().map(|tools| {
+                    tools
+                        .iter()
+                        .filter(|tool| tool.function.name == named.function.name)
+                        .cloned()
+                        .collect()
+                })
+            }
+            _ => request.tools.clone(),
+        };
 
         let request = hypr_llama::LlamaRequest {
             messages,
@@ -407,8 +431,15 @@ async fn build_chat_completion_response(
             }
         }
 
+        let finish_reason = if tool_calls.is_empty() {
+            FinishReason::Stop
+        } else {
+            FinishReason::ToolCalls
+        };
+
         let res = CreateChatCompletionResponse {
             choices: vec![ChatChoice {
+                finish_reason: Some(finish_reason),
                 message: ChatCompletionResponseMessage {
                     content: if completion.is_empty() {
                         None
@@ -429,69 +460,71 @@ async fn build_chat_completion_response(
 
         Ok(ChatCompletionResponse::NonStream(res))
     } else {
-        let source_stream = response_stream_fn()?;
-        let stream = Box::pin(
-            source_stream
-                .enumerate()
-                .map(move |(index, event)| {
-                    let delta_template = empty_stream_response_delta.clone();
-                    let response_template = base_stream_response_template.clone();
-
-                    match event {
-                        StreamEvent::Response(llama_response) => match llama_response {
-                            hypr_llama::Response::TextDelta(chunk) => {
-                                Some(Ok(CreateChatCompletionStreamResponse {
-                                    choices: vec![ChatChoiceStream {
-                                        index: 0,
-                                        delta: ChatCompletionStreamResponseDelta {
-                                            content: Some(chunk),
-                                            ..delta_template
-                                        },
-                                        finish_reason: None,
-                                        logprobs: None,
-                                    }],
-                                    ..response_template
-                                }))
-                            }
-                            hypr_llama::Response::Reasoning(_) => None,
-                            hypr_llama::Response::ToolCall { name, arguments } => {
-                                Some(Ok(CreateChatCompletionStreamResponse {
-                                    choices: vec![ChatChoiceStream {
-                                        index: 0,
-                                        delta: ChatCompletionStreamResponseDelta {
-                                            tool_calls: Some(vec![
-                                                ChatCompletionMessageToolCallChunk {
-                                                    index: index.try_into().unwrap_or(0),
-                                                    id: Some(uuid::Uuid::new_v4().to_string()),
-                                                    r#type: Some(ChatCompletionToolType::Function),
-                                                    function: Some(FunctionCallStream {
-                                                        name: Some(name),
-                                                        arguments: Some(
-                                                            serde_json::to_string(&arguments)
-                                                                .unwrap_or_default(),
-                                                        ),
-                                                    }),
-                                                },
-                                            ]),
-                                            ..delta_template
-                                        },
-                                        finish_reason: None,
-                                        logprobs: None,
-                                    }],
-                                    ..response_template
-                                }))
-                            }
-                        },
-                        StreamEvent::Progress(v) => {
-                            progress_fn(v);
-                            None
-                        }
+        let mut source_stream = response_stream_fn()?;
+        let chunk = move |delta: ChatCompletionStreamResponseDelta,
+                          finish_reason: Option<FinishReason>|
+              -> Result<CreateChatCompletionStreamResponse, StreamBodyError> {
+            Ok(CreateChatCompletionStreamResponse {
+                choices: vec![ChatChoiceStream {
+                    index: 0,
+                    delta,
+                    finish_reason,
+                    logprobs: None,
+                }],
+                ..base_stream_response_template.clone()
+            })
+        };
+
+        let stream = async_stream::stream! {
+            // Each call arrives whole, so it goes out as a single delta under
+            // its own index.
+            let mut tool_call_count: u32 = 0;
+
+            while let Some(event) = source_stream.next().await {
+                match event {
+                    StreamEvent::Response(hypr_llama::Response::TextDelta(text)) => {
+                        yield chunk(
+                            ChatCompletionStreamResponseDelta {
+                                content: Some(text),
+                                ..empty_stream_response_delta.clone()
+                            },
+                            None,
+                        );
                     }
-                })
-                .filter_map(|x| async move { x }),
-        );
+                    StreamEvent::Response(hypr_llama::Response::Reasoning(_)) => {}
+                    StreamEvent::Response(hypr_llama::Response::ToolCall { name, arguments }) => {
+                        yield chunk(
+                            ChatCompletionStreamResponseDelta {
+                                tool_calls: Some(vec![ChatCompletionMessageToolCallChunk {
+                                    index: tool_call_count,
+                                    id: Some(uuid::Uuid::new_v4().to_string()),
+                                    r#type: Some(ChatCompletionToolType::Function),
+                                    function: Some(FunctionCallStream {
+                                        name: Some(name),
+                                        arguments: Some(
+                                            serde_json::to_string(&arguments).unwrap_or_default(),
+                                        ),
+                                    }),
+                                }]),
+                                ..empty_stream_response_delta.clone()
+                            },
+                            None,
+                        );
+                        tool_call_count += 1;
+                    }
+                    StreamEvent::Progress(v) => progress_fn(v),
+                }
+            }
+
+            let finish_reason = if tool_call_count > 0 {
+                FinishReason::ToolCalls
+            } else {
+                FinishReason::Stop
+            };
+            yield chunk(empty_stream_response_delta.clone(), Some(finish_reason));
+        };
 
-        Ok(ChatCompletionResponse::Stream(stream))
+        Ok(ChatCompletionResponse::Stream(Box::pin(stream)))
     }
 }
 
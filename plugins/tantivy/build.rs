@@ -1,4 +1,11 @@
-const COMMANDS: &[&str] = &["search", "reindex"];
+const COMMANDS: &[&str] = &[
+    "search",
+    "reindex",
+    "add_document",
+    "update_document",
+    "remove_document",
+    "set_embedding_model",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
@@ -45,6 +45,14 @@ async removeDocument(id: string, collection: string | null) : Promise<Result<nul
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async setEmbeddingModel(modelPath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:tantivy|set_embedding_model", { modelPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
@@ -60,10 +68,20 @@ async removeDocument(id: string, collection: string | null) : Promise<Result<nul
 
 export type CreatedAtFilter = { gte: number | null; lte: number | null; gt: number | null; lt: number | null; eq: number | null }
 export type HighlightRange = { start: number; end: number }
+export type MatchedPassage = { 
+/**
+ * Byte range in `content`.
+ */
+start: number; end: number; text: string }
 export type SearchDocument = { id: string; doc_type: string; language: string | null; title: string; content: string; created_at: number; facets?: string[] }
 export type SearchFilters = { created_at: CreatedAtFilter | null; doc_type: string | null; facet: string | null }
-export type SearchHit = { score: number; document: SearchDocument; title_snippet: Snippet | null; content_snippet: Snippet | null }
-export type SearchOptions = { fuzzy: boolean | null; distance: number | null; snippets: boolean | null; snippet_max_chars: number | null; phrase_slop: number | null }
+export type SearchHit = { score: number; document: SearchDocument; title_snippet: Snippet | null; content_snippet: Snippet | null; passage?: MatchedPassage | null }
+export type SearchOptions = { fuzzy: boolean | null; distance: number | null; snippets: boolean | null; snippet_max_chars: number | null; phrase_slop: number | null; 
+/**
+ * Blend BM25 with embedding similarity. Falls back to BM25 alone when
+ * no embedding model is set.
+ */
+hybrid: boolean | null }
 export type SearchRequest = { query: string; collection?: string | null; filters?: SearchFilters; limit?: number; options?: SearchOptions }
 export type SearchResult = { hits: SearchHit[]; count: number }
 export type Snippet = { fragment: string; highlights: HighlightRange[] }
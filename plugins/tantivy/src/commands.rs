@@ -62,3 +62,15 @@ pub(crate) async fn remove_document<R: tauri::Runtime>(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn set_embedding_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    model_path: String,
+) -> Result<(), String> {
+    app.tantivy()
+        .set_embedding_model(model_path)
+        .await
+        .map_err(|e| e.to_string())
+}
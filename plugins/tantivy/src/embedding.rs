@@ -0,0 +1,56 @@
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+
+use sha2::{Digest, Sha256};
+
+/// Turns passages into vectors for semantic search.
+pub trait Embedder: Send + Sync {
+    /// Vectors are only ever compared with ones from the same model.
+    fn model_id(&self) -> &str;
+
+    /// One unit-length vector per text.
+    fn embed(
+        &self,
+        texts: Vec<String>,
+    ) -> Pin<Box<dyn Future<Output = crate::Result<Vec<Vec<f32>>>> + Send + '_>>;
+}
+
+/// A local GGUF embedding model, run through llama.cpp.
+pub struct LlamaEmbedding {
+    model_id: String,
+    inner: hypr_llama::LlamaEmbedder,
+}
+
+impl LlamaEmbedding {
+    pub fn new(model_path: impl AsRef<Path>) -> crate::Result<Self> {
+        let model_path = model_path.as_ref();
+
+        Ok(Self {
+            model_id: content_hash(model_path)?,
+            inner: hypr_llama::LlamaEmbedder::new(model_path)?,
+        })
+    }
+}
+
+// Keyed on the file's contents rather than its name, so a different
+// quantization or a newer download saved under the same name still gets
+// everything re-embedded.
+fn content_hash(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+impl Embedder for LlamaEmbedding {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed(
+        &self,
+        texts: Vec<String>,
+    ) -> Pin<Box<dyn Future<Output = crate::Result<Vec<Vec<f32>>>> + Send + '_>> {
+        Box::pin(async move { Ok(self.inner.embed(texts).await?) })
+    }
+}
@@ -14,6 +14,8 @@ pub enum Error {
     Tauri(#[from] tauri::Error),
     #[error(transparent)]
     Settings(#[from] tauri_plugin_settings::Error),
+    #[error(transparent)]
+    Llama(#[from] hypr_llama::Error),
     #[error("Index not initialized")]
     IndexNotInitialized,
     #[error("Collection not found: {0}")]
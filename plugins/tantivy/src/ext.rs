@@ -1,23 +1,36 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
-use tantivy::collector::{Count, TopDocs};
+use tantivy::collector::{Count, DocSetCollector, TopDocs};
 use tantivy::query::{
-    BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, QueryParser, TermQuery,
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, QueryParser,
+    TermQuery,
 };
-use tantivy::schema::{Facet, IndexRecordOption};
+use tantivy::schema::{Facet, IndexRecordOption, Value};
 use tantivy::snippet::SnippetGenerator;
-use tantivy::{Index, ReloadPolicy, TantivyDocument, Term};
+use tantivy::{DocAddress, Index, ReloadPolicy, TantivyDocument, Term};
 use tauri_plugin_settings::SettingsPluginExt;
 
 use crate::query::build_created_at_range_query;
-use crate::schema::{extract_search_document, get_fields};
+use crate::schema::{SchemaFields, extract_search_document, get_fields};
 use crate::tokenizer::register_tokenizers;
+use crate::vector::{
+    PASSAGE_MAX_CHARS, PASSAGE_OVERLAP_CHARS, Passage, VectorStore, chunk_passages,
+    reciprocal_rank_fusion,
+};
 use crate::{
-    CollectionConfig, CollectionIndex, HighlightRange, IndexState, SearchDocument, SearchHit,
-    SearchRequest, SearchResult, Snippet,
+    CollectionConfig, CollectionIndex, Embedder, HighlightRange, IndexState, LlamaEmbedding,
+    MatchedPassage, SearchDocument, SearchFilters, SearchHit, SearchRequest, SearchResult, Snippet,
 };
 
+// Candidates taken from each of BM25 and vector search before fusing.
+const HYBRID_CANDIDATES: usize = 50;
+// Documents embedded between saves while catching up.
+const BACKFILL_BATCH: usize = 16;
+
 pub fn detect_language(text: &str) -> hypr_language::Language {
     hypr_language::detect(text)
 }
@@ -72,6 +85,111 @@ fn parse_query_parts(query: &str) -> (Vec<&str>, Vec<&str>) {
     (phrases, regular_terms)
 }
 
+fn build_filter_queries(fields: &SchemaFields, filters: &SearchFilters) -> Vec<Box<dyn Query>> {
+    let mut queries: Vec<Box<dyn Query>> = Vec::new();
+
+    if let Some(ref created_at_filter) = filters.created_at {
+        if let Some(rq) = build_created_at_range_query(fields.created_at, created_at_filter) {
+            queries.push(rq);
+        }
+    }
+
+    if let Some(ref doc_type) = filters.doc_type {
+        let doc_type_term = Term::from_field_text(fields.doc_type, doc_type);
+        queries.push(Box::new(TermQuery::new(
+            doc_type_term,
+            IndexRecordOption::Basic,
+        )));
+    }
+
+    if let Some(ref facet_path) = filters.facet {
+        if let Ok(facet) = Facet::from_text(facet_path) {
+            let facet_term = Term::from_facet(fields.facets, &facet);
+            queries.push(Box::new(TermQuery::new(
+                facet_term,
+                IndexRecordOption::Basic,
+            )));
+        }
+    }
+
+    queries
+}
+
+fn with_filters(query: Box<dyn Query>, filters: &[Box<dyn Query>]) -> Box<dyn Query> {
+    if filters.is_empty() {
+        return query;
+    }
+
+    let mut clauses = vec![(Occur::Must, query)];
+    clauses.extend(filters.iter().map(|f| (Occur::Must, f.box_clone())));
+    Box::new(BooleanQuery::new(clauses))
+}
+
+fn commit(collection_index: &mut CollectionIndex) -> Result<(), crate::Error> {
+    collection_index.writer.commit()?;
+    collection_index.pending_writes.store(0, Ordering::SeqCst);
+    *collection_index.last_commit.lock().unwrap() = Instant::now();
+    collection_index.vectors.save()?;
+    Ok(())
+}
+
+// Whether `document` is already indexed exactly as given. Only committed
+// documents are seen, so with writes pending the answer is always no.
+fn is_unchanged(
+    collection_index: &CollectionIndex,
+    document: &SearchDocument,
+) -> Result<bool, crate::Error> {
+    if collection_index.pending_writes.load(Ordering::SeqCst) > 0 {
+        return Ok(false);
+    }
+
+    let schema = &collection_index.schema;
+    let fields = get_fields(schema);
+    let searcher = collection_index.reader.searcher();
+
+    let id_query = TermQuery::new(
+        Term::from_field_text(fields.id, &document.id),
+        IndexRecordOption::Basic,
+    );
+    let Some((_, address)) = searcher
+        .search(&id_query, &TopDocs::with_limit(1))?
+        .into_iter()
+        .next()
+    else {
+        return Ok(false);
+    };
+
+    let doc: TantivyDocument = searcher.doc(address)?;
+    let Some(stored) = extract_search_document(schema, &fields, &doc) else {
+        return Ok(false);
+    };
+
+    Ok(stored.doc_type == document.doc_type
+        && stored.language.as_deref().unwrap_or("") == document.language.as_deref().unwrap_or("")
+        && stored.title == document.title
+        && stored.content == document.content
+        && stored.created_at == document.created_at
+        && stored.facets == document.facets)
+}
+
+async fn embed_passages(
+    embedder: &dyn Embedder,
+    content: &str,
+) -> Result<Vec<Passage>, crate::Error> {
+    let ranges = chunk_passages(content, PASSAGE_MAX_CHARS, PASSAGE_OVERLAP_CHARS);
+    let texts = ranges
+        .iter()
+        .map(|&(start, end)| content[start..end].to_string())
+        .collect();
+    let vectors = embedder.embed(texts).await?;
+
+    Ok(ranges
+        .into_iter()
+        .zip(vectors)
+        .map(|((start, end), vector)| Passage { start, end, vector })
+        .collect())
+}
+
 pub struct Tantivy<'a, R: tauri::Runtime, M: tauri::Manager<R>> {
     manager: &'a M,
     _runtime: std::marker::PhantomData<fn() -> R>,
@@ -85,6 +203,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         std::fs::create_dir_all(&index_path)?;
 
+        let embedder = self.embedder().await;
         let state = self.manager.state::<IndexState>();
         let mut guard = state.inner.write().await;
 
@@ -130,6 +249,11 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         let writer = index.writer(50_000_000)?;
 
+        let mut vectors = VectorStore::load(index_path.join("vectors.bin"));
+        if let Some(embedder) = &embedder {
+            vectors.set_model(embedder.model_id());
+        }
+
         let collection_index = CollectionIndex {
             schema,
             index,
@@ -139,6 +263,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
             commit_interval_ms: config.commit_interval_ms,
             pending_writes: AtomicU64::new(0),
             last_commit: std::sync::Mutex::new(Instant::now()),
+            vectors,
         };
 
         guard
@@ -159,6 +284,12 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
     }
 
     pub async fn search(&self, request: SearchRequest) -> Result<SearchResult, crate::Error> {
+        let query_vector = if request.options.hybrid.unwrap_or(false) {
+            self.embed_query(&request.query).await
+        } else {
+            None
+        };
+
         let collection_name = Self::get_collection_name(request.collection);
         let state = self.manager.state::<IndexState>();
         let guard = state.inner.read().await;
@@ -279,44 +410,82 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
             query_parser.parse_query(&request.query)?
         };
 
-        // Apply created_at filter
-        if let Some(ref created_at_filter) = request.filters.created_at {
-            let range_query = build_created_at_range_query(fields.created_at, created_at_filter);
-            if let Some(rq) = range_query {
-                combined_query = Box::new(BooleanQuery::new(vec![
-                    (Occur::Must, combined_query),
-                    (Occur::Must, rq),
-                ]));
-            }
-        }
-
-        // Apply doc_type filter
-        if let Some(ref doc_type) = request.filters.doc_type {
-            let doc_type_term = Term::from_field_text(fields.doc_type, doc_type);
-            let doc_type_query = TermQuery::new(doc_type_term, IndexRecordOption::Basic);
-            combined_query = Box::new(BooleanQuery::new(vec![
-                (Occur::Must, combined_query),
-                (Occur::Must, Box::new(doc_type_query)),
-            ]));
-        }
+        let filter_queries = build_filter_queries(&fields, &request.filters);
+        combined_query = with_filters(combined_query, &filter_queries);
 
-        // Apply facet filter
-        if let Some(ref facet_path) = request.filters.facet {
-            if let Ok(facet) = Facet::from_text(facet_path) {
-                let facet_term = Term::from_facet(fields.facets, &facet);
-                let facet_query = TermQuery::new(facet_term, IndexRecordOption::Basic);
-                combined_query = Box::new(BooleanQuery::new(vec![
-                    (Occur::Must, combined_query),
-                    (Occur::Must, Box::new(facet_query)),
-                ]));
-            }
-        }
+        let candidates = match query_vector {
+            Some(_) => request.limit.max(HYBRID_CANDIDATES),
+            None => request.limit,
+        };
 
         // Use tuple collector to get both top docs and total count
-        let (top_docs, count) = searcher.search(
-            &combined_query,
-            &(TopDocs::with_limit(request.limit), Count),
-        )?;
+        let (top_docs, count) =
+            searcher.search(&combined_query, &(TopDocs::with_limit(candidates), Count))?;
+
+        let mut passages = HashMap::new();
+
+        let top_docs = match query_vector {
+            None => top_docs,
+            Some(query_vector) => {
+                let matches = collection_index.vectors.search(&query_vector, candidates);
+
+                // Vector matches go through tantivy too, so filters apply and
+                // documents removed since they were embedded drop out.
+                let id_query: Box<dyn Query> = Box::new(BooleanQuery::new(
+                    matches
+                        .iter()
+                        .map(|m| {
+                            let term = Term::from_field_text(fields.id, &m.id);
+                            let query: Box<dyn Query> =
+                                Box::new(TermQuery::new(term, IndexRecordOption::Basic));
+                            (Occur::Should, query)
+                        })
+                        .collect(),
+                ));
+                let vector_docs = searcher.search(
+                    &with_filters(id_query, &filter_queries),
+                    &TopDocs::with_limit(matches.len().max(1)),
+                )?;
+
+                let mut addresses: HashMap<String, DocAddress> = HashMap::new();
+                let mut id_of = |address: DocAddress| -> Result<String, crate::Error> {
+                    let doc: TantivyDocument = searcher.doc(address)?;
+                    let id = doc
+                        .get_first(fields.id)
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    addresses.insert(id.clone(), address);
+                    Ok(id)
+                };
+
+                let lexical_ranking = top_docs
+                    .iter()
+                    .map(|(_, address)| id_of(*address))
+                    .collect::<Result<Vec<_>, _>>()?;
+                for (_, address) in vector_docs {
+                    id_of(address)?;
+                }
+
+                let vector_ranking: Vec<String> = matches
+                    .into_iter()
+                    .filter(|m| addresses.contains_key(&m.id))
+                    .map(|m| {
+                        let id = m.id.clone();
+                        passages.insert(m.id.clone(), m);
+                        id
+                    })
+                    .collect();
+
+                // `count` stays the lexical total; vector matches have no
+                // cutoff, so counting them would only count the candidates.
+                reciprocal_rank_fusion(&[lexical_ranking, vector_ranking])
+                    .into_iter()
+                    .take(request.limit)
+                    .map(|(id, score)| (score, addresses[&id]))
+                    .collect::<Vec<_>>()
+            }
+        };
 
         let generate_snippets = request.options.snippets.unwrap_or(false);
         let snippet_max_chars = request.options.snippet_max_chars.unwrap_or(150);
@@ -370,11 +539,20 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
                     }
                 });
 
+                let passage = passages.get(&search_doc.id).and_then(|m| {
+                    Some(MatchedPassage {
+                        start: m.start,
+                        end: m.end,
+                        text: search_doc.content.get(m.start..m.end)?.to_string(),
+                    })
+                });
+
                 hits.push(SearchHit {
                     score,
                     document: search_doc,
                     title_snippet,
                     content_snippet,
+                    passage,
                 });
             }
         }
@@ -399,10 +577,8 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         let fields = get_fields(schema);
 
-        writer.commit()?;
-
-        collection_index.pending_writes.store(0, Ordering::SeqCst);
-        *collection_index.last_commit.lock().unwrap() = Instant::now();
+        collection_index.vectors.clear();
+        commit(collection_index)?;
 
         tracing::info!(
             "Reindex completed for collection '{}'. Index cleared and ready for new documents. Fields: {:?}",
@@ -418,6 +594,9 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
         collection: Option<String>,
         document: SearchDocument,
     ) -> Result<(), crate::Error> {
+        // Before taking the lock, so searches aren't held up by the model.
+        let passages = self.embed_document(&document).await;
+
         let collection_name = Self::get_collection_name(collection);
         let state = self.manager.state::<IndexState>();
         let mut guard = state.inner.write().await;
@@ -447,6 +626,11 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         writer.add_document(doc)?;
 
+        match passages {
+            Some(passages) => collection_index.vectors.upsert(&document.id, passages),
+            None => collection_index.vectors.remove(&document.id),
+        }
+
         collection_index
             .pending_writes
             .fetch_add(1, Ordering::SeqCst);
@@ -459,9 +643,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
         };
 
         if should_commit {
-            writer.commit()?;
-            collection_index.pending_writes.store(0, Ordering::SeqCst);
-            *collection_index.last_commit.lock().unwrap() = Instant::now();
+            commit(collection_index)?;
         }
 
         tracing::debug!(
@@ -480,6 +662,23 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
     ) -> Result<(), crate::Error> {
         let collection_name = Self::get_collection_name(collection);
         let state = self.manager.state::<IndexState>();
+
+        // Callers re-send every document on start; the index is on disk, so
+        // only the ones that changed since need rewriting and embedding.
+        {
+            let guard = state.inner.read().await;
+            let collection_index = guard
+                .collections
+                .get(&collection_name)
+                .ok_or_else(|| crate::Error::CollectionNotFound(collection_name.clone()))?;
+            if is_unchanged(collection_index, &document)? {
+                return Ok(());
+            }
+        }
+
+        // Before taking the lock, so searches aren't held up by the model.
+        let passages = self.embed_document(&document).await;
+
         let mut guard = state.inner.write().await;
 
         let collection_index = guard
@@ -510,6 +709,11 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         writer.add_document(doc)?;
 
+        match passages {
+            Some(passages) => collection_index.vectors.upsert(&document.id, passages),
+            None => collection_index.vectors.remove(&document.id),
+        }
+
         collection_index
             .pending_writes
             .fetch_add(1, Ordering::SeqCst);
@@ -522,9 +726,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
         };
 
         if should_commit {
-            writer.commit()?;
-            collection_index.pending_writes.store(0, Ordering::SeqCst);
-            *collection_index.last_commit.lock().unwrap() = Instant::now();
+            commit(collection_index)?;
         }
 
         tracing::debug!(
@@ -556,6 +758,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         let id_term = Term::from_field_text(fields.id, &id);
         writer.delete_term(id_term);
+        collection_index.vectors.remove(&id);
 
         collection_index
             .pending_writes
@@ -569,9 +772,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
         };
 
         if should_commit {
-            writer.commit()?;
-            collection_index.pending_writes.store(0, Ordering::SeqCst);
-            *collection_index.last_commit.lock().unwrap() = Instant::now();
+            commit(collection_index)?;
         }
 
         tracing::debug!(
@@ -583,6 +784,169 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
         Ok(())
     }
 
+    async fn embedder(&self) -> Option<Arc<dyn Embedder>> {
+        let state = self.manager.state::<IndexState>();
+        let embedder = state.embedder.read().await;
+        embedder.clone()
+    }
+
+    /// `None` without an embedding model, or if embedding failed; the
+    /// document is then only found lexically until it's embedded again.
+    async fn embed_document(&self, document: &SearchDocument) -> Option<Vec<Passage>> {
+        let embedder = self.embedder().await?;
+
+        match embed_passages(&*embedder, &document.content).await {
+            Ok(passages) => Some(passages),
+            Err(e) => {
+                tracing::warn!("Failed to embed document '{}': {}", document.id, e);
+                None
+            }
+        }
+    }
+
+    async fn embed_query(&self, query: &str) -> Option<Vec<f32>> {
+        let embedder = self.embedder().await?;
+
+        match embedder.embed(vec![query.to_string()]).await {
+            Ok(vectors) => vectors.into_iter().next(),
+            Err(e) => {
+                tracing::warn!("Failed to embed query, using BM25 only: {}", e);
+                None
+            }
+        }
+    }
+
+    pub async fn set_embedding_model(
+        &self,
+        model_path: impl Into<PathBuf>,
+    ) -> Result<(), crate::Error> {
+        let model_path = model_path.into();
+        let state = self.manager.state::<IndexState>();
+
+        // The settings apply this on every start and change; loading the
+        // same model again would only throw away the one in memory.
+        if state.embedding_model_path.read().await.as_ref() == Some(&model_path) {
+            return Ok(());
+        }
+
+        let path = model_path.clone();
+        let embedder =
+            tauri::async_runtime::spawn_blocking(move || LlamaEmbedding::new(path)).await??;
+
+        self.set_embedder(Arc::new(embedder)).await?;
+        *state.embedding_model_path.write().await = Some(model_path);
+        Ok(())
+    }
+
+    /// Switches semantic search to `embedder`, then embeds every document
+    /// without vectors from it in the background.
+    pub async fn set_embedder(&self, embedder: Arc<dyn Embedder>) -> Result<(), crate::Error> {
+        let state = self.manager.state::<IndexState>();
+        *state.embedder.write().await = Some(embedder.clone());
+        *state.embedding_model_path.write().await = None;
+
+        let collection_names: Vec<String> = {
+            let mut guard = state.inner.write().await;
+            for collection_index in guard.collections.values_mut() {
+                collection_index.vectors.set_model(embedder.model_id());
+            }
+            guard.collections.keys().cloned().collect()
+        };
+
+        tracing::info!("Embedding model set to '{}'", embedder.model_id());
+
+        let app = self.manager.app_handle().clone();
+        tauri::async_runtime::spawn(async move {
+            for name in collection_names {
+                if let Err(e) = app.tantivy().embed_missing(Some(name.clone())).await {
+                    tracing::error!("Failed to embed collection '{}': {}", name, e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Embeds the documents of a collection that have no vectors yet, like
+    /// ones indexed before an embedding model was set.
+    pub async fn embed_missing(&self, collection: Option<String>) -> Result<(), crate::Error> {
+        let Some(embedder) = self.embedder().await else {
+            return Ok(());
+        };
+
+        let collection_name = Self::get_collection_name(collection);
+        let state = self.manager.state::<IndexState>();
+
+        let pending: Vec<SearchDocument> = {
+            let guard = state.inner.read().await;
+            let collection_index = guard
+                .collections
+                .get(&collection_name)
+                .ok_or_else(|| crate::Error::CollectionNotFound(collection_name.clone()))?;
+
+            let schema = &collection_index.schema;
+            let fields = get_fields(schema);
+            let searcher = collection_index.reader.searcher();
+
+            let mut pending = Vec::new();
+            for address in searcher.search(&AllQuery, &DocSetCollector)? {
+                let doc: TantivyDocument = searcher.doc(address)?;
+                if let Some(document) = extract_search_document(schema, &fields, &doc) {
+                    if !collection_index.vectors.contains(&document.id) {
+                        pending.push(document);
+                    }
+                }
+            }
+            pending
+        };
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Embedding {} documents in collection '{}'",
+            pending.len(),
+            collection_name
+        );
+
+        for batch in pending.chunks(BACKFILL_BATCH) {
+            let mut embedded = Vec::new();
+            for document in batch {
+                match embed_passages(&*embedder, &document.content).await {
+                    Ok(passages) => embedded.push((document.id.clone(), Some(passages))),
+                    Err(e) => {
+                        tracing::warn!("Failed to embed document '{}': {}", document.id, e);
+                        embedded.push((document.id.clone(), None));
+                    }
+                }
+            }
+
+            let mut guard = state.inner.write().await;
+            let Some(collection_index) = guard.collections.get_mut(&collection_name) else {
+                return Ok(());
+            };
+
+            // Another model was set meanwhile; its own backfill takes over.
+            if collection_index.vectors.model() != embedder.model_id() {
+                return Ok(());
+            }
+
+            for (id, passages) in embedded {
+                match passages {
+                    // Updated while we were embedding, with fresher vectors.
+                    Some(_) if collection_index.vectors.contains(&id) => {}
+                    Some(passages) => collection_index.vectors.upsert(&id, passages),
+                    // Like `update_document`, no vectors beat stale ones.
+                    None => collection_index.vectors.remove(&id),
+                }
+            }
+            collection_index.vectors.save()?;
+        }
+
+        Ok(())
+    }
+
     pub async fn flush(&self, collection: Option<String>) -> Result<(), crate::Error> {
         let collection_name = Self::get_collection_name(collection);
         let state = self.manager.state::<IndexState>();
@@ -595,9 +959,7 @@ impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> Tantivy<'a, R, M> {
 
         let pending = collection_index.pending_writes.load(Ordering::SeqCst);
         if pending > 0 {
-            collection_index.writer.commit()?;
-            collection_index.pending_writes.store(0, Ordering::SeqCst);
-            *collection_index.last_commit.lock().unwrap() = Instant::now();
+            commit(collection_index)?;
             tracing::debug!(
                 "Flushed {} pending writes for collection '{}'",
                 pending,
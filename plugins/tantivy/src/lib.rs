@@ -1,12 +1,15 @@
 mod commands;
+mod embedding;
 mod error;
 mod ext;
 mod query;
 mod schema;
 mod tokenizer;
+mod vector;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 use tantivy::schema::Schema;
@@ -14,10 +17,12 @@ use tantivy::{Index, IndexReader, IndexWriter};
 use tauri::Manager;
 use tokio::sync::RwLock;
 
+pub use embedding::{Embedder, LlamaEmbedding};
 pub use error::{Error, Result};
 pub use ext::*;
 pub use schema::build_schema;
 pub use tokenizer::get_tokenizer_name_for_language;
+pub use vector::VectorStore;
 
 const PLUGIN_NAME: &str = "tantivy";
 
@@ -45,12 +50,23 @@ pub struct HighlightRange {
     pub end: usize,
 }
 
+/// The part of a document's content that matched a semantic search.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct MatchedPassage {
+    /// Byte range in `content`.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct SearchHit {
     pub score: f32,
     pub document: SearchDocument,
     pub title_snippet: Option<Snippet>,
     pub content_snippet: Option<Snippet>,
+    #[serde(default)]
+    pub passage: Option<MatchedPassage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -82,6 +98,9 @@ pub struct SearchOptions {
     pub snippets: Option<bool>,
     pub snippet_max_chars: Option<usize>,
     pub phrase_slop: Option<u32>,
+    /// Blend BM25 with embedding similarity. Falls back to BM25 alone when
+    /// no embedding model is set.
+    pub hybrid: Option<bool>,
 }
 
 fn default_limit() -> usize {
@@ -121,6 +140,7 @@ pub struct CollectionIndex {
     pub commit_interval_ms: u64,
     pub pending_writes: AtomicU64,
     pub last_commit: std::sync::Mutex<Instant>,
+    pub vectors: VectorStore,
 }
 
 pub struct IndexStateInner {
@@ -137,12 +157,17 @@ impl Default for IndexStateInner {
 
 pub struct IndexState {
     pub inner: RwLock<IndexStateInner>,
+    pub embedder: RwLock<Option<Arc<dyn Embedder>>>,
+    /// The GGUF `embedder` was loaded from, if it came from a file.
+    pub embedding_model_path: RwLock<Option<std::path::PathBuf>>,
 }
 
 impl Default for IndexState {
     fn default() -> Self {
         Self {
             inner: RwLock::new(IndexStateInner::default()),
+            embedder: RwLock::new(None),
+            embedding_model_path: RwLock::new(None),
         }
     }
 }
@@ -156,6 +181,7 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::add_document::<tauri::Wry>,
             commands::update_document::<tauri::Wry>,
             commands::remove_document::<tauri::Wry>,
+            commands::set_embedding_model::<tauri::Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
@@ -191,6 +217,14 @@ pub fn init() -> tauri::plugin::TauriPlugin<tauri::Wry> {
                 let state = app.state::<IndexState>();
                 if let Ok(mut guard) = state.inner.try_write() {
                     for (name, collection) in guard.collections.iter_mut() {
+                        if let Err(e) = collection.vectors.save() {
+                            tracing::error!(
+                                "Failed to save vectors for collection '{}': {}",
+                                name,
+                                e
+                            );
+                        }
+
                         let pending = collection.pending_writes.load(Ordering::SeqCst);
                         if pending > 0 {
                             if let Err(e) = collection.writer.commit() {
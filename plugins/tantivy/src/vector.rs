@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+const MAGIC: &[u8; 4] = b"HYVS";
+const FORMAT_VERSION: u32 = 1;
+
+pub const PASSAGE_MAX_CHARS: usize = 1000;
+pub const PASSAGE_OVERLAP_CHARS: usize = 200;
+
+// https://plg.uwaterloo.ca/~gvcormac/cormacksigir09-rrf.pdf
+const RRF_K: f32 = 60.0;
+
+pub struct Passage {
+    /// Byte range in the document's content.
+    pub start: usize,
+    pub end: usize,
+    pub vector: Vec<f32>,
+}
+
+/// Passage embeddings of every document in a collection, kept in memory and
+/// saved next to the tantivy index.
+pub struct VectorStore {
+    path: PathBuf,
+    model: String,
+    docs: HashMap<String, Vec<Passage>>,
+    dirty: bool,
+}
+
+pub struct VectorMatch {
+    pub id: String,
+    pub score: f32,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl VectorStore {
+    /// Starts empty if the file is missing or unreadable.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        let mut store = Self {
+            path,
+            model: String::new(),
+            docs: HashMap::new(),
+            dirty: false,
+        };
+
+        if let Ok(file) = std::fs::File::open(&store.path) {
+            let mut reader = std::io::BufReader::new(file);
+            match read_store(&mut reader) {
+                Ok((model, docs)) => {
+                    store.model = model;
+                    store.docs = docs;
+                }
+                Err(e) => tracing::warn!(error = %e, path = ?store.path, "vector_store_unreadable"),
+            }
+        }
+
+        store
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Vectors from different models aren't comparable, so switching drops
+    /// everything embedded so far.
+    pub fn set_model(&mut self, model: &str) {
+        if self.model != model {
+            self.model = model.to_string();
+            self.docs.clear();
+            self.dirty = true;
+        }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.docs.contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    pub fn upsert(&mut self, id: &str, passages: Vec<Passage>) {
+        self.docs.insert(id.to_string(), passages);
+        self.dirty = true;
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        if self.docs.remove(id).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.docs.clear();
+        self.dirty = true;
+    }
+
+    /// Documents ranked by their best matching passage.
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<VectorMatch> {
+        let mut matches: Vec<VectorMatch> = self
+            .docs
+            .iter()
+            .filter_map(|(id, passages)| {
+                passages
+                    .iter()
+                    .map(|p| (dot(query, &p.vector), p))
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(score, p)| VectorMatch {
+                        id: id.clone(),
+                        score,
+                        start: p.start,
+                        end: p.end,
+                    })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        matches
+    }
+
+    pub fn save(&mut self) -> std::io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        // Written aside and renamed, so a crash never leaves half a file.
+        let tmp = self.path.with_extension("tmp");
+        {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&tmp)?);
+            write_store(&mut writer, &self.model, &self.docs)?;
+            writer.flush()?;
+        }
+        std::fs::rename(&tmp, &self.path)?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Splits text into overlapping passages of at most `max_chars` characters,
+/// preferring to break after a sentence and then at whitespace. Returns byte
+/// ranges.
+pub fn chunk_passages(text: &str, max_chars: usize, overlap_chars: usize) -> Vec<(usize, usize)> {
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let n_chars = boundaries.len() - 1;
+
+    let mut passages = Vec::new();
+    let mut start = 0;
+
+    while start < n_chars {
+        let mut end = (start + max_chars).min(n_chars);
+
+        if end < n_chars {
+            let window = &text[boundaries[start]..boundaries[end]];
+            let cut = window
+                .rfind(['.', '?', '!', '\n'])
+                .map(|i| i + 1)
+                .filter(|&i| i > window.len() / 2)
+                .or_else(|| window.rfind(char::is_whitespace).filter(|&i| i > 0));
+
+            if let Some(cut) = cut {
+                let cut = boundaries[start] + cut;
+                end = boundaries.partition_point(|&b| b < cut);
+            }
+        }
+
+        let (from, to) = (boundaries[start], boundaries[end]);
+        if !text[from..to].trim().is_empty() {
+            passages.push((from, to));
+        }
+
+        if end == n_chars {
+            break;
+        }
+
+        let mut next = end.saturating_sub(overlap_chars).max(start + 1);
+        // Back up to the start of the word the overlap begins in.
+        if let Some(ws) = text[boundaries[start]..boundaries[next]]
+            .rfind(char::is_whitespace)
+            .filter(|&i| i > 0)
+        {
+            next = boundaries.partition_point(|&b| b <= boundaries[start] + ws);
+        }
+        start = next;
+    }
+
+    passages
+}
+
+/// Reciprocal rank fusion: merges ranked lists of ids, best first.
+pub fn reciprocal_rank_fusion(rankings: &[Vec<String>]) -> Vec<(String, f32)> {
+    let mut scores: HashMap<&str, f32> = HashMap::new();
+    let mut first_seen: Vec<&str> = Vec::new();
+
+    for ranking in rankings {
+        for (rank, id) in ranking.iter().enumerate() {
+            let score = scores.entry(id).or_insert_with(|| {
+                first_seen.push(id);
+                0.0
+            });
+            *score += 1.0 / (RRF_K + rank as f32 + 1.0);
+        }
+    }
+
+    let mut fused: Vec<(String, f32)> = first_seen
+        .into_iter()
+        .map(|id| (id.to_string(), scores[id]))
+        .collect();
+    // Stable, so ties keep the order of the first ranking.
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+    fused
+}
+
+fn write_u32(w: &mut impl Write, v: u32) -> std::io::Result<()> {
+    w.write_all(&v.to_le_bytes())
+}
+
+fn write_str(w: &mut impl Write, s: &str) -> std::io::Result<()> {
+    write_u32(w, s.len() as u32)?;
+    w.write_all(s.as_bytes())
+}
+
+fn read_u32(r: &mut impl Read) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_str(r: &mut impl Read) -> std::io::Result<String> {
+    let mut buf = vec![0u8; read_u32(r)? as usize];
+    r.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+fn write_store(
+    w: &mut impl Write,
+    model: &str,
+    docs: &HashMap<String, Vec<Passage>>,
+) -> std::io::Result<()> {
+    let dim = docs
+        .values()
+        .flatten()
+        .next()
+        .map(|p| p.vector.len())
+        .unwrap_or(0);
+
+    w.write_all(MAGIC)?;
+    write_u32(w, FORMAT_VERSION)?;
+    write_str(w, model)?;
+    write_u32(w, dim as u32)?;
+    write_u32(w, docs.len() as u32)?;
+
+    for (id, passages) in docs {
+        write_str(w, id)?;
+        write_u32(w, passages.len() as u32)?;
+        for passage in passages {
+            write_u32(w, passage.start as u32)?;
+            write_u32(w, passage.end as u32)?;
+            for v in &passage.vector {
+                w.write_all(&v.to_le_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_store(r: &mut impl Read) -> std::io::Result<(String, HashMap<String, Vec<Passage>>)> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string());
+
+    let mut magic = [0u8; 4];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a vector store"));
+    }
+    if read_u32(r)? != FORMAT_VERSION {
+        return Err(invalid("unsupported vector store version"));
+    }
+
+    let model = read_str(r)?;
+    let dim = read_u32(r)? as usize;
+    let n_docs = read_u32(r)?;
+
+    let mut docs = HashMap::with_capacity(n_docs as usize);
+    for _ in 0..n_docs {
+        let id = read_str(r)?;
+        let n_passages = read_u32(r)?;
+
+        let mut passages = Vec::with_capacity(n_passages as usize);
+        for _ in 0..n_passages {
+            let start = read_u32(r)? as usize;
+            let end = read_u32(r)? as usize;
+
+            let mut bytes = vec![0u8; dim * 4];
+            r.read_exact(&mut bytes)?;
+            let vector = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+
+            passages.push(Passage { start, end, vector });
+        }
+        docs.insert(id, passages);
+    }
+
+    Ok((model, docs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(start: usize, vector: Vec<f32>) -> Passage {
+        Passage {
+            start,
+            end: start + 10,
+            vector,
+        }
+    }
+
+    #[test]
+    fn test_chunk_passages() {
+        assert!(chunk_passages("", 10, 2).is_empty());
+        assert_eq!(chunk_passages("short", 10, 2), vec![(0, 5)]);
+
+        let text = "One two three. Four five six. Seven eight nine.";
+        let passages = chunk_passages(text, 20, 5);
+        let texts: Vec<&str> = passages.iter().map(|&(s, e)| &text[s..e]).collect();
+        assert_eq!(texts[0], "One two three.");
+        assert_eq!(passages.last().unwrap().1, text.len());
+        for window in passages.windows(2) {
+            assert!(window[1].0 < window[0].1, "passages should overlap");
+        }
+
+        // Multi-byte characters never get split.
+        let text = "회의에서 가격 인상을 결정했습니다 다음 분기부터 적용합니다";
+        for (s, e) in chunk_passages(text, 7, 2) {
+            assert!(text.is_char_boundary(s) && text.is_char_boundary(e));
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let mut store = VectorStore::load("/nonexistent/vectors.bin");
+        store.upsert(
+            "a",
+            vec![passage(0, vec![1.0, 0.0]), passage(10, vec![0.6, 0.8])],
+        );
+        store.upsert("b", vec![passage(0, vec![0.0, 1.0])]);
+
+        let matches = store.search(&[0.0, 1.0], 10);
+        assert_eq!(matches[0].id, "b");
+        assert_eq!(matches[1].id, "a");
+        assert_eq!(matches[1].start, 10);
+        assert_eq!(store.search(&[0.0, 1.0], 1).len(), 1);
+
+        store.set_model("other");
+        assert_eq!(store.len(), 0);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.bin");
+
+        let mut store = VectorStore::load(&path);
+        store.set_model("bge-small");
+        store.upsert("a", vec![passage(5, vec![0.25, -1.5, 3.0])]);
+        store.save().unwrap();
+
+        let loaded = VectorStore::load(&path);
+        assert_eq!(loaded.model(), "bge-small");
+        let matches = loaded.search(&[0.0, 0.0, 1.0], 10);
+        assert_eq!(matches[0].id, "a");
+        assert_eq!((matches[0].start, matches[0].end), (5, 15));
+        assert_eq!(matches[0].score, 3.0);
+
+        std::fs::write(&path, b"garbage").unwrap();
+        assert_eq!(VectorStore::load(&path).len(), 0);
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let ids = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let fused = reciprocal_rank_fusion(&[ids(&["a", "b", "c"]), ids(&["b", "c"])]);
+
+        let order: Vec<&str> = fused.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+        assert!(fused[0].1 > fused[2].1);
+    }
+}
@@ -1,4 +1,12 @@
-const COMMANDS: &[&str] = &["render", "render_custom"];
+const COMMANDS: &[&str] = &[
+    "render",
+    "render_custom",
+    "plan_chunks",
+    "action_items_schema",
+    "collect_citations",
+    "validate_template",
+    "resolve_template",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
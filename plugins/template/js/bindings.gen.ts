@@ -21,6 +21,46 @@ async renderCustom(templateContent: string, ctx: Partial<{ [key in string]: Json
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async planChunks(transcripts: Transcript[], maxTokens: number) : Promise<Result<Transcript[][], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:template|plan_chunks", { transcripts, maxTokens }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async actionItemsSchema(participants: Participant[]) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:template|action_items_schema", { participants }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async collectCitations(answer: string, passages: MeetingPassage[]) : Promise<Result<Citation[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:template|collect_citations", { answer, passages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async validateTemplate(template: EnhanceTemplate) : Promise<Result<TemplateIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:template|validate_template", { template }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resolveTemplate(user: EnhanceUser) : Promise<Result<EnhanceTemplate | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("plugin:template|resolve_template", { user }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
@@ -34,19 +74,108 @@ async renderCustom(templateContent: string, ctx: Partial<{ [key in string]: Json
 
 /** user-defined types **/
 
+export type ActionItem = { text: string; 
+/**
+ * Participant name, exactly as given in the prompt.
+ */
+owner: string | null; 
+/**
+ * `YYYY-MM-DD`.
+ */
+dueDate: string | null; source: SourceSpan }
+export type ActionItems = { actionItems: ActionItem[]; decisions: Decision[] }
+export type ActionItemsSystem = { language: string | null }
+export type ActionItemsUser = { session: Session; participants: Participant[]; transcripts: Transcript[] }
 export type ChatContext = { title: string | null; date: string | null; rawContent: string | null; enhancedContent: string | null; transcript: Transcript | null }
 export type ChatSystem = { language: string | null; context: ChatContext | null }
+export type ChunkSystem = { language: string | null }
+export type ChunkUser = { session: Session; participants: Participant[]; transcripts: Transcript[]; part: number; total: number }
+export type Citation = { 
+/**
+ * The `[n]` marker used in the answer.
+ */
+marker: number; sessionId: string; startMs: number | null }
+export type Decision = { text: string; source: SourceSpan }
 export type EnhanceSystem = { language: string | null }
 export type EnhanceTemplate = { title: string; description: string | null; sections: TemplateSection[] }
-export type EnhanceUser = { session: Session; participants: Participant[]; template: EnhanceTemplate | null; transcripts: Transcript[] }
-export type Event = { name: string }
-export type Grammar = { task: "enhance"; sections: string[] | null } | { task: "title" } | { task: "tags" } | { task: "email-to-name" }
+export type EnhanceUser = { session: Session; participants: Participant[]; template: EnhanceTemplate | null; transcripts: Transcript[]; 
+/**
+ * Notes from a chunked first pass; when present they replace the transcript.
+ */
+partialSummaries: string[]; 
+/**
+ * Resolved into `template` when rendering, see [`EnhanceUser::resolved`].
+ */
+variables?: TemplateVariables }
+export type Event = { name: string; startedAt: string | null; endedAt: string | null }
+export type Grammar = { task: "enhance"; sections: string[] | null } | { task: "title" } | { task: "tags" } | { task: "email-to-name" } | { task: "json-schema"; schema: JsonValue }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * A passage retrieved from one of the user's meetings.
+ */
+export type MeetingPassage = { sessionId: string; title: string | null; date: string | null; 
+/**
+ * Milliseconds from the start of the recording, when the passage
+ * comes from the transcript.
+ */
+startMs: number | null; text: string }
+export type MeetingsChatSystem = { language: string | null; 
+/**
+ * Numbered from 1 in the prompt, which is what the answer cites.
+ */
+passages: MeetingPassage[] }
 export type Participant = { name: string; jobTitle: string | null }
+export type PreviousMeeting = { title: string | null; date: string | null; 
+/**
+ * The enhanced note of that meeting.
+ */
+summary: string }
+/**
+ * When a section is included; sections without one always are.
+ */
+export type SectionCondition = 
+/**
+ * Someone from outside the user's organization attended.
+ */
+{ type: "externalParticipants" } | 
+/**
+ * `variable` resolves to a value, e.g. there was a previous meeting.
+ */
+{ type: "hasValue"; variable: string } | 
+/**
+ * A participant with this name attended.
+ */
+{ type: "participantPresent"; name: string }
+export type SectionFormat = "paragraphs" | "bullets" | "table" | "checklist"
 export type Segment = { text: string; speaker: string }
 export type Session = { title: string | null; startedAt: string | null; endedAt: string | null; event: Event | null }
-export type Template = { enhanceSystem: EnhanceSystem } | { enhanceUser: EnhanceUser } | { titleSystem: TitleSystem } | { titleUser: TitleUser } | { chatSystem: ChatSystem }
-export type TemplateSection = { title: string; description: string | null }
+/**
+ * Inclusive range of transcript segments, see [`ActionItemsUser::segments`].
+ */
+export type SourceSpan = { start: number; end: number }
+export type Template = { enhanceSystem: EnhanceSystem } | { enhanceUser: EnhanceUser } | { chunkSystem: ChunkSystem } | { chunkUser: ChunkUser } | { titleSystem: TitleSystem } | { titleUser: TitleUser } | { chatSystem: ChatSystem } | { meetingsChatSystem: MeetingsChatSystem } | { actionItemsSystem: ActionItemsSystem } | { actionItemsUser: ActionItemsUser }
+export type TemplateIssue = { 
+/**
+ * Index of the offending section, or `None` for the template itself.
+ */
+section: number | null; message: string }
+export type TemplateSection = { title: string; description: string | null; 
+/**
+ * Extra guidance for the model, on top of the description.
+ */
+instructions?: string | null; format?: SectionFormat | null; condition?: SectionCondition | null }
+/**
+ * What user templates can see beyond the session and its participants.
+ */
+export type TemplateVariables = { 
+/**
+ * Names of participants from outside the user's organization.
+ */
+externalParticipants: string[]; 
+/**
+ * The last meeting of the same calendar series, if any.
+ */
+previousMeeting: PreviousMeeting | null }
 export type TitleSystem = { language: string | null }
 export type TitleUser = { enhancedNote: string }
 export type Transcript = { segments: Segment[]; startedAt: number | null; endedAt: number | null }
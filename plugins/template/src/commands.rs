@@ -18,3 +18,52 @@ pub async fn render_custom<R: tauri::Runtime>(
 ) -> Result<String, String> {
     app.template().render_custom(&template_content, ctx)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn plan_chunks<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    transcripts: Vec<hypr_template_app::Transcript>,
+    max_tokens: u32,
+) -> Result<Vec<Vec<hypr_template_app::Transcript>>, String> {
+    Ok(app
+        .template()
+        .plan_chunks(&transcripts, max_tokens as usize))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn action_items_schema<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    participants: Vec<hypr_template_app::Participant>,
+) -> Result<serde_json::Value, String> {
+    Ok(app.template().action_items_schema(&participants))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn collect_citations<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    answer: String,
+    passages: Vec<hypr_template_app::MeetingPassage>,
+) -> Result<Vec<hypr_template_app::Citation>, String> {
+    Ok(app.template().collect_citations(&answer, &passages))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_template<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    template: hypr_template_app::EnhanceTemplate,
+) -> Result<Vec<hypr_template_app::TemplateIssue>, String> {
+    Ok(app.template().validate_template(&template))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_template<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    user: hypr_template_app::EnhanceUser,
+) -> Result<Option<hypr_template_app::EnhanceTemplate>, String> {
+    Ok(app.template().resolve_template(&user))
+}
@@ -20,6 +20,50 @@ impl<R: tauri::Runtime, M: tauri::Manager<R>> Template<'_, R, M> {
             .map(|s| s.trim().to_string())
             .map_err(|e| e.to_string())
     }
+
+    #[tracing::instrument(skip_all)]
+    pub fn plan_chunks(
+        &self,
+        transcripts: &[hypr_template_app::Transcript],
+        max_tokens: usize,
+    ) -> Vec<Vec<hypr_template_app::Transcript>> {
+        hypr_template_app::plan_chunks(transcripts, max_tokens)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn action_items_schema(
+        &self,
+        participants: &[hypr_template_app::Participant],
+    ) -> serde_json::Value {
+        hypr_template_app::action_items_schema(participants)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn collect_citations(
+        &self,
+        answer: &str,
+        passages: &[hypr_template_app::MeetingPassage],
+    ) -> Vec<hypr_template_app::Citation> {
+        hypr_template_app::collect_citations(answer, passages)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn validate_template(
+        &self,
+        template: &hypr_template_app::EnhanceTemplate,
+    ) -> Vec<hypr_template_app::TemplateIssue> {
+        hypr_template_app::validate_template(template)
+    }
+
+    /// The template as the model sees it: conditions applied and variables
+    /// filled in.
+    #[tracing::instrument(skip_all)]
+    pub fn resolve_template(
+        &self,
+        user: &hypr_template_app::EnhanceUser,
+    ) -> Option<hypr_template_app::EnhanceTemplate> {
+        user.resolved().template
+    }
 }
 
 pub trait TemplatePluginExt<R: tauri::Runtime> {
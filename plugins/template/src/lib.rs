@@ -14,8 +14,14 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
         .commands(tauri_specta::collect_commands![
             commands::render::<Wry>,
             commands::render_custom::<Wry>,
+            commands::plan_chunks::<Wry>,
+            commands::action_items_schema::<Wry>,
+            commands::collect_citations::<Wry>,
+            commands::validate_template::<Wry>,
+            commands::resolve_template::<Wry>,
         ])
         .typ::<hypr_gbnf::Grammar>()
+        .typ::<hypr_template_app::ActionItems>()
         .error_handling(tauri_specta::ErrorHandlingMode::Result)
 }
 
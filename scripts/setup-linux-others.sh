@@ -10,6 +10,8 @@ sudo apt-get install -y \
   libgtk-4-dev \
   libasound2-dev \
   libpulse-dev \
+  libpipewire-0.3-dev \
+  libclang-dev \
   libgraphene-1.0-dev \
   pkg-config \
   patchelf \